@@ -0,0 +1,135 @@
+//! # Key audit
+//!
+//! Finds configuration keys that nothing knows about, usually typos in a config
+//! file: `iproyal.clientId` or `iproyal.endpiont` load fine, but are silently
+//! ignored by deserialization.
+//!
+//! [`audit_keys`] compares every leaf of a built [`config::Config`] with a key
+//! manifest, typically the keys of [`ApplyOverrides::override_keys`](crate::ApplyOverrides::override_keys)
+//! of one or more structs plus any keys set only from files. A manifest key also
+//! covers everything below it, so a table-valued key such as `iproyal.headers`
+//! accepts arbitrary names inside. Arrays are leaves.
+//!
+//! ```ignore
+//! let manifest = CLIArgs::override_keys().iter().map(|(_, key)| *key);
+//! for unknown in audit_keys(manifest, &config) {
+//!     match unknown.suggestion {
+//!         Some(known) => println!("{}: did you mean `{known}`?", unknown.key),
+//!         None => println!("{}: not a known key", unknown.key),
+//!     }
+//! }
+//! ```
+
+use config::{Config, Map, Source, Value, ValueKind};
+
+/// A configuration key outside the manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownKey {
+    /// Dotted key as found in the configuration, e.g. `iproyal.clientId`.
+    pub key: String,
+
+    /// Known key differing only in casing, `_` or `-`, e.g. `iproyal.client_id`.
+    pub suggestion: Option<String>,
+}
+
+/// Lists the leaf keys of `config` not covered by any key of `descriptors`, sorted.
+pub fn audit_keys<'a>(descriptors: impl IntoIterator<Item = &'a str>, config: &Config) -> Vec<UnknownKey> {
+    let known: Vec<&str> = descriptors.into_iter().collect();
+
+    let mut leaves = Vec::new();
+    if let Ok(root) = config.collect() {
+        collect_leaves("", root, &mut leaves);
+    }
+    leaves.sort();
+
+    leaves
+        .into_iter()
+        .filter(|key| !known.iter().any(|known| covers(known, key)))
+        .map(|key| {
+            let suggestion = known.iter().find_map(|known| suggest(known, &key));
+            UnknownKey { key, suggestion }
+        })
+        .collect()
+}
+
+/// Whether `known` is `key` or a table above it.
+fn covers(known: &str, key: &str) -> bool {
+    key.strip_prefix(known).is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+/// `key` spelled as `known` (plus the segments below it), if they only differ in
+/// casing, `_` or `-`.
+fn suggest(known: &str, key: &str) -> Option<String> {
+    let depth = known.split('.').count();
+    let segments: Vec<&str> = key.split('.').collect();
+    if segments.len() < depth || normalize(&segments[..depth].join(".")) != normalize(known) {
+        return None;
+    }
+    Some(std::iter::once(known).chain(segments[depth..].iter().copied()).collect::<Vec<_>>().join("."))
+}
+
+fn normalize(key: &str) -> String {
+    key.chars().filter(|c| !matches!(c, '_' | '-')).flat_map(char::to_lowercase).collect()
+}
+
+fn collect_leaves(prefix: &str, table: Map<String, Value>, out: &mut Vec<String>) {
+    for (name, value) in table {
+        let key = if prefix.is_empty() { name } else { format!("{prefix}.{name}") };
+        match value.kind {
+            ValueKind::Table(child) if !child.is_empty() => collect_leaves(&key, child, out),
+            _ => out.push(key),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(toml: &str) -> Config {
+        Config::builder()
+            .add_source(config::File::from_str(toml, config::FileFormat::Toml))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn unknown_leaves_are_reported() {
+        let config = config(
+            r#"
+            verbose = true
+            [iproyal]
+            endpoint = "https://api.iproyal.com"
+            endpiont = "https://api.iproyal.com"
+            [iproyal.headers]
+            X-Trace = "1"
+            "#,
+        );
+
+        let unknown = audit_keys(["verbose", "iproyal.endpoint", "iproyal.headers"], &config);
+
+        assert_eq!(unknown, [UnknownKey { key: "iproyal.endpiont".into(), suggestion: None }]);
+    }
+
+    #[test]
+    fn casing_mistakes_get_a_suggestion() {
+        let config = config(
+            r#"
+            [iproyal]
+            clientId = "abc"
+            [Http.proxy]
+            url = "http://proxy:3128"
+            "#,
+        );
+
+        let unknown = audit_keys(["iproyal.client_id", "http.proxy"], &config);
+
+        assert_eq!(
+            unknown,
+            [
+                UnknownKey { key: "Http.proxy.url".into(), suggestion: Some("http.proxy.url".into()) },
+                UnknownKey { key: "iproyal.clientId".into(), suggestion: Some("iproyal.client_id".into()) },
+            ]
+        );
+    }
+}
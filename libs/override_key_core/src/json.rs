@@ -0,0 +1,110 @@
+//! # JSON passthrough
+//!
+//! Runtime support for fields marked `#[override_key(json)]`, available with
+//! the `json` feature.
+//!
+//! Such a field holds a raw [`serde_json::Value`], typically parsed from a flag
+//! like `--extra-config '{"app":{"x":1}}'`. Instead of replacing a whole table,
+//! its objects are walked down to their leaves, and every leaf becomes one
+//! override, so sibling keys from files and the environment survive:
+//!
+//! ```ignore
+//! overrides("", &json!({"app": {"x": 1, "tags": ["a"]}}))
+//! → [("app.x", 1), ("app.tags", ["a"])]
+//!
+//! overrides("extra", &json!({"x": 1}))
+//! → [("extra.x", 1)]
+//! ```
+//!
+//! Arrays are leaves: they replace the configured array as a whole. `null`
+//! leaves set nothing.
+
+use config::{ConfigError, Map, Value, ValueKind};
+
+/// Flattens `json` into `(key, value)` overrides under `key`; `""` is the root.
+///
+/// # Errors
+/// A scalar or array at the root has no key to be set under.
+pub fn overrides(key: &str, json: &serde_json::Value) -> Result<Vec<(String, Value)>, ConfigError> {
+    if key.is_empty() && !json.is_object() {
+        return Err(ConfigError::Message(format!(
+            "a JSON override without a key must be an object, got {json}"
+        )));
+    }
+
+    let mut out = Vec::new();
+    flatten(key, json, &mut out);
+    Ok(out)
+}
+
+/// Converts a JSON tree into the equivalent `config` value tree.
+pub fn to_value(json: &serde_json::Value) -> Value {
+    let kind = match json {
+        serde_json::Value::Null => ValueKind::Nil,
+        serde_json::Value::Bool(b) => ValueKind::Boolean(*b),
+        serde_json::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                ValueKind::I64(i)
+            } else if let Some(u) = n.as_u64() {
+                ValueKind::U64(u)
+            } else {
+                ValueKind::Float(n.as_f64().unwrap_or(f64::NAN))
+            }
+        }
+        serde_json::Value::String(s) => ValueKind::String(s.clone()),
+        serde_json::Value::Array(items) => ValueKind::Array(items.iter().map(to_value).collect()),
+        serde_json::Value::Object(fields) => {
+            ValueKind::Table(fields.iter().map(|(k, v)| (k.clone(), to_value(v))).collect::<Map<_, _>>())
+        }
+    };
+    Value::new(None, kind)
+}
+
+fn flatten(key: &str, json: &serde_json::Value, out: &mut Vec<(String, Value)>) {
+    match json {
+        serde_json::Value::Null => {}
+        serde_json::Value::Object(fields) => {
+            for (name, value) in fields {
+                let nested = if key.is_empty() { name.clone() } else { format!("{key}.{name}") };
+                flatten(&nested, value, out);
+            }
+        }
+        leaf => out.push((key.to_string(), to_value(leaf))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Keys in sorted order, independent of `serde_json`'s map ordering.
+    fn keys(overrides: &[(String, Value)]) -> Vec<&str> {
+        let mut keys: Vec<&str> = overrides.iter().map(|(key, _)| key.as_str()).collect();
+        keys.sort();
+        keys
+    }
+
+    fn get<'a>(overrides: &'a [(String, Value)], key: &str) -> &'a Value {
+        &overrides.iter().find(|(k, _)| k == key).unwrap().1
+    }
+
+    #[test]
+    fn objects_are_flattened_to_leaves() {
+        let json = json!({"app": {"x": 1, "name": "demo", "tags": ["a", "b"], "unset": null}, "debug": true});
+
+        let root = overrides("", &json).unwrap();
+        assert_eq!(keys(&root), ["app.name", "app.tags", "app.x", "debug"]);
+        assert_eq!(get(&root, "app.tags").clone().into_array().unwrap().len(), 2);
+        assert_eq!(get(&root, "app.x").clone().into_int().unwrap(), 1);
+
+        let nested = overrides("extra", &json).unwrap();
+        assert_eq!(keys(&nested), ["extra.app.name", "extra.app.tags", "extra.app.x", "extra.debug"]);
+    }
+
+    #[test]
+    fn scalars_need_a_key() {
+        assert!(overrides("", &json!(3)).is_err());
+        assert_eq!(keys(&overrides("app.x", &json!(3)).unwrap()), ["app.x"]);
+    }
+}
@@ -68,11 +68,39 @@
 //!         &self,
 //!         builder: config::ConfigBuilder<config::builder::DefaultState>,
 //!     ) -> Result<config::ConfigBuilder<config::builder::DefaultState>, config::ConfigError>;
+//!
+//!     fn override_keys() -> &'static [(&'static str, &'static str)] where Self: Sized { &[] }
+//!
+//!     fn sensitive_keys() -> &'static [&'static str] where Self: Sized { &[] }
+//!
+//!     fn active_overrides(&self) -> Vec<(&'static str, config::Value)> { Vec::new() }
 //! }
 //! ```
 //!
 //! - `builder`: A [`config::ConfigBuilder`] representing the base configuration.
 //! - Returns a modified builder with all applicable overrides applied.
+//! - `override_keys()` lists `(field, key)` pairs for every field that can
+//!   override a key, so callers can tell users which flag sets a key.
+//! - `flag_key_map()` lists `(flag, key)` pairs of fields with a clap long flag,
+//!   with the `clap` feature of `override_key_derive`.
+//! - `sensitive_keys()` lists the keys of fields marked `sensitive`, so callers
+//!   can mask their values when displaying configuration.
+//! - `active_overrides()` returns the `(key, value)` pairs this instance would
+//!   apply, without a builder, for logging and assertions.
+//! - `apply_overrides_env_prefixed()` applies the same overrides with the
+//!   struct's `key_prefix()` replaced by one read from an environment variable,
+//!   e.g. one prefix per tenant of a shared deployment.
+//!
+//! With `#[apply_overrides(map_output)]`, the derive macro also generates
+//! `as_override_map()`, which nests the active overrides into a
+//! [`config::Map`] through the [`map`] module, for services building their
+//! configuration from a plain map.
+//!
+//! [`audit::audit_keys`] checks a built configuration against these key lists
+//! and reports keys nothing can set, usually typos in a config file.
+//!
+//! With the `json` feature, the [`json`] module backs `#[override_key(json)]`
+//! fields, which merge a raw `serde_json::Value` tree into the configuration.
 //!
 //! The derive macro ensures that this method **never panics**, and that
 //! all builder calls are chained in a fallible manner (`?`).
@@ -102,7 +130,8 @@
 //! ## Safety Notes
 //!
 //! - This trait is pure Rust — no `unsafe` code is involved.
-//! - It has **no external dependencies** other than [`config`].
+//! - It has **no external dependencies** other than [`config`] (plus `serde_json` with
+//!   the `json` feature).
 //! - It provides a stable ABI for the `override_key_derive` macro to target.
 //!
 //! ---
@@ -110,6 +139,11 @@
 //! [`config`]: https://docs.rs/config/latest/config/
 //! [`config::ConfigBuilder`]: https://docs.rs/config/latest/config/struct.ConfigBuilder.html
 
+pub mod audit;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod map;
+
 /// Core runtime trait used by the `#[derive(ApplyOverrides)]` macro.
 ///
 /// Implemented automatically by the `override_key_derive` procedural macro.
@@ -134,8 +168,162 @@ pub trait ApplyOverrides {
     /// # Notes
     /// - The generated implementations are **deterministic** and **idempotent**.
     /// - All calls are chained with `?`, preserving the builder’s fallible API.
+    /// - Overrides are applied in field declaration order, so when two fields set
+    ///   the same key the later one wins. `#[apply_overrides(emit_order_test)]`
+    ///   generates a test guarding this.
     fn apply_overrides(
         &self,
         builder: config::ConfigBuilder<config::builder::DefaultState>,
     ) -> Result<config::ConfigBuilder<config::builder::DefaultState>, config::ConfigError>;
-}
\ No newline at end of file
+
+    /// `(field name, configuration key)` pairs of all fields that apply overrides.
+    ///
+    /// The derive macro lists them in declaration order; skipped fields are omitted.
+    ///
+    /// # Example
+    /// ```ignore
+    /// for (field, key) in CLIArgs::override_keys() {
+    ///     println!("--{} sets {key}", field.replace('_', "-"));
+    /// }
+    /// ```
+    fn override_keys() -> &'static [(&'static str, &'static str)]
+    where
+        Self: Sized,
+    {
+        &[]
+    }
+
+    /// `(long flag, configuration key)` pairs of the fields that have a clap long
+    /// flag, flags without their leading dashes.
+    ///
+    /// Generated with the `clap` feature of `override_key_derive`, from
+    /// `#[arg(long)]` (the kebab-cased field name) or `#[arg(long = "...")]`;
+    /// empty otherwise.
+    ///
+    /// # Example
+    /// ```ignore
+    /// for (flag, key) in CLIArgs::flag_key_map() {
+    ///     println!("--{flag} sets {key}");
+    /// }
+    /// ```
+    fn flag_key_map() -> &'static [(&'static str, &'static str)]
+    where
+        Self: Sized,
+    {
+        &[]
+    }
+
+    /// Configuration keys whose values are secrets.
+    ///
+    /// The derive macro lists the keys of fields marked
+    /// `#[override_key(..., sensitive)]`, in declaration order. Use it to mask
+    /// values when printing or logging the merged configuration.
+    ///
+    /// # Example
+    /// ```ignore
+    /// for key in CLIArgs::sensitive_keys() {
+    ///     println!("{key} = ********");
+    /// }
+    /// ```
+    fn sensitive_keys() -> &'static [&'static str]
+    where
+        Self: Sized,
+    {
+        &[]
+    }
+
+    /// `(configuration key, value)` pairs this instance overrides, in declaration order.
+    ///
+    /// Reflects the current field values independently of any builder: `None`
+    /// fields are left out, exactly as [`apply_overrides`](Self::apply_overrides)
+    /// skips them. Values of sensitive keys are included as-is, so mask them with
+    /// [`sensitive_keys`](Self::sensitive_keys) before logging.
+    ///
+    /// # Example
+    /// ```ignore
+    /// for (key, value) in args.active_overrides() {
+    ///     println!("{key} = {value}");
+    /// }
+    /// ```
+    fn active_overrides(&self) -> Vec<(&'static str, config::Value)> {
+        Vec::new()
+    }
+
+    /// Struct-level prefix of the generated keys, from `#[apply_overrides(prefix = "...")]`
+    /// or `prefix_from_type`; `None` without one.
+    fn key_prefix() -> Option<&'static str>
+    where
+        Self: Sized,
+    {
+        None
+    }
+
+    /// Like [`apply_overrides`](Self::apply_overrides), with the key prefix read at
+    /// runtime from the environment variable `env_var`.
+    ///
+    /// Keys under the compile-time [`key_prefix`](Self::key_prefix) are moved under
+    /// the prefix from `env_var`; other keys stay where they are. Without a
+    /// compile-time prefix, the runtime one is prepended to every key. When
+    /// `env_var` is unset or empty, this is exactly `apply_overrides`.
+    ///
+    /// # Example
+    /// ```ignore
+    /// // TENANT_PREFIX=tenant_a: `iproyal.timeout` is set as `tenant_a.timeout`
+    /// let merged = args.apply_overrides_env_prefixed(builder, "TENANT_PREFIX")?;
+    /// ```
+    fn apply_overrides_env_prefixed(
+        &self,
+        mut builder: config::ConfigBuilder<config::builder::DefaultState>,
+        env_var: &str,
+    ) -> Result<config::ConfigBuilder<config::builder::DefaultState>, config::ConfigError>
+    where
+        Self: Sized,
+    {
+        let prefix = match std::env::var(env_var) {
+            Ok(prefix) if !prefix.is_empty() => prefix,
+            _ => return self.apply_overrides(builder),
+        };
+
+        // Split values and deprecated keys are checked by the regular path, which
+        // also prints its warnings; its builder is discarded
+        let _ = self.apply_overrides(config::Config::builder())?;
+
+        // Tables (JSON and dynamic fields) are set leaf by leaf, so that sibling
+        // keys from other sources survive as with `apply_overrides`
+        for (key, value) in self.active_overrides() {
+            let key = rebase_key(key, Self::key_prefix(), &prefix);
+            for (leaf, value) in map::flatten(&key, value) {
+                builder = builder.set_override(leaf, value)?;
+            }
+        }
+        Ok(builder)
+    }
+}
+
+/// Moves `key` from the compile-time prefix to the runtime one; `""` stays the root.
+fn rebase_key(key: &str, compiled: Option<&str>, runtime: &str) -> String {
+    match compiled {
+        _ if key.is_empty() => String::new(),
+        Some(compiled) if key == compiled => runtime.to_string(),
+        Some(compiled) => match key.strip_prefix(compiled).and_then(|rest| rest.strip_prefix('.')) {
+            Some(rest) => format!("{runtime}.{rest}"),
+            None => key.to_string(),
+        },
+        None => format!("{runtime}.{key}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rebase_key;
+
+    #[test]
+    fn keys_move_from_the_compiled_prefix() {
+        assert_eq!(rebase_key("iproyal.timeout", Some("iproyal"), "tenant_a"), "tenant_a.timeout");
+        assert_eq!(rebase_key("iproyal", Some("iproyal"), "tenant_a"), "tenant_a");
+        assert_eq!(rebase_key("iproyalx.timeout", Some("iproyal"), "tenant_a"), "iproyalx.timeout");
+        assert_eq!(rebase_key("http.proxy", Some("iproyal"), "tenant_a"), "http.proxy");
+        assert_eq!(rebase_key("http.proxy", None, "tenant_a"), "tenant_a.http.proxy");
+        assert_eq!(rebase_key("", None, "tenant_a"), "");
+    }
+}
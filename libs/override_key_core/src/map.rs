@@ -0,0 +1,148 @@
+//! # Map output
+//!
+//! Runtime support for `#[apply_overrides(map_output)]`, whose generated
+//! `as_override_map()` hands overrides to services that build their
+//! configuration from a plain [`config::Map`] rather than a `ConfigBuilder`.
+//!
+//! Dotted keys become nested tables, the way `set_override` would store them:
+//!
+//! ```ignore
+//! nested([("iproyal.timeout", "20s"), ("iproyal.proxy.host", "localhost")])
+//! → {"iproyal": {"timeout": "20s", "proxy": {"host": "localhost"}}}
+//! ```
+//!
+//! Later pairs win, as with `set_override`. A table set at an existing table is
+//! merged into it, and a table set under `""` is merged into the root.
+//!
+//! [`flatten`] goes the other way, from a value tree to one pair per leaf.
+
+use config::{Map, Value, ValueKind};
+
+/// Nests `(key, value)` overrides into a table, in order.
+pub fn nested<'a>(overrides: impl IntoIterator<Item = (&'a str, Value)>) -> Map<String, Value> {
+    let mut root = Map::new();
+    for (key, value) in overrides {
+        if key.is_empty() {
+            if let ValueKind::Table(table) = value.kind {
+                merge(&mut root, table);
+            }
+        } else {
+            insert(&mut root, key, value);
+        }
+    }
+    root
+}
+
+/// Splits `value` into one `(key, value)` pair per leaf under `key` (`""` for the
+/// root), the reverse of [`nested`]. Arrays are leaves; nil values are dropped.
+pub fn flatten(key: &str, value: Value) -> Vec<(String, Value)> {
+    let mut out = Vec::new();
+    flatten_into(key, value, &mut out);
+    out
+}
+
+fn flatten_into(key: &str, value: Value, out: &mut Vec<(String, Value)>) {
+    match value.kind {
+        ValueKind::Nil => {}
+        ValueKind::Table(table) => {
+            for (name, value) in table {
+                let nested = if key.is_empty() { name } else { format!("{key}.{name}") };
+                flatten_into(&nested, value, out);
+            }
+        }
+        _ => out.push((key.to_string(), value)),
+    }
+}
+
+/// Sets `value` at the dotted `key`, replacing scalars on the way with tables.
+fn insert(table: &mut Map<String, Value>, key: &str, value: Value) {
+    match key.split_once('.') {
+        None => match (table.get_mut(key), value) {
+            (Some(Value { kind: ValueKind::Table(existing), .. }), Value { kind: ValueKind::Table(new), .. }) => {
+                merge(existing, new)
+            }
+            (_, value) => {
+                table.insert(key.to_string(), value);
+            }
+        },
+        Some((head, rest)) => {
+            let entry = table.entry(head.to_string()).or_insert_with(|| Value::new(None, ValueKind::Table(Map::new())));
+            if !matches!(entry.kind, ValueKind::Table(_)) {
+                *entry = Value::new(None, ValueKind::Table(Map::new()));
+            }
+            if let ValueKind::Table(child) = &mut entry.kind {
+                insert(child, rest, value);
+            }
+        }
+    }
+}
+
+/// Merges `new` into `table`, recursing into tables present in both.
+fn merge(table: &mut Map<String, Value>, new: Map<String, Value>) {
+    for (key, value) in new {
+        // keys of a merged table are single segments, even when they contain dots
+        match (table.get_mut(&key), value) {
+            (Some(Value { kind: ValueKind::Table(existing), .. }), Value { kind: ValueKind::Table(new), .. }) => {
+                merge(existing, new)
+            }
+            (_, value) => {
+                table.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table(value: &Value) -> Map<String, Value> {
+        value.clone().into_table().unwrap()
+    }
+
+    #[test]
+    fn dotted_keys_become_nested_tables() {
+        let map = nested([
+            ("iproyal.timeout", Value::from("20s")),
+            ("iproyal.proxy.host", Value::from("localhost")),
+            ("iproyal.proxy.port", Value::from(8080)),
+            ("verbose", Value::from(true)),
+        ]);
+
+        let iproyal = table(&map["iproyal"]);
+        assert_eq!(iproyal["timeout"].clone().into_string().unwrap(), "20s");
+        let proxy = table(&iproyal["proxy"]);
+        assert_eq!(proxy["host"].clone().into_string().unwrap(), "localhost");
+        assert_eq!(proxy["port"].clone().into_int().unwrap(), 8080);
+        assert!(map["verbose"].clone().into_bool().unwrap());
+    }
+
+    #[test]
+    fn flatten_reverses_nesting() {
+        let map = nested([("iproyal.proxy.host", Value::from("localhost")), ("verbose", Value::from(true))]);
+
+        let mut leaves: Vec<String> = flatten("", Value::from(map)).into_iter().map(|(key, _)| key).collect();
+        leaves.sort();
+
+        assert_eq!(leaves, ["iproyal.proxy.host", "verbose"]);
+        assert_eq!(flatten("app", Value::from(3)).len(), 1);
+    }
+
+    #[test]
+    fn later_pairs_win_and_tables_merge() {
+        let cache: Map<String, Value> = [("max_age".to_string(), Value::from("1h"))].into_iter().collect();
+        let root: Map<String, Value> = [("cache".to_string(), Value::from(cache))].into_iter().collect();
+
+        let map = nested([
+            ("cache.enabled", Value::from(false)),
+            ("timeout", Value::from("10s")),
+            ("timeout", Value::from("20s")),
+            ("", Value::from(root)),
+        ]);
+
+        assert_eq!(map["timeout"].clone().into_string().unwrap(), "20s");
+        let cache = table(&map["cache"]);
+        assert!(!cache["enabled"].clone().into_bool().unwrap());
+        assert_eq!(cache["max_age"].clone().into_string().unwrap(), "1h");
+    }
+}
@@ -11,4 +11,12 @@ fn compile_fails_for_invalid_usage() {
     t.compile_fail("tests/errors/not_struct.rs");
     t.compile_fail("tests/errors/multiple_errors.rs");
     t.compile_fail("tests/errors/invalid_struct_meta.rs");
+    t.compile_fail("tests/errors/skip_combined_with_infer.rs");
+    t.compile_fail("tests/errors/key_combined_with_infer.rs");
+    t.compile_fail("tests/errors/deny_unannotated_field.rs");
+    t.compile_fail("tests/errors/invalid_key_literal.rs");
+    t.compile_fail("tests/errors/split_arity.rs");
+    t.compile_fail("tests/errors/prefix_from_type_conflicts.rs");
+    t.compile_fail("tests/errors/strict_without_deprecated.rs");
+    t.compile_fail("tests/errors/dynamic_conflicts.rs");
 }
\ No newline at end of file
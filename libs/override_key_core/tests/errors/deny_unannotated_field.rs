@@ -0,0 +1,20 @@
+use override_key_derive::ApplyOverrides;
+
+#[derive(ApplyOverrides)]
+#[apply_overrides(infer_keys, prefix = "iproyal", deny_unannotated)]
+struct StrictArgs {
+    #[override_key(infer)]
+    timeout: Option<String>,
+
+    #[override_key(skip)]
+    config: Option<String>,
+
+    // neither mapped nor skipped
+    endpoint: Option<String>,
+
+    // `sensitive` alone does not choose a key
+    #[override_key(sensitive)]
+    token: Option<String>,
+}
+
+fn main() {}
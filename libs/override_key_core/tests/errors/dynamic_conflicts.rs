@@ -0,0 +1,17 @@
+use override_key_derive::ApplyOverrides;
+
+#[derive(ApplyOverrides)]
+struct DynamicConflicts {
+    #[override_key(dynamic)]
+    set: Vec<(String, String)>,
+
+    // a second set of pairs would race with the first
+    #[override_key(dynamic)]
+    extra: Vec<(String, String)>,
+
+    // runtime keys cannot be listed as sensitive
+    #[override_key(dynamic, sensitive)]
+    secrets: Vec<(String, String)>,
+}
+
+fn main() {}
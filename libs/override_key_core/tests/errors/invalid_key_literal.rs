@@ -0,0 +1,26 @@
+use override_key_derive::ApplyOverrides;
+
+#[derive(ApplyOverrides)]
+struct InvalidKeys {
+    #[override_key = "iproyal..endpoint"]
+    endpoint: Option<String>,
+
+    #[override_key = "iproyal.connect timeout"]
+    timeout: Option<String>,
+
+    #[override_key(key = "iproyal.token.")]
+    token: Option<String>,
+
+    // unknown placeholder
+    #[override_key = "providers.{feild}.enabled"]
+    enabled: Option<bool>,
+
+    // trailing dot in the prefix doubles the separator
+    #[override_key(infer, prefix = "infatica.")]
+    region: Option<String>,
+
+    #[override_key = ""]
+    empty: Option<String>,
+}
+
+fn main() {}
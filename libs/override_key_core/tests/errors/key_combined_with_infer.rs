@@ -0,0 +1,10 @@
+use override_key_derive::ApplyOverrides;
+
+#[derive(ApplyOverrides)]
+struct KeyWithInfer {
+    // an explicit key leaves nothing to infer
+    #[override_key(key = "app.field", infer)]
+    field: Option<String>,
+}
+
+fn main() {}
@@ -0,0 +1,16 @@
+use override_key_derive::ApplyOverrides;
+
+#[derive(ApplyOverrides)]
+#[apply_overrides(infer_keys, prefix = "iproyal", prefix_from_type)]
+struct IPRoyalArgs {
+    endpoint: Option<String>,
+}
+
+// Stripping `Args` leaves nothing
+#[derive(ApplyOverrides)]
+#[apply_overrides(infer_keys, prefix_from_type)]
+struct Args {
+    endpoint: Option<String>,
+}
+
+fn main() {}
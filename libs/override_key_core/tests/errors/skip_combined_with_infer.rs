@@ -0,0 +1,10 @@
+use override_key_derive::ApplyOverrides;
+
+#[derive(ApplyOverrides)]
+struct SkipWithInfer {
+    // `skip` must stand alone
+    #[override_key(skip, infer)]
+    field: Option<String>,
+}
+
+fn main() {}
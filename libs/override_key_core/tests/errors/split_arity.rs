@@ -0,0 +1,19 @@
+use override_key_derive::ApplyOverrides;
+
+#[derive(ApplyOverrides)]
+struct SplitArity {
+    // a single key would not split anything
+    #[override_key(split = ":", keys("db.host"))]
+    db: Option<String>,
+
+    #[override_key(keys("cache.host", "cache.port"))]
+    cache: Option<String>,
+
+    #[override_key(split = ":", keys("a.host", "a.port"), infer)]
+    proxy: Option<String>,
+
+    #[override_key(split = "", keys("b.host", "b.port"))]
+    broker: Option<String>,
+}
+
+fn main() {}
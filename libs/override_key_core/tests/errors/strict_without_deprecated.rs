@@ -0,0 +1,13 @@
+use override_key_derive::ApplyOverrides;
+
+#[derive(ApplyOverrides)]
+struct StrictOnly {
+    // nothing to be strict about
+    #[override_key(infer, strict)]
+    timeout: Option<String>,
+
+    #[override_key(split = ":", keys("db.host", "db.port"), deprecated = "db.address")]
+    db: Option<String>,
+}
+
+fn main() {}
@@ -0,0 +1,33 @@
+use override_key_core::ApplyOverrides;
+use override_key_derive::ApplyOverrides;
+use config::Value;
+
+#[derive(ApplyOverrides)]
+#[apply_overrides(infer_keys, prefix = "iproyal")]
+struct ActiveArgs {
+    timeout: Option<String>,
+    endpoint: Option<String>,
+    retries: u32,
+
+    #[override_key(skip)]
+    #[allow(dead_code)]
+    verbose: bool,
+}
+
+#[test]
+fn active_overrides_reflect_instance() {
+    let args = ActiveArgs {
+        timeout: Some("20s".into()),
+        endpoint: None,
+        retries: 3,
+        verbose: true,
+    };
+
+    let active = args.active_overrides();
+
+    // None and skipped fields are left out, order follows the declaration
+    let keys: Vec<_> = active.iter().map(|(key, _)| *key).collect();
+    assert_eq!(keys, ["iproyal.timeout", "iproyal.retries"]);
+    assert_eq!(active[0].1, Value::from("20s"));
+    assert_eq!(active[1].1.clone().into_int().unwrap(), 3);
+}
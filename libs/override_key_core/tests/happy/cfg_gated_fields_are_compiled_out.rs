@@ -0,0 +1,38 @@
+use override_key_core::ApplyOverrides;
+use override_key_derive::ApplyOverrides;
+use config::Config;
+
+// `test` is always set for this crate, standing in for feature flags
+#[derive(ApplyOverrides)]
+#[apply_overrides(infer_keys, prefix = "kafka")]
+struct GatedArgs {
+    brokers: Option<String>,
+
+    #[cfg(not(test))]
+    #[override_key(sensitive)]
+    password: Option<String>,
+
+    #[cfg(test)]
+    #[override_key(sensitive)]
+    token: Option<String>,
+}
+
+#[test]
+fn cfg_gated_fields_are_compiled_out() {
+    let args = GatedArgs {
+        brokers: Some("localhost:9092".into()),
+        token: Some("secret".into()),
+    };
+
+    let cfg = args
+        .apply_overrides(Config::builder())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(cfg.get_string("kafka.brokers").unwrap(), "localhost:9092");
+    assert_eq!(cfg.get_string("kafka.token").unwrap(), "secret");
+    assert_eq!(GatedArgs::override_keys(), &[("brokers", "kafka.brokers"), ("token", "kafka.token")]);
+    assert_eq!(GatedArgs::sensitive_keys(), &["kafka.token"]);
+    assert_eq!(args.active_overrides().len(), 2);
+}
@@ -0,0 +1,36 @@
+use override_key_core::ApplyOverrides;
+use override_key_derive::ApplyOverrides;
+use config::Config;
+
+#[derive(ApplyOverrides)]
+#[apply_overrides(prefix = "iproyal", deny_unannotated)]
+struct StrictArgs {
+    #[override_key(infer)]
+    timeout: Option<String>,
+
+    #[override_key = "iproyal.endpoint"]
+    url: Option<String>,
+
+    #[override_key(skip)]
+    #[allow(dead_code)]
+    config: Option<String>,
+}
+
+#[test]
+fn deny_unannotated_accepts_mapped_fields() {
+    let args = StrictArgs {
+        timeout: Some("20s".into()),
+        url: Some("https://api.iproyal.com".into()),
+        config: Some("config.toml".into()),
+    };
+
+    let cfg = args
+        .apply_overrides(Config::builder())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(cfg.get_string("iproyal.timeout").unwrap(), "20s");
+    assert_eq!(cfg.get_string("iproyal.endpoint").unwrap(), "https://api.iproyal.com");
+    assert_eq!(StrictArgs::override_keys(), &[("timeout", "iproyal.timeout"), ("url", "iproyal.endpoint")]);
+}
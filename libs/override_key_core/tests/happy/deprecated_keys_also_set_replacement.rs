@@ -0,0 +1,57 @@
+use override_key_core::ApplyOverrides;
+use override_key_derive::ApplyOverrides;
+use config::{Config, ConfigError};
+
+#[derive(ApplyOverrides)]
+#[apply_overrides(infer_keys, prefix = "iproyal")]
+struct MigratingArgs {
+    #[override_key(deprecated = "iproyal.endpoint")]
+    url: Option<String>,
+
+    #[override_key(key = "iproyal.api_token", deprecated = "iproyal.{field}", sensitive)]
+    token: Option<String>,
+
+    #[override_key(infer, deprecated = "iproyal.timeout", strict)]
+    wait: Option<String>,
+}
+
+#[test]
+fn deprecated_keys_also_set_replacement() {
+    let args = MigratingArgs {
+        url: Some("https://api.iproyal.com".into()),
+        token: Some("secret".into()),
+        wait: None,
+    };
+
+    let cfg = args
+        .apply_overrides(Config::builder())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    // both the deprecated and the replacement key are set
+    assert_eq!(cfg.get_string("iproyal.url").unwrap(), "https://api.iproyal.com");
+    assert_eq!(cfg.get_string("iproyal.endpoint").unwrap(), "https://api.iproyal.com");
+    assert_eq!(cfg.get_string("iproyal.token").unwrap(), "secret");
+
+    // replacements are not the deprecated fields' own keys, but stay secret
+    assert_eq!(
+        MigratingArgs::override_keys(),
+        &[("url", "iproyal.url"), ("token", "iproyal.api_token"), ("wait", "iproyal.wait")]
+    );
+    assert_eq!(MigratingArgs::sensitive_keys(), &["iproyal.api_token", "iproyal.token"]);
+    assert_eq!(args.active_overrides().len(), 4);
+}
+
+#[test]
+fn strict_deprecated_keys_are_rejected() {
+    let args = MigratingArgs { url: None, token: None, wait: Some("5s".into()) };
+
+    let Err(err) = args.apply_overrides(Config::builder()) else {
+        panic!("a strict deprecated key must be rejected");
+    };
+    assert!(
+        matches!(&err, ConfigError::Message(msg) if msg == "config key `iproyal.wait` is deprecated, use `iproyal.timeout` instead"),
+        "{err}"
+    );
+}
@@ -0,0 +1,63 @@
+use std::collections::BTreeMap;
+
+use override_key_core::ApplyOverrides;
+use override_key_derive::ApplyOverrides;
+use config::Config;
+
+#[derive(ApplyOverrides)]
+#[apply_overrides(infer_keys, prefix = "iproyal")]
+struct SetArgs {
+    timeout: Option<String>,
+
+    // declared last, so its pairs win over the dedicated flags
+    #[override_key(dynamic)]
+    set: Vec<(String, String)>,
+}
+
+#[derive(ApplyOverrides)]
+struct MapArgs {
+    #[override_key(dynamic)]
+    set: Option<BTreeMap<String, String>>,
+}
+
+#[test]
+fn dynamic_pairs_are_applied_verbatim() {
+    let args = SetArgs {
+        timeout: Some("20s".into()),
+        set: vec![
+            ("cache.max_age".into(), "1h".into()),
+            ("iproyal.timeout".into(), "30s".into()),
+        ],
+    };
+
+    let cfg = args
+        .apply_overrides(Config::builder())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(cfg.get_string("cache.max_age").unwrap(), "1h");
+    assert_eq!(cfg.get_string("iproyal.timeout").unwrap(), "30s");
+    assert_eq!(SetArgs::override_keys(), &[("timeout", "iproyal.timeout")]);
+
+    // runtime keys are reported as one table at the root
+    let active = args.active_overrides();
+    assert_eq!(active.len(), 2);
+    assert_eq!(active[1].0, "");
+    let table = active[1].1.clone().into_table().unwrap();
+    assert_eq!(table["cache"].clone().into_table().unwrap()["max_age"].clone().into_string().unwrap(), "1h");
+}
+
+#[test]
+fn dynamic_maps_are_supported() {
+    let args = MapArgs { set: Some(BTreeMap::from([("output.filter".to_string(), "US".to_string())])) };
+
+    let cfg = args
+        .apply_overrides(Config::builder())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(cfg.get_string("output.filter").unwrap(), "US");
+    assert!(MapArgs { set: None }.active_overrides().is_empty());
+}
@@ -0,0 +1,26 @@
+use override_key_core::ApplyOverrides;
+use override_key_derive::ApplyOverrides;
+
+// without a fixture, `emit_order_test` only checks the key table against the declaration order
+#[derive(ApplyOverrides)]
+#[apply_overrides(infer_keys, emit_order_test)]
+#[allow(dead_code)]
+struct Unordered {
+    #[override_key = "http.timeout"]
+    timeout: Option<String>,
+
+    #[cfg(any())]
+    #[override_key = "http.timeout"]
+    gated: Option<String>,
+
+    #[override_key = "http.timeout"]
+    legacy_timeout: Option<String>,
+}
+
+#[test]
+fn keys_are_listed_in_declaration_order() {
+    assert_eq!(
+        Unordered::override_keys(),
+        &[("timeout", "http.timeout"), ("legacy_timeout", "http.timeout")]
+    );
+}
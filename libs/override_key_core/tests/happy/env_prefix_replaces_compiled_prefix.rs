@@ -0,0 +1,45 @@
+use override_key_core::ApplyOverrides;
+use override_key_derive::ApplyOverrides;
+use config::Config;
+
+#[derive(ApplyOverrides)]
+#[apply_overrides(infer_keys, prefix = "iproyal")]
+struct TenantArgs {
+    timeout: Option<String>,
+
+    #[override_key = "http.proxy"]
+    proxy: Option<String>,
+}
+
+fn args() -> TenantArgs {
+    TenantArgs { timeout: Some("20s".into()), proxy: Some("http://proxy:3128".into()) }
+}
+
+// Each test uses its own variable, as tests run in parallel
+#[test]
+fn env_prefix_replaces_compiled_prefix() {
+    unsafe { std::env::set_var("OVERRIDE_KEY_TEST_TENANT_PREFIX", "tenant_a") };
+
+    let cfg = args()
+        .apply_overrides_env_prefixed(Config::builder(), "OVERRIDE_KEY_TEST_TENANT_PREFIX")
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(TenantArgs::key_prefix(), Some("iproyal"));
+    assert_eq!(cfg.get_string("tenant_a.timeout").unwrap(), "20s");
+    assert!(cfg.get_string("iproyal.timeout").is_err());
+    assert_eq!(cfg.get_string("http.proxy").unwrap(), "http://proxy:3128");
+}
+
+#[test]
+fn unset_env_prefix_falls_back_to_compiled_prefix() {
+    let cfg = args()
+        .apply_overrides_env_prefixed(Config::builder(), "OVERRIDE_KEY_TEST_UNSET_PREFIX")
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(cfg.get_string("iproyal.timeout").unwrap(), "20s");
+    assert_eq!(cfg.get_string("http.proxy").unwrap(), "http://proxy:3128");
+}
@@ -0,0 +1,31 @@
+use override_key_core::ApplyOverrides;
+use override_key_derive::ApplyOverrides;
+use config::Config;
+
+#[derive(ApplyOverrides)]
+#[apply_overrides(infer_keys, prefix = "providers", suffix = "endpoint")]
+struct ProviderArgs {
+    iproyal: Option<String>,
+
+    #[override_key(infer, suffix = "timeout")]
+    infatica: Option<String>,
+}
+
+#[test]
+fn field_level_suffix_overrides_struct_suffix() {
+    let args = ProviderArgs {
+        iproyal: Some("https://api.iproyal.com".into()),
+        infatica: Some("30s".into()),
+    };
+
+    let cfg = args
+        .apply_overrides(Config::builder())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    // iproyal → "providers.iproyal.endpoint"
+    assert_eq!(cfg.get_string("providers.iproyal.endpoint").unwrap(), "https://api.iproyal.com");
+    // infatica keeps the struct prefix but uses its own suffix
+    assert_eq!(cfg.get_string("providers.infatica.timeout").unwrap(), "30s");
+}
@@ -0,0 +1,36 @@
+use override_key_core::ApplyOverrides;
+use override_key_derive::ApplyOverrides;
+use config::Config;
+
+#[derive(ApplyOverrides)]
+struct ProviderToggles {
+    #[override_key = "providers.{field}.enabled"]
+    iproyal: Option<bool>,
+
+    #[override_key = "providers.{field}.enabled"]
+    netnut_proxy: Option<bool>,
+
+    #[override_key(key = "{field}.token", sensitive)]
+    infatica: Option<String>,
+}
+
+#[test]
+fn field_placeholder_expands_in_explicit_keys() {
+    let args = ProviderToggles {
+        iproyal: Some(true),
+        netnut_proxy: Some(false),
+        infatica: Some("secret".into()),
+    };
+
+    let cfg = args
+        .apply_overrides(Config::builder())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert!(cfg.get_bool("providers.iproyal.enabled").unwrap());
+    // underscores in the field name become dots
+    assert!(!cfg.get_bool("providers.netnut.proxy.enabled").unwrap());
+    assert_eq!(cfg.get_string("infatica.token").unwrap(), "secret");
+    assert_eq!(ProviderToggles::sensitive_keys(), &["infatica.token"]);
+}
@@ -0,0 +1,45 @@
+use clap::Parser;
+use override_key_core::ApplyOverrides;
+use override_key_derive::ApplyOverrides;
+
+#[derive(Parser, ApplyOverrides)]
+#[apply_overrides(infer_keys)]
+struct FlagArgs {
+    #[arg(long, global = true)]
+    #[override_key = "iproyal.endpoint"]
+    iproyal_endpoint: Option<String>,
+
+    #[arg(long = "proxy-url", value_parser = clap::value_parser!(String))]
+    #[override_key = "http.proxy.url"]
+    proxy: Option<String>,
+
+    #[arg(long)]
+    #[override_key(split = ":", keys("db.host", "db.port"))]
+    db: Option<String>,
+
+    // short only: a key, but no long flag
+    #[arg(short)]
+    #[override_key = "verbose"]
+    verbose: bool,
+
+    #[arg(long)]
+    #[override_key(skip)]
+    config: Option<String>,
+}
+
+#[test]
+fn flag_key_map_pairs_clap_flags() {
+    assert_eq!(
+        FlagArgs::flag_key_map(),
+        &[
+            ("iproyal-endpoint", "iproyal.endpoint"),
+            ("proxy-url", "http.proxy.url"),
+            ("db", "db.host"),
+            ("db", "db.port"),
+        ]
+    );
+
+    let args = FlagArgs::parse_from(["app", "--proxy-url", "http://proxy:3128", "-v"]);
+    assert_eq!(args.proxy.as_deref(), Some("http://proxy:3128"));
+    assert!(args.config.is_none());
+}
@@ -0,0 +1,43 @@
+use override_key_core::ApplyOverrides;
+use override_key_derive::ApplyOverrides;
+use config::{Config, File, FileFormat};
+use serde_json::json;
+
+#[derive(ApplyOverrides)]
+struct JsonArgs {
+    #[override_key(json)]
+    extra_config: Option<serde_json::Value>,
+
+    #[override_key(key = "iproyal.headers", json)]
+    iproyal_headers: Option<serde_json::Value>,
+}
+
+#[test]
+fn json_fields_merge_value_trees() {
+    let args = JsonArgs {
+        extra_config: Some(json!({"app": {"x": 1, "tags": ["a", "b"]}})),
+        iproyal_headers: Some(json!({"x-trace": "on"})),
+    };
+
+    let base = Config::builder().add_source(File::from_str("[app]\ny = 2\nx = 0", FileFormat::Toml));
+    let cfg = args.apply_overrides(base).unwrap().build().unwrap();
+
+    assert_eq!(cfg.get_int("app.x").unwrap(), 1);
+    // siblings from other sources survive the merge
+    assert_eq!(cfg.get_int("app.y").unwrap(), 2);
+    assert_eq!(cfg.get::<Vec<String>>("app.tags").unwrap(), ["a", "b"]);
+    assert_eq!(cfg.get_string("iproyal.headers.x-trace").unwrap(), "on");
+
+    // the root field has no key of its own
+    assert_eq!(JsonArgs::override_keys(), &[("iproyal_headers", "iproyal.headers")]);
+    let active = args.active_overrides();
+    assert_eq!(active[0].0, "");
+    assert_eq!(active[1].0, "iproyal.headers");
+}
+
+#[test]
+fn root_json_must_be_an_object() {
+    let args = JsonArgs { extra_config: Some(json!([1, 2])), iproyal_headers: None };
+
+    assert!(args.apply_overrides(Config::builder()).is_err());
+}
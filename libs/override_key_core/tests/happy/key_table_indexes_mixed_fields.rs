@@ -0,0 +1,52 @@
+use override_key_core::ApplyOverrides;
+use override_key_derive::ApplyOverrides;
+use config::Config;
+
+// Split, deprecated and inferred fields share one key table; each must read its own entries
+#[derive(ApplyOverrides)]
+#[apply_overrides(infer_keys, prefix = "db")]
+struct MixedArgs {
+    #[override_key(split = ":", keys("db.host", "db.port"))]
+    address: Option<String>,
+
+    #[override_key(infer, deprecated = "db.pool_size")]
+    pool: Option<u32>,
+
+    #[override_key = "db.user"]
+    user: Option<String>,
+
+    name: Option<String>,
+}
+
+#[test]
+fn key_table_indexes_mixed_fields() {
+    let args = MixedArgs {
+        address: Some("localhost:5432".into()),
+        pool: Some(4),
+        user: Some("admin".into()),
+        name: Some("locations".into()),
+    };
+
+    let cfg = args
+        .apply_overrides(Config::builder())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(cfg.get_string("db.host").unwrap(), "localhost");
+    assert_eq!(cfg.get_string("db.port").unwrap(), "5432");
+    assert_eq!(cfg.get_int("db.pool_size").unwrap(), 4);
+    assert_eq!(cfg.get_string("db.user").unwrap(), "admin");
+    assert_eq!(cfg.get_string("db.name").unwrap(), "locations");
+    assert_eq!(
+        MixedArgs::override_keys(),
+        &[
+            ("address", "db.host"),
+            ("address", "db.port"),
+            ("pool", "db.pool"),
+            ("user", "db.user"),
+            ("name", "db.name"),
+        ]
+    );
+    assert_eq!(args.active_overrides().len(), 6);
+}
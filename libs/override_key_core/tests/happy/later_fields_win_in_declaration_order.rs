@@ -0,0 +1,48 @@
+use override_key_core::ApplyOverrides;
+use override_key_derive::ApplyOverrides;
+use config::Config;
+
+// `emit_order_test` adds its own `overrides_follow_field_declaration_order` test and,
+// with a fixture, `later_fields_win_over_earlier_ones`, run on `conflicting()`
+#[derive(ApplyOverrides)]
+#[apply_overrides(infer_keys, prefix = "iproyal", emit_order_test = "conflicting")]
+struct OrderedArgs {
+    timeout: Option<String>,
+
+    #[override_key = "iproyal.timeout"]
+    legacy_timeout: Option<String>,
+
+    #[override_key(skip)]
+    #[allow(dead_code)]
+    config: Option<String>,
+
+    endpoint: Option<String>,
+}
+
+fn conflicting() -> OrderedArgs {
+    OrderedArgs {
+        timeout: Some("20s".into()),
+        legacy_timeout: Some("30s".into()),
+        config: None,
+        endpoint: Some("https://api.iproyal.com".into()),
+    }
+}
+
+#[test]
+fn later_fields_win_in_declaration_order() {
+    let cfg = conflicting()
+        .apply_overrides(Config::builder())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(cfg.get_string("iproyal.timeout").unwrap(), "30s");
+    assert_eq!(
+        OrderedArgs::override_keys(),
+        &[
+            ("timeout", "iproyal.timeout"),
+            ("legacy_timeout", "iproyal.timeout"),
+            ("endpoint", "iproyal.endpoint"),
+        ]
+    );
+}
@@ -0,0 +1,36 @@
+use override_key_derive::ApplyOverrides;
+
+#[derive(ApplyOverrides)]
+#[apply_overrides(infer_keys, prefix = "iproyal", map_output)]
+struct MapArgs {
+    timeout: Option<String>,
+
+    #[override_key(split = ":", keys("iproyal.proxy.host", "iproyal.proxy.port"))]
+    proxy: Option<String>,
+
+    #[override_key = "verbose"]
+    verbose: bool,
+
+    endpoint: Option<String>,
+}
+
+#[test]
+fn map_output_nests_dotted_keys() {
+    let args = MapArgs {
+        timeout: Some("20s".into()),
+        proxy: Some("localhost:8080".into()),
+        verbose: true,
+        endpoint: None,
+    };
+
+    let map = args.as_override_map();
+
+    assert_eq!(map.len(), 2);
+    assert!(map["verbose"].clone().into_bool().unwrap());
+    let iproyal = map["iproyal"].clone().into_table().unwrap();
+    assert_eq!(iproyal["timeout"].clone().into_string().unwrap(), "20s");
+    assert!(!iproyal.contains_key("endpoint"));
+    let proxy = iproyal["proxy"].clone().into_table().unwrap();
+    assert_eq!(proxy["host"].clone().into_string().unwrap(), "localhost");
+    assert_eq!(proxy["port"].clone().into_string().unwrap(), "8080");
+}
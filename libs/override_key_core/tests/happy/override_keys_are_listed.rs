@@ -0,0 +1,30 @@
+use override_key_core::ApplyOverrides;
+use override_key_derive::ApplyOverrides;
+
+#[derive(ApplyOverrides)]
+#[apply_overrides(infer_keys, prefix = "app")]
+#[allow(dead_code)]
+struct Args {
+    #[override_key(skip)]
+    config: Vec<String>,
+
+    #[override_key = "auth.client_id"]
+    client_id: Option<String>,
+
+    #[override_key(infer, prefix = "db", sensitive)]
+    password: Option<String>,
+
+    region_id: Option<u32>,
+}
+
+#[test]
+fn override_keys_are_listed_in_declaration_order() {
+    assert_eq!(
+        Args::override_keys(),
+        &[
+            ("client_id", "auth.client_id"),
+            ("password", "db.password"),
+            ("region_id", "app.region.id"),
+        ],
+    );
+}
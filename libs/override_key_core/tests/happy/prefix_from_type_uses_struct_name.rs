@@ -0,0 +1,32 @@
+use override_key_core::ApplyOverrides;
+use override_key_derive::ApplyOverrides;
+
+#[derive(ApplyOverrides)]
+#[apply_overrides(infer_keys, prefix_from_type)]
+struct InfaticaArgs {
+    endpoint: Option<String>,
+    timeout: Option<String>,
+}
+
+#[derive(ApplyOverrides)]
+#[apply_overrides(infer_keys, prefix_from_type = "Options")]
+struct IPRoyalOptions {
+    endpoint: Option<String>,
+}
+
+// Nothing to strip: the whole name is used
+#[derive(ApplyOverrides)]
+#[apply_overrides(infer_keys, prefix_from_type)]
+struct Cache {
+    dir: Option<String>,
+}
+
+#[test]
+fn prefix_from_type_uses_struct_name() {
+    assert_eq!(
+        InfaticaArgs::override_keys(),
+        &[("endpoint", "infatica.endpoint"), ("timeout", "infatica.timeout")]
+    );
+    assert_eq!(IPRoyalOptions::override_keys(), &[("endpoint", "iproyal.endpoint")]);
+    assert_eq!(Cache::override_keys(), &[("dir", "cache.dir")]);
+}
@@ -0,0 +1,53 @@
+use override_key_core::ApplyOverrides;
+use override_key_derive::ApplyOverrides;
+use config::Config;
+
+#[derive(ApplyOverrides)]
+#[apply_overrides(infer_keys, prefix = "app")]
+struct SensitiveArgs {
+    #[override_key(sensitive)]
+    api_token: Option<String>,
+
+    #[override_key(key = "auth.client_secret", sensitive)]
+    client_secret: Option<String>,
+
+    #[override_key(infer, prefix = "db", sensitive)]
+    password: Option<String>,
+
+    region_id: Option<u32>,
+}
+
+#[derive(ApplyOverrides)]
+struct NoSecrets {
+    #[override_key = "app.endpoint"]
+    endpoint: Option<String>,
+}
+
+#[test]
+fn sensitive_keys_are_listed() {
+    assert_eq!(
+        SensitiveArgs::sensitive_keys(),
+        &["app.api.token", "auth.client_secret", "db.password"],
+    );
+    assert!(NoSecrets::sensitive_keys().is_empty());
+}
+
+#[test]
+fn sensitive_fields_are_still_applied() {
+    let args = SensitiveArgs {
+        api_token: Some("tok".into()),
+        client_secret: Some("secret".into()),
+        password: Some("pw".into()),
+        region_id: None,
+    };
+
+    let cfg = args
+        .apply_overrides(Config::builder())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(cfg.get_string("app.api.token").unwrap(), "tok");
+    assert_eq!(cfg.get_string("auth.client_secret").unwrap(), "secret");
+    assert_eq!(cfg.get_string("db.password").unwrap(), "pw");
+}
@@ -0,0 +1,46 @@
+use override_key_core::ApplyOverrides;
+use override_key_derive::ApplyOverrides;
+use config::Config;
+
+#[derive(ApplyOverrides)]
+#[apply_overrides(infer_keys, prefix = "iproyal.http")]
+struct KebabArgs {
+    // struct prefix keeps its dots, the field name uses dashes
+    #[override_key(infer, separator = "-")]
+    connect_timeout: Option<String>,
+
+    // struct-level separator (none) still applies
+    retry_count: Option<u32>,
+}
+
+#[derive(ApplyOverrides)]
+#[apply_overrides(infer_keys, prefix = "infatica", separator = "_")]
+struct SnakeArgs {
+    request_timeout: Option<String>,
+
+    #[override_key(infer, separator = "-", suffix = "max")]
+    page_size: Option<u32>,
+}
+
+#[test]
+fn separator_applies_to_field_name_only() {
+    let kebab = KebabArgs {
+        connect_timeout: Some("5s".into()),
+        retry_count: Some(3),
+    };
+    let snake = SnakeArgs {
+        request_timeout: Some("30s".into()),
+        page_size: Some(100),
+    };
+
+    let cfg = snake
+        .apply_overrides(kebab.apply_overrides(Config::builder()).unwrap())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(cfg.get_string("iproyal.http.connect-timeout").unwrap(), "5s");
+    assert_eq!(cfg.get_int("iproyal.http.retry.count").unwrap(), 3);
+    assert_eq!(cfg.get_string("infatica.request_timeout").unwrap(), "30s");
+    assert_eq!(cfg.get_int("infatica.page-size.max").unwrap(), 100);
+}
@@ -0,0 +1,41 @@
+use override_key_core::ApplyOverrides;
+use override_key_derive::ApplyOverrides;
+use config::Config;
+
+// Not convertible into `config::Value`: compiling at all proves no code is generated for it.
+#[allow(dead_code)]
+enum Command {
+    Compare,
+}
+
+// Skipped fields are never read by the generated code.
+#[allow(dead_code)]
+#[derive(ApplyOverrides)]
+#[apply_overrides(infer_keys, prefix = "app")]
+struct SkipArgs {
+    #[override_key(skip)]
+    command: Option<Command>,
+
+    #[override_key(skip)]
+    config: Option<String>,
+
+    region_id: Option<u32>,
+}
+
+#[test]
+fn skip_excludes_field_from_inference() {
+    let args = SkipArgs {
+        command: Some(Command::Compare),
+        config: Some("config.toml".into()),
+        region_id: Some(7),
+    };
+
+    let cfg = args
+        .apply_overrides(Config::builder())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert!(cfg.get::<String>("app.config").is_err(), "skipped field was applied");
+    assert_eq!(cfg.get_int("app.region.id").unwrap(), 7);
+}
@@ -0,0 +1,52 @@
+use override_key_core::ApplyOverrides;
+use override_key_derive::ApplyOverrides;
+use config::{Config, ConfigError};
+
+#[derive(ApplyOverrides)]
+struct SplitArgs {
+    #[override_key(split = ":", keys("db.host", "db.port"))]
+    db: Option<String>,
+
+    #[override_key(split = "@", keys("{field}.user", "{field}.host"), sensitive)]
+    proxy: String,
+}
+
+#[test]
+fn split_value_is_applied_to_several_keys() {
+    let args = SplitArgs {
+        db: Some("db.example.com:5432".into()),
+        proxy: "alice@proxy.example.com".into(),
+    };
+
+    let cfg = args
+        .apply_overrides(Config::builder())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(cfg.get_string("db.host").unwrap(), "db.example.com");
+    assert_eq!(cfg.get_int("db.port").unwrap(), 5432);
+    assert_eq!(cfg.get_string("proxy.user").unwrap(), "alice");
+    assert_eq!(cfg.get_string("proxy.host").unwrap(), "proxy.example.com");
+
+    assert_eq!(
+        SplitArgs::override_keys(),
+        &[("db", "db.host"), ("db", "db.port"), ("proxy", "proxy.user"), ("proxy", "proxy.host")]
+    );
+    assert_eq!(SplitArgs::sensitive_keys(), &["proxy.user", "proxy.host"]);
+    assert_eq!(args.active_overrides().len(), 4);
+}
+
+#[test]
+fn values_that_do_not_split_cleanly_are_rejected() {
+    for db in ["db.example.com", "db.example.com:5432:1", "db.example.com:"] {
+        let args = SplitArgs { db: Some(db.into()), proxy: "alice@proxy".into() };
+
+        let Err(err) = args.apply_overrides(Config::builder()) else {
+            panic!("{db:?} must not split cleanly");
+        };
+        assert!(matches!(&err, ConfigError::Message(msg) if msg.contains("`db`")), "{err}");
+        // apply_overrides reports it, active_overrides leaves it out
+        assert_eq!(args.active_overrides().len(), 2);
+    }
+}
@@ -0,0 +1,43 @@
+use override_key_core::ApplyOverrides;
+use override_key_derive::ApplyOverrides;
+use config::Config;
+
+#[derive(ApplyOverrides)]
+struct SuffixArgs {
+    #[override_key(infer, suffix = "endpoint")]
+    iproyal: Option<String>,
+
+    #[override_key(infer, prefix = "providers", suffix = "enabled")]
+    infatica: Option<bool>,
+
+    // empty suffix adds no trailing dot
+    #[override_key(infer, suffix = "")]
+    region_id: Option<u32>,
+}
+
+#[test]
+fn suffix_is_appended_to_inferred_keys() {
+    let args = SuffixArgs {
+        iproyal: Some("https://api.iproyal.com".into()),
+        infatica: Some(true),
+        region_id: Some(7),
+    };
+
+    let cfg = args
+        .apply_overrides(Config::builder())
+        .unwrap()
+        .build()
+        .unwrap();
+
+    assert_eq!(cfg.get_string("iproyal.endpoint").unwrap(), "https://api.iproyal.com");
+    assert!(cfg.get_bool("providers.infatica.enabled").unwrap());
+    assert_eq!(cfg.get_int("region.id").unwrap(), 7);
+    assert_eq!(
+        SuffixArgs::override_keys(),
+        &[
+            ("iproyal", "iproyal.endpoint"),
+            ("infatica", "providers.infatica.enabled"),
+            ("region_id", "region.id"),
+        ]
+    );
+}
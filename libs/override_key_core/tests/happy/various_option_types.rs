@@ -20,7 +20,7 @@ fn test_various_option_types() {
 
     let cfg = args.apply_overrides(Config::builder()).unwrap().build().unwrap();
 
-    assert_eq!(cfg.get_bool("opt.bool.flag").unwrap(), true);
+    assert!(cfg.get_bool("opt.bool.flag").unwrap());
     assert_eq!(cfg.get_int("opt.retries").unwrap(), 5);
     assert_eq!(cfg.get_string("opt.api.url").unwrap(), "https://example.org");
 }
\ No newline at end of file
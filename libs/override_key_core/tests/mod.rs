@@ -1,18 +1,40 @@
 mod happy {
+    mod active_overrides_reflect_instance;
+    mod cfg_gated_fields_are_compiled_out;
     mod complex_option_type;
     mod default_none_behavior;
+    mod deny_unannotated_accepts_mapped_fields;
+    mod deprecated_keys_also_set_replacement;
     mod derive_macro_basic;
     mod double_option;
+    mod dynamic_pairs_are_applied_verbatim;
+    mod emit_order_test_checks_declaration_order;
     mod empty_option_fields_are_skipped;
     mod empty_prefix_does_not_create_leading_dot;
     mod empty_prefix_no_dot;
+    mod env_prefix_replaces_compiled_prefix;
     mod explicit_keys_are_applied_verbatim;
     mod field_level_prefix_overrides_struct_prefix;
+    mod field_level_suffix_overrides_struct_suffix;
+    mod field_placeholder_expands_in_explicit_keys;
+    mod flag_key_map_pairs_clap_flags;
+    #[cfg(feature = "json")]
+    mod json_fields_merge_value_trees;
+    mod key_table_indexes_mixed_fields;
+    mod later_fields_win_in_declaration_order;
+    mod map_output_nests_dotted_keys;
     mod mixed_option_and_non_option;
     mod mixed_option_non_option_fields_override_correctly;
     mod non_option_field_always_overrides;
+    mod override_keys_are_listed;
+    mod prefix_from_type_uses_struct_name;
+    mod sensitive_keys_are_listed;
+    mod separator_applies_to_field_name_only;
+    mod skip_excludes_field_from_inference;
     mod skips_none_fields;
+    mod split_value_is_applied_to_several_keys;
     mod struct_level_infer_with_prefix_applies_to_all_fields;
+    mod suffix_is_appended_to_inferred_keys;
     mod underscores_are_replaced_with_dots;
     mod various_option_types;
 }
@@ -22,16 +22,49 @@
 //! The emitted code has this structure (simplified):
 //!
 //! ```ignore
+//! const __MYSTRUCT_OVERRIDE_KEYS: &[&str] = &["my.prefix.some_field", "my.prefix.secret"];
+//!
+//! const __MYSTRUCT_OVERRIDE_VALUES: &[(&str, fn(&MyStruct) -> Option<config::Value>)] = &[
+//!     (__MYSTRUCT_OVERRIDE_KEYS[0], |s| s.some_field.as_ref().map(|v| v.clone().into())),
+//!     (__MYSTRUCT_OVERRIDE_KEYS[1], |s| s.secret.as_ref().map(|v| v.clone().into())),
+//! ];
+//!
 //! impl ::override_key_core::ApplyOverrides for MyStruct {
 //!     fn apply_overrides(
 //!         &self,
 //!         mut builder: config::ConfigBuilder<config::builder::DefaultState>,
 //!     ) -> Result<config::ConfigBuilder<config::builder::DefaultState>, config::ConfigError> {
-//!         if let Some(v) = &self.some_field {
-//!             builder = builder.set_override("my.prefix.some_field", v.clone())?;
+//!         for (key, value) in __MYSTRUCT_OVERRIDE_VALUES {
+//!             if let Some(value) = value(self) {
+//!                 builder = builder.set_override(*key, value)?;
+//!             }
 //!         }
 //!         Ok(builder)
 //!     }
+//!
+//!     fn override_keys() -> &'static [(&'static str, &'static str)] {
+//!         const KEYS: &[(&str, &str)] =
+//!             &[("some_field", __MYSTRUCT_OVERRIDE_KEYS[0]), ("secret", __MYSTRUCT_OVERRIDE_KEYS[1])];
+//!         KEYS
+//!     }
+//!
+//!     fn sensitive_keys() -> &'static [&'static str] {
+//!         &["my.prefix.secret"]
+//!     }
+//!
+//!     fn key_prefix() -> Option<&'static str> {
+//!         Some("my.prefix")
+//!     }
+//!
+//!     fn active_overrides(&self) -> Vec<(&'static str, config::Value)> {
+//!         let mut overrides = Vec::new();
+//!         for (key, value) in __MYSTRUCT_OVERRIDE_VALUES {
+//!             if let Some(value) = value(self) {
+//!                 overrides.push((*key, value));
+//!             }
+//!         }
+//!         overrides
+//!     }
 //! }
 //! ```
 //!
@@ -50,7 +83,14 @@
 //!
 //! - This module only assembles and emits the final `TokenStream`.
 //! - It guarantees deterministic ordering of generated code (field order preserved).
+//! - Each key literal is emitted once, in the [`KeyTable`] `const`; the generated
+//!   methods index it, which keeps the impl compact for structs with many flags.
+//! - Consecutive fields setting one key with their plain value share a `const`
+//!   table of `(key, accessor)` pairs, applied by one loop in each method; fields
+//!   with code of their own end the run, so declaration order is kept.
 //! - It never panics; all errors are represented as `syn::Error`.
+//! - Optional items (`as_override_map()`, the order test) are emitted only when
+//!   requested by the struct-level attribute.
 //!
 //! ## Implementation Flow
 //!
@@ -63,12 +103,13 @@
 //! The final result is returned as a `proc_macro2::TokenStream`
 //! ready to be consumed by the derive entry point in `lib.rs`.
 
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::{DeriveInput, Error};
 
 use crate::{
-    field_parser::process_field,
+    field_parser::{cfg_attrs, process_field},
     struct_config::parse_struct_level_config,
+    types::KeyTable,
 };
 
 /// Main entry point for generating the `impl ApplyOverrides` block.
@@ -99,27 +140,77 @@ pub fn generate_impl(input: &DeriveInput) -> Result<proc_macro2::TokenStream, Er
     // Additionally, `parse_struct_level_config()` may return one or more `syn::Error`s if
     // the attribute contains invalid syntax or unsupported options. These errors are collected
     // into `struct_errors` and merged into the shared accumulator below.
-    let (struct_infer, struct_prefix, struct_errors) = parse_struct_level_config(input);
+    let (struct_config, struct_errors) = parse_struct_level_config(input);
     let mut errors = struct_errors;
 
     // Extract all named fields from the struct (enforces named field constraint)
-    let fields = match super::field_parser::parse_fields(input) {
-        Ok(f) => f,
-        Err(e) => return Err(e), // bubble up early if the struct itself is malformed
-    };
+    // (bubbles up early if the struct itself is malformed)
+    let fields = super::field_parser::parse_fields(input)?;
 
-    // Collect compile-time parsing errors and generated per-field snippets
+    // Collect compile-time parsing errors, generated per-field snippets and key tables
+    let mut table = KeyTable {
+        ident: format_ident!("__{}_OVERRIDE_KEYS", name.to_string().to_uppercase()),
+        keys: Vec::new(),
+    };
     let mut generated = Vec::new();
+    // Accessor tables of runs of consecutive plain fields, and the run being collected
+    let mut values = ValueTables { name, tables: Vec::new() };
+    let mut run = Vec::new();
+    // `(field, key)` and sensitive key entries, each carrying its field's `#[cfg(...)]`
+    let mut key_entries = Vec::new();
+    let mut sensitive_keys = Vec::new();
+    let mut flag_entries = Vec::new();
+    let mut active = Vec::new();
+    // The one `dynamic` field, if any
+    let mut dynamic_field: Option<&syn::Ident> = None;
+    // All field names in declaration order, for the generated order test
+    let mut declared = Vec::new();
 
     // Process each field in order — this preserves the declaration order,
     //     which improves debug readability in generated code.
     for field in fields {
-        if let Some(code) =
-            process_field(field, struct_infer, struct_prefix.as_deref(), &mut errors)
+        let ident = field.ident.as_ref();
+        if let Some(ident) = ident {
+            let (ident, cfg) = (ident.to_string(), cfg_attrs(field));
+            declared.push(quote! { #cfg #ident });
+        }
+        if let Some(field) =
+            process_field(field, &struct_config, &mut table, &mut errors)
         {
-            generated.push(code);
+            // Several `dynamic` fields would make the precedence of their pairs arbitrary
+            if field.dynamic {
+                match (dynamic_field, ident) {
+                    (Some(first), Some(ident)) => errors.push(Error::new_spanned(
+                        ident,
+                        format!("only one field can be `dynamic`; `{first}` already is"),
+                    )),
+                    (None, ident) => dynamic_field = ident,
+                    _ => {}
+                }
+            }
+            let (name, cfg) = (&field.field, &field.cfg);
+            // one `(field, key)` pair per key, so split fields appear once per part
+            for index in field.keys {
+                let key = table.entry(index);
+                if let Some(flag) = &field.flag {
+                    flag_entries.push(quote! { #cfg (#flag, #key) });
+                }
+                key_entries.push(quote! { #cfg (#name, #key) });
+            }
+            for key in &field.sensitive_keys {
+                sensitive_keys.push(quote! { #cfg #key });
+            }
+            match field.accessor {
+                Some(entry) => run.push(entry),
+                None => {
+                    values.flush(&mut run, &mut generated, &mut active);
+                    generated.push(field.snippet);
+                    active.push(field.active);
+                }
+            }
         }
     }
+    values.flush(&mut run, &mut generated, &mut active);
 
     // If any struct-level or field-level errors occurred, emit all as compile errors.
     if !errors.is_empty() {
@@ -127,12 +218,126 @@ pub fn generate_impl(input: &DeriveInput) -> Result<proc_macro2::TokenStream, Er
         return Ok(quote! { #(#compile_errors)* });
     }
 
+    // `emit_order_test`: overrides are applied field by field, so a later field wins
+    // over an earlier one mapped to the same key. The generated test pins that
+    // contract by checking the key table against the declaration order; with a
+    // fixture, a second test checks it on the built configuration: with the
+    // fixture's conflicting fields applied, every leaf holds the value of the last
+    // field setting it.
+    let fixture_test = struct_config.order_test_fixture.as_ref().map(|fixture| {
+        quote! {
+            #[test]
+            fn later_fields_win_over_earlier_ones() {
+                let instance: #name = #fixture();
+
+                // The last value set for each leaf, and how many leaves were set twice
+                let mut expected: Vec<(String, config::Value)> = Vec::new();
+                let mut conflicts = 0;
+                for (key, value) in ::override_key_core::ApplyOverrides::active_overrides(&instance) {
+                    for (leaf, value) in ::override_key_core::map::flatten(key, value) {
+                        if let Some(i) = expected.iter().position(|(known, _)| *known == leaf) {
+                            expected.remove(i);
+                            conflicts += 1;
+                        }
+                        expected.push((leaf, value));
+                    }
+                }
+                assert!(
+                    conflicts > 0,
+                    "`{}` sets no key of `{}` through several fields",
+                    stringify!(#fixture),
+                    stringify!(#name),
+                );
+
+                let built = ::override_key_core::ApplyOverrides::apply_overrides(&instance, config::Config::builder())
+                    .and_then(|builder| builder.build())
+                    .expect("overrides apply");
+                for (leaf, value) in expected {
+                    let actual = built.get::<config::Value>(&leaf).expect("overridden key is set");
+                    assert_eq!(
+                        actual.to_string(),
+                        value.to_string(),
+                        "`{}` of `{}` does not hold the value of the last field setting it",
+                        leaf,
+                        stringify!(#name),
+                    );
+                }
+            }
+        }
+    });
+    let order_test = struct_config.emit_order_test.then(|| {
+        let module = format_ident!("__{}_override_order", name.to_string().to_lowercase());
+        quote! {
+            #[cfg(test)]
+            mod #module {
+                use super::*;
+
+                #[test]
+                fn overrides_follow_field_declaration_order() {
+                    const DECLARED: &[&str] = &[#(#declared),*];
+                    let keys = <#name as ::override_key_core::ApplyOverrides>::override_keys();
+                    let positions: Vec<usize> = keys
+                        .iter()
+                        .map(|(field, _)| DECLARED.iter().position(|d| d == field).expect("field is declared"))
+                        .collect();
+                    assert!(
+                        positions.windows(2).all(|w| w[0] <= w[1]),
+                        "override keys of `{}` are not in field declaration order: {:?}",
+                        stringify!(#name),
+                        keys,
+                    );
+                }
+
+                #fixture_test
+            }
+        }
+    });
+
+    // `map_output`: overrides as a plain nested map, for configurations not built
+    // through a `ConfigBuilder`
+    let map_output = struct_config.map_output.then(|| {
+        quote! {
+            impl #name {
+                /// Active overrides nested into tables by their dotted keys.
+                pub fn as_override_map(&self) -> config::Map<String, config::Value> {
+                    ::override_key_core::map::nested(
+                        ::override_key_core::ApplyOverrides::active_overrides(self),
+                    )
+                }
+            }
+        }
+    });
+
+    // `clap` feature: long flags next to their keys; structs without flags keep the
+    // trait's empty default
+    let flag_key_map = (!flag_entries.is_empty()).then(|| {
+        quote! {
+            fn flag_key_map() -> &'static [(&'static str, &'static str)] {
+                const FLAGS: &[(&str, &str)] = &[#(#flag_entries),*];
+                FLAGS
+            }
+        }
+    });
+
     // Assemble the final code block.
     //
     // Note: We intentionally use a fully-qualified trait path (`::override_key_core::ApplyOverrides`)
     // to remove the requirement for downstream crates to import the trait explicitly.
     // This guarantees stable linkage across crates and simplifies usage.
+    let (table_ident, table_keys, value_tables) = (&table.ident, &table.keys, &values.tables);
+    // an empty prefix adds nothing to the keys, so there is none to replace at runtime
+    let key_prefix = match struct_config.prefix.as_deref() {
+        Some(prefix) if !prefix.is_empty() => quote! { Some(#prefix) },
+        _ => quote! { None },
+    };
     Ok(quote! {
+        // all keys, read by index below; entries of cfg-gated fields may go unused
+        #[doc(hidden)]
+        #[allow(dead_code)]
+        const #table_ident: &[&str] = &[#(#table_keys),*];
+
+        #(#value_tables)*
+
         impl ::override_key_core::ApplyOverrides for #name {
             fn apply_overrides(
                 &self,
@@ -142,6 +347,81 @@ pub fn generate_impl(input: &DeriveInput) -> Result<proc_macro2::TokenStream, Er
                 #(#generated)*
                 Ok(builder)
             }
+
+            fn override_keys() -> &'static [(&'static str, &'static str)] {
+                const KEYS: &[(&str, &str)] = &[#(#key_entries),*];
+                KEYS
+            }
+
+            fn sensitive_keys() -> &'static [&'static str] {
+                &[#(#sensitive_keys),*]
+            }
+
+            #flag_key_map
+
+            fn key_prefix() -> Option<&'static str> {
+                #key_prefix
+            }
+
+            fn active_overrides(&self) -> Vec<(&'static str, config::Value)> {
+                #[allow(unused_mut)]
+                let mut overrides = Vec::new();
+                #(#active)*
+                overrides
+            }
         }
+
+        #map_output
+
+        #order_test
     })
-}
\ No newline at end of file
+}
+/// Accessor tables of runs of consecutive plain fields, each applied by one loop.
+///
+/// A field with code of its own (`split`, `json`, `dynamic` or deprecated) ends the
+/// run before it, so fields are still applied in declaration order.
+struct ValueTables<'a> {
+    name: &'a syn::Ident,
+    tables: Vec<proc_macro2::TokenStream>,
+}
+
+impl ValueTables<'_> {
+    /// Emits the entries of `run` as a table, appending the loops reading it to the
+    /// bodies of `apply_overrides()` and `active_overrides()`.
+    fn flush(
+        &mut self,
+        run: &mut Vec<proc_macro2::TokenStream>,
+        generated: &mut Vec<proc_macro2::TokenStream>,
+        active: &mut Vec<proc_macro2::TokenStream>,
+    ) {
+        if run.is_empty() {
+            return;
+        }
+        let name = self.name;
+        let upper = name.to_string().to_uppercase();
+        let ident = match self.tables.len() {
+            0 => format_ident!("__{}_OVERRIDE_VALUES", upper),
+            n => format_ident!("__{}_OVERRIDE_VALUES_{}", upper, n),
+        };
+        let entries = run.drain(..);
+        self.tables.push(quote! {
+            // `(key, value)` accessors of plain fields, in declaration order
+            #[doc(hidden)]
+            const #ident: &[(&str, fn(&#name) -> Option<config::Value>)] = &[#(#entries),*];
+        });
+        generated.push(quote! {
+            for (key, value) in #ident {
+                if let Some(value) = value(self) {
+                    builder = builder.set_override(*key, value)?;
+                }
+            }
+        });
+        active.push(quote! {
+            for (key, value) in #ident {
+                if let Some(value) = value(self) {
+                    overrides.push((*key, value));
+                }
+            }
+        });
+    }
+}
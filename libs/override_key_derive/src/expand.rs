@@ -0,0 +1,153 @@
+//! # expand.rs
+//!
+//! Snapshot tests of the code generated by `#[derive(ApplyOverrides)]`, plus a
+//! benchmark of the expansion itself.
+//!
+//! Each test feeds a representative struct to [`generate_impl`] and compares the
+//! pretty-printed output with its snapshot in `src/snapshots/`. A failing test
+//! means the emitted code changed: if intended, re-run with `INSTA_UPDATE=always`
+//! and review the snapshot diff like any other code change.
+
+use std::time::Instant;
+
+use quote::{format_ident, quote};
+use syn::DeriveInput;
+
+use crate::builder_gen::generate_impl;
+
+/// Expands `input` and formats the result like `cargo expand` would.
+fn expand(input: proc_macro2::TokenStream) -> String {
+    let input: DeriveInput = syn::parse2(input).expect("test input is a valid struct");
+    let output = generate_impl(&input).expect("struct has named fields");
+    let file = syn::parse2(output).expect("generated code parses as items");
+    prettyplease::unparse(&file)
+}
+
+#[test]
+fn explicit_and_inferred_keys() {
+    insta::assert_snapshot!(expand(quote! {
+        #[apply_overrides(infer_keys, prefix = "iproyal")]
+        struct CLIArgs {
+            #[override_key = "iproyal.endpoint"]
+            iproyal_endpoint: Option<String>,
+
+            #[override_key(infer, prefix = "netnut", separator = "-")]
+            connect_timeout: Option<String>,
+
+            #[override_key(skip)]
+            config: Option<String>,
+
+            verbose: bool,
+        }
+    }));
+}
+
+#[test]
+fn sensitive_and_deprecated_keys() {
+    insta::assert_snapshot!(expand(quote! {
+        struct CLIArgs {
+            #[override_key(key = "iproyal.token", sensitive)]
+            iproyal_token: Option<String>,
+
+            #[override_key(infer, deprecated = "iproyal.endpoint")]
+            iproyal_url: Option<String>,
+
+            #[override_key(key = "netnut.url", deprecated = "netnut.endpoint", strict)]
+            netnut_url: Option<String>,
+        }
+    }));
+}
+
+#[test]
+fn split_json_and_cfg_gated_fields() {
+    insta::assert_snapshot!(expand(quote! {
+        #[apply_overrides(infer_keys, prefix = "kafka")]
+        struct CLIArgs {
+            #[override_key(split = ":", keys("db.host", "db.port"))]
+            db: Option<String>,
+
+            #[override_key(json)]
+            extra_config: Option<serde_json::Value>,
+
+            #[override_key(json, key = "cache")]
+            cache: Option<serde_json::Value>,
+
+            #[cfg(feature = "kafka")]
+            #[override_key(sensitive)]
+            password: Option<String>,
+        }
+    }));
+}
+
+#[test]
+fn emit_order_test_module() {
+    insta::assert_snapshot!(expand(quote! {
+        #[apply_overrides(infer_keys, emit_order_test)]
+        struct CLIArgs {
+            timeout: Option<String>,
+            #[override_key = "timeout"]
+            legacy_timeout: Option<String>,
+        }
+    }));
+}
+
+#[test]
+fn emit_order_test_module_with_fixture() {
+    insta::assert_snapshot!(expand(quote! {
+        #[apply_overrides(infer_keys, emit_order_test = "conflicting")]
+        struct CLIArgs {
+            timeout: Option<String>,
+            #[override_key = "timeout"]
+            legacy_timeout: Option<String>,
+        }
+    }));
+}
+
+#[test]
+fn map_output_method() {
+    insta::assert_snapshot!(expand(quote! {
+        #[apply_overrides(map_output)]
+        struct CLIArgs {
+            #[override_key = "iproyal.timeout"]
+            timeout: Option<String>,
+        }
+    }));
+}
+
+/// Times the expansion of a struct with as many flags as the CLI is expected to
+/// grow to, and reports the size of the generated code.
+///
+/// Run with `cargo test -p override_key_derive --release --lib -- --ignored --nocapture`.
+#[test]
+#[ignore = "benchmark"]
+fn bench_large_struct_expansion() {
+    const FIELDS: usize = 64;
+    const ROUNDS: u32 = 200;
+
+    let fields = (0..FIELDS).map(|i| {
+        let ident = format_ident!("provider_{i}_timeout");
+        match i % 4 {
+            0 => quote! { #[override_key(sensitive)] #ident: Option<String>, },
+            1 => quote! { #[override_key(infer, separator = "-")] #ident: Option<String>, },
+            2 => quote! { #[override_key = "providers.{field}.value"] #ident: Option<u64>, },
+            _ => quote! { #ident: Option<bool>, },
+        }
+    });
+    let input: DeriveInput = syn::parse2(quote! {
+        #[apply_overrides(infer_keys, prefix = "app")]
+        struct LargeArgs { #(#fields)* }
+    })
+    .expect("benchmark input is a valid struct");
+
+    let start = Instant::now();
+    let mut output = proc_macro2::TokenStream::new();
+    for _ in 0..ROUNDS {
+        output = generate_impl(&input).expect("struct has named fields");
+    }
+    let elapsed = start.elapsed() / ROUNDS;
+
+    println!(
+        "{FIELDS} fields: {elapsed:?} per expansion, {} bytes of generated code",
+        output.to_string().len(),
+    );
+}
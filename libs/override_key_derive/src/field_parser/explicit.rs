@@ -11,6 +11,15 @@
 //!
 //! which directly maps a struct field to a fixed configuration key string.
 //!
+//! The key may contain a `{field}` placeholder, expanded at macro time to the
+//! field name with underscores converted to dots, so that families of similarly
+//! shaped keys share one template:
+//!
+//! ```ignore
+//! #[override_key = "providers.{field}.enabled"]
+//! pub iproyal: Option<bool>,   // → "providers.iproyal.enabled"
+//! ```
+//!
 //! ## Responsibilities
 //!
 //! - Validate that the left-hand identifier is literally `override_key`.
@@ -37,6 +46,7 @@
 //! | Wrong identifier | `#[something_else = "foo"]` | Emits error: “expected `override_key` identifier before `=`” |
 //! | Non-string literal | `#[override_key = 123]` | Emits error: “expected string literal, e.g. #[override_key = \"custom.path\"]” |
 //! | Non-literal RHS | `#[override_key = SOME_CONST]` | Same as above |
+//! | Malformed key | `#[override_key = "iproyal..endpoint"]` | Emits error from key validation in `process_field`, e.g. “empty segment” |
 //!
 //! ## Safety and Design Notes
 //!
@@ -8,10 +8,42 @@
 //! ```ignore
 //! #[override_key(infer)]
 //! #[override_key(infer, prefix = "netnut")]
+//! #[override_key(infer, suffix = "endpoint")]
+//! #[override_key(infer, separator = "-")]
+//! #[override_key(key = "iproyal.client_secret")]
+//! #[override_key(split = ":", keys("db.host", "db.port"))]
+//! #[override_key(skip)]
+//! #[override_key(sensitive)]
+//! #[override_key(dynamic)]
 //! ```
 //!
-//! These tell the macro to derive the configuration key automatically from
-//! the field name, optionally adding a prefix (e.g. `"netnut"`).
+//! The first four tell the macro to derive the configuration key automatically from
+//! the field name, optionally adding a prefix (e.g. `"netnut"`) or a suffix (e.g.
+//! `"endpoint"`, so that field `iproyal` maps to `iproyal.endpoint`). `separator`
+//! replaces `_` in the field name instead of `.`, so that `connect_timeout` under
+//! prefix `"iproyal"` maps to `iproyal.connect-timeout`. `key = "..."` is the
+//! list form of `#[override_key = "..."]`, so that it can be combined with flags;
+//! it expands the same `{field}` placeholder. `split` applies one string value to
+//! several keys: `"db.example.com:5432"` sets `db.host` and `db.port`; a value
+//! without exactly one part per key is a runtime `ConfigError`.
+//! `skip` excludes the field entirely, which is needed for non-config fields (such as
+//! clap subcommands) in structs using struct-level `infer_keys`.
+//!
+//! `sensitive` can be added to `infer` or `key` forms, or used alone to keep the
+//! struct-level defaults; it marks the field's key as holding a secret.
+//! `deprecated = "new.key"` can be used the same way: when the field is set, the
+//! generated code warns on stderr that its key is deprecated and also sets the
+//! replacement key. Adding `strict` turns the warning into a `ConfigError`.
+//!
+//! `json` marks a `serde_json::Value` field whose object tree is merged leaf by
+//! leaf under the field's key, or at the configuration root when `json` stands
+//! alone: `--extra-config '{"app":{"x":1}}'` sets `app.x`. It requires the `json`
+//! feature of `override_key_core`.
+//!
+//! `dynamic` marks the one field of a struct holding runtime `(key, value)` pairs,
+//! such as a `Vec<(String, String)>` filled by repeated `--set key=value` flags or
+//! a `BTreeMap<String, String>`. Each pair is applied verbatim, so users can reach
+//! keys without a dedicated flag. Like `skip`, it stands alone.
 //!
 //! ## Example
 //!
@@ -23,7 +55,7 @@
 //! ➜ becomes ➜
 //!
 //! ```ignore
-//! FieldOverrideMeta::Infer { prefix: Some("netnut") }
+//! FieldOverrideMeta::Infer { prefix: Some("netnut"), suffix: None, separator: None }
 //! ```
 //!
 //! ## Error Conditions
@@ -31,14 +63,19 @@
 //! | Condition | Example | Result |
 //! |------------|----------|--------|
 //! | Missing `infer` keyword | `#[override_key(prefix = "foo")]` | Emits error: “missing `infer` keyword” |
-//! | Unexpected token | `#[override_key(foo)]` | Emits error: “unexpected token … expected `infer` or `prefix = ...`” |
-//! | Invalid prefix literal | `#[override_key(infer, prefix = 123)]` | Emits error from `syn` parse |
+//! | Unexpected token | `#[override_key(foo)]` | Emits error: “unexpected token … expected `infer`, `prefix = ...`, `suffix = ...`, `separator = ...`, `key = ...`, `skip` or `sensitive`” |
+//! | Invalid prefix or suffix literal | `#[override_key(infer, prefix = 123)]` | Emits error from `syn` parse |
+//! | `skip` mixed with other options | `#[override_key(skip, infer)]` | Emits error: “`skip` cannot be combined …” |
+//! | `dynamic` mixed with other options | `#[override_key(dynamic, sensitive)]` | Emits error: “`dynamic` cannot be combined …” |
+//! | `key` mixed with inference | `#[override_key(key = "a.b", infer)]` | Emits error: “`key` cannot be combined …” |
+//! | `split` without `keys`, or fewer than two keys | `#[override_key(split = ":", keys("a"))]` | Emits error: “`split` needs at least two keys …” |
+//! | `split` mixed with inference or `key` | `#[override_key(split = ":", keys("a", "b"), infer)]` | Emits error: “`split` cannot be combined …” |
 //!
 //! ## Implementation Notes
 //!
 //! - Uses `syn::Attribute::parse_nested_meta` (v2 API) to walk nested meta items.
-//! - Returns a [`FieldOverrideMeta::Infer`] variant on success.
-//! - Always validates that the keyword `infer` is explicitly present.
+//! - Returns a [`FieldAttr`] whose meta is [`FieldOverrideMeta::Infer`] on success.
+//! - Always validates that the keyword `infer` is explicitly present when a prefix, suffix or separator is given.
 //!
 //! ## Design Rationale
 //!
@@ -54,30 +91,55 @@
 use syn::{Attribute, Error, LitStr};
 use syn::meta::ParseNestedMeta;
 
-use crate::types::FieldOverrideMeta;
+use crate::types::{Deprecation, FieldAttr, FieldOverrideMeta};
 use super::utils::push_error;
 
-/// Parses `#[override_key(infer[, prefix = "..."])]` attributes.
+/// Parses the list form `#[override_key(...)]`: `infer` with optional `prefix`, `suffix`
+/// and `separator` strings, `key = "..."`, `split = "..."` with `keys(...)`, or `skip`;
+/// each but `skip` optionally with `sensitive` and `deprecated = "..."[, strict]`.
 ///
 /// # Behavior
-/// - Extracts the presence of the `infer` flag.
-/// - Optionally captures a string `prefix` literal.
-/// - Returns [`FieldOverrideMeta::Infer`] if valid.
+/// - Extracts the presence of the `infer`, `skip`, `sensitive` and `strict` flags.
+/// - Optionally captures a string `prefix`, `suffix`, `separator`, `key` or `deprecated` literal.
+/// - Returns [`FieldOverrideMeta::Skip`] for a lone `skip`.
+/// - Returns [`FieldOverrideMeta::Dynamic`] for a lone `dynamic`.
+/// - Returns [`FieldOverrideMeta::Explicit`] for `key = "..."`.
+/// - Returns [`FieldOverrideMeta::Split`] for `split = "..."` with `keys(...)`.
+/// - Returns [`FieldOverrideMeta::Infer`] for `infer`.
+/// - Returns [`FieldOverrideMeta::JsonRoot`] for a lone `json`.
+/// - Returns [`FieldOverrideMeta::None`] for a lone `sensitive` or `deprecated`, deferring
+///   to struct-level defaults.
 /// - Accumulates syntax errors otherwise.
 ///
 /// # Example
 /// ```rust,ignore
-/// #[override_key(infer, prefix = "iproyal")]
+/// #[override_key(infer, prefix = "iproyal", sensitive)]
 /// pub api_token: Option<String>;
 /// ```
 ///
 /// ➜
 /// ```ignore
-/// FieldOverrideMeta::Infer { prefix: Some("iproyal") }
+/// FieldAttr {
+///     meta: FieldOverrideMeta::Infer { prefix: Some("iproyal"), suffix: None, separator: None },
+///     sensitive: true,
+///     deprecated: None,
+///     json: false,
+/// }
 /// ```
-pub fn parse_field_infer_list(attr: &Attribute, errors: &mut Vec<Error>) -> FieldOverrideMeta {
+pub fn parse_field_infer_list(attr: &Attribute, errors: &mut Vec<Error>) -> FieldAttr {
     let mut prefix = None;
+    let mut suffix = None;
+    let mut separator = None;
+    let mut key = None;
+    let mut split: Option<LitStr> = None;
+    let mut split_keys: Option<Vec<LitStr>> = None;
     let mut infer = false;
+    let mut skip = false;
+    let mut sensitive = false;
+    let mut deprecated: Option<LitStr> = None;
+    let mut strict = false;
+    let mut json = false;
+    let mut dynamic = false;
 
     // Walk each token inside the parentheses (...)
     let res = attr.parse_nested_meta(|meta: ParseNestedMeta| {
@@ -85,33 +147,166 @@ pub fn parse_field_infer_list(attr: &Attribute, errors: &mut Vec<Error>) -> Fiel
             // Mark presence of `infer`
             infer = true;
             Ok(())
+        } else if meta.path.is_ident("skip") {
+            // Exclude this field from override generation
+            skip = true;
+            Ok(())
+        } else if meta.path.is_ident("dynamic") {
+            // Field holds runtime (key, value) pairs
+            dynamic = true;
+            Ok(())
+        } else if meta.path.is_ident("sensitive") {
+            // Field holds a secret
+            sensitive = true;
+            Ok(())
+        } else if meta.path.is_ident("json") {
+            // Field holds a raw JSON tree
+            json = true;
+            Ok(())
+        } else if meta.path.is_ident("deprecated") {
+            // Parse replacement key: deprecated = "new.key"
+            deprecated = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("strict") {
+            // Deprecated key is an error rather than a warning
+            strict = true;
+            Ok(())
         } else if meta.path.is_ident("prefix") {
             // Parse prefix literal: prefix = "some.value"
             let lit: LitStr = meta.value()?.parse()?;
             prefix = Some(lit.value());
             Ok(())
+        } else if meta.path.is_ident("suffix") {
+            // Parse suffix literal: suffix = "some.value"
+            let lit: LitStr = meta.value()?.parse()?;
+            suffix = Some(lit.value());
+            Ok(())
+        } else if meta.path.is_ident("separator") {
+            // Parse separator literal: separator = "-"
+            let lit: LitStr = meta.value()?.parse()?;
+            separator = Some(lit.value());
+            Ok(())
+        } else if meta.path.is_ident("key") {
+            // Parse explicit key literal: key = "some.path"
+            let lit: LitStr = meta.value()?.parse()?;
+            key = Some(lit);
+            Ok(())
+        } else if meta.path.is_ident("split") {
+            // Parse split separator: split = ":"
+            split = Some(meta.value()?.parse()?);
+            Ok(())
+        } else if meta.path.is_ident("keys") {
+            // Parse target keys: keys("a.b", "c.d")
+            let content;
+            syn::parenthesized!(content in meta.input);
+            let list = content.parse_terminated(|input| input.parse::<LitStr>(), syn::Token![,])?;
+            split_keys = Some(list.into_iter().collect());
+            Ok(())
         } else {
             // Unexpected argument → human-readable diagnostic
             Err(meta.error(
-                r#"unexpected token in #[override_key(...)] — expected `infer` or `prefix = "..."`"#,
+                r#"unexpected token in #[override_key(...)] — expected `infer`, `prefix = "..."`, `suffix = "..."`, `separator = "..."`, `key = "..."`, `split = "..."`, `keys(...)`, `deprecated = "..."`, `strict`, `json`, `dynamic`, `skip` or `sensitive`"#,
             ))
         }
     });
 
+    // Flags independent of the key mapping
+    let deprecated = match (deprecated, strict) {
+        (Some(replacement), strict) => Some(Deprecation { replacement, strict }),
+        (None, true) => {
+            push_error(errors, attr, "`strict` requires `deprecated = \"...\"`");
+            None
+        }
+        (None, false) => None,
+    };
+    let field_attr = |meta| FieldAttr { meta, sensitive, deprecated: deprecated.clone(), json };
+
+    let invalid = field_attr(FieldOverrideMeta::Invalid);
+
     // Register parsing error from syn if meta traversal failed
     if let Err(e) = res {
         push_error(errors, attr, &format!("invalid #[override_key(...)] syntax: {}", e));
     }
 
+    // Options that only shape an inferred key
+    let shaped = prefix.is_some() || suffix.is_some() || separator.is_some();
+    let splits = split.is_some() || split_keys.is_some();
+
+    // `skip` must stand alone: a skipped field has no key to infer, prefix or mark
+    if skip {
+        if infer || shaped || splits || key.is_some() || sensitive || deprecated.is_some() || json || dynamic {
+            push_error(errors, attr, "`skip` cannot be combined with any other option");
+            return invalid;
+        }
+        return field_attr(FieldOverrideMeta::Skip);
+    }
+
+    // Runtime pairs bring their own keys: nothing to infer, split, mark or migrate
+    if dynamic {
+        if infer || shaped || splits || key.is_some() || sensitive || deprecated.is_some() || json {
+            push_error(errors, attr, "`dynamic` cannot be combined with any other option");
+            return invalid;
+        }
+        return field_attr(FieldOverrideMeta::Dynamic);
+    }
+
+    // A JSON tree is merged as-is: no splitting, and no single key to migrate
+    if json && (splits || deprecated.is_some()) {
+        push_error(errors, attr, "`json` cannot be combined with `split` or `deprecated`");
+        return invalid;
+    }
+
+    // Split values map onto their own explicit keys
+    if splits {
+        if infer || shaped || key.is_some() || deprecated.is_some() {
+            push_error(errors, attr, "`split` cannot be combined with `infer`, `prefix`, `suffix`, `separator`, `key` or `deprecated`");
+            return invalid;
+        }
+        let Some(split) = split else {
+            push_error(errors, attr, "`keys(...)` requires `split = \"...\"`");
+            return invalid;
+        };
+        // Arity: one key per part, and a single key would not split anything
+        let keys = split_keys.unwrap_or_default();
+        if keys.len() < 2 {
+            push_error(errors, attr, "`split` needs at least two keys in `keys(\"...\", \"...\")`, one per part");
+            return invalid;
+        }
+        if split.value().is_empty() {
+            push_error(errors, &split, "`split` separator must not be empty");
+            return invalid;
+        }
+        return field_attr(FieldOverrideMeta::Split { separator: split, keys });
+    }
+
+    // An explicit key leaves nothing to infer
+    if let Some(key) = key {
+        if infer || shaped {
+            push_error(errors, attr, "`key` cannot be combined with `infer`, `prefix`, `suffix` or `separator`");
+            return invalid;
+        }
+        return field_attr(FieldOverrideMeta::Explicit(key));
+    }
+
+    // A lone `json` merges the value at the configuration root
+    if json && !infer && !shaped {
+        return field_attr(FieldOverrideMeta::JsonRoot);
+    }
+
+    // Lone flags (`sensitive`, `deprecated`) keep whatever the struct-level defaults decide
+    if (sensitive || deprecated.is_some()) && !infer && !shaped {
+        return field_attr(FieldOverrideMeta::None);
+    }
+
     // Ensure that `infer` was explicitly present
     if !infer {
         push_error(
             errors,
             attr,
-            "missing `infer` keyword — expected #[override_key(infer[, prefix = \"...\"])]",
+            "missing `infer` keyword — expected #[override_key(infer[, prefix = \"...\"][, suffix = \"...\"])]",
         );
-        FieldOverrideMeta::Invalid
+        invalid
     } else {
-        FieldOverrideMeta::Infer { prefix }
+        field_attr(FieldOverrideMeta::Infer { prefix, suffix, separator })
     }
-}
\ No newline at end of file
+}
@@ -6,7 +6,14 @@
 //! Specifically, it handles:
 //!
 //! - `#[override_key = "some.path"]` — explicit override mapping
-//! - `#[override_key(infer[, prefix = "..."])]` — inferred key mapping
+//! - `#[override_key(infer[, prefix = "..."][, suffix = "..."])]` — inferred key mapping
+//! - `#[override_key(key = "some.path")]` — explicit mapping in list form
+//! - `#[override_key(split = ":", keys("a.b", "c.d"))]` — one value split across several keys
+//! - `#[override_key(skip)]` — field excluded from overrides
+//! - `sensitive` flag in the list form — key reported by `sensitive_keys()`
+//! - `deprecated = "new.key"[, strict]` in the list form — key migrated to a replacement
+//! - `json` in the list form — a `serde_json::Value` merged under the key, or at the root
+//! - `#[override_key(dynamic)]` — runtime `(key, value)` pairs applied verbatim
 //! - Unannotated fields — optionally inferred from struct-level defaults
 //!
 //! ## Role in the Pipeline
@@ -45,6 +52,10 @@
 //! #[override_key = "iproyal.token"]
 //! #[override_key(infer)]
 //! #[override_key(infer, prefix = "netnut")]
+//! #[override_key(infer, suffix = "endpoint")]
+//! #[override_key(key = "iproyal.client_secret", sensitive)]
+//! #[override_key(skip)]
+//! #[override_key(sensitive)]
 //! ```
 //!
 //! ## Example
@@ -78,6 +89,7 @@
 //! }
 //! ```
 
+use quote::quote;
 use syn::{Attribute, Data, DeriveInput, Error, Field, Fields};
 
 mod explicit;
@@ -88,7 +100,9 @@ use explicit::parse_field_explicit;
 use infer::parse_field_infer_list;
 use utils::*;
 
-use crate::types::{FieldOverrideMeta, KeyStrategy};
+pub use utils::cfg_attrs;
+
+use crate::types::{Deprecation, FieldAttr, FieldOverrideMeta, GeneratedField, KeyStrategy, KeyTable, StructConfig};
 
 /// Extracts named fields from a struct definition.
 ///
@@ -132,26 +146,36 @@ pub fn parse_fields(
 ///
 /// # Parameters
 /// - `field`: The AST node representing the struct field.
-/// - `struct_infer`: Whether struct-level `infer_keys` is enabled.
-/// - `struct_prefix`: Optional prefix from the struct-level attribute.
+/// - `struct_config`: Options of the struct-level attribute (`infer_keys`, `prefix`, `suffix`).
+/// - `table`: Key table receiving the field's keys, which the generated code reads by index.
 /// - `errors`: Mutable vector for collecting parsing errors.
 ///
 /// # Returns
-/// - `Some(TokenStream)` containing builder override code if successful.
-/// - `None` if the field is not relevant or has no attribute.
+/// - `Some(GeneratedField)` containing builder override code and the field's key if successful.
+/// - `None` if the field is not relevant or has no attribute; under `deny_unannotated`
+///   a field without a mapping also records an error.
 ///
 /// # Example Output
+///
+/// The accessor entry of a field setting one key with its plain value:
+///
+/// ```rust,ignore
+/// (__CLIARGS_OVERRIDE_KEYS[0usize], |s| s.iproyal_endpoint.as_ref().map(|v| v.clone().into()))
+/// ```
+///
+/// The block of a deprecated, `split`, `json` or `dynamic` field:
+///
 /// ```rust,ignore
-/// if let Some(v) = &self.iproyal_endpoint {
-///     builder = builder.set_override("iproyal.endpoint", v.clone())?;
+/// if let Some(v) = &self.db {
+///     // split the value, then one `set_override` per part
 /// }
 /// ```
 pub fn process_field(
     field: &Field,
-    struct_infer: bool,
-    struct_prefix: Option<&str>,
+    struct_config: &StructConfig,
+    table: &mut KeyTable,
     errors: &mut Vec<Error>,
-) -> Option<proc_macro2::TokenStream> {
+) -> Option<GeneratedField> {
     // Field identifier (e.g., iproyal_token)
     let ident = field.ident.as_ref()?;
     let ty = &field.ty;
@@ -159,43 +183,130 @@ pub fn process_field(
     // Find `#[override_key(...)]` attribute if present
     let attr = field.attrs.iter().find(|a| a.path().is_ident("override_key"));
 
-    // Parse field attribute → FieldOverrideMeta
-    let field_meta = parse_field_override_meta(attr, errors);
+    // Parse field attribute → FieldAttr
+    let FieldAttr { meta, sensitive, deprecated, json } = parse_field_override_meta(attr, errors);
+
+    // Strict mode: silent inference (no attribute, or a lone `sensitive`) is an error
+    if struct_config.deny_unannotated && matches!(meta, FieldOverrideMeta::None) {
+        push_error(
+            errors,
+            ident,
+            &format!(
+                "field `{ident}` has no key mapping — `deny_unannotated` requires \
+                 #[override_key = \"...\"], #[override_key(infer)] or #[override_key(skip)]"
+            ),
+        );
+        return None;
+    }
 
     // Combine field meta + struct-level config into final strategy
-    let strategy = merge_with_struct_defaults(field_meta, struct_infer, struct_prefix)?;
+    let strategy = merge_with_struct_defaults(meta, struct_config)?;
+
+    // Compute key literal strings ("iproyal.token", inferred variant, or one per split part)
+    let keys = make_key_literals(ident, &strategy);
+
+    // The replacement of a deprecated key is explicit, with `{field}` expanded
+    let deprecated = deprecated.map(|d| Deprecation { replacement: expand_field_placeholder(ident, &d.replacement), ..d });
+
+    // Reject malformed keys at the literal (explicit) or field name (inferred)
+    let mut valid = true;
+    for key in keys.iter().chain(deprecated.as_ref().map(|d| &d.replacement)) {
+        if let Err(msg) = validate_key(&key.value()) {
+            push_error(errors, key, &msg);
+            valid = false;
+        }
+    }
+    if !valid {
+        return None;
+    }
+
+    // A secret stays secret under its replacement key
+    let sensitive_keys = if sensitive {
+        keys.iter().cloned().chain(deprecated.clone().map(|d| d.replacement)).collect()
+    } else {
+        Vec::new()
+    };
+
+    // Emit final builder code for this field, reading its keys from the table
+    let positions = table.insert(keys);
+    let mut accessor = None;
+    let (snippet, active) = match (&strategy, positions.len()) {
+        (KeyStrategy::Split { separator, .. }, count) => {
+            let keys = table.slice(&positions);
+            (
+                build_split_snippet(ident, ty, separator, &keys, count),
+                build_split_active_snippet(ident, ty, separator, &keys, count),
+            )
+        }
+        // Runtime pairs carry their own keys
+        (KeyStrategy::Dynamic, 0) => (build_dynamic_snippet(ident, ty), build_dynamic_active_snippet(ident, ty)),
+        // JSON trees are merged leaf by leaf, from the root or under the key
+        (KeyStrategy::JsonRoot, 0) => {
+            let root = quote! { "" };
+            (build_json_snippet(ident, ty, &root), build_json_active_snippet(ident, ty, &root))
+        }
+        (_, 1) if json => {
+            let key = table.entry(positions.start);
+            (build_json_snippet(ident, ty, &key), build_json_active_snippet(ident, ty, &key))
+        }
+        // Plain values are read through the shared accessor tables
+        (_, 1) if deprecated.is_none() => {
+            accessor = Some(build_accessor_entry(ident, ty, &table.entry(positions.start)));
+            (proc_macro2::TokenStream::new(), proc_macro2::TokenStream::new())
+        }
+        (_, 1) => {
+            let key = table.entry(positions.start);
+            (
+                build_override_snippet(ident, ty, &key, deprecated.as_ref()),
+                build_active_snippet(ident, ty, &key, deprecated.as_ref()),
+            )
+        }
+        _ => return None,
+    };
+
+    // Fields behind `#[cfg(...)]` only generate code when they exist
+    let cfg = cfg_attrs(field);
 
-    // Compute key literal string ("iproyal.token" or inferred variant)
-    let key = make_key_literal(ident, &strategy);
+    // Long clap flag, listed by `flag_key_map()`
+    let flag = if cfg!(feature = "clap") { clap_long_name(field) } else { None };
 
-    // Emit final builder code for this field
-    Some(build_override_snippet(ident, ty, &key))
+    Some(GeneratedField {
+        snippet: with_cfg(&cfg, snippet),
+        active: with_cfg(&cfg, active),
+        accessor: accessor.map(|entry| quote! { #cfg #entry }),
+        field: ident.to_string(),
+        keys: positions,
+        sensitive_keys,
+        cfg,
+        flag,
+        dynamic: matches!(strategy, KeyStrategy::Dynamic),
+    })
 }
 
 // ------------------------------------------------------------------------------------------------
 // Dispatcher: Selects appropriate parsing strategy for `#[override_key(...)]`
 // ------------------------------------------------------------------------------------------------
 
-/// Parses a field’s `#[override_key(...)]` attribute into a [`FieldOverrideMeta`].
+/// Parses a field’s `#[override_key(...)]` attribute into a [`FieldAttr`].
 ///
 /// This acts as a **dispatcher**, deciding whether to call:
 /// - [`parse_field_explicit`] for `#[override_key = "..."]`
-/// - [`parse_field_infer_list`] for `#[override_key(infer[, prefix = "..."])]`
+/// - [`parse_field_infer_list`] for the list form `#[override_key(...)]`
 ///
 /// # Error Handling
 /// Invalid forms (e.g. `#[override_key("...")]`) are recognized and
 /// emit descriptive `compile_error!` diagnostics via [`push_error`].
-fn parse_field_override_meta(attr: Option<&Attribute>, errors: &mut Vec<Error>) -> FieldOverrideMeta {
+fn parse_field_override_meta(attr: Option<&Attribute>, errors: &mut Vec<Error>) -> FieldAttr {
     // No attribute — handled later by struct-level inference
     let Some(attr) = attr else {
-        return FieldOverrideMeta::None;
+        return FieldAttr { meta: FieldOverrideMeta::None, sensitive: false, deprecated: None, json: false };
     };
 
-    match &attr.meta {
+    let meta = match &attr.meta {
         // Explicit form: #[override_key = "iproyal.token"]
         syn::Meta::NameValue(nv) => parse_field_explicit(nv, errors),
 
-        // Inferred form: #[override_key(infer[, prefix = "..."])]
+        // List form: #[override_key(infer[, prefix = "..."])], #[override_key(skip)], ...
         syn::Meta::List(list) => {
             // Handle common mistake #[override_key("...")] gracefully
             if list.tokens.to_string().starts_with('"') {
@@ -206,7 +317,7 @@ fn parse_field_override_meta(attr: Option<&Attribute>, errors: &mut Vec<Error>)
                 );
                 FieldOverrideMeta::Invalid
             } else {
-                parse_field_infer_list(attr, errors)
+                return parse_field_infer_list(attr, errors);
             }
         }
 
@@ -216,9 +327,11 @@ fn parse_field_override_meta(attr: Option<&Attribute>, errors: &mut Vec<Error>)
                 errors,
                 attr,
                 "missing form — expected #[override_key = \"...\"] \
-                 or #[override_key(infer[, prefix = \"...\"])]",
+                 or #[override_key(infer[, prefix = \"...\"][, suffix = \"...\"])]",
             );
             FieldOverrideMeta::Invalid
         }
-    }
+    };
+
+    FieldAttr { meta, sensitive: false, deprecated: None, json: false }
 }
\ No newline at end of file
@@ -10,11 +10,19 @@
 //!    are collected and reported as `compile_error!()`s instead of panics.
 //! 2. **Key strategy merging** — via [`merge_with_struct_defaults`], which merges
 //!    field-specific and struct-level inference rules.
-//! 3. **Key generation** — via [`make_key_literal`], which computes the final
-//!    configuration key string (replacing `_` with `.` and applying optional prefixes).
-//! 4. **Type inspection** — via [`is_option_type`] to detect optional fields for safe codegen.
-//! 5. **Code snippet generation** — via [`build_override_snippet`], which emits the final
-//!    `builder.set_override()` calls for each field.
+//! 3. **Key generation** — via [`make_key_literals`], which computes the final
+//!    configuration key strings (replacing `_` with the separator and applying
+//!    optional prefixes and suffixes).
+//! 4. **Key validation** — via [`validate_key`], which rejects keys that would fail
+//!    or silently mis-nest at runtime.
+//! 5. **Type inspection** — via [`is_option_type`] to detect optional fields for safe codegen.
+//! 6. **Conditional compilation** — via [`cfg_attrs`] and [`with_cfg`], which carry a
+//!    field's `#[cfg(...)]` over to the code generated for it.
+//! 7. **Clap flags** — via [`clap_long_name`], the long flag clap derives for a
+//!    field, for `flag_key_map()`.
+//! 8. **Code snippet generation** — via [`build_override_snippet`], which emits the final
+//!    `builder.set_override()` calls for each field, and via [`build_active_snippet`]
+//!    the matching entries of `active_overrides()`.
 //!
 //! ## Module Role
 //!
@@ -46,9 +54,32 @@
 //! ```
 
 use quote::quote;
-use syn::{Error, Type, PathArguments, LitStr};
+use syn::ext::IdentExt;
+use syn::{Error, Type, PathArguments, LitStr, Token};
 
-use crate::types::{FieldOverrideMeta, KeyStrategy};
+use crate::types::{Deprecation, FieldOverrideMeta, KeyStrategy, StructConfig};
+
+/// Placeholder in explicit keys replaced by the dot-converted field name.
+const FIELD_PLACEHOLDER: &str = "{field}";
+
+/// Collects a field's `#[cfg(...)]` attributes.
+///
+/// Code generated for a field behind a feature must be compiled out along with
+/// the field, so these attributes are repeated on its snippets and on its
+/// entries in the generated key lists.
+pub fn cfg_attrs(field: &syn::Field) -> proc_macro2::TokenStream {
+    let cfgs = field.attrs.iter().filter(|a| a.path().is_ident("cfg"));
+    quote! { #(#cfgs)* }
+}
+
+/// Applies `cfg` attributes to a field's statements by wrapping them in a block.
+pub fn with_cfg(cfg: &proc_macro2::TokenStream, code: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    if cfg.is_empty() {
+        code
+    } else {
+        quote! { #cfg { #code } }
+    }
+}
 
 /// Pushes a new [`syn::Error`] into the shared error accumulator.
 ///
@@ -72,32 +103,48 @@ pub fn push_error<T: quote::ToTokens>(errors: &mut Vec<Error>, span_src: &T, msg
 /// - Explicit field attribute → [`KeyStrategy::Explicit`]
 /// - Field-level inference → [`KeyStrategy::Inferred`]
 /// - Struct-level inference (no field attr) → [`KeyStrategy::Inferred`]
+/// - `#[override_key(skip)]` → no code, regardless of struct-level inference
 ///
 /// # Parameters
 /// * `field_meta` — Result of parsing the field’s `#[override_key(...)]` attribute.
-/// * `struct_infer` — Whether `#[apply_overrides(infer_keys)]` was set.
-/// * `struct_prefix` — Optional struct-level prefix (e.g. `"iproyal"`).
+/// * `struct_config` — Options of `#[apply_overrides(...)]`; its `prefix`,
+///   `suffix` and `separator` apply where the field does not set its own.
 ///
 /// # Returns
 /// `Some(KeyStrategy)` if the field should generate code, or `None` if the field
 /// should be ignored (no applicable rule).
 pub fn merge_with_struct_defaults(
     field_meta: FieldOverrideMeta,
-    struct_infer: bool,
-    struct_prefix: Option<&str>,
+    struct_config: &StructConfig,
 ) -> Option<KeyStrategy> {
     match field_meta {
         // Explicit attribute — always wins
         FieldOverrideMeta::Explicit(lit) => Some(KeyStrategy::Explicit(lit)),
 
-        // Field-level infer with optional prefix
-        FieldOverrideMeta::Infer { prefix } => Some(KeyStrategy::Inferred {
-            prefix: prefix.or(struct_prefix.map(str::to_owned)),
+        // Split into explicit keys — struct-level options do not apply
+        FieldOverrideMeta::Split { separator, keys } => Some(KeyStrategy::Split { separator, keys }),
+
+        // JSON merged at the root — no key to infer
+        FieldOverrideMeta::JsonRoot => Some(KeyStrategy::JsonRoot),
+
+        // Runtime pairs — keys known only at runtime
+        FieldOverrideMeta::Dynamic => Some(KeyStrategy::Dynamic),
+
+        // Field-level infer with optional prefix, suffix and separator
+        FieldOverrideMeta::Infer { prefix, suffix, separator } => Some(KeyStrategy::Inferred {
+            prefix: prefix.or_else(|| struct_config.prefix.clone()),
+            suffix: suffix.or_else(|| struct_config.suffix.clone()),
+            separator: separator.or_else(|| struct_config.separator.clone()),
         }),
 
+        // Explicitly excluded field
+        FieldOverrideMeta::Skip => None,
+
         // No attribute but struct-level inference enabled
-        FieldOverrideMeta::None if struct_infer => Some(KeyStrategy::Inferred {
-            prefix: struct_prefix.map(str::to_owned),
+        FieldOverrideMeta::None if struct_config.infer_keys => Some(KeyStrategy::Inferred {
+            prefix: struct_config.prefix.clone(),
+            suffix: struct_config.suffix.clone(),
+            separator: struct_config.separator.clone(),
         }),
 
         // No attribute and no struct-level inference
@@ -105,36 +152,138 @@ pub fn merge_with_struct_defaults(
     }
 }
 
-/// Constructs a [`LitStr`] key literal for a field.
+/// Constructs the [`LitStr`] key literals for a field: one, or one per part of a `split` field.
 ///
-/// - Replaces underscores (`_`) in the field name with dots (`.`).
-/// - Applies prefix if present.
+/// - Expands `{field}` in explicit and split keys to the field name with dots for underscores.
+/// - Replaces underscores (`_`) in the field name with the separator, dots (`.`) by default.
+/// - Applies prefix and suffix if present, each joined with a dot.
 /// - Returns a string literal suitable for use in generated code.
 ///
 /// # Example
 /// ```ignore
-/// make_key_literal("iproyal_timeout", &Inferred { prefix: Some("iproyal") })
-/// → "iproyal.iproyal.timeout"
+/// make_key_literals("iproyal_timeout", &Inferred { prefix: Some("iproyal"), suffix: None, separator: None })
+/// → ["iproyal.iproyal.timeout"]
+///
+/// make_key_literals("iproyal", &Inferred { prefix: None, suffix: Some("endpoint"), separator: None })
+/// → ["iproyal.endpoint"]
+///
+/// make_key_literals("connect_timeout", &Inferred { prefix: Some("iproyal"), suffix: None, separator: Some("-") })
+/// → ["iproyal.connect-timeout"]
+///
+/// make_key_literals("netnut_proxy", &Explicit("providers.{field}.enabled"))
+/// → ["providers.netnut.proxy.enabled"]
+///
+/// make_key_literals("db", &Split { separator: ":", keys: ["{field}.host", "{field}.port"] })
+/// → ["db.host", "db.port"]
 /// ```
-pub fn make_key_literal(ident: &syn::Ident, strategy: &KeyStrategy) -> LitStr {
+pub fn make_key_literals(ident: &syn::Ident, strategy: &KeyStrategy) -> Vec<LitStr> {
     match strategy {
-        // Explicit: use provided literal as-is
-        KeyStrategy::Explicit(lit) => lit.clone(),
+        // Explicit: use provided literal as-is, expanding the `{field}` placeholder
+        KeyStrategy::Explicit(lit) => vec![expand_field_placeholder(ident, lit)],
 
-        // Inferred: construct from field name + optional prefix
-        KeyStrategy::Inferred { prefix } => {
-            let mut key = ident.to_string().replace('_', ".");
+        // Split: each key is explicit
+        KeyStrategy::Split { keys, .. } => keys.iter().map(|lit| expand_field_placeholder(ident, lit)).collect(),
+
+        // JSON root and dynamic pairs: the value provides the keys
+        KeyStrategy::JsonRoot | KeyStrategy::Dynamic => Vec::new(),
+
+        // Inferred: construct from field name + optional prefix and suffix
+        KeyStrategy::Inferred { prefix, suffix, separator } => {
+            // only the field-name portion uses the separator
+            let mut key = ident.to_string().replace('_', separator.as_deref().unwrap_or("."));
             if let Some(pre) = prefix.as_deref() {
                 // only prepend prefix if non-empty
                 if !pre.is_empty() {
                     key = format!("{}.{}", pre, key);
                 }
             }
-            LitStr::new(&key, ident.span())
+            if let Some(suf) = suffix.as_deref() {
+                // only append suffix if non-empty
+                if !suf.is_empty() {
+                    key = format!("{}.{}", key, suf);
+                }
+            }
+            vec![LitStr::new(&key, ident.span())]
         }
     }
 }
 
+/// Replaces `{field}` in an explicit key with the dot-converted field name.
+pub fn expand_field_placeholder(ident: &syn::Ident, lit: &LitStr) -> LitStr {
+    let key = lit.value();
+    if key.contains(FIELD_PLACEHOLDER) {
+        let field = ident.to_string().replace('_', ".");
+        LitStr::new(&key.replace(FIELD_PLACEHOLDER, &field), lit.span())
+    } else {
+        lit.clone()
+    }
+}
+
+/// Checks that a final key is a well-formed configuration path.
+///
+/// A key is one or more `.`-separated segments, each non-empty and made of ASCII
+/// letters, digits, `_` and `-`. This catches `"iproyal..endpoint"`, trailing dots,
+/// spaces, and unknown placeholders such as `"{feild}"` at compile time.
+///
+/// # Returns
+/// `Err` with a human-readable reason, to be attached to the key's span.
+pub fn validate_key(key: &str) -> Result<(), String> {
+    if key.is_empty() {
+        return Err("override key is empty".to_string());
+    }
+    if key.split('.').any(str::is_empty) {
+        return Err(format!(
+            "invalid override key `{key}`: empty segment (leading, trailing or doubled `.`)"
+        ));
+    }
+    if let Some(c) = key.chars().find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))) {
+        return Err(format!(
+            "invalid override key `{key}`: unexpected character {c:?} — \
+             keys may only contain ASCII letters, digits, `_`, `-` and `.`"
+        ));
+    }
+    Ok(())
+}
+
+/// Long flag clap derives for a field, without the leading dashes.
+///
+/// Reads `long` from the field's `#[arg(...)]` or `#[clap(...)]` attributes: a
+/// bare `long` is the kebab-cased field name, `long = "name"` the given name.
+/// `None` for fields without a long flag, or whose name is not a string literal.
+///
+/// # Example
+/// ```ignore
+/// #[arg(long, global = true)]        // field `iproyal_endpoint` → "iproyal-endpoint"
+/// #[arg(long = "endpoint")]          // → "endpoint"
+/// ```
+pub fn clap_long_name(field: &syn::Field) -> Option<String> {
+    let ident = field.ident.as_ref()?;
+    let mut long = None;
+
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("arg") || a.path().is_ident("clap")) {
+        // Anything malformed is clap's to report; the flag is then just unknown
+        let _ = attr.parse_nested_meta(|meta| {
+            let is_long = meta.path.is_ident("long");
+            if meta.input.peek(Token![=]) {
+                let value: syn::Expr = meta.value()?.parse()?;
+                if let (true, syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(name), .. })) = (is_long, &value) {
+                    long = Some(name.value());
+                }
+            } else if meta.input.peek(syn::token::Paren) {
+                // e.g. `value_parser!(...)`-like list arguments: skip their contents
+                let content;
+                syn::parenthesized!(content in meta.input);
+                content.parse::<proc_macro2::TokenStream>()?;
+            } else if is_long {
+                long = Some(ident.unraw().to_string().replace('_', "-"));
+            }
+            Ok(())
+        });
+    }
+
+    long
+}
+
 /// Determines whether a field type is an [`Option<T>`].
 ///
 /// Used to decide whether code generation should wrap the `builder.set_override`
@@ -148,7 +297,7 @@ pub fn make_key_literal(ident: &syn::Ident, strategy: &KeyStrategy) -> LitStr {
 /// and ensures it has angle-bracketed type arguments.
 pub fn is_option_type(ty: &Type) -> bool {
     matches!(ty, Type::Path(tp)
-        if tp.path.segments.last().map_or(false, |seg| {
+        if tp.path.segments.last().is_some_and(|seg| {
             seg.ident == "Option" && matches!(seg.arguments, PathArguments::AngleBracketed(_))
         }))
 }
@@ -162,29 +311,242 @@ pub fn is_option_type(ty: &Type) -> bool {
 ///
 /// ```ignore
 /// if let Some(v) = &self.iproyal_token {
-///     builder = builder.set_override("iproyal.token", v.clone())?;
+///     builder = builder.set_override(__CLIARGS_OVERRIDE_KEYS[0usize], v.clone())?;
 /// }
 /// ```
 ///
 /// # Behavior
 /// - Wraps value access in `if let Some` if the field type is `Option`.
 /// - Otherwise generates an unconditional call.
+/// - For a deprecated key, warns on stderr and also sets the replacement key, or
+///   with `strict` returns a `config::ConfigError::Message` instead.
 pub fn build_override_snippet(
     ident: &syn::Ident,
     ty: &Type,
-    key: &LitStr,
+    key: &proc_macro2::TokenStream,
+    deprecated: Option<&Deprecation>,
+) -> proc_macro2::TokenStream {
+    let body = match deprecated {
+        None => quote! {
+            builder = builder.set_override(#key, v.clone())?;
+        },
+        Some(Deprecation { replacement, strict: true }) => quote! {
+            let _ = v;
+            return Err(config::ConfigError::Message(format!(
+                "config key `{}` is deprecated, use `{}` instead", #key, #replacement,
+            )));
+        },
+        Some(Deprecation { replacement, strict: false }) => quote! {
+            eprintln!("warning: config key `{}` is deprecated, use `{}` instead", #key, #replacement);
+            builder = builder.set_override(#key, v.clone())?;
+            builder = builder.set_override(#replacement, v.clone())?;
+        },
+    };
+    wrap_field_access(ident, ty, body)
+}
+
+/// Builds the `(key, accessor)` entry of a field setting one key with its plain value.
+///
+/// The accessor returns the value converted with the same `Into<config::Value>`
+/// bound as `set_override`, or `None` for an unset `Option` field.
+///
+/// # Example Output
+///
+/// ```ignore
+/// (__CLIARGS_OVERRIDE_KEYS[0usize], |s| s.iproyal_token.as_ref().map(|v| v.clone().into()))
+/// ```
+pub fn build_accessor_entry(ident: &syn::Ident, ty: &Type, key: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    if is_option_type(ty) {
+        quote! { (#key, |s| s.#ident.as_ref().map(|v| v.clone().into())) }
+    } else {
+        quote! { (#key, |s| Some(s.#ident.clone().into())) }
+    }
+}
+
+/// Builds the code collecting a single field's override for `active_overrides()`.
+///
+/// Mirrors [`build_override_snippet`]: an `Option` field contributes only when
+/// `Some`, any other field always does, and a deprecated key comes with its
+/// replacement. The value is converted with the same `Into<config::Value>` bound
+/// as `set_override`.
+///
+/// # Example Output
+///
+/// ```ignore
+/// if let Some(v) = &self.iproyal_token {
+///     overrides.push((__CLIARGS_OVERRIDE_KEYS[0usize], v.clone().into()));
+/// }
+/// ```
+pub fn build_active_snippet(
+    ident: &syn::Ident,
+    ty: &Type,
+    key: &proc_macro2::TokenStream,
+    deprecated: Option<&Deprecation>,
+) -> proc_macro2::TokenStream {
+    let replacement = deprecated.map(|d| {
+        let replacement = &d.replacement;
+        quote! { overrides.push((#replacement, v.clone().into())); }
+    });
+    wrap_field_access(ident, ty, quote! {
+        overrides.push((#key, v.clone().into()));
+        #replacement
+    })
+}
+
+/// Builds the code splitting a field's value and overriding one key per part.
+///
+/// `keys` is a slice of the key table holding `count` keys. The value must
+/// implement `AsRef<str>`. A value that does not split into exactly `count`
+/// non-empty parts makes `apply_overrides()` return a `config::ConfigError::Message`
+/// naming the field.
+///
+/// # Example Output
+///
+/// ```ignore
+/// if let Some(v) = &self.db {
+///     let parts: Vec<&str> = AsRef::<str>::as_ref(v).split(":").collect();
+///     if parts.len() != 2usize || parts.iter().any(|p| p.is_empty()) {
+///         return Err(config::ConfigError::Message(/* ... */));
+///     }
+///     for (key, part) in __ARGS_OVERRIDE_KEYS[0usize..2usize].iter().zip(parts) {
+///         builder = builder.set_override(*key, part)?;
+///     }
+/// }
+/// ```
+pub fn build_split_snippet(
+    ident: &syn::Ident,
+    ty: &Type,
+    separator: &LitStr,
+    keys: &proc_macro2::TokenStream,
+    count: usize,
+) -> proc_macro2::TokenStream {
+    let field = ident.to_string();
+    let body = quote! {
+        let value = AsRef::<str>::as_ref(v);
+        let parts: Vec<&str> = value.split(#separator).collect();
+        if parts.len() != #count || parts.iter().any(|p| p.is_empty()) {
+            return Err(config::ConfigError::Message(format!(
+                "`{}` must be {} non-empty values separated by {:?}, got {:?}",
+                #field, #count, #separator, value,
+            )));
+        }
+        for (key, part) in #keys.iter().zip(parts) {
+            builder = builder.set_override(*key, part)?;
+        }
+    };
+    wrap_field_access(ident, ty, body)
+}
+
+/// Builds the code collecting a split field's parts for `active_overrides()`.
+///
+/// Values that do not split cleanly are left out; `apply_overrides()` reports them.
+pub fn build_split_active_snippet(
+    ident: &syn::Ident,
+    ty: &Type,
+    separator: &LitStr,
+    keys: &proc_macro2::TokenStream,
+    count: usize,
+) -> proc_macro2::TokenStream {
+    let body = quote! {
+        let parts: Vec<&str> = AsRef::<str>::as_ref(v).split(#separator).collect();
+        if parts.len() == #count && !parts.iter().any(|p| p.is_empty()) {
+            for (key, part) in #keys.iter().zip(parts) {
+                overrides.push((*key, part.into()));
+            }
+        }
+    };
+    wrap_field_access(ident, ty, body)
+}
+
+/// Builds the code merging a `json` field's value tree under `key` (`""` for the root).
+///
+/// Each leaf becomes one `set_override`, via `override_key_core::json::overrides`,
+/// which requires the `json` feature of `override_key_core`.
+///
+/// # Example Output
+///
+/// ```ignore
+/// if let Some(v) = &self.extra_config {
+///     for (key, value) in ::override_key_core::json::overrides("", v)? {
+///         builder = builder.set_override(key, value)?;
+///     }
+/// }
+/// ```
+pub fn build_json_snippet(ident: &syn::Ident, ty: &Type, key: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    wrap_field_access(ident, ty, quote! {
+        for (key, value) in ::override_key_core::json::overrides(#key, v)? {
+            builder = builder.set_override(key, value)?;
+        }
+    })
+}
+
+/// Builds the code collecting a `json` field for `active_overrides()`: the whole
+/// value tree under its key, `""` for the root.
+pub fn build_json_active_snippet(ident: &syn::Ident, ty: &Type, key: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    wrap_field_access(ident, ty, quote! {
+        overrides.push((#key, ::override_key_core::json::to_value(v)));
+    })
+}
+
+/// Builds the code applying a `dynamic` field's runtime `(key, value)` pairs verbatim.
+///
+/// Works for any field iterating by reference as pairs of string-like keys and
+/// values convertible into `config::Value`, e.g. `Vec<(String, String)>` or
+/// `BTreeMap<String, String>`. A malformed key fails `apply_overrides()`.
+///
+/// # Example Output
+///
+/// ```ignore
+/// {
+///     let v = &self.set;
+///     for (key, value) in v {
+///         builder = builder.set_override(AsRef::<str>::as_ref(key), value.clone())?;
+///     }
+/// }
+/// ```
+pub fn build_dynamic_snippet(ident: &syn::Ident, ty: &Type) -> proc_macro2::TokenStream {
+    wrap_field_access(ident, ty, quote! {
+        for (key, value) in v {
+            builder = builder.set_override(AsRef::<str>::as_ref(key), value.clone())?;
+        }
+    })
+}
+
+/// Builds the code collecting a `dynamic` field for `active_overrides()`.
+///
+/// Its keys are not `'static`, so the pairs are nested into one table pushed at
+/// the root (`""`), like a root `json` field.
+pub fn build_dynamic_active_snippet(ident: &syn::Ident, ty: &Type) -> proc_macro2::TokenStream {
+    wrap_field_access(ident, ty, quote! {
+        let pairs: Vec<(&str, config::Value)> = v
+            .into_iter()
+            .map(|(key, value)| (AsRef::<str>::as_ref(key), value.clone().into()))
+            .collect();
+        if !pairs.is_empty() {
+            overrides.push(("", ::override_key_core::map::nested(pairs).into()));
+        }
+    })
+}
+
+/// Runs `body` with `v` bound to a reference to the field's value, only when
+/// `Some` for `Option` fields.
+fn wrap_field_access(
+    ident: &syn::Ident,
+    ty: &Type,
+    body: proc_macro2::TokenStream,
 ) -> proc_macro2::TokenStream {
     if is_option_type(ty) {
-        // Optional field → only override if value is present
         quote! {
             if let Some(v) = &self.#ident {
-                builder = builder.set_override(#key, v.clone())?;
+                #body
             }
         }
     } else {
-        // Non-optional field → always override
         quote! {
-            builder = builder.set_override(#key, self.#ident.clone())?;
+            {
+                let v = &self.#ident;
+                #body
+            }
         }
     }
-}
\ No newline at end of file
+}
@@ -51,6 +51,16 @@
 //!     #[override_key(infer, prefix = "netnut")]
 //!     pub netnut_token: Option<String>,
 //!
+//!     // Per-field inference with a suffix and no prefix
+//!     // field `netnut` → key "netnut.endpoint"
+//!     #[override_key(infer, prefix = "", suffix = "endpoint")]
+//!     pub netnut: Option<String>,
+//!
+//!     // Struct prefix, field name joined with dashes
+//!     // field `connect_timeout` → key "iproyal.connect-timeout"
+//!     #[override_key(infer, separator = "-")]
+//!     pub connect_timeout: Option<String>,
+//!
 //!     // Struct-level inference + prefix
 //!     // field `iproyal_timeout` → key "iproyal.iproyal.timeout"
 //!     pub iproyal_timeout: Option<String>,
@@ -58,31 +68,108 @@
 //!     // Struct-level inference + prefix
 //!     // field `region_id` → key "iproyal.region.id"
 //!     pub region_id: Option<u32>,
+//!
+//!     // Explicit key template
+//!     // field `netnut_proxy` → key "providers.netnut.proxy.enabled"
+//!     #[override_key = "providers.{field}.enabled"]
+//!     pub netnut_proxy: Option<bool>,
+//!
+//!     // One `host:port` value split across two keys; a value without
+//!     // exactly one part per key makes `apply_overrides` fail
+//!     #[override_key(split = ":", keys("iproyal.proxy.host", "iproyal.proxy.port"))]
+//!     pub iproyal_proxy: Option<String>,
+//!
+//!     // Deprecated key: setting it warns and also sets the replacement;
+//!     // add `strict` to reject it instead
+//!     #[override_key(infer, deprecated = "iproyal.endpoint")]
+//!     pub iproyal_url: Option<String>,
+//!
+//!     // Excluded from overrides despite struct-level inference
+//!     #[override_key(skip)]
+//!     pub config: Option<String>,
+//!
+//!     // Explicit key, listed by `CLIArgs::sensitive_keys()`
+//!     #[override_key(key = "iproyal.token", sensitive)]
+//!     pub iproyal_token: Option<String>,
 //! }
 //! ```
 //!
-//! The generated implementation looks roughly like this:
+//! `#[override_key(json)]` takes a `serde_json::Value` and merges its leaves over
+//! the configuration; with `key = "..."` the tree is rooted at that key. It needs
+//! the `json` feature of `override_key_core` and cannot be combined with `split`
+//! or `deprecated`.
+//!
+//! `#[override_key(dynamic)]` takes `(key, value)` pairs, e.g. a
+//! `Vec<(String, String)>` from a repeatable `--set key=value` flag or a
+//! `BTreeMap<String, String>`, and sets each value at its own dotted key. Declared
+//! last, its pairs win over every other field. It takes no other option, and a
+//! struct can have only one such field.
+//!
+//! A field's `#[cfg(...)]` attributes are repeated on all code generated for it,
+//! so feature-gated CLI flags compile with the feature on or off.
+//!
+//! `#[apply_overrides(prefix_from_type)]` derives the struct-level prefix from the
+//! type name instead, e.g. `"infatica"` for `InfaticaArgs`.
+//!
+//! With `#[apply_overrides(deny_unannotated)]`, every field must carry an explicit
+//! key, `infer` or `skip`; unannotated fields become compile errors instead of
+//! being inferred or ignored silently.
+//!
+//! With the `clap` feature, fields with a long clap flag (`#[arg(long)]` or
+//! `#[arg(long = "...")]`) are also listed by `flag_key_map()` as
+//! `(flag, key)` pairs, flags without their leading dashes, so help output and
+//! config documentation can point at each other.
+//!
+//! `#[apply_overrides(map_output)]` also generates
+//! `fn as_override_map(&self) -> config::Map<String, config::Value>`, the active
+//! overrides nested into tables by their dotted keys, for services that build
+//! their configuration from a plain map.
+//!
+//! Overrides are applied in field declaration order, so a later field wins over an
+//! earlier one mapped to the same key. `#[apply_overrides(emit_order_test)]`
+//! generates a `#[cfg(test)]` module checking that order for the struct. With
+//! `emit_order_test = "fixture"` it also applies the overrides of `fixture()`,
+//! which returns an instance setting some key through several fields, and checks
+//! the built configuration holds the value of the last of them for every key.
+//!
+//! The generated implementation looks roughly like this, with every key stored
+//! once in a `const` table, and the fields setting one key with their plain
+//! value read through a table of accessors that a single loop applies:
 //!
 //! ```ignore
+//! const __CLIARGS_OVERRIDE_KEYS: &[&str] =
+//!     &["iproyal.endpoint", "netnut.netnut_token", "iproyal.timeout", "iproyal.token"];
+//!
+//! const __CLIARGS_OVERRIDE_VALUES: &[(&str, fn(&CLIArgs) -> Option<config::Value>)] = &[
+//!     (__CLIARGS_OVERRIDE_KEYS[0], |s| s.iproyal_endpoint.as_ref().map(|v| v.clone().into())),
+//!     (__CLIARGS_OVERRIDE_KEYS[1], |s| s.netnut_token.as_ref().map(|v| v.clone().into())),
+//!     (__CLIARGS_OVERRIDE_KEYS[2], |s| s.iproyal_timeout.as_ref().map(|v| v.clone().into())),
+//!     (__CLIARGS_OVERRIDE_KEYS[3], |s| s.iproyal_token.as_ref().map(|v| v.clone().into())),
+//! ];
+//!
 //! impl override_key_core::ApplyOverrides for CLIArgs {
 //!     fn apply_overrides(
 //!         &self,
 //!         mut builder: config::ConfigBuilder<config::builder::DefaultState>,
 //!     ) -> Result<config::ConfigBuilder<config::builder::DefaultState>, config::ConfigError> {
-//!         if let Some(v) = &self.iproyal_endpoint {
-//!             builder = builder.set_override("iproyal.endpoint", v.clone())?;
-//!         }
-//!         if let Some(v) = &self.netnut_token {
-//!             builder = builder.set_override("netnut.netnut_token", v.clone())?;
-//!         }
-//!         if let Some(v) = &self.iproyal_timeout {
-//!             builder = builder.set_override("iproyal.timeout", v.clone())?;
+//!         for (key, value) in __CLIARGS_OVERRIDE_VALUES {
+//!             if let Some(value) = value(self) {
+//!                 builder = builder.set_override(*key, value)?;
+//!             }
 //!         }
 //!         Ok(builder)
 //!     }
+//!
+//!     fn sensitive_keys() -> &'static [&'static str] {
+//!         &["iproyal.token"]
+//!     }
 //! }
 //! ```
 //!
+//! Fields with code of their own (`split`, `json`, `dynamic` and deprecated keys)
+//! keep a block each, emitted between the loops so declaration order still decides
+//! which field wins.
+//!
 //! ## Design Overview
 //!
 //! - `lib.rs` serves as the **entry point** only — it parses the input syntax tree
@@ -124,16 +211,28 @@
 //! - Add new configuration hints to `struct_config.rs`.
 //! - Update codegen in `builder_gen.rs` if the emitted code shape changes.
 //!
+//! ## Expansion Snapshots
+//!
+//! `expand.rs` snapshots the code generated for representative structs under
+//! `src/snapshots/`, so codegen changes are reviewed against their actual
+//! output. Accept intended changes with `INSTA_UPDATE=always cargo test -p
+//! override_key_derive` and review the snapshot diff. An ignored benchmark in the
+//! same module times the expansion of a large struct:
+//! `cargo test -p override_key_derive --release --lib -- --ignored --nocapture`.
+//!
 //! ```
 
 use proc_macro::TokenStream;
-use syn::{parse_macro_input, DeriveInput, Error};
+use syn::{parse_macro_input, DeriveInput};
 
 mod builder_gen;
 mod struct_config;
 mod types;
 mod field_parser;
 
+#[cfg(test)]
+mod expand;
+
 use builder_gen::generate_impl;
 
 /// Derive macro entry point for `#[derive(ApplyOverrides)]`.
@@ -12,6 +12,28 @@
 //!    have an explicit `#[override_key(...)]` attribute.
 //! 2. **`prefix`** — optional string that will be prepended to all inferred keys
 //!    (e.g., `"iproyal"` → `"iproyal.timeout"`).
+//! 3. **`suffix`** — optional string that will be appended to all inferred keys
+//!    (e.g., `"endpoint"` → `"iproyal.endpoint"` for field `iproyal`).
+//! 4. **`separator`** — optional string replacing `_` in field names of inferred
+//!    keys instead of `.` (e.g., `"-"` → `"iproyal.connect-timeout"` for field
+//!    `connect_timeout`); prefix and suffix are still joined with dots.
+//! 5. **`deny_unannotated`** — strict mode: every field must carry an
+//!    `#[override_key(...)]` attribute choosing its key (explicit or `infer`) or
+//!    `skip`; any other field is a compile error instead of being silently
+//!    inferred or ignored.
+//! 6. **`prefix_from_type`** — derives `prefix` from the struct name, lowercased
+//!    after stripping a type suffix: `Args` by default (`InfaticaArgs` →
+//!    `"infatica"`), or the one given as `prefix_from_type = "Options"`. Cannot be
+//!    combined with `prefix`.
+//! 7. **`emit_order_test`** — generates a `#[cfg(test)]` module asserting that
+//!    overrides are applied in field declaration order, for code relying on a
+//!    later field winning over an earlier one mapped to the same key. With
+//!    `emit_order_test = "fixture"` the module also applies the overrides of
+//!    `fixture()`, an instance setting a key through several fields, and asserts
+//!    that the later field wins in the built config.
+//! 8. **`map_output`** — generates an inherent `as_override_map()` returning the
+//!    active overrides as a `config::Map<String, config::Value>`, nested into
+//!    tables by their dotted keys.
 //!
 //! ## Example
 //!
@@ -33,11 +55,11 @@
 //! ## Return Value
 //!
 //! ```ignore
-//! (bool, Option<String>, Vec<Error>)
+//! (StructConfig, Vec<Error>)
 //! ```
 //!
-//! - **`bool`** → whether `infer_keys` was specified.
-//! - **`Option<String>`** → the parsed prefix string, if present.
+//! - **`StructConfig`** → whether `infer_keys` was specified, and the parsed
+//!   prefix, suffix and separator strings, if present.
 //! - **`Vec<syn::Error>`** → accumulated syntax or semantic errors to be surfaced
 //!   as `compile_error!()`s later during code generation.
 //!
@@ -47,20 +69,35 @@
 //! |----------------|--------|
 //! | `#[apply_overrides(infer_keys)]` | Enables inference for all fields |
 //! | `#[apply_overrides(prefix = "foo")]` | Applies `"foo."` prefix to inferred keys |
+//! | `#[apply_overrides(suffix = "bar")]` | Applies `".bar"` suffix to inferred keys |
+//! | `#[apply_overrides(separator = "-")]` | Joins field-name words with `-` in inferred keys |
+//! | `#[apply_overrides(deny_unannotated)]` | Rejects fields without a key mapping or `skip` |
+//! | `#[apply_overrides(prefix_from_type)]` | Uses `"infatica"` as prefix for `InfaticaArgs` |
+//! | `#[apply_overrides(emit_order_test)]` | Generates a test of the key application order |
+//! | `#[apply_overrides(emit_order_test = "fixture")]` | Also checks the built config of `fixture()` |
+//! | `#[apply_overrides(map_output)]` | Generates `as_override_map()` |
 //! | `#[apply_overrides(infer_keys, prefix = "foo")]` | Enables both behaviors |
 //!
-//! - If no `#[apply_overrides(...)]` attribute is present, defaults to `(false, None, vec![])`.
+//! - If no `#[apply_overrides(...)]` attribute is present, defaults to
+//!   `(StructConfig::default(), vec![])`.
 //! - Invalid tokens (e.g., `#[apply_overrides("bad")]`) produce `syn::Error` instances
 //!   but do **not** cause an immediate panic; errors are accumulated and reported later.
 //! - Compatible with **Rust 2024** and **syn v2+** (uses `ParseNestedMeta` API).
 
-use syn::{DeriveInput, Error, LitStr};
+use syn::{DeriveInput, Error, LitStr, Token};
 use syn::meta::ParseNestedMeta;
+use syn::spanned::Spanned;
+
+use crate::types::StructConfig;
+
+/// Suffix stripped from the type name by a bare `prefix_from_type`.
+const DEFAULT_TYPE_SUFFIX: &str = "Args";
 
 /// Parses the `#[apply_overrides(...)]` struct-level attribute.
 ///
 /// This function scans all attributes attached to the struct, looking for
-/// `#[apply_overrides(...)]`, and extracts its parameters (`infer_keys` and `prefix`).
+/// `#[apply_overrides(...)]`, and extracts its parameters (`infer_keys`, `prefix`,
+/// `suffix`, `separator`, `deny_unannotated`, `prefix_from_type`, `emit_order_test` and `map_output`).
 ///
 /// # Arguments
 ///
@@ -69,12 +106,12 @@ use syn::meta::ParseNestedMeta;
 /// # Returns
 ///
 /// ```ignore
-/// (infer_keys_enabled, optional_prefix, collected_errors)
+/// (struct_config, collected_errors)
 /// ```
 ///
 /// Example:
 /// ```ignore
-/// (true, Some("iproyal".to_string()), vec![])
+/// (StructConfig { infer_keys: true, prefix: Some("iproyal".to_string()), ..StructConfig::default() }, vec![])
 /// ```
 ///
 /// # Error Handling
@@ -84,15 +121,18 @@ use syn::meta::ParseNestedMeta;
 ///   and collected in the returned `Vec<Error>`.
 /// - It never returns `Err`; instead, errors are surfaced later as
 ///   `compile_error!` tokens in the generated output.
-pub fn parse_struct_level_config(input: &DeriveInput) -> (bool, Option<String>, Vec<Error>) {
-    // Accumulators for parsed options
-    let mut infer_keys = false;       // default: disabled
-    let mut prefix: Option<String> = None; // default: no prefix
+pub fn parse_struct_level_config(input: &DeriveInput) -> (StructConfig, Vec<Error>) {
+    // Accumulator for parsed options; defaults: inference disabled, no prefix or suffix,
+    // dot separator
+    let mut config = StructConfig::default();
 
     // Collector for any syntax/semantic errors we encounter while parsing.
     // We never panic; we return all errors for the caller to emit.
     let mut errors: Vec<Error> = Vec::new();
 
+    // Type suffix stripped by `prefix_from_type`, once requested
+    let mut type_suffix: Option<LitStr> = None;
+
     // Walk all attributes attached to the struct
     for attr in &input.attrs {
         // Only care about: #[apply_overrides(...)]
@@ -104,13 +144,37 @@ pub fn parse_struct_level_config(input: &DeriveInput) -> (bool, Option<String>,
         // Example accepted forms:
         //   - infer_keys
         //   - prefix = "iproyal"
+        //   - suffix = "endpoint"
         //   - infer_keys, prefix = "iproyal"
         //
         // Any unrecognized token becomes a syn::Error we push into `errors`.
         if let Err(e) = attr.parse_nested_meta(|meta: ParseNestedMeta| {
             // Flag: infer unannotated field names into config keys
             if meta.path.is_ident("infer_keys") {
-                infer_keys = true;
+                config.infer_keys = true;
+                return Ok(());
+            }
+
+            // Flag: require an explicit mapping or `skip` on every field
+            if meta.path.is_ident("deny_unannotated") {
+                config.deny_unannotated = true;
+                return Ok(());
+            }
+
+            // Option: emit_order_test or emit_order_test = "fixture", generating a test
+            // pinning the key application order
+            if meta.path.is_ident("emit_order_test") {
+                config.emit_order_test = true;
+                if meta.input.peek(Token![=]) {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    config.order_test_fixture = Some(lit.parse()?);
+                }
+                return Ok(());
+            }
+
+            // Flag: generate `as_override_map()`
+            if meta.path.is_ident("map_output") {
+                config.map_output = true;
                 return Ok(());
             }
 
@@ -118,18 +182,60 @@ pub fn parse_struct_level_config(input: &DeriveInput) -> (bool, Option<String>,
             if meta.path.is_ident("prefix") {
                 // Move to the value side of `prefix = ...`, then parse a string literal
                 let lit: LitStr = meta.value()?.parse()?;
-                prefix = Some(lit.value());
+                config.prefix = Some(lit.value());
+                return Ok(());
+            }
+
+            // Option: prefix_from_type or prefix_from_type = "Options"
+            if meta.path.is_ident("prefix_from_type") {
+                type_suffix = Some(if meta.input.peek(Token![=]) {
+                    meta.value()?.parse()?
+                } else {
+                    LitStr::new(DEFAULT_TYPE_SUFFIX, meta.path.span())
+                });
+                return Ok(());
+            }
+
+            // Option: suffix = "some.leaf"
+            if meta.path.is_ident("suffix") {
+                let lit: LitStr = meta.value()?.parse()?;
+                config.suffix = Some(lit.value());
+                return Ok(());
+            }
+
+            // Option: separator = "-"
+            if meta.path.is_ident("separator") {
+                let lit: LitStr = meta.value()?.parse()?;
+                config.separator = Some(lit.value());
                 return Ok(());
             }
 
             // Anything else is considered invalid for this attribute
-            Err(meta.error(r#"expected `infer_keys` or `prefix = "..."`"#))
+            Err(meta.error(
+                r#"expected `infer_keys`, `deny_unannotated`, `emit_order_test[ = "..."]`, `map_output`, `prefix = "..."`, `prefix_from_type[ = "..."]`, `suffix = "..."` or `separator = "..."`"#,
+            ))
         }) {
             // If parse_nested_meta returns Err, record it (don’t panic).
             errors.push(e);
         }
     }
 
+    // Derive the prefix from the type name: `InfaticaArgs` → "infatica"
+    if let Some(type_suffix) = type_suffix {
+        let name = input.ident.to_string();
+        let stem = name.strip_suffix(type_suffix.value().as_str()).unwrap_or(&name);
+        if config.prefix.is_some() {
+            errors.push(Error::new_spanned(&type_suffix, "`prefix_from_type` cannot be combined with `prefix`"));
+        } else if stem.is_empty() {
+            errors.push(Error::new_spanned(
+                &input.ident,
+                format!("`prefix_from_type` leaves no prefix: `{name}` is only the stripped suffix"),
+            ));
+        } else {
+            config.prefix = Some(stem.to_lowercase());
+        }
+    }
+
     // Return parsed flags + any collected errors for the caller to surface
-    (infer_keys, prefix, errors)
+    (config, errors)
 }
\ No newline at end of file
@@ -3,11 +3,15 @@
 //! Shared internal data structures used by the `#[derive(ApplyOverrides)]`
 //! procedural macro.
 //!
-//! This module defines two core enums that represent the **semantic
+//! This module defines the core types that represent the **semantic
 //! results of attribute parsing** before code generation occurs:
 //!
+//! - [`StructConfig`] → options of the struct-level `#[apply_overrides(...)]` attribute
 //! - [`FieldOverrideMeta`] → raw parse result for `#[override_key(...)]` attributes
+//! - [`FieldAttr`] → that parse result plus field flags such as `sensitive`
 //! - [`KeyStrategy`] → normalized representation of how to compute the final key
+//! - [`GeneratedField`] → per-field output handed back to `builder_gen`
+//! - [`KeyTable`] → all keys of the struct, emitted once as a `const` table
 //!
 //! These types form the boundary between the *parsing* stage (in `field_parser.rs`)
 //! and the *code generation* stage (in `builder_gen.rs`).
@@ -40,7 +44,7 @@
 //! struct-level prefix = "iproyal"
 //!         │
 //!         ▼
-//! KeyStrategy::Inferred { prefix: Some("iproyal") }
+//! KeyStrategy::Inferred { prefix: Some("iproyal"), suffix: None, separator: None }
 //! ```
 //!
 //! ## Design Principles
@@ -55,8 +59,42 @@
 //! - [`builder_gen`] consumes it, merges it with struct-level defaults,
 //!   and emits a [`KeyStrategy`] to drive code emission.
 
+use std::ops::Range;
+
+use quote::quote;
 use syn::LitStr;
 
+/// Options parsed from the struct-level `#[apply_overrides(...)]` attribute.
+///
+/// Field-level `prefix`, `suffix` and `separator` take precedence over these.
+#[derive(Default)]
+pub struct StructConfig {
+    /// Whether unannotated fields get inferred keys (`infer_keys`).
+    pub infer_keys: bool,
+
+    /// Prepended to inferred keys (e.g. `"iproyal"`), given or from `prefix_from_type`.
+    pub prefix: Option<String>,
+
+    /// Appended to inferred keys (e.g. `"endpoint"`).
+    pub suffix: Option<String>,
+
+    /// Replaces `_` in field names of inferred keys; `"."` when unset.
+    pub separator: Option<String>,
+
+    /// Whether every field must map or skip itself explicitly (`deny_unannotated`).
+    pub deny_unannotated: bool,
+
+    /// Whether to generate a test checking that keys follow field order (`emit_order_test`).
+    pub emit_order_test: bool,
+
+    /// Fixture of the generated test checking that later fields win on the built
+    /// configuration (`emit_order_test = "..."`).
+    pub order_test_fixture: Option<syn::Path>,
+
+    /// Whether to generate `as_override_map()` (`map_output`).
+    pub map_output: bool,
+}
+
 /// Represents how a single field-level `#[override_key(...)]` attribute was parsed.
 ///
 /// This enum reflects *exactly what was found* during parsing,
@@ -67,8 +105,22 @@ use syn::LitStr;
 /// - `Explicit(LitStr)` — The attribute provided a concrete key string,
 ///   e.g. `#[override_key = "iproyal.token"]`.
 ///
-/// - `Infer { prefix }` — The attribute requested key inference, optionally with
-///   a per-field prefix, e.g. `#[override_key(infer, prefix = "netnut")]`.
+/// - `Infer { prefix, suffix, separator }` — The attribute requested key inference,
+///   optionally with a per-field prefix, suffix and separator, e.g.
+///   `#[override_key(infer, prefix = "netnut", suffix = "endpoint")]`.
+///
+/// - `Split { separator, keys }` — The field's string value is split on `separator`
+///   and each part applied to its own key, e.g.
+///   `#[override_key(split = ":", keys("db.host", "db.port"))]`.
+///
+/// - `JsonRoot` — A lone `#[override_key(json)]`: the field's JSON object is merged
+///   at the configuration root.
+///
+/// - `Dynamic` — `#[override_key(dynamic)]`: the field holds `(key, value)` pairs
+///   known only at runtime, e.g. from repeated `--set key=value` flags.
+///
+/// - `Skip` — The field is explicitly excluded, e.g. `#[override_key(skip)]`,
+///   even when struct-level `infer_keys` is enabled.
 ///
 /// - `Invalid` — The attribute was present but malformed.
 ///   (The macro will emit a compile error but continue processing other fields.)
@@ -78,12 +130,36 @@ pub enum FieldOverrideMeta {
     /// Explicit key provided by the user.
     Explicit(LitStr),
 
-    /// Key should be inferred automatically; may include custom prefix.
+    /// Key should be inferred automatically; may include custom prefix and suffix.
     Infer {
         /// Optional string prefix (e.g. `"netnut"`).
         prefix: Option<String>,
+
+        /// Optional string suffix (e.g. `"endpoint"`).
+        suffix: Option<String>,
+
+        /// Optional replacement for `_` in the field name (e.g. `"-"`).
+        separator: Option<String>,
+    },
+
+    /// Value is split into one part per key.
+    Split {
+        /// Separator between the parts (e.g. `":"`).
+        separator: LitStr,
+
+        /// Keys receiving the parts, in order; at least two.
+        keys: Vec<LitStr>,
     },
 
+    /// JSON value merged at the root, without a key of its own.
+    JsonRoot,
+
+    /// Runtime `(key, value)` pairs, applied verbatim.
+    Dynamic,
+
+    /// Field is excluded from override generation.
+    Skip,
+
     /// Parsing failed — invalid attribute form or syntax.
     Invalid,
 
@@ -91,6 +167,105 @@ pub enum FieldOverrideMeta {
     None,
 }
 
+/// A parsed field attribute: the key mapping plus flags independent of it.
+///
+/// `sensitive` marks fields holding secrets, e.g.
+/// `#[override_key(key = "iproyal.client_secret", sensitive)]`; their keys are
+/// listed by the generated `sensitive_keys()` so callers can mask them.
+///
+/// `deprecated` marks the field's key as replaced, e.g.
+/// `#[override_key(deprecated = "iproyal.endpoint")]`.
+pub struct FieldAttr {
+    /// How the key is obtained.
+    pub meta: FieldOverrideMeta,
+
+    /// Whether the field's value must not be displayed.
+    pub sensitive: bool,
+
+    /// Replacement of the field's key, if it is deprecated.
+    pub deprecated: Option<Deprecation>,
+
+    /// Whether the field holds a `serde_json::Value` merged under its key (`json`).
+    pub json: bool,
+}
+
+/// A deprecated key's replacement, from `deprecated = "..."` and `strict`.
+#[derive(Clone)]
+pub struct Deprecation {
+    /// Key set along with the deprecated one (`{field}` not yet expanded).
+    pub replacement: LitStr,
+
+    /// Whether using the deprecated key is an error rather than a warning.
+    pub strict: bool,
+}
+
+/// Output of processing one field that produces an override.
+pub struct GeneratedField {
+    /// `builder.set_override(...)` code for this field.
+    pub snippet: proc_macro2::TokenStream,
+
+    /// Code pushing this field's `(key, value)` for `active_overrides()`.
+    pub active: proc_macro2::TokenStream,
+
+    /// `(key, accessor)` entry of a field setting one key with its plain value, read
+    /// by the shared loops of both methods; `snippet` and `active` are then empty.
+    pub accessor: Option<proc_macro2::TokenStream>,
+
+    /// Field name, listed with its key by `override_keys()`.
+    pub field: String,
+
+    /// Positions of the final configuration keys in the [`KeyTable`]; several for
+    /// `split` fields. A deprecated field's replacement key is not listed, so that
+    /// `override_keys()` maps it to the field replacing this one.
+    pub keys: Range<usize>,
+
+    /// Keys reported by `sensitive_keys()`: none, or all keys the field sets.
+    pub sensitive_keys: Vec<LitStr>,
+
+    /// The field's `#[cfg(...)]` attributes, repeated on everything generated for it.
+    pub cfg: proc_macro2::TokenStream,
+
+    /// Long clap flag of the field, without dashes, with the `clap` feature.
+    pub flag: Option<String>,
+
+    /// Whether the field is the struct's `dynamic` one.
+    pub dynamic: bool,
+}
+
+/// Keys of all fields, emitted once as `const __<STRUCT>_OVERRIDE_KEYS: &[&str]`.
+///
+/// Generated code indexes the table instead of repeating string literals, which
+/// keeps the impl of structs with many flags compact. Keys of `#[cfg(...)]`-gated
+/// fields are kept unconditionally so that positions do not depend on the cfg.
+pub struct KeyTable {
+    /// Name of the generated `const`.
+    pub ident: syn::Ident,
+
+    /// Keys in field declaration order.
+    pub keys: Vec<LitStr>,
+}
+
+impl KeyTable {
+    /// Appends `keys` and returns their positions.
+    pub fn insert(&mut self, keys: Vec<LitStr>) -> Range<usize> {
+        let start = self.keys.len();
+        self.keys.extend(keys);
+        start..self.keys.len()
+    }
+
+    /// Expression reading the key at `index`, e.g. `__CLIARGS_OVERRIDE_KEYS[3usize]`.
+    pub fn entry(&self, index: usize) -> proc_macro2::TokenStream {
+        let ident = &self.ident;
+        quote! { #ident[#index] }
+    }
+
+    /// Expression reading the keys in `range` as a slice.
+    pub fn slice(&self, range: &Range<usize>) -> proc_macro2::TokenStream {
+        let (ident, start, end) = (&self.ident, range.start, range.end);
+        quote! { #ident[#start..#end] }
+    }
+}
+
 /// Represents the *finalized strategy* for computing a key after
 /// merging field-level and struct-level configuration.
 ///
@@ -99,22 +274,57 @@ pub enum FieldOverrideMeta {
 /// ## Variants
 ///
 /// - `Explicit(LitStr)` — Use the given key string verbatim.
-/// - `Inferred { prefix }` — Construct a key by replacing underscores
-///   in the field name with dots (`_` → `.`), optionally prepending a prefix.
+/// - `Inferred { prefix, suffix, separator }` — Construct a key by replacing
+///   underscores in the field name with the separator (`_` → `.` by default),
+///   optionally prepending a prefix and appending a suffix. Prefix and suffix
+///   are always joined with dots.
+/// - `Split { separator, keys }` — Split the value on `separator` and apply the
+///   parts to `keys`, each used verbatim.
+/// - `JsonRoot` — No key: the JSON value's leaves are applied from the root.
+/// - `Dynamic` — No key: the field's runtime `(key, value)` pairs are applied as given.
 ///
 /// Example:
 /// ```text
-/// prefix = Some("iproyal")
+/// prefix = Some("iproyal"), suffix = None
 /// field ident = "region_id"
 /// → "iproyal.region.id"
+///
+/// prefix = None, suffix = Some("endpoint")
+/// field ident = "iproyal"
+/// → "iproyal.endpoint"
+///
+/// prefix = Some("iproyal"), separator = Some("-")
+/// field ident = "connect_timeout"
+/// → "iproyal.connect-timeout"
 /// ```
 pub enum KeyStrategy {
     /// Use a literal key string directly.
     Explicit(LitStr),
 
-    /// Infer key from field name and optional prefix.
+    /// Infer key from field name and optional prefix and suffix.
     Inferred {
         /// Optional prefix (e.g. `"iproyal"`).
         prefix: Option<String>,
+
+        /// Optional suffix (e.g. `"endpoint"`).
+        suffix: Option<String>,
+
+        /// Replacement for `_` in the field name; `"."` when `None`.
+        separator: Option<String>,
     },
+
+    /// Split the value into one part per key.
+    Split {
+        /// Separator between the parts (e.g. `":"`).
+        separator: LitStr,
+
+        /// Keys receiving the parts, in order.
+        keys: Vec<LitStr>,
+    },
+
+    /// Merge the field's JSON object at the configuration root; no key.
+    JsonRoot,
+
+    /// Apply the field's runtime `(key, value)` pairs; no key.
+    Dynamic,
 }
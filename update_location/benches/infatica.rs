@@ -0,0 +1,52 @@
+//! Parsing of Infatica's nested-array payloads.
+//!
+//! Compares flattening while deserializing ([`Flattened`]) with deserializing the
+//! nested `Vec<Vec<T>>` and flattening it afterwards, on a zip_codes-shaped
+//! payload: many small inner arrays, as the API returns them.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use std::hint::black_box;
+use update_location::infatica::{Flattened, InfaticaZipRecord};
+
+/// Builds a `[[zip record, ...], ...]` payload of `groups` arrays of `per_group` records.
+fn zip_payload(groups: usize, per_group: usize) -> String {
+    let group = |g: usize| {
+        let records: Vec<String> = (0..per_group)
+            .map(|i| {
+                format!(
+                    r#"{{"country":"DE","subdivision":"Region {g}","city":"City {g}","zip":"{:05}"}}"#,
+                    (g * per_group + i) % 100_000
+                )
+            })
+            .collect();
+        format!("[{}]", records.join(","))
+    };
+    let groups: Vec<String> = (0..groups).map(group).collect();
+    format!("[{}]", groups.join(","))
+}
+
+fn zip_codes(c: &mut Criterion) {
+    let mut bench = c.benchmark_group("infatica_zip_codes");
+    for (groups, per_group) in [(10_000, 10), (100, 1_000)] {
+        let payload = zip_payload(groups, per_group);
+        let size = format!("{groups}x{per_group}");
+        bench.throughput(Throughput::Bytes(payload.len() as u64));
+
+        bench.bench_with_input(BenchmarkId::new("nested_then_flatten", &size), &payload, |b, payload| {
+            b.iter(|| {
+                let nested: Vec<Vec<InfaticaZipRecord>> = serde_json::from_str(payload).unwrap();
+                black_box(nested.into_iter().flatten().collect::<Vec<_>>())
+            })
+        });
+        bench.bench_with_input(BenchmarkId::new("flattened", &size), &payload, |b, payload| {
+            b.iter(|| {
+                let flat: Flattened<InfaticaZipRecord> = serde_json::from_str(payload).unwrap();
+                black_box(flat.into_inner())
+            })
+        });
+    }
+    bench.finish();
+}
+
+criterion_group!(benches, zip_codes);
+criterion_main!(benches);
@@ -0,0 +1,70 @@
+//! Stages of the location pipeline, on the bundled fixtures in `../fixtures/`.
+//!
+//! The fixtures are synthetic but shaped like real responses: Infatica geo
+//! nodes as nested arrays of small groups, and an iproyal country tree mixing
+//! countries split into states with countries listing cities directly. Each
+//! stage is measured on its own, from parsed input to its output:
+//! - `infatica_parse`: deserializing the geo-node payload;
+//! - `iproyal_flatten`: flattening the country tree into leaves;
+//! - `unify`: converting both providers' data into unified records;
+//! - `normalize`: resolving country and subdivision codes of those records.
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+use std::hint::black_box;
+use update_location::infatica::{Flattened, InfaticaGeoNodeRecord, TargetingResolver};
+use update_location::iproyal::models::Root;
+use update_location::unified::LocationRecord;
+use update_location::{iproyal, normalize, unified};
+
+const INFATICA_GEO_NODES: &str = include_str!("../fixtures/infatica_geo_nodes.json");
+const IPROYAL_COUNTRIES: &str = include_str!("../fixtures/iproyal_countries.json");
+
+fn geo_nodes() -> Vec<InfaticaGeoNodeRecord> {
+    serde_json::from_str::<Flattened<_>>(INFATICA_GEO_NODES).unwrap().into_inner()
+}
+
+fn iproyal_root() -> Root {
+    serde_json::from_str(IPROYAL_COUNTRIES).unwrap()
+}
+
+fn unified_records() -> Vec<LocationRecord> {
+    let mut records = unified::from_iproyal(&iproyal::flatten(&iproyal_root()));
+    records.extend(unified::from_infatica(&geo_nodes(), &TargetingResolver::default()));
+    records
+}
+
+fn infatica_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("infatica_parse");
+    group.throughput(Throughput::Bytes(INFATICA_GEO_NODES.len() as u64));
+    group.bench_function("geo_nodes", |b| {
+        b.iter(|| serde_json::from_str::<Flattened<InfaticaGeoNodeRecord>>(black_box(INFATICA_GEO_NODES)).unwrap())
+    });
+    group.finish();
+}
+
+fn iproyal_flatten(c: &mut Criterion) {
+    let root = iproyal_root();
+    c.bench_function("iproyal_flatten", |b| b.iter(|| iproyal::flatten(black_box(&root))));
+}
+
+fn unify(c: &mut Criterion) {
+    let locations = iproyal::flatten(&iproyal_root());
+    let nodes = geo_nodes();
+    let mut group = c.benchmark_group("unify");
+    group.bench_function("iproyal", |b| b.iter(|| unified::from_iproyal(black_box(&locations))));
+    group.bench_function("infatica", |b| b.iter(|| unified::from_infatica(black_box(&nodes), &TargetingResolver::default())));
+    group.finish();
+}
+
+fn normalize_records(c: &mut Criterion) {
+    let records = unified_records();
+    let mut group = c.benchmark_group("normalize");
+    group.throughput(Throughput::Elements(records.len() as u64));
+    group.bench_function("records", |b| {
+        b.iter_batched_ref(|| records.clone(), |records| normalize::normalize(records), BatchSize::SmallInput)
+    });
+    group.finish();
+}
+
+criterion_group!(benches, infatica_parse, iproyal_flatten, unify, normalize_records);
+criterion_main!(benches);
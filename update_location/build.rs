@@ -0,0 +1,17 @@
+//! Generates the gRPC server and client from `proto/` when the `grpc` feature is enabled.
+
+fn main() {
+    #[cfg(feature = "grpc")]
+    grpc();
+}
+
+#[cfg(feature = "grpc")]
+fn grpc() {
+    const PROTO: &str = "proto/location.proto";
+
+    println!("cargo:rerun-if-changed={PROTO}");
+    let descriptors = protox::compile([PROTO], ["proto"]).expect("failed to parse the gRPC protos");
+    tonic_prost_build::configure()
+        .compile_fds(descriptors)
+        .expect("failed to generate the gRPC code");
+}
@@ -0,0 +1,53 @@
+//! # Coverage alerts
+//!
+//! Evaluates the configured [`AlertRule`]s against normalized records. A rule is
+//! violated when the summed node estimates of its provider in its country fall
+//! below `min_nodes`; a provider that failed to fetch therefore violates every
+//! rule that targets it.
+
+use std::fmt;
+use serde::Serialize;
+use crate::models::AlertRule;
+use crate::unified::{LocationRecord, Provider};
+
+/// A rule whose threshold was not met.
+#[derive(Debug, Clone, Serialize)]
+pub struct AlertViolation {
+    pub provider: Provider,
+    pub country: String,
+    pub min_nodes: u64,
+    /// Nodes actually observed.
+    pub nodes: u64,
+}
+
+impl fmt::Display for AlertViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {}: {} nodes, expected at least {}",
+            self.provider, self.country, self.nodes, self.min_nodes
+        )
+    }
+}
+
+/// Returns the violated rules, in configuration order.
+pub fn evaluate(records: &[LocationRecord], rules: &[AlertRule]) -> Vec<AlertViolation> {
+    rules
+        .iter()
+        .filter_map(|rule| {
+            let country = rule.country.to_ascii_uppercase();
+            let nodes = records
+                .iter()
+                .filter(|r| r.provider == rule.provider && r.country == country)
+                .filter_map(LocationRecord::node_estimate)
+                .sum::<u64>();
+
+            (nodes < rule.min_nodes).then_some(AlertViolation {
+                provider: rule.provider,
+                country,
+                min_nodes: rule.min_nodes,
+                nodes,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,11 @@
+use thiserror::Error;
+
+/// Failure while writing to the provider cache.
+#[derive(Debug, Error)]
+pub enum CacheError {
+    #[error("cache I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("failed to serialize cached data: {0}")]
+    Serialize(#[from] serde_json::Error),
+}
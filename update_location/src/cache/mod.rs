@@ -0,0 +1,234 @@
+//! # Provider response cache
+//!
+//! Stores each provider's parsed dataset on disk so repeated local runs can skip
+//! vendor API calls. Datasets are kept apart per `key`, a hash of the settings that
+//! change what a fetch returns (see [`key`]), so editing the endpoint, credentials
+//! or filters of a provider never serves data fetched with the old ones. Layout
+//! under the cache directory:
+//!
+//! ```text
+//! <dir>/<provider>/<key>/<sha256>.json   dataset blob, named by the hash of its contents
+//! <dir>/<provider>/<key>/latest.json     { "hash": "...", "fetched_at": <unix seconds> }
+//! ```
+//!
+//! Datasets are always written after a successful fetch; they are only read back
+//! when a maximum age is configured (`cache.max_age` / `--max-age`) and the latest
+//! entry is younger than it. Cache failures never fail a run: unreadable entries
+//! count as a miss, and write errors are reported as warnings.
+
+mod errors;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use crate::{status, ui};
+
+pub use errors::CacheError;
+
+const LATEST_FILE: &str = "latest.json";
+
+/// Pointer to the most recent dataset blob of a provider.
+#[derive(Serialize, Deserialize)]
+struct LatestEntry {
+    hash: String,
+    fetched_at: u64,
+}
+
+/// Handle to the on-disk cache directory.
+#[derive(Clone)]
+pub struct Cache {
+    dir: PathBuf,
+    max_age: Option<Duration>,
+}
+
+impl Cache {
+    pub fn new(dir: impl Into<PathBuf>, max_age: Option<Duration>) -> Self {
+        Self {
+            dir: dir.into(),
+            max_age,
+        }
+    }
+
+    /// Returns the cached dataset for `provider` fetched with the settings hashed to `key`,
+    /// if one exists and is fresh enough.
+    ///
+    /// Always `None` when no maximum age is configured.
+    pub fn load<T: DeserializeOwned>(&self, provider: &str, key: &str) -> Option<(T, Duration)> {
+        let max_age = self.max_age?;
+        let dir = self.dir.join(provider).join(key);
+
+        let latest: LatestEntry = serde_json::from_slice(&fs::read(dir.join(LATEST_FILE)).ok()?).ok()?;
+        let age = now_secs().saturating_sub(latest.fetched_at);
+        if Duration::from_secs(age) > max_age {
+            return None;
+        }
+
+        let data = serde_json::from_slice(&fs::read(blob_path(&dir, &latest.hash)).ok()?).ok()?;
+        Some((data, Duration::from_secs(age)))
+    }
+
+    /// Writes `data` as the latest dataset for `provider` fetched with the settings hashed to `key`.
+    ///
+    /// Identical payloads hash to the same blob, so unchanged data is not duplicated;
+    /// the previous blob is removed once the pointer moves to a different one.
+    pub fn store<T: Serialize>(&self, provider: &str, key: &str, data: &T) -> Result<(), CacheError> {
+        let dir = self.dir.join(provider).join(key);
+        fs::create_dir_all(&dir)?;
+
+        let bytes = serde_json::to_vec(data)?;
+        let hash = format!("{:x}", Sha256::digest(&bytes));
+
+        let previous: Option<LatestEntry> = fs::read(dir.join(LATEST_FILE))
+            .ok()
+            .and_then(|b| serde_json::from_slice(&b).ok());
+
+        fs::write(blob_path(&dir, &hash), &bytes)?;
+        let latest = LatestEntry { hash, fetched_at: now_secs() };
+        fs::write(dir.join(LATEST_FILE), serde_json::to_vec(&latest)?)?;
+
+        if let Some(previous) = previous
+            && previous.hash != latest.hash
+        {
+            let _ = fs::remove_file(blob_path(&dir, &previous.hash));
+        }
+
+        Ok(())
+    }
+
+    /// Serves `provider` from the cache when fresh, otherwise awaits `fetch` and caches its result.
+    pub async fn get_or_fetch<T, E, F>(&self, provider: &str, key: &str, fetch: F) -> Result<T, E>
+    where
+        T: Serialize + DeserializeOwned,
+        F: Future<Output = Result<T, E>>,
+    {
+        if let Some((data, age)) = self.load::<T>(provider, key) {
+            status!("{provider}: using cached data ({}s old)", age.as_secs());
+            return Ok(data);
+        }
+
+        let result = fetch.await;
+        if let Ok(data) = &result
+            && let Err(e) = self.store(provider, key, data)
+        {
+            ui::suspend(|| eprintln!("{provider}: failed to update cache: {e}"));
+        }
+        result
+    }
+}
+
+/// Cache key of the settings `fetch` that decide what a fetch returns.
+///
+/// A hash, so credentials among them are not written to disk.
+pub fn key(fetch: &impl Serialize) -> String {
+    let bytes = serde_json::to_vec(fetch).unwrap_or_default();
+    format!("{:x}", Sha256::digest(bytes))[..16].to_string()
+}
+
+fn blob_path(dir: &Path, hash: &str) -> PathBuf {
+    dir.join(format!("{hash}.json"))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An empty cache directory unique to `name`.
+    fn dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("update_location_cache_{}_{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    /// Dataset blobs stored for `provider` under `key`.
+    fn blobs(dir: &Path, provider: &str, key: &str) -> Vec<String> {
+        let mut names = fs::read_dir(dir.join(provider).join(key))
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .filter(|name| name != LATEST_FILE)
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn stored_datasets_are_loaded_back() {
+        let dir = dir("load");
+        let cache = Cache::new(&dir, Some(Duration::from_secs(60)));
+
+        cache.store("iproyal", "k1", &vec!["DE", "US"]).unwrap();
+        let (data, age) = cache.load::<Vec<String>>("iproyal", "k1").unwrap();
+        assert_eq!(data, ["DE", "US"]);
+        assert!(age <= Duration::from_secs(1));
+
+        // Other settings, other providers and caches without a maximum age miss
+        assert!(cache.load::<Vec<String>>("iproyal", "k2").is_none());
+        assert!(cache.load::<Vec<String>>("infatica", "k1").is_none());
+        assert!(Cache::new(&dir, None).load::<Vec<String>>("iproyal", "k1").is_none());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn datasets_older_than_the_maximum_age_are_not_loaded() {
+        let dir = dir("expiry");
+        let cache = Cache::new(&dir, Some(Duration::from_secs(60)));
+        cache.store("iproyal", "k1", &vec!["DE"]).unwrap();
+
+        let latest_path = dir.join("iproyal").join("k1").join(LATEST_FILE);
+        let mut latest: LatestEntry = serde_json::from_slice(&fs::read(&latest_path).unwrap()).unwrap();
+        latest.fetched_at -= 61;
+        fs::write(&latest_path, serde_json::to_vec(&latest).unwrap()).unwrap();
+
+        assert!(cache.load::<Vec<String>>("iproyal", "k1").is_none());
+        let (_, age) = Cache::new(&dir, Some(Duration::from_secs(120))).load::<Vec<String>>("iproyal", "k1").unwrap();
+        assert!(age >= Duration::from_secs(61));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn identical_payloads_share_one_blob() {
+        let dir = dir("dedup");
+        let cache = Cache::new(&dir, Some(Duration::from_secs(60)));
+
+        cache.store("iproyal", "k1", &vec!["DE"]).unwrap();
+        cache.store("iproyal", "k1", &vec!["DE"]).unwrap();
+
+        let hash = format!("{:x}", Sha256::digest(br#"["DE"]"#));
+        assert_eq!(blobs(&dir, "iproyal", "k1"), [format!("{hash}.json")]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn the_previous_blob_is_removed() {
+        let dir = dir("cleanup");
+        let cache = Cache::new(&dir, Some(Duration::from_secs(60)));
+
+        cache.store("iproyal", "k1", &vec!["DE"]).unwrap();
+        cache.store("iproyal", "k1", &vec!["US"]).unwrap();
+
+        let hash = format!("{:x}", Sha256::digest(br#"["US"]"#));
+        assert_eq!(blobs(&dir, "iproyal", "k1"), [format!("{hash}.json")]);
+        assert_eq!(cache.load::<Vec<String>>("iproyal", "k1").unwrap().0, ["US"]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn keys_change_with_the_fetch_settings() {
+        assert_eq!(key(&("https://a.example/", 50)), key(&("https://a.example/", 50)));
+        assert_ne!(key(&("https://a.example/", 50)), key(&("https://a.example/", 100)));
+        assert_ne!(key(&("https://a.example/", 50)), key(&("https://b.example/", 50)));
+    }
+}
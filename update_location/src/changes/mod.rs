@@ -0,0 +1,276 @@
+//! # Run-to-run changes
+//!
+//! Compares the unified records of two fetches. Records are identified by
+//! provider, country, subdivision, city and ISP; a record present in both whose
+//! node count or availability differs is reported as [`ChangeKind::CountChanged`].
+//! Vendors may report a location more than once, e.g. Infatica per ASN and ZIP
+//! code, so the records of each location are [aggregated](aggregate) first.
+//!
+//! [`ChangeLog`] keeps the records of the last run next to the provider cache:
+//!
+//! ```text
+//! <cache dir>/changes/records.json
+//! ```
+//!
+//! Only providers fetched successfully take part in a comparison, so a provider
+//! outage is not reported as every one of its locations disappearing.
+//!
+//! The stored records carry their [schema version](crate::unified::schema): older
+//! ones are migrated before comparing, while records a newer build stored make the
+//! comparison fail instead of reporting layout differences as changes.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::cache::CacheError;
+use crate::unified::schema::{self, SchemaError};
+use crate::unified::{LocationRecord, Provider, SCHEMA_VERSION};
+
+const STATE_DIR: &str = "changes";
+const STATE_FILE: &str = "records.json";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    CountChanged,
+}
+
+/// One location that differs between two fetches.
+#[derive(Debug, Clone, Serialize)]
+pub struct Change {
+    pub kind: ChangeKind,
+    /// The newer record; the older one for [`ChangeKind::Removed`].
+    pub record: LocationRecord,
+    /// Node estimates before and after; `None` on the side the record is missing from.
+    pub nodes_before: Option<u64>,
+    pub nodes_after: Option<u64>,
+}
+
+/// Identity of a record across fetches.
+type RecordKey<'a> = (Provider, &'a str, Option<&'a str>, Option<&'a str>, Option<&'a str>);
+
+fn key(record: &LocationRecord) -> RecordKey<'_> {
+    (
+        record.provider,
+        &record.country,
+        record.subdivision.as_deref(),
+        record.city.as_deref(),
+        record.isp.as_deref(),
+    )
+}
+
+/// One record per location of `records`, in the order of their first record.
+///
+/// The first record of a location stands for all of them, with their node counts
+/// added up; the availability is that of the first record reporting one.
+fn aggregate(records: &[LocationRecord]) -> Vec<LocationRecord> {
+    let mut index: HashMap<RecordKey, usize> = HashMap::new();
+    let mut aggregated: Vec<LocationRecord> = Vec::new();
+    for record in records {
+        let Some(&i) = index.get(&key(record)) else {
+            index.insert(key(record), aggregated.len());
+            aggregated.push(record.clone());
+            continue;
+        };
+        let location = &mut aggregated[i];
+        if let Some(nodes) = record.nodes {
+            location.nodes = Some(location.nodes.unwrap_or_default() + nodes);
+        }
+        location.availability = location.availability.or(record.availability);
+    }
+    aggregated
+}
+
+/// Locations of `current` added, removed or changed relative to `previous`.
+///
+/// Locations reported more than once are compared as [aggregated](aggregate).
+/// Changes are ordered like `current`, followed by the removed records in the order of `previous`.
+pub fn diff(previous: &[LocationRecord], current: &[LocationRecord]) -> Vec<Change> {
+    let (previous, current) = (aggregate(previous), aggregate(current));
+    let mut old: HashMap<RecordKey, &LocationRecord> = previous.iter().map(|r| (key(r), r)).collect();
+
+    let mut changes = Vec::new();
+    for record in &current {
+        match old.remove(&key(record)) {
+            None => changes.push(Change {
+                kind: ChangeKind::Added,
+                record: record.clone(),
+                nodes_before: None,
+                nodes_after: record.node_estimate(),
+            }),
+            Some(before) if before.nodes != record.nodes || before.availability != record.availability => {
+                changes.push(Change {
+                    kind: ChangeKind::CountChanged,
+                    record: record.clone(),
+                    nodes_before: before.node_estimate(),
+                    nodes_after: record.node_estimate(),
+                })
+            }
+            Some(_) => {}
+        }
+    }
+
+    changes.extend(previous.iter().filter(|r| old.contains_key(&key(r))).map(|r| Change {
+        kind: ChangeKind::Removed,
+        record: r.clone(),
+        nodes_before: r.node_estimate(),
+        nodes_after: None,
+    }));
+
+    changes
+}
+
+/// Changes from the state before `earlier` to the state after `later`, where `later`
+/// was observed after `earlier`.
+///
+/// A location changed by both is reported once, from its state before `earlier` to
+/// its state after `later`; one added and removed again, or whose node count went
+/// back to where it was, is not reported at all. Changes keep the order of
+/// `earlier`, followed by those only in `later`.
+pub fn squash(earlier: Vec<Change>, later: Vec<Change>) -> Vec<Change> {
+    let index: HashMap<RecordKey, usize> = later.iter().enumerate().map(|(i, c)| (key(&c.record), i)).collect();
+    let matches: Vec<Option<usize>> = earlier.iter().map(|c| index.get(&key(&c.record)).copied()).collect();
+    let mut later: Vec<Option<Change>> = later.into_iter().map(Some).collect();
+
+    let mut squashed = Vec::new();
+    for (first, matched) in earlier.into_iter().zip(matches) {
+        let Some(second) = matched.and_then(|i| later[i].take()) else {
+            squashed.push(first);
+            continue;
+        };
+        let kind = match (first.kind, second.kind) {
+            (ChangeKind::Added, ChangeKind::Removed) => continue,
+            (ChangeKind::Added, _) => ChangeKind::Added,
+            (_, ChangeKind::Removed) => ChangeKind::Removed,
+            (ChangeKind::Removed, _) | (ChangeKind::CountChanged, _) => ChangeKind::CountChanged,
+        };
+        let record = if kind == ChangeKind::Removed { first.record } else { second.record };
+        let change = Change { kind, record, nodes_before: first.nodes_before, nodes_after: second.nodes_after };
+        if kind == ChangeKind::CountChanged && change.nodes_before == change.nodes_after {
+            continue;
+        }
+        squashed.push(change);
+    }
+    squashed.extend(later.into_iter().flatten());
+    squashed
+}
+
+/// Layout of the stored state; version 1 stored the bare list of records.
+#[derive(Serialize, Deserialize)]
+struct State<R> {
+    schema_version: u32,
+    records: Vec<R>,
+}
+
+/// Records of the last run, stored in the cache directory.
+pub struct ChangeLog {
+    path: PathBuf,
+}
+
+impl ChangeLog {
+    pub fn new(cache_dir: &Path) -> Self {
+        Self {
+            path: cache_dir.join(STATE_DIR).join(STATE_FILE),
+        }
+    }
+
+    /// Records of the last run, migrated to the current schema; `None` before the
+    /// first one. An unreadable file counts as missing.
+    pub fn previous(&self) -> Result<Option<Vec<LocationRecord>>, SchemaError> {
+        let Some(stored) = fs::read(&self.path).ok().and_then(|data| serde_json::from_slice::<Value>(&data).ok()) else {
+            return Ok(None);
+        };
+        let state = match stored {
+            Value::Array(records) => State { schema_version: schema::UNVERSIONED, records },
+            stored => match serde_json::from_value::<State<Value>>(stored) {
+                Ok(state) => state,
+                Err(_) => return Ok(None),
+            },
+        };
+        schema::migrate(state.schema_version, state.records).map(Some)
+    }
+
+    /// Changes of the `fetched` providers since the last run; `None` before the first one.
+    pub fn compare(&self, current: &[LocationRecord], fetched: &[Provider]) -> Result<Option<Vec<Change>>, SchemaError> {
+        let Some(previous) = self.previous()? else {
+            return Ok(None);
+        };
+        let before = previous.into_iter().filter(|r| fetched.contains(&r.provider)).collect::<Vec<_>>();
+        let after = current.iter().filter(|r| fetched.contains(&r.provider)).cloned().collect::<Vec<_>>();
+        Ok(Some(diff(&before, &after)))
+    }
+
+    /// Stores `current` as the last run of the `fetched` providers; other providers keep
+    /// their previous records.
+    ///
+    /// Call once the changes are delivered, so a failed delivery is retried by the next run.
+    pub fn record(&self, current: &[LocationRecord], fetched: &[Provider]) -> Result<(), CacheError> {
+        let mut next: Vec<LocationRecord> = self
+            .previous()
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|r| !fetched.contains(&r.provider))
+            .collect();
+        next.extend(current.iter().filter(|r| fetched.contains(&r.provider)).cloned());
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let state = State { schema_version: SCHEMA_VERSION, records: next };
+        fs::write(&self.path, serde_json::to_vec(&state)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(city: &str, nodes: u64) -> LocationRecord {
+        LocationRecord {
+            subdivision: Some("Berlin".to_string()),
+            city: Some(city.to_string()),
+            isp: Some("Telekom".to_string()),
+            nodes: Some(nodes),
+            ..LocationRecord::sample(Provider::Infatica, "DE")
+        }
+    }
+
+    fn summary(changes: &[Change]) -> Vec<(ChangeKind, &str, Option<u64>, Option<u64>)> {
+        changes
+            .iter()
+            .map(|c| (c.kind, c.record.city.as_deref().unwrap(), c.nodes_before, c.nodes_after))
+            .collect()
+    }
+
+    #[test]
+    fn aggregates_locations_reported_more_than_once() {
+        // One record per ASN / ZIP code of the same location on both sides
+        let previous = [record("Berlin", 3), record("Hamburg", 1), record("Berlin", 4), record("Hamburg", 1)];
+        let current = [record("Berlin", 4), record("Berlin", 2), record("Berlin", 1), record("Hamburg", 2)];
+        assert!(diff(&previous, &current).is_empty());
+
+        let current = [record("Berlin", 5), record("Berlin", 5), record("Munich", 1), record("Munich", 1)];
+        assert_eq!(
+            summary(&diff(&previous, &current)),
+            [
+                (ChangeKind::CountChanged, "Berlin", Some(7), Some(10)),
+                (ChangeKind::Added, "Munich", None, Some(2)),
+                (ChangeKind::Removed, "Hamburg", Some(2), None),
+            ],
+        );
+    }
+
+    #[test]
+    fn squashes_changes_of_consecutive_runs() {
+        let first = diff(&[record("Berlin", 3)], &[record("Berlin", 5), record("Hamburg", 1)]);
+        let second = diff(&[record("Berlin", 5), record("Hamburg", 1)], &[record("Berlin", 3)]);
+        assert!(squash(first, second).is_empty());
+    }
+}
@@ -0,0 +1,114 @@
+//! # Clock
+//!
+//! Code that waits or measures time, such as the retry delays and fetch
+//! deadlines of the [pipeline](crate::pipeline) or the expiry of iproyal tokens,
+//! reads it through a [`Clock`] rather than from tokio directly. Runs use the
+//! [`SystemClock`]; a [`ManualClock`] lets unit tests exercise the same code
+//! without waiting.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Future returned by [`Clock::sleep`], boxed so clocks can be trait objects.
+pub type Sleep = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// Source of the current time and of delays.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+
+    /// Completes once `duration` has passed on this clock.
+    fn sleep(&self, duration: Duration) -> Sleep;
+}
+
+/// The clock of the tokio runtime.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) -> Sleep {
+        Box::pin(tokio::time::sleep(duration))
+    }
+}
+
+/// A shared [`SystemClock`], the default wherever a clock can be injected.
+pub fn system() -> Arc<dyn Clock> {
+    Arc::new(SystemClock)
+}
+
+/// A clock that only moves when told to or slept on.
+///
+/// Sleeping advances the clock by the slept duration and completes after
+/// yielding once, so anything racing a sleep, such as a fetch bounded by a
+/// deadline, gets one chance to finish first. Every sleep is recorded for
+/// assertions. Clones share the same time.
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    state: Arc<Mutex<ManualState>>,
+}
+
+#[derive(Debug)]
+struct ManualState {
+    now: Instant,
+    sleeps: Vec<Duration>,
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ManualClock {
+    /// A clock starting at the current instant.
+    pub fn new() -> Self {
+        Self { state: Arc::new(Mutex::new(ManualState { now: Instant::now(), sleeps: Vec::new() })) }
+    }
+
+    /// Moves the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        self.state.lock().expect("clock lock poisoned").now += duration;
+    }
+
+    /// The durations slept so far, in order.
+    pub fn sleeps(&self) -> Vec<Duration> {
+        self.state.lock().expect("clock lock poisoned").sleeps.clone()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.state.lock().expect("clock lock poisoned").now
+    }
+
+    fn sleep(&self, duration: Duration) -> Sleep {
+        let state = self.state.clone();
+        Box::pin(async move {
+            tokio::task::yield_now().await;
+            let mut state = state.lock().expect("clock lock poisoned");
+            state.now += duration;
+            state.sleeps.push(duration);
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn manual_sleeps_advance_the_clock_at_once() {
+        let clock = ManualClock::new();
+        let start = clock.now();
+        clock.sleep(Duration::from_secs(3600)).await;
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(clock.now() - start, Duration::from_secs(3601));
+        assert_eq!(clock.sleeps(), [Duration::from_secs(3600)]);
+    }
+}
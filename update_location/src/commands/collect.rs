@@ -0,0 +1,190 @@
+use tokio_util::sync::CancellationToken;
+use crate::cache::Cache;
+use crate::models::AppConfig;
+use crate::normalize::{self, ValidationReport};
+use crate::pipeline::{self, Dataset, Outcome};
+use crate::unified::{self, LocationRecord, Provider};
+use crate::infatica::InfaticaQueryResults;
+use crate::iproyal::models::{IPRoyalQueryResults, Root};
+use crate::{geo, status};
+
+/// Normalized records of a run.
+pub struct Collected {
+    pub records: Vec<LocationRecord>,
+    pub report: ValidationReport,
+    /// The iproyal location tree as returned by the API, if iproyal was fetched.
+    pub iproyal_tree: Option<Root>,
+    /// Providers whose data is complete.
+    pub fetched: Vec<Provider>,
+    /// Records dropped by the `dedup` policy as reported by a preferred provider.
+    pub deduped: usize,
+}
+
+/// Fetches the given providers concurrently (skipping disabled ones) and returns their normalized unified records,
+/// with their provenance in run `run_id`.
+///
+/// Each provider gets a progress spinner, see [`pipeline::fetch_all`].
+/// Provider failures and timeouts are reported on stderr and that provider contributes
+/// no records, so commands can still work with whatever data was retrieved. The same
+/// applies to providers still being fetched when `cancel` fires.
+pub async fn collect(
+    cfg: &AppConfig,
+    cache: &Cache,
+    providers: &[Provider],
+    run_id: &str,
+    cancel: &CancellationToken,
+) -> Collected {
+    let mut records = Vec::new();
+    let mut iproyal_tree = None;
+    let mut fetched = Vec::new();
+    for run in pipeline::fetch_all(cfg, cache, providers, cancel).await {
+        match run.outcome {
+            Outcome::Fetched(dataset) => {
+                records.extend(dataset.records(cfg, run_id));
+                fetched.push(run.provider);
+                if let Dataset::IPRoyal(results) = dataset {
+                    iproyal_tree = Some(results.into_countries());
+                }
+            }
+            Outcome::Failed(errors) => {
+                for err in errors {
+                    eprintln!("{}: {err}", run.provider);
+                }
+            }
+            Outcome::Panicked(err) => eprintln!("{}: fetch task failed: {err}", run.provider),
+            Outcome::TimedOut(limit) => {
+                eprintln!("{}: no response within fetch_timeout ({})", run.provider, humantime::format_duration(limit));
+            }
+            Outcome::Cancelled => {}
+        }
+    }
+
+    prepare(cfg, records, iproyal_tree, fetched)
+}
+
+/// Loads the given, enabled providers from `cache`, however old their data, and
+/// returns their normalized unified records, or the providers without cached data.
+pub fn collect_cached(
+    cfg: &AppConfig,
+    cache: &Cache,
+    providers: &[Provider],
+    run_id: &str,
+) -> Result<Collected, Vec<Provider>> {
+    let mut records = Vec::new();
+    let mut iproyal_tree = None;
+    let mut missing = Vec::new();
+    let mut fetched = Vec::new();
+    let enabled = [
+        (Provider::IPRoyal, cfg.iproyal.is_some()),
+        (Provider::Infatica, cfg.infatica.is_some()),
+    ];
+    for (provider, _) in enabled.into_iter().filter(|(p, enabled)| *enabled && providers.contains(p)) {
+        let loaded = match provider {
+            Provider::IPRoyal => cfg
+                .iproyal
+                .as_ref()
+                .and_then(|c| cache.load::<IPRoyalQueryResults>(provider.as_str(), &c.get_cache_key()))
+                .map(|(results, age)| {
+                    let dataset = Dataset::IPRoyal(results);
+                    let records = dataset.records(cfg, run_id);
+                    if let Dataset::IPRoyal(results) = dataset {
+                        iproyal_tree = Some(results.into_countries());
+                    }
+                    (records, age)
+                }),
+            Provider::Infatica => cfg
+                .infatica
+                .as_ref()
+                .and_then(|c| cache.load::<InfaticaQueryResults>(provider.as_str(), &c.get_cache_key()))
+                .map(|(results, age)| (Dataset::Infatica(results).records(cfg, run_id), age)),
+        };
+        match loaded {
+            Some((cached, age)) => {
+                status!("{provider}: using cached data ({} old)", humantime::format_duration(age));
+                records.extend(cached);
+                fetched.push(provider);
+            }
+            None => missing.push(provider),
+        }
+    }
+    if !missing.is_empty() {
+        return Err(missing);
+    }
+    Ok(prepare(cfg, records, iproyal_tree, fetched))
+}
+
+/// Normalizes `records`, transliterating city names with `matching.transliterate`,
+/// resolves duplicates per `dedup` and adds coordinates from `geo.dataset`.
+///
+/// Every command working with unified records prepares them here, so reports,
+/// exports, sinks and notifications of the same data agree.
+pub fn prepare(cfg: &AppConfig, mut records: Vec<LocationRecord>, iproyal_tree: Option<Root>, fetched: Vec<Provider>) -> Collected {
+    let report = normalize::normalize(&mut records);
+    #[cfg(feature = "translit")]
+    if cfg.matching.get_transliterate() {
+        normalize::transliterate(&mut records);
+    }
+
+    let deduped = cfg.dedup.as_ref().map_or(0, |policy| unified::dedup(&mut records, policy));
+    if deduped > 0 {
+        status!("dedup: dropped {deduped} record(s) reported by several providers");
+    }
+
+    if let Some(dataset) = cfg.geo.get_dataset()
+        && let Err(err) = geo::enrich(&mut records, dataset)
+    {
+        eprintln!("geo enrichment failed: {err}");
+    }
+
+    Collected { records, report, iproyal_tree, fetched, deduped }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::DedupConfig;
+    use super::*;
+
+    fn record(provider: Provider, nodes: u64) -> LocationRecord {
+        LocationRecord {
+            city: Some("Berlin".to_string()),
+            isp: Some("Telekom".to_string()),
+            nodes: Some(nodes),
+            ..LocationRecord::sample(provider, "de")
+        }
+    }
+
+    #[test]
+    fn resolves_duplicates_per_the_dedup_policy() {
+        let records = vec![record(Provider::IPRoyal, 3), record(Provider::Infatica, 5)];
+        let fetched = vec![Provider::IPRoyal, Provider::Infatica];
+
+        let kept = prepare(&AppConfig::default(), records.clone(), None, fetched.clone());
+        assert_eq!((kept.records.len(), kept.deduped), (2, 0));
+
+        let cfg = AppConfig { dedup: Some(DedupConfig::new(vec![Provider::Infatica], None)), ..AppConfig::default() };
+        let deduped = prepare(&cfg, records, None, fetched);
+        assert_eq!(deduped.deduped, 1);
+        assert_eq!(deduped.records.iter().map(|r| (r.provider, r.country.as_str())).collect::<Vec<_>>(), [(Provider::Infatica, "DE")]);
+    }
+
+    #[cfg(feature = "translit")]
+    #[test]
+    fn transliterates_per_the_matching_toggle() {
+        let mut moscow = record(Provider::IPRoyal, 2);
+        moscow.city = Some("Москва".to_string());
+        let mut records = vec![record(Provider::Infatica, 1), moscow];
+        records[0].city = Some("Moskva".to_string());
+        let fetched = vec![Provider::Infatica, Provider::IPRoyal];
+        let enabled = AppConfig {
+            matching: serde_json::from_value(serde_json::json!({ "transliterate": true })).unwrap(),
+            ..AppConfig::default()
+        };
+
+        let off = prepare(&AppConfig::default(), records.clone(), None, fetched.clone());
+        assert_eq!(off.records[1].city_ascii, None);
+        assert_eq!(crate::matching::coverage(&off.records, 0.92).len(), 2);
+        let on = prepare(&enabled, records, None, fetched);
+        assert_eq!(on.records[1].city_ascii.as_deref(), Some("Moskva"));
+        assert_eq!(crate::matching::coverage(&on.records, 0.92).len(), 1);
+    }
+}
@@ -0,0 +1,98 @@
+//! `update_location compare`: side-by-side coverage of the selected providers.
+//!
+//! Prints two tables built from the cross-provider [`coverage`] view:
+//! - per country: number of cities and summed node estimates per provider,
+//!   plus how many cities only one provider serves;
+//! - per city: node estimates per provider, with cities served by a single
+//!   provider flagged as `only <provider>`.
+//!
+//! Node figures are Infatica node counts and, for iproyal, the lower bound of
+//! the reported availability.
+
+use std::collections::BTreeMap;
+use std::process::ExitCode;
+use tokio_util::sync::CancellationToken;
+use crate::cache::Cache;
+use crate::commands::collect::{collect, Collected};
+use crate::commands::table::print_table;
+use crate::matching::{coverage, CoverageEntry};
+use crate::models::{AppConfig, CompareArgs};
+use crate::shutdown::EXIT_INTERRUPTED;
+use crate::unified::Provider;
+use crate::run_id;
+
+pub async fn run(cfg: &AppConfig, args: &CompareArgs, cancel: &CancellationToken) -> ExitCode {
+    let cache = Cache::new(cfg.cache.get_dir(), cfg.cache.get_max_age().copied());
+    let Collected { records, report, .. } = collect(cfg, &cache, &args.providers, run_id::current(), cancel).await;
+
+    if !report.is_clean() {
+        eprint!("normalization issues:\n{report}");
+    }
+
+    let country_filter = args.country.as_deref().map(str::to_ascii_uppercase);
+    let entries: Vec<CoverageEntry> = coverage(&records, cfg.matching.get_threshold())
+        .into_iter()
+        .filter(|e| country_filter.as_ref().is_none_or(|c| *c == e.country))
+        .collect();
+
+    print_countries(&entries, &args.providers);
+    println!();
+    print_cities(&entries, &args.providers, args.only_exclusive);
+
+    if cancel.is_cancelled() {
+        eprintln!("interrupted: tables only cover providers fetched before the signal");
+        ExitCode::from(EXIT_INTERRUPTED)
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn print_countries(entries: &[CoverageEntry], providers: &[Provider]) {
+    let mut header = vec!["COUNTRY".to_string()];
+    for p in providers {
+        header.push(format!("{} CITIES", p.as_str().to_uppercase()));
+        header.push(format!("{} NODES", p.as_str().to_uppercase()));
+    }
+    header.push("EXCLUSIVE".to_string());
+
+    let mut by_country: BTreeMap<&str, Vec<&CoverageEntry>> = BTreeMap::new();
+    for e in entries {
+        by_country.entry(&e.country).or_default().push(e);
+    }
+
+    let rows = by_country.iter().map(|(country, cities)| {
+        let mut row = vec![country.to_string()];
+        for p in providers {
+            let served: Vec<_> = cities.iter().filter_map(|c| c.providers.get(p)).collect();
+            row.push(served.len().to_string());
+            row.push(served.iter().map(|s| s.nodes).sum::<u64>().to_string());
+        }
+        row.push(cities.iter().filter(|c| c.is_exclusive()).count().to_string());
+        row
+    });
+
+    print_table(header, rows.collect());
+}
+
+fn print_cities(entries: &[CoverageEntry], providers: &[Provider], only_exclusive: bool) {
+    let mut header = vec!["COUNTRY".to_string(), "CITY".to_string()];
+    header.extend(providers.iter().map(|p| p.as_str().to_uppercase()));
+    header.push(String::new());
+
+    let rows = entries
+        .iter()
+        .filter(|e| !only_exclusive || e.is_exclusive())
+        .map(|e| {
+            let mut row = vec![e.country.clone(), e.city.clone()];
+            for p in providers {
+                row.push(e.providers.get(p).map_or("-".to_string(), |c| c.nodes.to_string()));
+            }
+            row.push(match e.providers.keys().next() {
+                Some(only) if e.is_exclusive() => format!("* only {only}"),
+                _ => String::new(),
+            });
+            row
+        });
+
+    print_table(header, rows.collect());
+}
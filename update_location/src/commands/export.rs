@@ -0,0 +1,136 @@
+//! `update_location export`: fetch all providers and write a snapshot.
+//!
+//! The records are fetched, or with `--from cache` / `--from snapshot` taken from
+//! the cached provider datasets or the latest snapshot without calling any vendor.
+//! There is no `--from db`: no sink stores records in a database, so the latest
+//! snapshot is the stored state to regenerate files from.
+//! Writes the normalized records in the formats of `--format` (or `output.formats`),
+//! compressed per `--compress` (or `output.compression`), as a new snapshot with
+//! its integrity manifest under `output.dir`, see [`crate::export`], keeping only
+//! the records matching `--filter` (or `output.filter`) if set (`tree-json`, the
+//! iproyal tree as fetched, is never filtered; `delta-json` lists the changes
+//! since the previous snapshot), then prunes
+//! the snapshots the retention rules no longer keep. The run's aggregates are
+//! appended to the export history read by `stats --history`. Nothing is written when
+//! cancelled by a signal, since the snapshot would be incomplete. Runs are
+//! serialized by the [run lock](crate::lock).
+
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime};
+use tokio_util::sync::CancellationToken;
+use crate::cache::Cache;
+use crate::commands::collect::{collect, collect_cached, Collected};
+use crate::export;
+use crate::export::history::{History, RunAggregate};
+use crate::export::manifest::{ExportRun, ProviderSource};
+use crate::models::{AppConfig, ExportArgs, ExportFormat, ExportSource};
+use crate::normalize::ValidationReport;
+use crate::shutdown::EXIT_INTERRUPTED;
+use crate::unified::{LocationRecord, Provider};
+use crate::{run_id, status};
+
+pub async fn run(cfg: &AppConfig, args: &ExportArgs, cancel: &CancellationToken) -> ExitCode {
+    let _lock = match super::lock_run(cfg, cancel).await {
+        Ok(lock) => lock,
+        Err(code) => return code,
+    };
+    let run_id = run_id::current();
+    let Collected { mut records, report, iproyal_tree, .. } = match args.from {
+        ExportSource::Fetch => {
+            let cache = Cache::new(cfg.cache.get_dir(), cfg.cache.get_max_age().copied());
+            collect(cfg, &cache, &Provider::ALL, run_id, cancel).await
+        }
+        ExportSource::Cache => {
+            let cache = Cache::new(cfg.cache.get_dir(), Some(Duration::MAX));
+            match collect_cached(cfg, &cache, &Provider::ALL, run_id) {
+                Ok(collected) => collected,
+                Err(missing) => {
+                    let missing: Vec<_> = missing.iter().map(Provider::as_str).collect();
+                    eprintln!("export failed: no cached data for {}; fetch it first", missing.join(", "));
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        ExportSource::Snapshot => match export::read_latest(cfg.output.get_dir()) {
+            Ok((name, records)) => {
+                status!("re-exporting snapshot {name}");
+                Collected { records, report: ValidationReport::default(), iproyal_tree: None, fetched: Vec::new(), deduped: 0 }
+            }
+            Err(err) => {
+                eprintln!("export failed: {err}");
+                return ExitCode::FAILURE;
+            }
+        },
+    };
+    if cancel.is_cancelled() {
+        eprintln!("interrupted: no snapshot written");
+        return ExitCode::from(EXIT_INTERRUPTED);
+    }
+    if !report.is_clean() {
+        eprint!("normalization issues:\n{report}");
+    }
+
+    let filter = cfg.output.get_filter();
+    if let Some(filter) = filter {
+        records.retain(|r| filter.matches(r));
+    }
+
+    let formats = if args.format.is_empty() { cfg.output.get_formats() } else { &args.format };
+    let run = ExportRun::new(run_id.to_string(), sources(cfg, &records), filter.map(|f| f.source().to_string()));
+    let compression = args.compress.unwrap_or(cfg.output.get_compression());
+    if filter.is_some() && formats.contains(&ExportFormat::TreeJson) {
+        eprintln!("warning: tree-json keeps the whole iproyal tree, output.filter does not apply to it");
+    }
+    let tree = iproyal_tree.as_ref();
+    let snapshot = match export::write_snapshot(cfg.output.get_dir(), formats, compression, &records, tree, &run) {
+        Ok(snapshot) => snapshot,
+        Err(err) => {
+            eprintln!("export failed: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    println!("Snapshot {} ({} records, run {}) in {}", snapshot.name, records.len(), run.run_id, snapshot.path.display());
+    for artifact in &snapshot.artifacts {
+        println!("  {}", artifact.display());
+    }
+
+    let aggregate = RunAggregate::new(&snapshot.name, &run.run_id, SystemTime::now(), &records);
+    if let Err(err) = History::new(cfg.output.get_dir()).append(&aggregate) {
+        eprintln!("failed to record the run in the export history: {err}");
+    }
+
+    match export::prune(cfg.output.get_dir(), &cfg.export) {
+        Ok(pruned) => {
+            for entry in pruned {
+                println!("Pruned {}", entry.name);
+            }
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("pruning old snapshots failed: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Enabled providers with their endpoint, without credentials, and record count.
+fn sources(cfg: &AppConfig, records: &[LocationRecord]) -> Vec<ProviderSource> {
+    let endpoints = [
+        (Provider::IPRoyal, cfg.iproyal.as_ref().map(|c| c.get_endpoint())),
+        (Provider::Infatica, cfg.infatica.as_ref().map(|c| c.get_endpoint())),
+    ];
+    endpoints
+        .into_iter()
+        .filter_map(|(provider, endpoint)| {
+            let mut endpoint = endpoint?.clone();
+            let _ = endpoint.set_username("");
+            let _ = endpoint.set_password(None);
+            Some(ProviderSource {
+                provider,
+                endpoint: endpoint.to_string(),
+                records: records.iter().filter(|r| r.provider == provider).count(),
+            })
+        })
+        .collect()
+}
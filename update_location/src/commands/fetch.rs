@@ -0,0 +1,336 @@
+//! Default command: fetch every provider and print a summary of each dataset.
+//!
+//! Dataset counts are status output and sample records are only shown with
+//! `--verbose`; the sections from the provider timings (per endpoint, see
+//! [`EndpointStats`](crate::http::EndpointStats)) onwards form the final report,
+//! which `--quiet` keeps. With a change sink or [notifier](crate::notify)
+//! configured, the report ends with the changes since the last run, which are
+//! published to the sinks and summarized in one notification, or with `--dry-run`
+//! only printed as the messages each would receive. Exits with
+//! [`EXIT_ALERTS`] when any configured alert rule is violated, or
+//! [`EXIT_INTERRUPTED`] when cancelled by a signal; the report then covers only
+//! the providers fetched before it. Runs are serialized by the
+//! [run lock](crate::lock). The report starts with the [run id](crate::run_id),
+//! which the records and sink messages of the run carry as well. Swings of the
+//! [quality metrics](crate::quality) since the last run are listed as anomalies
+//! and attached to the run summaries sent to the sinks. The records go through
+//! the same [preparation](crate::commands::collect::prepare) as those of every
+//! other command before any of this.
+
+use std::process::ExitCode;
+use tokio_util::sync::CancellationToken;
+use crate::cache::Cache;
+use crate::commands::collect::{prepare, Collected};
+use crate::alerts::AlertViolation;
+use crate::changes::{Change, ChangeKind, ChangeLog};
+use crate::models::AppConfig;
+use crate::shutdown::EXIT_INTERRUPTED;
+use crate::unified::{LocationRecord, Provider};
+use crate::infatica::{InfaticaQueryResults, TargetingResolver};
+use crate::iproyal::models::IPRoyalQueryResults;
+use crate::pipeline::{self, Dataset, Outcome};
+use crate::notify::{self, Notification};
+use crate::quality::{Anomaly, QualityLog};
+use crate::{alerts, iproyal, matching, quality, run_id, sinks, status, unified, verbose};
+
+/// Exit code reported when at least one alert rule is violated.
+pub const EXIT_ALERTS: u8 = 2;
+
+pub async fn run(cfg: &AppConfig, dry_run: bool, cancel: &CancellationToken) -> ExitCode {
+    let _lock = match super::lock_run(cfg, cancel).await {
+        Ok(lock) => lock,
+        Err(code) => return code,
+    };
+    let cache = Cache::new(cfg.cache.get_dir(), cfg.cache.get_max_age().copied());
+    let mut records = Vec::new();
+    // Providers whose data is complete, for the change log
+    let mut fetched = Vec::new();
+
+    let run_id = run_id::current();
+    let runs = pipeline::fetch_all(cfg, &cache, &Provider::ALL, cancel).await;
+    for run in &runs {
+        let mut provider_records = match &run.outcome {
+            Outcome::Fetched(Dataset::IPRoyal(results)) => summarize_iproyal(results),
+            Outcome::Fetched(Dataset::Infatica(results)) => summarize_infatica(results),
+            Outcome::Failed(errors) => {
+                eprintln!("{} query failed with {} error(s):", run.provider, errors.len());
+                for err in errors {
+                    let retry = if err.retryable { ", retryable" } else { "" };
+                    eprintln!("  - {err} ({}{retry})", err.kind);
+                }
+                continue;
+            }
+            Outcome::Panicked(err) => {
+                eprintln!("{} fetch task failed: {err}", run.provider);
+                continue;
+            }
+            Outcome::TimedOut(_) | Outcome::Cancelled => continue,
+        };
+        if let Outcome::Fetched(dataset) = &run.outcome
+            && let Some(provenance) = dataset.provenance(cfg, run_id)
+        {
+            provenance.attach(&mut provider_records);
+        }
+        records.extend(provider_records);
+        fetched.push(run.provider);
+    }
+
+    println!("--- PROVIDERS ---");
+    println!("Run: {run_id}");
+    for run in &runs {
+        let outcome = match &run.outcome {
+            Outcome::Fetched(_) => "fetched".to_string(),
+            Outcome::Failed(_) | Outcome::Panicked(_) => "failed".to_string(),
+            Outcome::TimedOut(limit) => format!("timed out (fetch_timeout {})", humantime::format_duration(*limit)),
+            Outcome::Cancelled => "cancelled".to_string(),
+        };
+        println!("{}: {outcome} in {:.2}s", run.provider, run.elapsed.as_secs_f64());
+        if let Outcome::Fetched(dataset) = &run.outcome {
+            if dataset.endpoints().is_empty() {
+                println!("  (from cache)");
+            }
+            for endpoint in dataset.endpoints() {
+                println!(
+                    "  {}: {:.2}s, {} bytes in {} request(s), {} retried",
+                    endpoint.endpoint,
+                    endpoint.elapsed.as_secs_f64(),
+                    endpoint.bytes,
+                    endpoint.requests,
+                    endpoint.retries,
+                );
+            }
+        }
+    }
+    println!();
+
+    let Collected { records, report, fetched, deduped, .. } = prepare(cfg, records, None, fetched);
+    println!("--- NORMALIZATION ---");
+    println!("Unified records: {}", records.len());
+    if cfg.dedup.is_some() {
+        println!("Dropped as duplicates of a preferred provider: {deduped}");
+    }
+    print!("{report}");
+    println!();
+
+    for run in &runs {
+        if let Outcome::Fetched(Dataset::Infatica(results)) = &run.outcome {
+            println!("--- DATA QUALITY ---");
+            print!("{}", results.zip_quality());
+            println!();
+        }
+    }
+
+    let quality_log = QualityLog::new(&cfg.cache.get_dir());
+    let metrics = quality::compute(&records, &report);
+    let anomalies = quality::anomalies(&quality_log.previous(), &metrics);
+    println!("--- QUALITY ---");
+    for provider_metrics in &metrics {
+        println!("{provider_metrics}");
+    }
+    println!("Anomalies: {}", anomalies.len());
+    for anomaly in &anomalies {
+        println!("  - {anomaly}");
+    }
+    if !dry_run && let Err(err) = quality_log.record(&metrics) {
+        eprintln!("failed to record quality metrics of this run: {err}");
+    }
+    println!();
+
+    if cfg.geo.get_dataset().is_some() {
+        println!("--- GEO ---");
+        let resolved = records.iter().filter(|r| r.latitude.is_some()).count();
+        println!("Resolved coordinates: {resolved} of {}", records.len());
+        println!();
+    }
+
+    let coverage = matching::coverage(&records, cfg.matching.get_threshold());
+    let shared = coverage.iter().filter(|e| !e.is_exclusive()).count();
+    println!("--- COVERAGE ---");
+    println!("Cities: {} ({} served by several providers)", coverage.len(), shared);
+    for provider in unified::Provider::ALL {
+        let exclusive = coverage
+            .iter()
+            .filter(|e| e.is_exclusive() && e.providers.contains_key(&provider))
+            .count();
+        println!("Only {provider}: {exclusive}");
+    }
+
+    let violations = alerts::evaluate(&records, &cfg.alerts);
+    println!();
+    println!("--- ALERTS ---");
+    println!("Rules: {} ({} violated)", cfg.alerts.len(), violations.len());
+    for violation in &violations {
+        println!("  - {violation}");
+    }
+
+    if (sinks::configured(cfg) || notify::configured(cfg)) && !cancel.is_cancelled() {
+        println!();
+        println!("--- CHANGES ---");
+        publish_changes(cfg, &records, &fetched, &anomalies, &violations, dry_run).await;
+    }
+
+    if cancel.is_cancelled() {
+        eprintln!("interrupted: the report only covers providers fetched before the signal");
+        ExitCode::from(EXIT_INTERRUPTED)
+    } else if violations.is_empty() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(EXIT_ALERTS)
+    }
+}
+
+/// Prints the iproyal dataset summary and returns its unified records.
+fn summarize_iproyal(results: &IPRoyalQueryResults) -> Vec<LocationRecord> {
+    let r = results.countries();
+    status!("iproyal countries {}", r.countries.len());
+    if let Some(first) = r.countries.first() {
+        verbose!(
+            "iproyal first country: {{ code: \"{}\", name: \"{}\", cities: \"{}\", states: \"{}\", ip_availability: \"{}\" }}",
+            first.code,
+            first.name,
+            first.cities.as_ref().map(|c| c.options.len()).unwrap_or(0),
+            first.states.as_ref().map(|c| c.options.len()).unwrap_or(0),
+            first.ip_availability.as_deref().unwrap_or("no data"),
+        );
+    }
+
+    let locations = iproyal::flatten(r);
+    status!("iproyal flattened locations: {}", locations.len());
+    status!(
+        "iproyal known availability (lower bound): {}",
+        locations.iter().filter_map(|l| l.availability.lower_bound()).sum::<u64>(),
+    );
+    if let Some(first) = locations.first() {
+        verbose!(
+            "iproyal first location: {{ country: \"{}\", state: \"{}\", city: \"{}\", isp: \"{}\", ip_availability: \"{}\" }}",
+            first.country,
+            first.state.as_deref().unwrap_or("-"),
+            first.city.as_deref().unwrap_or("-"),
+            first.isp.as_deref().unwrap_or("-"),
+            first.ip_availability.as_deref().unwrap_or("no data"),
+        );
+    }
+    status!("iproyal isps: {}", results.isps().len());
+    status!("iproyal availability records: {}", results.availability().len());
+    status!();
+    unified::from_iproyal(&locations)
+}
+
+/// Prints the Infatica dataset summaries and returns the unified geo-node records.
+fn summarize_infatica(results: &InfaticaQueryResults) -> Vec<LocationRecord> {
+    status!("--- GEO NODES ---");
+    status!("Records: {}", results.geo_nodes().len());
+    if let Some(first) = results.geo_nodes().first() {
+        verbose!("First record: {:?}", first);
+    }
+    status!();
+
+    status!("--- REGION CODES ---");
+    status!("Records: {}", results.region_codes().len());
+    if let Some(first) = results.region_codes().first() {
+        verbose!("First record: {:?}", first);
+    }
+    status!();
+
+    status!("--- ZIP CODES ---");
+    status!("Records: {}", results.zip_codes().len());
+    if let Some(first) = results.zip_codes().first() {
+        verbose!("First record: {:?}", first);
+    }
+    status!();
+
+    status!("--- ISP CODES ---");
+    status!("Records: {}", results.isp_codes().len());
+    if let Some(first) = results.isp_codes().first() {
+        verbose!("First record: {:?}", first);
+    }
+    status!();
+
+    unified::from_infatica(results.geo_nodes(), &TargetingResolver::from_results(results))
+}
+
+/// Reports the changes of the `fetched` providers since the last run and publishes the run to the sinks.
+///
+/// Sinks only see records and changes matching `output.filter`; change detection
+/// itself always covers every record, so editing the filter does not report the
+/// records it newly admits or excludes as added or removed. A dry run leaves the
+/// change log untouched, so the next run reports the same changes.
+async fn publish_changes(
+    cfg: &AppConfig,
+    records: &[LocationRecord],
+    fetched: &[Provider],
+    anomalies: &[Anomaly],
+    violations: &[AlertViolation],
+    dry_run: bool,
+) {
+    let log = ChangeLog::new(&cfg.cache.get_dir());
+
+    let changes = match log.compare(records, fetched) {
+        Ok(changes) => changes,
+        Err(err) => {
+            eprintln!("cannot compare with the last run: {err}");
+            return;
+        }
+    };
+
+    match &changes {
+        None if dry_run => println!("First run: {} records would be recorded as the baseline", records.len()),
+        None => println!("First run: recorded {} records as the baseline", records.len()),
+        Some(changes) => {
+            let count = |kind| changes.iter().filter(|c| c.kind == kind).count();
+            println!("Added: {}", count(ChangeKind::Added));
+            println!("Removed: {}", count(ChangeKind::Removed));
+            println!("Count changed: {}", count(ChangeKind::CountChanged));
+        }
+    }
+
+    let filter = cfg.output.get_filter();
+    let selected = filter.map(|f| records.iter().filter(|r| f.matches(r)).cloned().collect::<Vec<_>>());
+    let selected_changes = filter.zip(changes.as_ref()).map(|(f, changes)| {
+        changes.iter().filter(|c| f.matches(&c.record)).cloned().collect::<Vec<_>>()
+    });
+    let published = selected.as_deref().unwrap_or(records);
+    let published_changes = selected_changes.as_deref().or(changes.as_deref());
+
+    let failures = sinks::publish(cfg, run_id::current(), published, fetched, published_changes, anomalies, dry_run).await;
+    if dry_run {
+        for (sink, err) in failures {
+            eprintln!("{sink}: {err}");
+        }
+        notify_changes(cfg, published_changes, anomalies, violations, true).await;
+        println!("Dry run: the change log was not updated");
+        return;
+    }
+    if !failures.is_empty() {
+        for (sink, err) in failures {
+            eprintln!("{sink}: failed to publish changes: {err}");
+        }
+        eprintln!("changes will be published again by the next run");
+        return;
+    }
+    notify_changes(cfg, published_changes, anomalies, violations, false).await;
+
+    if let Err(err) = log.record(records, fetched) {
+        eprintln!("failed to record this run for change detection: {err}");
+    }
+}
+
+/// Notifies about the changes and violated alert rules of a run, unless there are
+/// none; the first run has no changes.
+async fn notify_changes(
+    cfg: &AppConfig,
+    changes: Option<&[Change]>,
+    anomalies: &[Anomaly],
+    violations: &[AlertViolation],
+    dry_run: bool,
+) {
+    if !notify::configured(cfg) {
+        return;
+    }
+    let changes = changes.map(<[Change]>::to_vec).unwrap_or_default();
+    let notification =
+        Notification::new(run_id::current(), changes, anomalies.to_vec()).with_violations(violations.to_vec());
+    if !notification.is_empty() {
+        notify::deliver(cfg, notification, dry_run).await;
+    }
+}
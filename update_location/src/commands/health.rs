@@ -0,0 +1,201 @@
+//! `update_location health`: probe the enabled providers.
+//!
+//! Sends `--probes` requests one after another to each provider's cheapest
+//! authenticated endpoint, the same calls `validate` makes (see
+//! [`iproyal::preflight`] and [`infatica::preflight`]), probing the providers
+//! concurrently. Reports per provider whether it answered, whether it accepted
+//! the credentials, and latency percentiles of the successful probes.
+//!
+//! A provider is down when none of its probes succeeded, and degraded when only
+//! some did. Exits non-zero when any provider is down.
+
+use std::process::ExitCode;
+use std::time::Duration;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use crate::commands::table::print_table;
+use crate::infatica::InfaticaQueryError;
+use crate::iproyal::errors::IPRoyalQueryError;
+use crate::models::{AppConfig, HealthArgs, InfaticaConfig, IPRoyalConfig, ProviderError, ProviderErrorKind};
+use crate::shutdown::EXIT_INTERRUPTED;
+use crate::unified::Provider;
+use crate::{infatica, iproyal};
+
+/// Results of one provider's probes.
+struct Probes {
+    provider: Provider,
+    /// Latency of each successful probe.
+    latencies: Vec<Duration>,
+    /// Error of each failed probe.
+    errors: Vec<ProviderError>,
+}
+
+impl Probes {
+    fn total(&self) -> usize {
+        self.latencies.len() + self.errors.len()
+    }
+
+    fn status(&self) -> &'static str {
+        if self.errors.is_empty() {
+            "up"
+        } else if self.latencies.is_empty() {
+            "DOWN"
+        } else {
+            "degraded"
+        }
+    }
+
+    /// Whether the provider answered at all, even if only with an error.
+    fn reachable(&self) -> bool {
+        !self.latencies.is_empty()
+            || self.errors.iter().any(|e| !matches!(e.kind, ProviderErrorKind::Network | ProviderErrorKind::Timeout))
+    }
+
+    /// `valid` once a probe succeeded, `rejected` when the credentials were refused.
+    fn auth(&self) -> &'static str {
+        if !self.latencies.is_empty() {
+            "valid"
+        } else if self.errors.iter().any(|e| e.kind == ProviderErrorKind::Auth) {
+            "rejected"
+        } else {
+            "unknown"
+        }
+    }
+}
+
+pub async fn run(cfg: &AppConfig, args: &HealthArgs, cancel: &CancellationToken) -> ExitCode {
+    if cfg.iproyal.is_none() && cfg.infatica.is_none() {
+        eprintln!("no provider section configured");
+        return ExitCode::FAILURE;
+    }
+
+    let probe_all = async {
+        tokio::join!(
+            async { Some(probe_iproyal(cfg.iproyal.as_ref()?, args.probes).await) },
+            async { Some(probe_infatica(cfg.infatica.as_ref()?, args.probes).await) },
+        )
+    };
+    let Some((iproyal, infatica)) = cancel.run_until_cancelled(probe_all).await else {
+        eprintln!("interrupted");
+        return ExitCode::from(EXIT_INTERRUPTED);
+    };
+    let results: Vec<Probes> = [iproyal, infatica].into_iter().flatten().collect();
+
+    print_results(&results);
+
+    if results.iter().any(|p| p.latencies.is_empty()) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+async fn probe_iproyal(cfg: &IPRoyalConfig, probes: u32) -> Probes {
+    probe(Provider::IPRoyal, probes, || async {
+        iproyal::preflight(cfg)
+            .await
+            .map_err(|err| ProviderError::from(IPRoyalQueryError::Countries(err)))
+    }).await
+}
+
+async fn probe_infatica(cfg: &InfaticaConfig, probes: u32) -> Probes {
+    probe(Provider::Infatica, probes, || async {
+        infatica::preflight(cfg)
+            .await
+            .map_err(|err| ProviderError::from(InfaticaQueryError::RegionCodes(err)))
+    }).await
+}
+
+/// Runs `request` `probes` times in a row, timing each run.
+async fn probe<F, Fut>(provider: Provider, probes: u32, request: F) -> Probes
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<(), ProviderError>>,
+{
+    let mut results = Probes { provider, latencies: Vec::new(), errors: Vec::new() };
+    for _ in 0..probes {
+        let started = Instant::now();
+        match request().await {
+            Ok(()) => results.latencies.push(started.elapsed()),
+            Err(err) => results.errors.push(err),
+        }
+    }
+    results
+}
+
+/// Nearest-rank percentile `p` (0–100) of ascending `sorted` latencies.
+fn percentile(sorted: &[Duration], p: u32) -> Option<Duration> {
+    let rank = (sorted.len() * p as usize).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied()
+}
+
+fn print_results(results: &[Probes]) {
+    let header = ["PROVIDER", "STATUS", "REACHABLE", "AUTH", "OK", "P50", "P90", "P99", "MAX"];
+    let rows = results
+        .iter()
+        .map(|probes| {
+            let mut sorted = probes.latencies.clone();
+            sorted.sort();
+            let ms = |latency: Option<Duration>| latency.map_or("-".to_string(), |l| format!("{}ms", l.as_millis()));
+
+            vec![
+                probes.provider.to_string(),
+                probes.status().to_string(),
+                if probes.reachable() { "yes" } else { "no" }.to_string(),
+                probes.auth().to_string(),
+                format!("{}/{}", probes.latencies.len(), probes.total()),
+                ms(percentile(&sorted, 50)),
+                ms(percentile(&sorted, 90)),
+                ms(percentile(&sorted, 99)),
+                ms(sorted.last().copied()),
+            ]
+        })
+        .collect();
+    print_table(header.map(String::from).to_vec(), rows);
+
+    for probes in results {
+        // The last error is the most relevant after a recovery or a flapping run
+        if let Some(err) = probes.errors.last() {
+            println!();
+            println!("{}: {} of {} probe(s) failed, last with: {err} ({})", probes.provider, probes.errors.len(), probes.total(), err.kind);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(values: &[u64]) -> Vec<Duration> {
+        values.iter().copied().map(Duration::from_millis).collect()
+    }
+
+    fn error(kind: ProviderErrorKind) -> ProviderError {
+        ProviderError::new(kind, Provider::Infatica, None, "probe failed")
+    }
+
+    #[test]
+    fn percentiles_use_the_nearest_rank() {
+        let sorted = ms(&[10, 20, 30, 40, 50, 60, 70, 80, 90, 100]);
+        assert_eq!(percentile(&sorted, 50), Some(Duration::from_millis(50)));
+        assert_eq!(percentile(&sorted, 90), Some(Duration::from_millis(90)));
+        assert_eq!(percentile(&sorted, 99), Some(Duration::from_millis(100)));
+        assert_eq!(percentile(&ms(&[7]), 50), Some(Duration::from_millis(7)));
+        assert_eq!(percentile(&[], 50), None);
+    }
+
+    #[test]
+    fn classifies_providers() {
+        let up = Probes { provider: Provider::Infatica, latencies: ms(&[5]), errors: Vec::new() };
+        assert_eq!((up.status(), up.reachable(), up.auth()), ("up", true, "valid"));
+
+        let flapping = Probes { provider: Provider::Infatica, latencies: ms(&[5]), errors: vec![error(ProviderErrorKind::Timeout)] };
+        assert_eq!((flapping.status(), flapping.reachable(), flapping.auth()), ("degraded", true, "valid"));
+
+        let rejected = Probes { provider: Provider::Infatica, latencies: Vec::new(), errors: vec![error(ProviderErrorKind::Auth)] };
+        assert_eq!((rejected.status(), rejected.reachable(), rejected.auth()), ("DOWN", true, "rejected"));
+
+        let unreachable = Probes { provider: Provider::Infatica, latencies: Vec::new(), errors: vec![error(ProviderErrorKind::Network)] };
+        assert_eq!((unreachable.status(), unreachable.reachable(), unreachable.auth()), ("DOWN", false, "unknown"));
+    }
+}
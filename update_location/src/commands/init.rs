@@ -0,0 +1,43 @@
+//! `update_location init`: write a starter configuration.
+//!
+//! The template is rendered from [`CONFIG_KEYS`](crate::init::CONFIG_KEYS), so
+//! it lists every key with its type, default, environment variable and CLI flag.
+//! Runs without loading any configuration. An existing `--output` file is only
+//! replaced with `--force`.
+
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::process::ExitCode;
+use crate::init::config_template;
+use crate::models::InitArgs;
+
+pub fn run(args: &InitArgs) -> ExitCode {
+    let template = config_template();
+    let Some(path) = &args.output else {
+        print!("{template}");
+        return ExitCode::SUCCESS;
+    };
+
+    let mut options = OpenOptions::new();
+    options.write(true);
+    if args.force {
+        options.create(true).truncate(true);
+    } else {
+        options.create_new(true);
+    }
+
+    match options.open(path).and_then(|mut file| file.write_all(template.as_bytes())) {
+        Ok(()) => {
+            eprintln!("wrote {}", path.display());
+            ExitCode::SUCCESS
+        }
+        Err(err) if err.kind() == io::ErrorKind::AlreadyExists => {
+            eprintln!("{} already exists; pass --force to overwrite it", path.display());
+            ExitCode::FAILURE
+        }
+        Err(err) => {
+            eprintln!("failed to write {}: {err}", path.display());
+            ExitCode::FAILURE
+        }
+    }
+}
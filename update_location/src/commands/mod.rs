@@ -0,0 +1,46 @@
+//! # Subcommand implementations
+//!
+//! `main` loads the configuration and dispatches to one of these modules based on
+//! the parsed [`Command`](crate::models::Command).
+
+mod collect;
+pub mod compare;
+pub mod export;
+pub mod fetch;
+pub mod health;
+pub mod init;
+pub mod print_config;
+pub mod prune;
+pub mod serve;
+pub mod stats;
+mod table;
+pub mod validate;
+
+use std::process::ExitCode;
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use crate::lock::{LockError, RunLock, EXIT_RUN_IN_PROGRESS};
+use crate::models::AppConfig;
+use crate::shutdown::EXIT_INTERRUPTED;
+use crate::status;
+
+/// Takes the run lock of the cache directory, or reports why not and returns the exit code.
+async fn lock_run(cfg: &AppConfig, cancel: &CancellationToken) -> Result<RunLock, ExitCode> {
+    let wait = cfg.run.get_lock_wait();
+    let dir = cfg.cache.get_dir();
+    let lock = match RunLock::acquire(&dir, Duration::ZERO, cancel).await {
+        Err(LockError::InProgress { .. }) if !wait.is_zero() => {
+            status!("waiting up to {} for the run in progress", humantime::format_duration(wait));
+            RunLock::acquire(&dir, wait, cancel).await
+        }
+        lock => lock,
+    };
+    lock.map_err(|err| {
+        eprintln!("{err}");
+        match err {
+            LockError::InProgress { .. } => ExitCode::from(EXIT_RUN_IN_PROGRESS),
+            LockError::Interrupted => ExitCode::from(EXIT_INTERRUPTED),
+            LockError::Io { .. } => ExitCode::FAILURE,
+        }
+    })
+}
@@ -0,0 +1,79 @@
+//! `update_location print-config`: the effective merged configuration.
+//!
+//! Prints what [`load_config`](crate::init::load_config) would deserialize after
+//! merging the config file, environment and CLI flags, which helps when a value
+//! does not come from the expected source. Values of keys reported by
+//! [`CLIArgs::sensitive_keys`] are masked.
+//!
+//! `--format debug` instead prints the deserialized [`AppConfig`](crate::models::AppConfig),
+//! with `*_file` secrets resolved, through its redacting `Debug` implementation.
+
+use std::process::ExitCode;
+use override_key_core::ApplyOverrides;
+use serde_json::Value;
+use crate::init::{load_config, load_raw_config};
+use crate::models::{CLIArgs, ConfigFormat, PrintConfigArgs, MASK};
+
+pub async fn run(args: &CLIArgs, print: &PrintConfigArgs) -> ExitCode {
+    if let ConfigFormat::Debug = print.format {
+        return match load_config(args).await {
+            Ok(cfg) => {
+                println!("{cfg:#?}");
+                ExitCode::SUCCESS
+            }
+            Err(err) => {
+                eprintln!("{err}");
+                ExitCode::FAILURE
+            }
+        };
+    }
+
+    let merged = load_raw_config(args)
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|cfg| cfg.try_deserialize::<Value>().map_err(|e| e.to_string()));
+
+    let mut value = match merged {
+        Ok(v) => v,
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    for key in CLIArgs::sensitive_keys() {
+        mask(&mut value, key);
+    }
+
+    let rendered = match print.format {
+        ConfigFormat::Toml => toml::to_string_pretty(&value).map_err(|e| e.to_string()),
+        ConfigFormat::Json => serde_json::to_string_pretty(&value).map_err(|e| e.to_string()),
+        ConfigFormat::Debug => unreachable!("handled above"),
+    };
+
+    match rendered {
+        Ok(text) => {
+            println!("{}", text.trim_end());
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("failed to render configuration: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Masks the value at a dotted `key`, if it is set.
+fn mask(value: &mut Value, key: &str) {
+    let mut node = value;
+    for part in key.split('.') {
+        match node.get_mut(part) {
+            Some(child) => node = child,
+            None => return,
+        }
+    }
+
+    if !node.is_null() {
+        *node = Value::String(MASK.to_string());
+    }
+}
@@ -0,0 +1,31 @@
+//! `update_location prune`: apply the export retention rules.
+//!
+//! Removes the snapshots under `output.dir` that neither `export.keep_last` nor
+//! `export.keep_days` keeps; the snapshot `LATEST` points at always stays.
+
+use std::process::ExitCode;
+use crate::export;
+use crate::models::{AppConfig, PruneArgs};
+
+pub fn run(cfg: &AppConfig, args: &PruneArgs) -> ExitCode {
+    let result = if args.dry_run {
+        export::expired(cfg.output.get_dir(), &cfg.export)
+    } else {
+        export::prune(cfg.output.get_dir(), &cfg.export)
+    };
+
+    match result {
+        Ok(entries) => {
+            let verb = if args.dry_run { "Would remove" } else { "Removed" };
+            for entry in &entries {
+                println!("{verb} {}", entry.name);
+            }
+            println!("{verb} {} snapshot(s)", entries.len());
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("prune failed: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
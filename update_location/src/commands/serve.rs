@@ -0,0 +1,162 @@
+//! `update_location serve`: HTTP API over the latest fetched data.
+//!
+//! Fetches all enabled providers once at startup (honouring the cache), then
+//! serves the result through [`crate::server`]. With `--refresh`, providers are
+//! refetched periodically and the snapshot is swapped in once complete; a failed
+//! provider keeps serving nothing for that provider until the next refresh.
+//! With the `grpc` feature, `--grpc-listen` serves the same snapshot over gRPC.
+//! Every complete snapshot is also saved under the cache directory in the binary
+//! form of [`server::persist`]; while it is younger than `cache.max_age`, a
+//! restart serves it straight away instead of fetching or reading the cache.
+//! With a [notifier](crate::notify) configured, the changes found by refreshes are
+//! collected for `notify.window` and sent as one message.
+//! A termination signal stops the servers after in-flight requests finish.
+
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime};
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+use crate::cache::Cache;
+use crate::commands::collect::{collect, Collected};
+use crate::models::{AppConfig, ServeArgs};
+use crate::notify::{self, Aggregator, Notification};
+use crate::server::{self, persist, PersistError, Snapshot, SnapshotStore};
+use crate::unified::Provider;
+use crate::{changes, run_id, status};
+
+/// Name of the saved snapshot in the cache directory.
+const SNAPSHOT_FILE: &str = "serve.snapshot";
+
+pub async fn run(cfg: &AppConfig, args: &ServeArgs, cancel: &CancellationToken) -> ExitCode {
+    let cache = Cache::new(cfg.cache.get_dir(), cfg.cache.get_max_age().copied());
+    let saved = cfg.cache.get_dir().join(SNAPSHOT_FILE);
+    let snapshot = match reload(cfg, &saved) {
+        Some(snapshot) => snapshot,
+        None => {
+            let (snapshot, _) = fetch(cfg, &cache, cancel).await;
+            if cancel.is_cancelled() {
+                return ExitCode::SUCCESS;
+            }
+            save(&saved, &snapshot);
+            snapshot
+        }
+    };
+    let store = SnapshotStore::new(snapshot);
+
+    let refresh = async {
+        let Some(period) = args.refresh.map(Duration::from) else {
+            return std::future::pending().await;
+        };
+        let mut ticks = tokio::time::interval_at(Instant::now() + period, period);
+        let mut aggregator = Aggregator::new(cfg.notify.get_window());
+        loop {
+            let deadline = aggregator.deadline();
+            tokio::select! {
+                _ = ticks.tick() => {}
+                () = tokio::time::sleep_until(deadline.unwrap_or_else(Instant::now)), if deadline.is_some() => {
+                    if let Some(notification) = aggregator.take() {
+                        notify::deliver(cfg, notification, false).await;
+                    }
+                    continue;
+                }
+            }
+            let (snapshot, fetched) = fetch(cfg, &cache, cancel).await;
+            if cancel.is_cancelled() {
+                // Changes collected so far are not worth losing to the window
+                if let Some(notification) = aggregator.take() {
+                    notify::deliver(cfg, notification, false).await;
+                }
+                return std::future::pending().await;
+            }
+            status!("refreshed: {} records", snapshot.records.len());
+            if notify::configured(cfg) {
+                aggregator.push(refresh_changes(&store.current(), &snapshot, &fetched));
+            }
+            save(&saved, &snapshot);
+            store.replace(snapshot);
+        }
+    };
+
+    status!("listening on http://{}", args.listen);
+    let http = async {
+        server::serve(args.listen, store.clone(), cancel.clone().cancelled_owned())
+            .await
+            .map_err(|err| format!("server failed on {}: {err}", args.listen))
+    };
+    let grpc = async {
+        #[cfg(feature = "grpc")]
+        if let Some(addr) = args.grpc_listen {
+            status!("gRPC listening on {addr}");
+            return server::grpc::serve(addr, store.clone(), cancel.clone())
+                .await
+                .map_err(|err| format!("gRPC server failed on {addr}: {err}"));
+        }
+        cancel.cancelled().await;
+        Ok(())
+    };
+
+    let result = tokio::select! {
+        result = async { tokio::try_join!(http, grpc) } => result,
+        () = refresh => unreachable!("refresh never completes"),
+    };
+
+    match result {
+        Ok(_) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// A new snapshot, and the providers whose data is complete in it.
+async fn fetch(cfg: &AppConfig, cache: &Cache, cancel: &CancellationToken) -> (Snapshot, Vec<Provider>) {
+    let Collected { records, report, fetched, .. } = collect(cfg, cache, &Provider::ALL, run_id::current(), cancel).await;
+    if !report.is_clean() {
+        eprint!("normalization issues:\n{report}");
+    }
+    (Snapshot::new(records, cfg.matching.get_threshold()), fetched)
+}
+
+/// Changes from `previous` to `current` of the providers fetched into both, so a
+/// provider failing or recovering is not reported as all of its locations
+/// disappearing or appearing.
+fn refresh_changes(previous: &Snapshot, current: &Snapshot, fetched: &[Provider]) -> Notification {
+    let compared: Vec<Provider> = fetched
+        .iter()
+        .copied()
+        .filter(|p| previous.records.iter().any(|r| r.provider == *p))
+        .collect();
+    let of_fetched = |snapshot: &Snapshot| {
+        snapshot.records.iter().filter(|r| compared.contains(&r.provider)).cloned().collect::<Vec<_>>()
+    };
+    let changes = changes::diff(&of_fetched(previous), &of_fetched(current));
+    Notification::new(run_id::current(), changes, Vec::new())
+}
+
+/// The snapshot saved at `path`, when it is younger than `cache.max_age`.
+fn reload(cfg: &AppConfig, path: &Path) -> Option<Snapshot> {
+    let max_age = *cfg.cache.get_max_age()?;
+    let snapshot = match persist::load(path, cfg.matching.get_threshold()) {
+        Ok(snapshot) => snapshot,
+        Err(PersistError::Read { source, .. }) if source.kind() == std::io::ErrorKind::NotFound => return None,
+        Err(err) => {
+            eprintln!("ignoring saved snapshot: {err}");
+            return None;
+        }
+    };
+    let age = SystemTime::now().duration_since(snapshot.fetched_at).unwrap_or_default();
+    if age > max_age {
+        return None;
+    }
+    status!("serving saved snapshot: {} records ({}s old)", snapshot.records.len(), age.as_secs());
+    Some(snapshot)
+}
+
+/// Saves `snapshot` for the next start; failures only cost that start a fetch.
+fn save(path: &Path, snapshot: &Snapshot) {
+    if let Err(err) = persist::save(path, snapshot) {
+        eprintln!("failed to save snapshot: {err}");
+    }
+}
@@ -0,0 +1,173 @@
+//! `update_location stats`: coverage aggregates of the latest export snapshot.
+//!
+//! Works offline on the snapshot `LATEST` points at, see [`crate::export`], and
+//! prints the [`crate::stats`] of each provider as tables or JSON.
+//!
+//! With `--history`, reports how node totals per provider and country changed
+//! between the first and the last export of the period instead, from the
+//! export history (see [`crate::export::history`]).
+
+use std::collections::BTreeSet;
+use std::process::ExitCode;
+use std::time::{Duration, SystemTime};
+use serde::Serialize;
+use crate::commands::table::print_table;
+use crate::export;
+use crate::export::history::{self, CountryTrend, History};
+use crate::models::{AppConfig, StatsArgs, StatsFormat};
+use crate::stats::{self, ProviderStats};
+
+#[derive(Serialize)]
+struct Report<'a> {
+    snapshot: &'a str,
+    providers: &'a [ProviderStats],
+}
+
+#[derive(Serialize)]
+struct HistoryReport<'a> {
+    runs: usize,
+    first: Option<&'a str>,
+    last: Option<&'a str>,
+    countries: &'a [CountryTrend],
+}
+
+pub fn run(cfg: &AppConfig, args: &StatsArgs) -> ExitCode {
+    if let Some(period) = args.history {
+        return run_history(cfg, args.format, period.into());
+    }
+
+    let (snapshot, records) = match export::read_latest(cfg.output.get_dir()) {
+        Ok(latest) => latest,
+        Err(err) => {
+            eprintln!("stats failed: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let providers = stats::compute(&records, args.top);
+
+    match args.format {
+        StatsFormat::Json => return print_json(&Report { snapshot: &snapshot, providers: &providers }),
+        StatsFormat::Table => {
+            println!("Snapshot {snapshot} ({} records)", records.len());
+            println!();
+            print_summary(&providers);
+            println!();
+            print_cities(&providers);
+            println!();
+            print_isps(&providers);
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+fn run_history(cfg: &AppConfig, format: StatsFormat, period: Duration) -> ExitCode {
+    let since = SystemTime::now().checked_sub(period).unwrap_or(SystemTime::UNIX_EPOCH);
+    let runs = match History::new(cfg.output.get_dir()).since(since) {
+        Ok(runs) => runs,
+        Err(err) => {
+            eprintln!("stats failed: {err}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let (first, last) = (runs.first(), runs.last());
+    let trends = first.zip(last).map(|(first, last)| history::trend(first, last)).unwrap_or_default();
+
+    match format {
+        StatsFormat::Json => {
+            print_json(&HistoryReport {
+                runs: runs.len(),
+                first: first.map(|r| r.snapshot.as_str()),
+                last: last.map(|r| r.snapshot.as_str()),
+                countries: &trends,
+            })
+        }
+        StatsFormat::Table => {
+            let (Some(first), Some(last)) = (first, last) else {
+                println!("No exports recorded in the last {}", humantime::format_duration(period));
+                return ExitCode::SUCCESS;
+            };
+            println!("{} export(s) from {} to {}", runs.len(), first.recorded_at, last.recorded_at);
+            println!();
+            let header = ["PROVIDER", "COUNTRY", "NODES BEFORE", "NODES AFTER", "DELTA"].map(String::from).to_vec();
+            let count = |n: Option<u64>| n.map_or_else(|| "-".to_string(), |n| n.to_string());
+            let rows = trends
+                .iter()
+                .map(|t| {
+                    vec![
+                        t.provider.to_string(),
+                        t.country.clone(),
+                        count(t.nodes_first),
+                        count(t.nodes_last),
+                        format!("{:+}", t.delta),
+                    ]
+                })
+                .collect();
+            print_table(header, rows);
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+fn print_json(report: &impl Serialize) -> ExitCode {
+    match serde_json::to_string_pretty(report) {
+        Ok(json) => {
+            println!("{json}");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("stats failed: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn print_summary(providers: &[ProviderStats]) {
+    let header = ["PROVIDER", "RECORDS", "COUNTRIES", "CITIES", "NODES"].map(String::from).to_vec();
+    let rows = providers
+        .iter()
+        .map(|p| {
+            vec![
+                p.provider.to_string(),
+                p.records.to_string(),
+                p.countries.to_string(),
+                p.cities.to_string(),
+                p.nodes.to_string(),
+            ]
+        })
+        .collect();
+    print_table(header, rows);
+}
+
+fn print_cities(providers: &[ProviderStats]) {
+    let mut header = vec!["COUNTRY".to_string()];
+    header.extend(providers.iter().map(|p| format!("{} CITIES", p.provider.as_str().to_uppercase())));
+
+    let countries: BTreeSet<&str> = providers
+        .iter()
+        .flat_map(|p| p.cities_per_country.keys().map(String::as_str))
+        .collect();
+    let rows = countries
+        .into_iter()
+        .map(|country| {
+            let mut row = vec![country.to_string()];
+            row.extend(providers.iter().map(|p| {
+                p.cities_per_country.get(country).map_or_else(|| "-".to_string(), usize::to_string)
+            }));
+            row
+        })
+        .collect();
+    print_table(header, rows);
+}
+
+fn print_isps(providers: &[ProviderStats]) {
+    let header = ["PROVIDER", "ISP", "NODES"].map(String::from).to_vec();
+    let rows = providers
+        .iter()
+        .flat_map(|p| {
+            p.top_isps
+                .iter()
+                .map(|isp| vec![p.provider.to_string(), isp.isp.clone(), isp.nodes.to_string()])
+        })
+        .collect();
+    print_table(header, rows);
+}
@@ -0,0 +1,265 @@
+//! `update_location validate`: configuration and credential preflight.
+//!
+//! Parses the merged configuration, checks it with [`check_schema`] (reporting each
+//! problem on its own row), warns about keys no setting or flag uses (likely typos,
+//! see [`audit_keys`]), and performs a lightweight authenticated call per enabled
+//! provider (see [`iproyal::preflight`] and [`infatica::preflight`]). No dataset
+//! is fetched. Results are printed as a table; any failure exits non-zero.
+
+use std::process::ExitCode;
+use config::Config;
+use override_key_core::ApplyOverrides;
+use override_key_core::audit::audit_keys;
+use serde::de::DeserializeOwned;
+use crate::commands::table::print_table;
+use crate::init::{check_schema, load_raw_config, CONFIG_KEYS};
+use crate::models::{AppConfig, CLIArgs, ConfigError, HttpConfig, InfaticaConfig, IPRoyalConfig};
+use crate::models::constants::DEPRECATED_KEYS;
+use crate::unified::Provider;
+use crate::{infatica, iproyal};
+
+/// Outcome of a single check.
+enum Status {
+    Pass,
+    Fail(String),
+    /// Suspicious, but does not fail validation.
+    Warn(String),
+    Skip(String),
+}
+
+struct Check {
+    name: String,
+    status: Status,
+}
+
+impl Check {
+    fn new(name: impl Into<String>, result: Result<(), String>) -> Self {
+        let status = match result {
+            Ok(()) => Status::Pass,
+            Err(reason) => Status::Fail(reason),
+        };
+        Self { name: name.into(), status }
+    }
+
+    fn passed(&self) -> bool {
+        matches!(self.status, Status::Pass)
+    }
+}
+
+impl Status {
+    /// Result and detail columns of the report.
+    fn columns(&self) -> (&'static str, &str) {
+        match self {
+            Status::Pass => ("pass", ""),
+            Status::Fail(reason) => ("FAIL", reason),
+            Status::Warn(reason) => ("warn", reason),
+            Status::Skip(reason) => ("skip", reason),
+        }
+    }
+}
+
+pub async fn run(args: &CLIArgs) -> ExitCode {
+    let mut checks = Vec::new();
+
+    match load_raw_config(args).await {
+        Ok(raw) => {
+            check_config(&raw, &mut checks).await;
+            check_unknown_keys(&raw, &mut checks);
+        }
+        Err(err) => checks.push(Check::new("config", Err(err.to_string()))),
+    }
+
+    print_checks(&checks);
+    exit_code(&checks)
+}
+
+/// Fails when any check failed; warnings and skipped checks do not.
+fn exit_code(checks: &[Check]) -> ExitCode {
+    if checks.iter().any(|c| matches!(c.status, Status::Fail(_))) {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Checks the schema, then deserializes and contacts the enabled providers.
+async fn check_config(raw: &Config, checks: &mut Vec<Check>) {
+    match check_schema(raw) {
+        Ok(()) => {
+            let parsed = raw
+                .clone()
+                .try_deserialize::<AppConfig>()
+                .map(drop)
+                .map_err(|e| e.to_string());
+            checks.push(Check::new("config", parsed));
+            check_providers(raw, checks).await;
+        }
+        Err(report) => checks.extend(report.problems.iter().map(|problem| {
+            let detail = match problem.sources() {
+                Some(sources) => format!("{} ({sources})", problem.message),
+                None => problem.message.clone(),
+            };
+            Check::new(format!("config {}", problem.key), Err(detail))
+        })),
+    }
+}
+
+/// Warns about merged keys that neither a setting nor a CLI flag reads.
+///
+/// `profiles` holds whole configurations and is only checked once selected;
+/// deprecated keys are warned about while loading.
+fn check_unknown_keys(raw: &Config, checks: &mut Vec<Check>) {
+    let known = CONFIG_KEYS
+        .iter()
+        .map(|k| k.key)
+        .chain(CLIArgs::override_keys().iter().map(|(_, key)| *key))
+        .chain(DEPRECATED_KEYS.iter().map(|(old, _)| *old))
+        .chain(["profiles"]);
+
+    for unknown in audit_keys(known, raw) {
+        let detail = match unknown.suggestion {
+            Some(known) => format!("unknown key, ignored; did you mean `{known}`?"),
+            None => "unknown key, ignored".to_string(),
+        };
+        checks.push(Check { name: format!("config {}", unknown.key), status: Status::Warn(detail) });
+    }
+}
+
+async fn check_providers(raw: &Config, checks: &mut Vec<Check>) {
+    let mut enabled = 0;
+    let http = raw.get::<HttpConfig>("http").unwrap_or_default();
+
+    if let Some(mut cfg) = section::<IPRoyalConfig>(raw, Provider::IPRoyal, checks, &mut enabled)
+        && required(Provider::IPRoyal, resolve_and_check(&mut cfg, IPRoyalConfig::resolve_secret_files, IPRoyalConfig::check), checks)
+    {
+        cfg.inherit_http(&http);
+        let result = iproyal::preflight(&cfg).await.map_err(|e| e.to_string());
+        checks.push(Check::new(format!("{} auth", Provider::IPRoyal), result));
+    }
+
+    if let Some(mut cfg) = section::<InfaticaConfig>(raw, Provider::Infatica, checks, &mut enabled)
+        && required(Provider::Infatica, resolve_and_check(&mut cfg, InfaticaConfig::resolve_secret_files, InfaticaConfig::check), checks)
+    {
+        cfg.inherit_http(&http);
+        let result = infatica::preflight(&cfg).await.map_err(|e| e.to_string());
+        checks.push(Check::new(format!("{} auth", Provider::Infatica), result));
+    }
+
+    if enabled == 0 {
+        checks.push(Check::new("providers", Err("no provider section configured".to_string())));
+    }
+}
+
+/// Deserializes a provider section; a missing section marks the provider as disabled.
+fn section<C: DeserializeOwned>(
+    raw: &Config,
+    provider: Provider,
+    checks: &mut Vec<Check>,
+    enabled: &mut usize,
+) -> Option<C> {
+    let name = format!("{provider} config");
+    match raw.get::<C>(provider.as_str()) {
+        Ok(cfg) => {
+            *enabled += 1;
+            Some(cfg)
+        }
+        Err(config::ConfigError::NotFound(_)) => {
+            checks.push(Check { name, status: Status::Skip("disabled".to_string()) });
+            None
+        }
+        Err(err) => {
+            *enabled += 1;
+            checks.push(Check::new(name, Err(err.to_string())));
+            None
+        }
+    }
+}
+
+/// Reads secret files, then checks required fields.
+fn resolve_and_check<C>(
+    cfg: &mut C,
+    resolve: fn(&mut C) -> Result<(), ConfigError>,
+    check: fn(&C) -> Result<(), String>,
+) -> Result<(), String> {
+    resolve(cfg).map_err(|e| e.to_string())?;
+    check(cfg)
+}
+
+/// Records the required-field check; returns whether the provider can be contacted.
+fn required(provider: Provider, result: Result<(), String>, checks: &mut Vec<Check>) -> bool {
+    let check = Check::new(format!("{provider} config"), result);
+    let passed = check.passed();
+    checks.push(check);
+    passed
+}
+
+fn print_checks(checks: &[Check]) {
+    let header = vec!["CHECK".to_string(), "RESULT".to_string(), "DETAIL".to_string()];
+    let rows = checks
+        .iter()
+        .map(|c| {
+            let (result, detail) = c.status.columns();
+            vec![c.name.clone(), result.to_string(), detail.to_string()]
+        })
+        .collect();
+
+    print_table(header, rows);
+}
+
+#[cfg(test)]
+mod tests {
+    use config::{File, FileFormat};
+    use super::*;
+
+    async fn checks(toml: &str) -> Vec<Check> {
+        let raw = Config::builder().add_source(File::from_str(toml, FileFormat::Toml)).build().unwrap();
+        let mut checks = Vec::new();
+        check_config(&raw, &mut checks).await;
+        check_unknown_keys(&raw, &mut checks);
+        checks
+    }
+
+    fn rows(checks: &[Check]) -> Vec<(&str, &str, &str)> {
+        checks
+            .iter()
+            .map(|c| {
+                let (result, detail) = c.status.columns();
+                (c.name.as_str(), result, detail)
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn a_bad_config_fails_with_a_row_per_problem() {
+        let checks = checks(
+            "[infatica]\nemail = \"me@example.com\"\npassword = \"secret\"\n\
+             [cache]\nmax_age = \"soon\"\nmaxAge = \"1h\"\n",
+        )
+        .await;
+
+        assert_eq!(rows(&checks), [
+            (
+                "config infatica.endpoint",
+                "FAIL",
+                "missing (set `infatica.endpoint` in a config file, MYAPP_INFATICA__ENDPOINT, or --infatica-endpoint)",
+            ),
+            (
+                "config cache.max_age",
+                "FAIL",
+                "invalid duration `soon`: expected number at 0 (set `cache.max_age` in a config file, MYAPP_CACHE__MAX_AGE, or --max-age)",
+            ),
+            ("config cache.maxAge", "warn", "unknown key, ignored; did you mean `cache.max_age`?"),
+        ]);
+        assert_eq!(exit_code(&checks), ExitCode::FAILURE);
+    }
+
+    #[test]
+    fn warnings_and_skipped_checks_pass() {
+        let checks = [
+            Check { name: "config cache.maxAge".to_string(), status: Status::Warn("unknown key, ignored".to_string()) },
+            Check { name: "infatica config".to_string(), status: Status::Skip("disabled".to_string()) },
+            Check::new("iproyal auth", Ok(())),
+        ];
+        assert_eq!(exit_code(&checks), ExitCode::SUCCESS);
+    }
+}
@@ -0,0 +1,98 @@
+//! Streaming compression and decompression of exported artifacts.
+
+use std::io::{self, BufRead, BufReader, Read, Write};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use crate::models::ExportCompression;
+
+/// zstd level; the library default, a good balance for large text dumps.
+const ZSTD_LEVEL: i32 = 3;
+
+/// Suffix appended to the artifact extension, e.g. `.json.zst`.
+pub fn suffix(compression: ExportCompression) -> &'static str {
+    match compression {
+        ExportCompression::None => "",
+        ExportCompression::Gzip => ".gz",
+        ExportCompression::Zstd => ".zst",
+    }
+}
+
+/// Writer compressing into `W` as data arrives.
+pub enum Encoder<W: Write> {
+    Plain(W),
+    Gzip(GzEncoder<W>),
+    Zstd(zstd::Encoder<'static, W>),
+}
+
+impl<W: Write> Encoder<W> {
+    pub fn new(compression: ExportCompression, inner: W) -> io::Result<Self> {
+        Ok(match compression {
+            ExportCompression::None => Self::Plain(inner),
+            ExportCompression::Gzip => Self::Gzip(GzEncoder::new(inner, flate2::Compression::default())),
+            ExportCompression::Zstd => Self::Zstd(zstd::Encoder::new(inner, ZSTD_LEVEL)?),
+        })
+    }
+
+    /// Writes the compression trailer and returns the inner writer.
+    pub fn finish(self) -> io::Result<W> {
+        match self {
+            Self::Plain(inner) => Ok(inner),
+            Self::Gzip(encoder) => encoder.finish(),
+            Self::Zstd(encoder) => encoder.finish(),
+        }
+    }
+}
+
+impl<W: Write> Write for Encoder<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(inner) => inner.write(buf),
+            Self::Gzip(encoder) => encoder.write(buf),
+            Self::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(inner) => inner.flush(),
+            Self::Gzip(encoder) => encoder.flush(),
+            Self::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// Reader decompressing an artifact written with `compression`.
+pub fn decoder<'a>(compression: ExportCompression, inner: impl Read + 'a) -> io::Result<Box<dyn BufRead + 'a>> {
+    Ok(match compression {
+        ExportCompression::None => Box::new(BufReader::new(inner)),
+        ExportCompression::Gzip => Box::new(BufReader::new(GzDecoder::new(inner))),
+        ExportCompression::Zstd => Box::new(BufReader::new(zstd::Decoder::new(inner)?)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: [ExportCompression; 3] = [ExportCompression::None, ExportCompression::Gzip, ExportCompression::Zstd];
+
+    #[test]
+    fn every_compression_reads_back_what_it_wrote() {
+        let data = "{\"country\":\"DE\"}\n".repeat(1000);
+        for compression in ALL {
+            let mut encoder = Encoder::new(compression, Vec::new()).unwrap();
+            encoder.write_all(data.as_bytes()).unwrap();
+            let written = encoder.finish().unwrap();
+            assert_eq!(compression == ExportCompression::None, written.len() == data.len(), "{compression:?}");
+
+            let mut read = String::new();
+            decoder(compression, written.as_slice()).unwrap().read_to_string(&mut read).unwrap();
+            assert_eq!(read, data, "{compression:?}");
+        }
+    }
+
+    #[test]
+    fn suffixes_follow_the_compression() {
+        assert_eq!(ALL.map(suffix), ["", ".gz", ".zst"]);
+    }
+}
@@ -0,0 +1,55 @@
+//! The `delta-json` artifact: the records a snapshot adds, changes or removes
+//! relative to the previous one, for syncers applying updates incrementally.
+
+use serde::Serialize;
+use crate::changes::{self, Change};
+use crate::unified::{LocationRecord, SCHEMA_VERSION};
+
+/// Changes of a snapshot, as computed by [`changes::diff`].
+#[derive(Debug, Serialize)]
+pub struct Delta {
+    /// Version of the record layout, see [`crate::unified::schema`]; the base
+    /// snapshot's records are migrated to it first.
+    pub schema_version: u32,
+    /// Snapshot the changes apply to; `None` when there was none, in which case
+    /// every record is added.
+    pub base: Option<String>,
+    pub changes: Vec<Change>,
+}
+
+impl Delta {
+    /// Changes from `previous`, the records of snapshot `base`, to `current`.
+    pub fn new(base: Option<String>, previous: &[LocationRecord], current: &[LocationRecord]) -> Self {
+        Self { schema_version: SCHEMA_VERSION, base, changes: changes::diff(previous, current) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::unified::Provider;
+    use super::*;
+
+    fn record(city: &str, nodes: u64) -> LocationRecord {
+        LocationRecord {
+            city: Some(city.to_string()),
+            nodes: Some(nodes),
+            ..LocationRecord::sample(Provider::Infatica, "DE")
+        }
+    }
+
+    #[test]
+    fn lists_added_changed_and_removed_records() {
+        let previous = [record("Berlin", 5), record("Hamburg", 3), record("Munich", 1)];
+        let current = [record("Berlin", 5), record("Hamburg", 4), record("Cologne", 2)];
+        let delta = serde_json::to_value(Delta::new(Some("20261017T120000Z".to_string()), &previous, &current)).unwrap();
+
+        assert_eq!(delta["base"], "20261017T120000Z");
+        let changes: Vec<_> = delta["changes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| (c["kind"].as_str().unwrap(), c["record"]["city"].as_str().unwrap()))
+            .collect();
+        assert_eq!(changes, [("count_changed", "Hamburg"), ("added", "Cologne"), ("removed", "Munich")]);
+    }
+}
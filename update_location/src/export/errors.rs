@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+use thiserror::Error;
+use crate::unified::schema::SchemaError;
+
+/// Failure while writing or pruning export snapshots.
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("{}: {source}", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+
+    #[error("no snapshot in {}: run `update_location export` first", dir.display())]
+    NoSnapshot { dir: PathBuf },
+
+    #[error("{}: {source}", path.display())]
+    Decode { path: PathBuf, source: serde_json::Error },
+
+    #[error("snapshot {snapshot} has no JSON or NDJSON artifact to read")]
+    Unreadable { snapshot: String },
+
+    #[error("snapshot {snapshot}: {source}")]
+    Schema { snapshot: String, source: SchemaError },
+
+    #[error("tree-json needs the iproyal tree, which this run did not fetch or load")]
+    NoTree,
+}
+
+impl ExportError {
+    /// Adapter for `map_err` attaching the path being accessed.
+    pub fn io(path: impl Into<PathBuf>) -> impl FnOnce(std::io::Error) -> Self {
+        let path = path.into();
+        move |source| Self::Io { path, source }
+    }
+
+    /// Adapter for `map_err` attaching the path being decoded.
+    pub fn decode(path: impl Into<PathBuf>) -> impl FnOnce(serde_json::Error) -> Self {
+        let path = path.into();
+        move |source| Self::Decode { path, source }
+    }
+}
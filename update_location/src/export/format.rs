@@ -0,0 +1,118 @@
+//! Encoders of the export formats. All of them stream into a writer.
+
+use std::io::{self, BufRead, Write};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use crate::iproyal::ip_availability::Availability;
+use crate::iproyal::models::Root;
+use crate::models::ExportFormat;
+use super::delta::Delta;
+use crate::unified::LocationRecord;
+
+/// File extension of `format`.
+pub fn extension(format: ExportFormat) -> &'static str {
+    match format {
+        ExportFormat::Json => "json",
+        ExportFormat::Ndjson => "ndjson",
+        ExportFormat::Csv => "csv",
+        ExportFormat::TreeJson => "tree.json",
+        ExportFormat::DeltaJson => "delta.json",
+    }
+}
+
+/// Writes `records`, or for `tree-json` the iproyal `tree` and for `delta-json`
+/// the `delta`, to `out` in `format`; encoding failures surface as I/O errors.
+pub fn write(format: ExportFormat, records: &[LocationRecord], tree: Option<&Root>, delta: Option<&Delta>, mut out: impl Write) -> io::Result<()> {
+    match format {
+        ExportFormat::TreeJson => {
+            let tree = tree.ok_or_else(|| io::Error::other("no iproyal tree to write"))?;
+            serde_json::to_writer(&mut out, tree)?;
+        }
+        ExportFormat::DeltaJson => {
+            let delta = delta.ok_or_else(|| io::Error::other("no delta to write"))?;
+            serde_json::to_writer(&mut out, delta)?;
+        }
+        ExportFormat::Json => serde_json::to_writer(&mut out, records)?,
+        ExportFormat::Ndjson => {
+            for record in records {
+                serde_json::to_writer(&mut out, record)?;
+                out.write_all(b"\n")?;
+            }
+        }
+        ExportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(&mut out);
+            for record in records {
+                writer.serialize(CsvRow::from(record))?;
+            }
+            writer.flush()?;
+        }
+    }
+    out.flush()
+}
+
+/// Reads back records written in `format`, if the format keeps them losslessly, as `T`.
+///
+/// CSV flattens availability ranges, so it is not read back; `tree-json` holds no
+/// unified records and `delta-json` only some of them.
+pub fn read<T: DeserializeOwned>(format: ExportFormat, input: impl BufRead) -> Option<serde_json::Result<Vec<T>>> {
+    match format {
+        ExportFormat::Json => Some(serde_json::from_reader(input)),
+        ExportFormat::Ndjson => Some(
+            input
+                .lines()
+                .filter(|line| line.as_ref().is_ok_and(|l| !l.trim().is_empty()))
+                .map(|line| serde_json::from_str(&line.map_err(serde_json::Error::io)?))
+                .collect(),
+        ),
+        ExportFormat::Csv | ExportFormat::TreeJson | ExportFormat::DeltaJson => None,
+    }
+}
+
+/// Flat CSV shape of a [`LocationRecord`]; availability becomes a `min`/`max` pair.
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    provider: &'a str,
+    country: &'a str,
+    subdivision: Option<&'a str>,
+    subdivision_code: Option<&'a str>,
+    city: Option<&'a str>,
+    city_ascii: Option<&'a str>,
+    isp: Option<&'a str>,
+    nodes: Option<u64>,
+    availability_min: Option<u64>,
+    availability_max: Option<u64>,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    targeting: Option<&'a str>,
+    fetched_at: Option<u64>,
+    source_endpoint: Option<&'a str>,
+    run_id: Option<&'a str>,
+}
+
+impl<'a> From<&'a LocationRecord> for CsvRow<'a> {
+    fn from(record: &'a LocationRecord) -> Self {
+        let (availability_min, availability_max) = match record.availability {
+            Some(Availability::Approx(n)) => (Some(n), None),
+            Some(Availability::Range { min, max }) => (Some(min), Some(max)),
+            Some(Availability::Unknown) | None => (None, None),
+        };
+        Self {
+            provider: record.provider.as_str(),
+            country: &record.country,
+            subdivision: record.subdivision.as_deref(),
+            subdivision_code: record.subdivision_code.as_deref(),
+            city: record.city.as_deref(),
+            city_ascii: record.city_ascii.as_deref(),
+            isp: record.isp.as_deref(),
+            nodes: record.nodes,
+            availability_min,
+            availability_max,
+            latitude: record.latitude,
+            longitude: record.longitude,
+            targeting: record.targeting.as_deref(),
+            fetched_at: record.provenance.as_ref().and_then(|p| p.fetched_at),
+            source_endpoint: record.provenance.as_ref().map(|p| p.source_endpoint.as_str()),
+            run_id: record.provenance.as_ref().map(|p| p.run_id.as_str()),
+        }
+    }
+}
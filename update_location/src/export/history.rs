@@ -0,0 +1,189 @@
+//! `history.ndjson`: per-run aggregates outliving snapshot retention.
+//!
+//! Every export appends one line with the node and city totals per provider and
+//! country, so trends can be reported long after the snapshots themselves were
+//! pruned. Lines that fail to parse, e.g. a partial write, are skipped.
+//!
+//! The history is a file rather than tables of a SQLite or Postgres sink because
+//! the tool has no database sink: changes are only published to Kafka and NATS
+//! (see [`crate::sinks`]). A database backend would add the same two operations,
+//! [`History::append`] at export and [`History::since`] for `stats --history`,
+//! behind its own feature.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use serde::{Deserialize, Serialize};
+use crate::export::ExportError;
+use crate::unified::{LocationRecord, Provider};
+
+/// File name of the history inside the export directory.
+const HISTORY_FILE: &str = "history.ndjson";
+
+/// Aggregates of one exported run.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RunAggregate {
+    pub snapshot: String,
+    pub run_id: String,
+    /// RFC 3339 time of the export.
+    pub recorded_at: String,
+    pub countries: Vec<CountryAggregate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CountryAggregate {
+    pub provider: Provider,
+    pub country: String,
+    pub nodes: u64,
+    pub cities: usize,
+}
+
+impl RunAggregate {
+    pub fn new(snapshot: &str, run_id: &str, at: SystemTime, records: &[LocationRecord]) -> Self {
+        let mut totals: BTreeMap<(Provider, &str), (u64, BTreeSet<&str>)> = BTreeMap::new();
+        for record in records {
+            let (nodes, cities) = totals.entry((record.provider, &record.country)).or_default();
+            *nodes += record.node_estimate().unwrap_or(0);
+            if let Some(city) = &record.city {
+                cities.insert(city);
+            }
+        }
+
+        Self {
+            snapshot: snapshot.to_string(),
+            run_id: run_id.to_string(),
+            recorded_at: humantime::format_rfc3339_seconds(at).to_string(),
+            countries: totals
+                .into_iter()
+                .map(|((provider, country), (nodes, cities))| CountryAggregate {
+                    provider,
+                    country: country.to_string(),
+                    nodes,
+                    cities: cities.len(),
+                })
+                .collect(),
+        }
+    }
+
+    fn recorded_at(&self) -> Option<SystemTime> {
+        humantime::parse_rfc3339(&self.recorded_at).ok()
+    }
+}
+
+/// The history file of an export directory.
+pub struct History {
+    path: PathBuf,
+}
+
+impl History {
+    pub fn new(export_dir: &Path) -> Self {
+        Self { path: export_dir.join(HISTORY_FILE) }
+    }
+
+    pub fn append(&self, run: &RunAggregate) -> Result<(), ExportError> {
+        let mut line = serde_json::to_vec(run).map_err(ExportError::decode(&self.path))?;
+        line.push(b'\n');
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(&line))
+            .map_err(ExportError::io(&self.path))
+    }
+
+    /// Runs recorded at or after `since`, oldest first.
+    pub fn since(&self, since: SystemTime) -> Result<Vec<RunAggregate>, ExportError> {
+        let file = match std::fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(ExportError::io(&self.path)(err)),
+        };
+
+        let mut runs = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(ExportError::io(&self.path))?;
+            let Ok(run) = serde_json::from_str::<RunAggregate>(&line) else {
+                continue;
+            };
+            if run.recorded_at().is_some_and(|at| at >= since) {
+                runs.push(run);
+            }
+        }
+        runs.sort_by_key(|run| run.recorded_at());
+        Ok(runs)
+    }
+}
+
+/// Change of one provider and country between two runs.
+#[derive(Debug, Serialize)]
+pub struct CountryTrend {
+    pub provider: Provider,
+    pub country: String,
+    /// Totals in the first and last run; `None` when the country is missing from that run.
+    pub nodes_first: Option<u64>,
+    pub nodes_last: Option<u64>,
+    pub delta: i64,
+}
+
+/// Node-count changes from `first` to `last`, by provider and country.
+pub fn trend(first: &RunAggregate, last: &RunAggregate) -> Vec<CountryTrend> {
+    let index = |run: &RunAggregate| -> BTreeMap<(Provider, String), u64> {
+        run.countries
+            .iter()
+            .map(|c| ((c.provider, c.country.clone()), c.nodes))
+            .collect()
+    };
+    let (before, after) = (index(first), index(last));
+
+    let keys: BTreeSet<&(Provider, String)> = before.keys().chain(after.keys()).collect();
+    keys.into_iter()
+        .map(|key| {
+            let nodes_first = before.get(key).copied();
+            let nodes_last = after.get(key).copied();
+            CountryTrend {
+                provider: key.0,
+                country: key.1.clone(),
+                nodes_first,
+                nodes_last,
+                delta: nodes_last.unwrap_or(0) as i64 - nodes_first.unwrap_or(0) as i64,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aggregate(countries: &[(&str, u64)]) -> RunAggregate {
+        RunAggregate {
+            snapshot: String::new(),
+            run_id: String::new(),
+            recorded_at: String::new(),
+            countries: countries
+                .iter()
+                .map(|&(country, nodes)| CountryAggregate {
+                    provider: Provider::Infatica,
+                    country: country.to_string(),
+                    nodes,
+                    cities: 1,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn trend_covers_countries_of_either_run() {
+        let trends = trend(&aggregate(&[("DE", 100), ("FR", 40)]), &aggregate(&[("DE", 70), ("US", 10)]));
+        let summary: Vec<(&str, Option<u64>, Option<u64>, i64)> = trends
+            .iter()
+            .map(|t| (t.country.as_str(), t.nodes_first, t.nodes_last, t.delta))
+            .collect();
+        assert_eq!(
+            summary,
+            [("DE", Some(100), Some(70), -30), ("FR", Some(40), None, -40), ("US", None, Some(10), 10)]
+        );
+    }
+}
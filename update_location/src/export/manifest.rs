@@ -0,0 +1,185 @@
+//! `manifest.json`: integrity and provenance of a snapshot.
+//!
+//! Lists every artifact with its size and SHA-256 as stored on disk, after
+//! compression, so a loader can detect truncated or corrupted transfers, plus
+//! the run id, record counts and the provider endpoints (which carry the API
+//! version) the data came from.
+
+use std::io::{self, Write};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use crate::models::{ExportCompression, ExportFormat};
+use crate::unified::{schema, Provider, SCHEMA_VERSION};
+
+/// File name of the manifest inside a snapshot directory.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Version of the manifest layout, bumped on incompatible changes.
+const MANIFEST_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+pub struct Manifest<'a> {
+    pub manifest_version: u32,
+    /// Version of the record layout of the artifacts, see [`crate::unified::schema`].
+    pub schema_version: u32,
+    pub run_id: &'a str,
+    pub snapshot: &'a str,
+    /// RFC 3339 creation time.
+    pub created_at: String,
+    /// Name and version of the tool that wrote the snapshot.
+    pub generator: String,
+    /// Total records in each artifact.
+    pub records: usize,
+    pub providers: &'a [ProviderSource],
+    /// Filter expression the records were selected with, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<&'a str>,
+    pub artifacts: Vec<Artifact>,
+}
+
+impl<'a> Manifest<'a> {
+    pub fn new(run: &'a ExportRun, snapshot: &'a str, created_at: String, records: usize) -> Self {
+        Self {
+            manifest_version: MANIFEST_VERSION,
+            schema_version: SCHEMA_VERSION,
+            run_id: &run.run_id,
+            snapshot,
+            created_at,
+            generator: format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+            records,
+            providers: &run.providers,
+            filter: run.filter.as_deref(),
+            artifacts: Vec::new(),
+        }
+    }
+}
+
+/// The part of a written manifest needed to read a snapshot back.
+#[derive(Deserialize)]
+pub struct ManifestArtifacts {
+    /// Missing from manifests written before the layout was versioned.
+    #[serde(default = "unversioned")]
+    pub schema_version: u32,
+    pub artifacts: Vec<Artifact>,
+}
+
+fn unversioned() -> u32 {
+    schema::UNVERSIONED
+}
+
+/// Identity of the run being exported.
+pub struct ExportRun {
+    pub run_id: String,
+    pub providers: Vec<ProviderSource>,
+    pub filter: Option<String>,
+}
+
+impl ExportRun {
+    /// Describes run `run_id`, see [`crate::run_id`].
+    pub fn new(run_id: String, providers: Vec<ProviderSource>, filter: Option<String>) -> Self {
+        Self { run_id, providers, filter }
+    }
+}
+
+/// Where the records of one provider came from.
+#[derive(Serialize)]
+pub struct ProviderSource {
+    pub provider: Provider,
+    pub endpoint: String,
+    pub records: usize,
+}
+
+/// One exported file, relative to the snapshot directory.
+#[derive(Serialize, Deserialize)]
+pub struct Artifact {
+    pub path: String,
+    pub format: ExportFormat,
+    pub compression: ExportCompression,
+    /// Size and digest of the file as stored, i.e. after compression.
+    pub bytes: u64,
+    pub sha256: String,
+}
+
+/// Writer computing the size and SHA-256 of everything passed through it.
+pub struct HashingWriter<W> {
+    inner: W,
+    hasher: Sha256,
+    bytes: u64,
+}
+
+impl<W: Write> HashingWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self { inner, hasher: Sha256::new(), bytes: 0 }
+    }
+
+    /// Flushes and returns the byte count and hex digest.
+    pub fn finish(mut self) -> io::Result<(u64, String)> {
+        self.inner.flush()?;
+        Ok((self.bytes, format!("{:x}", self.hasher.finalize())))
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.bytes += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run() -> ExportRun {
+        let providers = vec![ProviderSource {
+            provider: Provider::IPRoyal,
+            endpoint: "https://example.com/v1".to_string(),
+            records: 2,
+        }];
+        ExportRun::new("run-1".to_string(), providers, Some("country = DE".to_string()))
+    }
+
+    #[test]
+    fn written_manifests_read_back_their_artifacts() {
+        let run = run();
+        let mut manifest = Manifest::new(&run, "20261017T120000Z", "2026-10-17T12:00:00Z".to_string(), 2);
+        manifest.artifacts.push(Artifact {
+            path: "records.ndjson.zst".to_string(),
+            format: ExportFormat::Ndjson,
+            compression: ExportCompression::Zstd,
+            bytes: 42,
+            sha256: "00ff".to_string(),
+        });
+
+        let json = serde_json::to_vec(&manifest).unwrap();
+        let read: ManifestArtifacts = serde_json::from_slice(&json).unwrap();
+        assert_eq!(read.schema_version, SCHEMA_VERSION);
+        let [artifact] = read.artifacts.as_slice() else { panic!("expected one artifact") };
+        assert_eq!(artifact.path, "records.ndjson.zst");
+        assert_eq!((artifact.format, artifact.compression), (ExportFormat::Ndjson, ExportCompression::Zstd));
+        assert_eq!((artifact.bytes, artifact.sha256.as_str()), (42, "00ff"));
+    }
+
+    #[test]
+    fn manifests_without_a_schema_version_are_unversioned() {
+        let read: ManifestArtifacts = serde_json::from_str(r#"{"artifacts": []}"#).unwrap();
+        assert_eq!(read.schema_version, schema::UNVERSIONED);
+    }
+
+    #[test]
+    fn hashing_writer_reports_size_and_sha256_of_the_written_bytes() {
+        let mut writer = HashingWriter::new(Vec::new());
+        writer.write_all(b"ab").unwrap();
+        writer.write_all(b"c").unwrap();
+        assert_eq!(
+            writer.finish().unwrap(),
+            (3, "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad".to_string())
+        );
+    }
+}
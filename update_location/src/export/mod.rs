@@ -0,0 +1,218 @@
+//! # Export snapshots
+//!
+//! Writes the unified records of a run into a new snapshot directory and keeps
+//! a `LATEST` pointer to the most recent complete one:
+//!
+//! ```text
+//! <output.dir>/LATEST                              name of the latest snapshot
+//! <output.dir>/history.ndjson                      aggregates of every run, see [`history`]
+//! <output.dir>/snapshots/20261017T120000Z/locations.json
+//! <output.dir>/snapshots/20261017T120000Z/locations.csv.zst   with `output.compression = "zstd"`
+//! <output.dir>/snapshots/20261017T120000Z/locations.tree.json   the iproyal tree, with `tree-json`
+//! <output.dir>/snapshots/20261017T120000Z/locations.delta.json  changes since the previous snapshot, with `delta-json`
+//! <output.dir>/snapshots/20261017T120000Z/locations.ndjson      base of the next delta, unless `json` or `ndjson` is written
+//! <output.dir>/snapshots/20261017T120000Z/manifest.json   see [`manifest`]
+//! ```
+//!
+//! A snapshot is written under a `.partial` name and renamed once all its
+//! artifacts are complete, so readers following `LATEST` or listing the
+//! directory never see half-written data. After each export, and on `prune`,
+//! snapshots no retention rule (`export.keep_last`, `export.keep_days`) keeps
+//! are removed. [`read_latest`] loads the records back for offline commands.
+
+mod compress;
+pub mod delta;
+mod errors;
+mod format;
+pub mod history;
+pub mod manifest;
+mod snapshots;
+
+pub use errors::ExportError;
+pub use snapshots::SnapshotEntry;
+
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use crate::models::{ExportCompression, ExportConfig, ExportFormat};
+use crate::iproyal::models::Root;
+use crate::unified::{schema, LocationRecord};
+use compress::Encoder;
+use delta::Delta;
+use manifest::{Artifact, ExportRun, HashingWriter, MANIFEST_FILE, Manifest, ManifestArtifacts};
+use snapshots::{Retention, SnapshotDir};
+
+/// Base name of the exported artifacts.
+const ARTIFACT_NAME: &str = "locations";
+
+/// A snapshot written by [`write_snapshot`].
+pub struct WrittenSnapshot {
+    pub name: String,
+    pub path: PathBuf,
+    pub artifacts: Vec<PathBuf>,
+}
+
+/// Writes `records` in every format of `formats`, compressed with `compression`, and
+/// their manifest as a new snapshot under `export_dir`, then points `LATEST` at it. `tree-json` writes
+/// `iproyal_tree` instead, and fails without one. `delta-json` writes the changes
+/// since the snapshot `LATEST` pointed at, and fails if its records cannot be read,
+/// including when a newer build wrote them; without `json` or `ndjson` among
+/// `formats`, `ndjson` is written too, so the next snapshot can be compared to this one.
+///
+/// Artifacts are encoded and compressed while being written, without buffering
+/// them in memory.
+pub fn write_snapshot(
+    export_dir: &Path,
+    formats: &[ExportFormat],
+    compression: ExportCompression,
+    records: &[LocationRecord],
+    iproyal_tree: Option<&Root>,
+    run: &ExportRun,
+) -> Result<WrittenSnapshot, ExportError> {
+    if iproyal_tree.is_none() && formats.contains(&ExportFormat::TreeJson) {
+        return Err(ExportError::NoTree);
+    }
+    let formats = &with_delta_base(formats);
+    let delta = if formats.contains(&ExportFormat::DeltaJson) {
+        Some(match read_latest(export_dir) {
+            Ok((base, previous)) => Delta::new(Some(base), &previous, records),
+            Err(ExportError::NoSnapshot { .. }) => Delta::new(None, &[], records),
+            Err(err) => return Err(err),
+        })
+    } else {
+        None
+    };
+
+    let dir = SnapshotDir::new(export_dir);
+    let now = SystemTime::now();
+    let (name, partial) = dir.begin(now)?;
+
+    let mut manifest = Manifest::new(run, &name, humantime::format_rfc3339_seconds(now).to_string(), records.len());
+    let mut artifacts = Vec::new();
+    for &format in formats {
+        let file_name = format!("{ARTIFACT_NAME}.{}{}", format::extension(format), compress::suffix(compression));
+        let path = partial.join(&file_name);
+        let file = File::create(&path).map_err(ExportError::io(&path))?;
+        let (bytes, sha256) = Encoder::new(compression, HashingWriter::new(BufWriter::new(file)))
+            .and_then(|mut out| {
+                format::write(format, records, iproyal_tree, delta.as_ref(), &mut out)?;
+                out.finish()
+            })
+            .and_then(HashingWriter::finish)
+            .map_err(ExportError::io(&path))?;
+
+        manifest.artifacts.push(Artifact { path: file_name.clone(), format, compression, bytes, sha256 });
+        artifacts.push(PathBuf::from(file_name));
+    }
+
+    let path = partial.join(MANIFEST_FILE);
+    let json = serde_json::to_vec_pretty(&manifest).map_err(|e| ExportError::io(&path)(e.into()))?;
+    std::fs::write(&path, json).map_err(ExportError::io(&path))?;
+    artifacts.push(PathBuf::from(MANIFEST_FILE));
+
+    let path = dir.commit(&name)?;
+    let artifacts = artifacts.into_iter().map(|a| path.join(a)).collect();
+    Ok(WrittenSnapshot { name, path, artifacts })
+}
+
+/// `formats`, with `ndjson` added when `delta-json` is among them without a format
+/// [`read_latest`] can read the records back from.
+fn with_delta_base(formats: &[ExportFormat]) -> Vec<ExportFormat> {
+    let mut formats = formats.to_vec();
+    let readable = formats.iter().any(|f| matches!(f, ExportFormat::Json | ExportFormat::Ndjson));
+    if formats.contains(&ExportFormat::DeltaJson) && !readable {
+        formats.push(ExportFormat::Ndjson);
+    }
+    formats
+}
+
+/// The records of the snapshot `LATEST` of `export_dir` points at, with its name.
+///
+/// Reads the first JSON or NDJSON artifact the manifest lists, migrating records
+/// of an older [schema version](crate::unified::schema); a snapshot written by a
+/// newer build is refused.
+pub fn read_latest(export_dir: &Path) -> Result<(String, Vec<LocationRecord>), ExportError> {
+    let dir = SnapshotDir::new(export_dir);
+    let name = dir.latest().ok_or_else(|| ExportError::NoSnapshot { dir: export_dir.into() })?;
+    let path = dir.path(&name);
+
+    let manifest_path = path.join(MANIFEST_FILE);
+    let manifest = std::fs::read(&manifest_path).map_err(ExportError::io(&manifest_path))?;
+    let manifest: ManifestArtifacts = serde_json::from_slice(&manifest).map_err(ExportError::decode(&manifest_path))?;
+    let version = manifest.schema_version;
+    let incompatible = |snapshot: &str| {
+        let snapshot = snapshot.to_string();
+        move |source| ExportError::Schema { snapshot, source }
+    };
+    schema::check(version).map_err(incompatible(&name))?;
+
+    for artifact in manifest.artifacts {
+        let path = path.join(&artifact.path);
+        let file = File::open(&path).map_err(ExportError::io(&path))?;
+        let input = compress::decoder(artifact.compression, file).map_err(ExportError::io(&path))?;
+        if let Some(records) = format::read(artifact.format, input) {
+            let records = schema::migrate(version, records.map_err(ExportError::decode(&path))?).map_err(incompatible(&name))?;
+            return Ok((name, records));
+        }
+    }
+    Err(ExportError::Unreadable { snapshot: name })
+}
+
+/// Snapshots of `export_dir` the retention rules of `cfg` no longer keep, oldest first.
+pub fn expired(export_dir: &Path, cfg: &ExportConfig) -> Result<Vec<SnapshotEntry>, ExportError> {
+    let dir = SnapshotDir::new(export_dir);
+    let retention = Retention {
+        keep_last: cfg.get_keep_last(),
+        keep_age: cfg.get_keep_age(),
+    };
+    let entries = dir.list()?;
+    let latest = dir.latest();
+    Ok(retention
+        .expired(&entries, latest.as_deref(), SystemTime::now())
+        .into_iter()
+        .cloned()
+        .collect())
+}
+
+/// Removes the snapshots [`expired`] reports, returning them.
+pub fn prune(export_dir: &Path, cfg: &ExportConfig) -> Result<Vec<SnapshotEntry>, ExportError> {
+    let dir = SnapshotDir::new(export_dir);
+    let expired = expired(export_dir, cfg)?;
+    for entry in &expired {
+        dir.remove(entry)?;
+    }
+    Ok(expired)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::unified::Provider;
+    use super::*;
+
+    fn record(city: &str, nodes: u64) -> LocationRecord {
+        LocationRecord {
+            city: Some(city.to_string()),
+            nodes: Some(nodes),
+            ..LocationRecord::sample(Provider::Infatica, "DE")
+        }
+    }
+
+    #[test]
+    fn consecutive_delta_only_exports_compare_to_the_previous_snapshot() {
+        let dir = std::env::temp_dir().join(format!("update_location_export_{}", std::process::id()));
+        let run = ExportRun::new("run-1".to_string(), Vec::new(), None);
+        let formats = [ExportFormat::DeltaJson];
+
+        let first = write_snapshot(&dir, &formats, ExportCompression::None, &[record("Berlin", 5)], None, &run).unwrap();
+        let second = write_snapshot(&dir, &formats, ExportCompression::None, &[record("Berlin", 7)], None, &run).unwrap();
+        let delta: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(second.path.join("locations.delta.json")).unwrap()).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(first.artifacts.iter().any(|a| a.ends_with("locations.ndjson")));
+        assert_eq!(delta["base"], first.name);
+        assert_eq!(delta["changes"][0]["kind"], "count_changed");
+        assert_eq!(delta["changes"].as_array().unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,228 @@
+//! Timestamped snapshot directories and the `LATEST` pointer.
+
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use super::ExportError;
+
+/// Subdirectory holding one directory per snapshot.
+const SNAPSHOTS_DIR: &str = "snapshots";
+
+/// File naming the most recent complete snapshot.
+pub const LATEST_FILE: &str = "LATEST";
+
+/// Suffix of a snapshot still being written; never listed or pruned.
+const PARTIAL_SUFFIX: &str = ".partial";
+
+/// A complete snapshot directory.
+#[derive(Debug, Clone)]
+pub struct SnapshotEntry {
+    /// Directory name, the UTC creation time such as `20261017T120000Z`.
+    pub name: String,
+    pub created: SystemTime,
+    pub path: PathBuf,
+}
+
+/// The export directory.
+pub struct SnapshotDir {
+    root: PathBuf,
+}
+
+impl SnapshotDir {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn snapshots(&self) -> PathBuf {
+        self.root.join(SNAPSHOTS_DIR)
+    }
+
+    /// Directory of the complete snapshot `name`.
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.snapshots().join(name)
+    }
+
+    /// Creates the directory of a new snapshot taken at `at`, still marked partial.
+    ///
+    /// A name already taken within the same second gets a `-<n>` suffix.
+    pub fn begin(&self, at: SystemTime) -> Result<(String, PathBuf), ExportError> {
+        let base = snapshot_name(at);
+        let snapshots = self.snapshots();
+        fs::create_dir_all(&snapshots).map_err(ExportError::io(&snapshots))?;
+
+        for n in 0.. {
+            let name = if n == 0 { base.clone() } else { format!("{base}-{n}") };
+            if snapshots.join(&name).exists() {
+                continue;
+            }
+            let partial = snapshots.join(format!("{name}{PARTIAL_SUFFIX}"));
+            match fs::create_dir(&partial) {
+                Ok(()) => return Ok((name, partial)),
+                Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(err) => return Err(ExportError::io(&partial)(err)),
+            }
+        }
+        unreachable!("snapshot names are unbounded")
+    }
+
+    /// Marks the snapshot `name` complete and points `LATEST` at it.
+    pub fn commit(&self, name: &str) -> Result<PathBuf, ExportError> {
+        let snapshots = self.snapshots();
+        let path = snapshots.join(name);
+        fs::rename(snapshots.join(format!("{name}{PARTIAL_SUFFIX}")), &path).map_err(ExportError::io(&path))?;
+
+        // Replace the pointer atomically so readers never see it empty
+        let latest = self.root.join(LATEST_FILE);
+        let tmp = self.root.join(format!("{LATEST_FILE}{PARTIAL_SUFFIX}"));
+        fs::write(&tmp, format!("{name}\n")).map_err(ExportError::io(&tmp))?;
+        fs::rename(&tmp, &latest).map_err(ExportError::io(&latest))?;
+
+        Ok(path)
+    }
+
+    /// Name of the snapshot `LATEST` points at.
+    pub fn latest(&self) -> Option<String> {
+        let name = fs::read_to_string(self.root.join(LATEST_FILE)).ok()?;
+        Some(name.trim().to_string()).filter(|n| !n.is_empty())
+    }
+
+    /// Complete snapshots, oldest first. Entries not named like a snapshot are ignored.
+    pub fn list(&self) -> Result<Vec<SnapshotEntry>, ExportError> {
+        let snapshots = self.snapshots();
+        let dir = match fs::read_dir(&snapshots) {
+            Ok(dir) => dir,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(ExportError::io(&snapshots)(err)),
+        };
+
+        let mut entries = Vec::new();
+        for entry in dir {
+            let entry = entry.map_err(ExportError::io(&snapshots))?;
+            let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+                continue;
+            };
+            if let Some(created) = parse_snapshot_name(&name)
+                && entry.path().is_dir()
+            {
+                entries.push(SnapshotEntry { name, created, path: entry.path() });
+            }
+        }
+        entries.sort_by(|a, b| a.created.cmp(&b.created).then_with(|| a.name.cmp(&b.name)));
+        Ok(entries)
+    }
+
+    /// Removes a snapshot directory.
+    pub fn remove(&self, entry: &SnapshotEntry) -> Result<(), ExportError> {
+        fs::remove_dir_all(&entry.path).map_err(ExportError::io(&entry.path))
+    }
+}
+
+/// Retention rules; a snapshot is kept when any rule keeps it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Retention {
+    pub keep_last: Option<usize>,
+    pub keep_age: Option<Duration>,
+}
+
+impl Retention {
+    /// Snapshots of `entries` (oldest first) no rule keeps. The `latest` one is always kept,
+    /// and nothing expires without any rule.
+    pub fn expired<'a>(&self, entries: &'a [SnapshotEntry], latest: Option<&str>, now: SystemTime) -> Vec<&'a SnapshotEntry> {
+        if self.keep_last.is_none() && self.keep_age.is_none() {
+            return Vec::new();
+        }
+
+        let newest = entries.len().saturating_sub(self.keep_last.unwrap_or(0));
+        entries
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i < newest)
+            .filter(|(_, e)| {
+                self.keep_age
+                    .is_none_or(|age| now.duration_since(e.created).unwrap_or_default() > age)
+            })
+            .filter(|(_, e)| latest != Some(e.name.as_str()))
+            .map(|(_, e)| e)
+            .collect()
+    }
+}
+
+/// `20261017T120000Z` for `at`.
+fn snapshot_name(at: SystemTime) -> String {
+    humantime::format_rfc3339_seconds(at).to_string().replace(['-', ':'], "")
+}
+
+/// Creation time of a snapshot named by [`snapshot_name`], with an optional `-<n>` suffix.
+fn parse_snapshot_name(name: &str) -> Option<SystemTime> {
+    let stamp = name.split_once('-').map_or(name, |(stamp, n)| {
+        if n.chars().all(|c| c.is_ascii_digit()) { stamp } else { "" }
+    });
+    let b = stamp.as_bytes();
+    if b.len() != 16 || b[8] != b'T' || b[15] != b'Z' {
+        return None;
+    }
+    let rfc3339 = format!(
+        "{}-{}-{}T{}:{}:{}Z",
+        &stamp[0..4], &stamp[4..6], &stamp[6..8], &stamp[9..11], &stamp[11..13], &stamp[13..15],
+    );
+    humantime::parse_rfc3339(&rfc3339).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    fn entries(now: SystemTime, ages_in_days: &[u64]) -> Vec<SnapshotEntry> {
+        ages_in_days
+            .iter()
+            .map(|days| {
+                let created = now - DAY * (*days as u32);
+                let name = snapshot_name(created);
+                SnapshotEntry { path: PathBuf::from(&name), name, created }
+            })
+            .collect()
+    }
+
+    fn names(expired: Vec<&SnapshotEntry>) -> Vec<String> {
+        expired.into_iter().map(|e| e.name.clone()).collect()
+    }
+
+    #[test]
+    fn snapshot_names_round_trip() {
+        let at = humantime::parse_rfc3339("2026-10-17T12:34:56Z").unwrap();
+        assert_eq!(snapshot_name(at), "20261017T123456Z");
+        assert_eq!(parse_snapshot_name("20261017T123456Z"), Some(at));
+        assert_eq!(parse_snapshot_name("20261017T123456Z-2"), Some(at));
+        assert_eq!(parse_snapshot_name("20261017T123456Z.partial"), None);
+        assert_eq!(parse_snapshot_name("notes"), None);
+    }
+
+    #[test]
+    fn without_rules_nothing_expires() {
+        let now = SystemTime::now();
+        let all = entries(now, &[400, 200, 1]);
+        assert!(Retention::default().expired(&all, None, now).is_empty());
+    }
+
+    #[test]
+    fn a_snapshot_expires_only_when_no_rule_keeps_it() {
+        let now = SystemTime::now();
+        let all = entries(now, &[120, 100, 95, 60, 10, 1]);
+        let retention = Retention { keep_last: Some(2), keep_age: Some(DAY * 90) };
+
+        assert_eq!(names(retention.expired(&all, None, now)), names(all.iter().take(3).collect()));
+        // Keeping the last 5 overrides the age limit for the two younger of the old ones
+        let retention = Retention { keep_last: Some(5), ..retention };
+        assert_eq!(names(retention.expired(&all, None, now)), vec![all[0].name.clone()]);
+    }
+
+    #[test]
+    fn the_latest_snapshot_never_expires() {
+        let now = SystemTime::now();
+        let all = entries(now, &[30, 20]);
+        let retention = Retention { keep_last: None, keep_age: Some(DAY) };
+        assert_eq!(names(retention.expired(&all, Some(&all[0].name), now)), vec![all[1].name.clone()]);
+    }
+}
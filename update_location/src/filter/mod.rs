@@ -0,0 +1,264 @@
+//! # Record filter expressions
+//!
+//! A small expression language selecting [`LocationRecord`]s, used by
+//! `output.filter` / `--filter` to produce targeted exports and sink events:
+//!
+//! ```text
+//! country in ["US", "DE"] && nodes > 10
+//! provider == "infatica" || (city != null && !(isp == "Comcast"))
+//! ```
+//!
+//! - Fields: `provider`, `country`, `subdivision`, `subdivision_code`, `city`,
+//!   `isp` (text) and `nodes`, `latitude`, `longitude` (numbers). `nodes` is the
+//!   node estimate, so iproyal availability counts as well.
+//! - Comparisons: `==`, `!=`, `in [..]` for all fields; `<`, `<=`, `>`, `>=` for
+//!   numbers. Text compares case-insensitively.
+//! - `field == null` / `field != null` test whether a value is present. A missing
+//!   value never equals a literal, so `city != "Berlin"` also matches records
+//!   without a city, while ordering comparisons and `in` never match them.
+//! - Combinators: `&&`, `||`, `!` and parentheses; `&&` binds tighter than `||`.
+//!
+//! Type errors, such as `country > 3`, are reported when parsing.
+
+mod parser;
+
+use std::fmt;
+use std::str::FromStr;
+use serde::Deserialize;
+use crate::unified::LocationRecord;
+
+/// A parsed filter expression together with its source text.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Filter {
+    source: String,
+    expr: Expr,
+}
+
+impl Filter {
+    /// Whether `record` satisfies the expression.
+    pub fn matches(&self, record: &LocationRecord) -> bool {
+        self.expr.eval(record)
+    }
+
+    /// The expression as written.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl FromStr for Filter {
+    type Err = FilterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self {
+            source: s.trim().to_string(),
+            expr: parser::parse(s)?,
+        })
+    }
+}
+
+impl TryFrom<String> for Filter {
+    type Error = FilterError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for Filter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+/// Syntax or type error in a filter expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterError {
+    /// 1-based character position of the offending token.
+    pub position: usize,
+    pub message: String,
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid filter at position {}: {}", self.position, self.message)
+    }
+}
+
+impl std::error::Error for FilterError {}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Field, Op, Literal),
+    In(Field, Vec<Literal>),
+    IsNull(Field, bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Literal {
+    Text(String),
+    Number(f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Provider,
+    Country,
+    Subdivision,
+    SubdivisionCode,
+    City,
+    Isp,
+    Nodes,
+    Latitude,
+    Longitude,
+}
+
+enum Value<'a> {
+    Text(Option<&'a str>),
+    Number(Option<f64>),
+}
+
+impl Field {
+    const ALL: [(&'static str, Field); 9] = [
+        ("provider", Field::Provider),
+        ("country", Field::Country),
+        ("subdivision", Field::Subdivision),
+        ("subdivision_code", Field::SubdivisionCode),
+        ("city", Field::City),
+        ("isp", Field::Isp),
+        ("nodes", Field::Nodes),
+        ("latitude", Field::Latitude),
+        ("longitude", Field::Longitude),
+    ];
+
+    fn is_numeric(self) -> bool {
+        matches!(self, Field::Nodes | Field::Latitude | Field::Longitude)
+    }
+
+    fn value(self, record: &LocationRecord) -> Value<'_> {
+        match self {
+            Field::Provider => Value::Text(Some(record.provider.as_str())),
+            Field::Country => Value::Text(Some(&record.country)),
+            Field::Subdivision => Value::Text(record.subdivision.as_deref()),
+            Field::SubdivisionCode => Value::Text(record.subdivision_code.as_deref()),
+            Field::City => Value::Text(record.city.as_deref()),
+            Field::Isp => Value::Text(record.isp.as_deref()),
+            Field::Nodes => Value::Number(record.node_estimate().map(|n| n as f64)),
+            Field::Latitude => Value::Number(record.latitude),
+            Field::Longitude => Value::Number(record.longitude),
+        }
+    }
+}
+
+impl Expr {
+    fn eval(&self, record: &LocationRecord) -> bool {
+        match self {
+            Expr::And(a, b) => a.eval(record) && b.eval(record),
+            Expr::Or(a, b) => a.eval(record) || b.eval(record),
+            Expr::Not(e) => !e.eval(record),
+            Expr::IsNull(field, null) => {
+                let present = match field.value(record) {
+                    Value::Text(v) => v.is_some(),
+                    Value::Number(v) => v.is_some(),
+                };
+                present != *null
+            }
+            Expr::In(field, list) => list.iter().any(|l| compare(field.value(record), Op::Eq, l)),
+            Expr::Compare(field, op, literal) => compare(field.value(record), *op, literal),
+        }
+    }
+}
+
+fn compare(value: Value<'_>, op: Op, literal: &Literal) -> bool {
+    let ordering = match (value, literal) {
+        (Value::Text(Some(v)), Literal::Text(l)) => Some(v.to_lowercase().cmp(&l.to_lowercase())),
+        (Value::Number(Some(v)), Literal::Number(l)) => v.partial_cmp(l),
+        _ => None,
+    };
+    match ordering {
+        Some(ordering) => match op {
+            Op::Eq => ordering.is_eq(),
+            Op::Ne => ordering.is_ne(),
+            Op::Lt => ordering.is_lt(),
+            Op::Le => ordering.is_le(),
+            Op::Gt => ordering.is_gt(),
+            Op::Ge => ordering.is_ge(),
+        },
+        // A missing value only differs from every literal
+        None => op == Op::Ne,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::iproyal::ip_availability::Availability;
+    use crate::unified::Provider;
+
+    fn record(provider: Provider, country: &str, city: Option<&str>, nodes: Option<u64>) -> LocationRecord {
+        LocationRecord {
+            city: city.map(str::to_string),
+            nodes,
+            ..LocationRecord::sample(provider, country)
+        }
+    }
+
+    fn matches(filter: &str, record: &LocationRecord) -> bool {
+        filter.parse::<Filter>().unwrap().matches(record)
+    }
+
+    #[test]
+    fn combines_membership_and_numbers() {
+        let filter = r#"country in ["US", "DE"] && nodes > 10"#;
+        assert!(matches(filter, &record(Provider::Infatica, "DE", None, Some(11))));
+        assert!(!matches(filter, &record(Provider::Infatica, "DE", None, Some(10))));
+        assert!(!matches(filter, &record(Provider::Infatica, "FR", None, Some(50))));
+    }
+
+    #[test]
+    fn nodes_use_the_availability_estimate() {
+        let mut r = record(Provider::IPRoyal, "US", None, None);
+        r.availability = Some(Availability::Range { min: 100, max: 500 });
+        assert!(matches("nodes >= 100", &r));
+    }
+
+    #[test]
+    fn precedence_negation_and_case() {
+        let r = record(Provider::IPRoyal, "US", Some("Berlin"), None);
+        assert!(matches(r#"provider == "INFATICA" || country == "us" && city == "berlin""#, &r));
+        assert!(!matches(r#"(provider == "infatica" || country == "us") && !(city == "Berlin")"#, &r));
+    }
+
+    #[test]
+    fn missing_values() {
+        let r = record(Provider::IPRoyal, "US", None, None);
+        assert!(matches("city == null && nodes == null", &r));
+        assert!(matches(r#"city != "Berlin""#, &r));
+        assert!(!matches("nodes < 5", &r));
+        assert!(!matches(r#"city in ["Berlin"]"#, &r));
+    }
+
+    #[test]
+    fn reports_errors_with_positions() {
+        let err = |s: &str| s.parse::<Filter>().unwrap_err();
+        assert_eq!(err("country > 3").position, 9);
+        assert_eq!(err("nodes > 1 &&").position, 13);
+        assert_eq!(err("town == \"x\"").position, 1);
+        assert_eq!(err("city == \"x").position, 9);
+        assert_eq!(err("(nodes > 1").position, 11);
+    }
+}
@@ -0,0 +1,254 @@
+//! Tokenizer and recursive-descent parser of filter expressions.
+//!
+//! ```text
+//! or         := and ("||" and)*
+//! and        := unary ("&&" unary)*
+//! unary      := "!" unary | "(" or ")" | comparison
+//! comparison := field ("==" | "!=" | "<" | "<=" | ">" | ">=") literal
+//!             | field ("==" | "!=") "null"
+//!             | field "in" "[" literal ("," literal)* "]"
+//! literal    := string | number
+//! ```
+
+use super::{Expr, Field, FilterError, Literal, Op};
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Text(String),
+    Number(f64),
+    Op(Op),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+}
+
+/// A token and its 1-based character position.
+type Spanned = (Token, usize);
+
+pub fn parse(input: &str) -> Result<Expr, FilterError> {
+    let tokens = tokenize(input)?;
+    let end = input.chars().count() + 1;
+    let mut parser = Parser { tokens, pos: 0, end };
+    let expr = parser.or()?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some((token, at)) => Err(error(at, format!("unexpected {}", describe(token)))),
+    }
+}
+
+fn error(position: usize, message: impl Into<String>) -> FilterError {
+    FilterError { position, message: message.into() }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Ident(s) => format!("`{s}`"),
+        Token::Text(s) => format!("\"{s}\""),
+        Token::Number(n) => format!("`{n}`"),
+        Token::Op(_) => "operator".to_string(),
+        Token::And => "`&&`".to_string(),
+        Token::Or => "`||`".to_string(),
+        Token::Not => "`!`".to_string(),
+        Token::LParen => "`(`".to_string(),
+        Token::RParen => "`)`".to_string(),
+        Token::LBracket => "`[`".to_string(),
+        Token::RBracket => "`]`".to_string(),
+        Token::Comma => "`,`".to_string(),
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Spanned>, FilterError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let at = i + 1;
+        let next = chars.get(i + 1).copied();
+
+        let (token, len) = match (c, next) {
+            (c, _) if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            ('&', Some('&')) => (Token::And, 2),
+            ('|', Some('|')) => (Token::Or, 2),
+            ('=', Some('=')) => (Token::Op(Op::Eq), 2),
+            ('!', Some('=')) => (Token::Op(Op::Ne), 2),
+            ('<', Some('=')) => (Token::Op(Op::Le), 2),
+            ('>', Some('=')) => (Token::Op(Op::Ge), 2),
+            ('<', _) => (Token::Op(Op::Lt), 1),
+            ('>', _) => (Token::Op(Op::Gt), 1),
+            ('!', _) => (Token::Not, 1),
+            ('(', _) => (Token::LParen, 1),
+            (')', _) => (Token::RParen, 1),
+            ('[', _) => (Token::LBracket, 1),
+            (']', _) => (Token::RBracket, 1),
+            (',', _) => (Token::Comma, 1),
+            ('"', _) => {
+                let mut text = String::new();
+                let mut j = i + 1;
+                loop {
+                    match chars.get(j) {
+                        None => return Err(error(at, "unterminated string")),
+                        Some('"') => break,
+                        Some('\\') if j + 1 < chars.len() => {
+                            text.push(chars[j + 1]);
+                            j += 2;
+                        }
+                        Some(&c) => {
+                            text.push(c);
+                            j += 1;
+                        }
+                    }
+                }
+                (Token::Text(text), j + 1 - i)
+            }
+            (c, _) if c.is_ascii_digit() || c == '-' || c == '.' => {
+                let len = chars[i..]
+                    .iter()
+                    .enumerate()
+                    .take_while(|(k, c)| c.is_ascii_digit() || **c == '.' || (*k == 0 && **c == '-'))
+                    .count();
+                let text: String = chars[i..i + len].iter().collect();
+                let number = text.parse().map_err(|_| error(at, format!("invalid number `{text}`")))?;
+                (Token::Number(number), len)
+            }
+            (c, _) if c.is_alphabetic() || c == '_' => {
+                let len = chars[i..].iter().take_while(|c| c.is_alphanumeric() || **c == '_').count();
+                (Token::Ident(chars[i..i + len].iter().collect()), len)
+            }
+            (c, _) => return Err(error(at, format!("unexpected character `{c}`"))),
+        };
+
+        tokens.push((token, at));
+        i += len;
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Spanned>,
+    pos: usize,
+    /// Position reported for a premature end of input.
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<(&Token, usize)> {
+        self.tokens.get(self.pos).map(|(t, at)| (t, *at))
+    }
+
+    fn next(&mut self, expected: &str) -> Result<Spanned, FilterError> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| error(self.end, format!("unexpected end of filter, expected {expected}")))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        let matched = self.peek().is_some_and(|(t, _)| t == token);
+        if matched {
+            self.pos += 1;
+        }
+        matched
+    }
+
+    fn expect(&mut self, token: Token, expected: &str) -> Result<(), FilterError> {
+        match self.next(expected)? {
+            (t, _) if t == token => Ok(()),
+            (t, at) => Err(error(at, format!("expected {expected}, found {}", describe(&t)))),
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, FilterError> {
+        let mut expr = self.and()?;
+        while self.eat(&Token::Or) {
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, FilterError> {
+        let mut expr = self.unary()?;
+        while self.eat(&Token::And) {
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, FilterError> {
+        if self.eat(&Token::Not) {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.eat(&Token::LParen) {
+            let expr = self.or()?;
+            self.expect(Token::RParen, "`)`")?;
+            return Ok(expr);
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, FilterError> {
+        let field = match self.next("a field")? {
+            (Token::Ident(name), at) => Field::ALL
+                .iter()
+                .find(|(n, _)| *n == name)
+                .map(|(_, f)| *f)
+                .ok_or_else(|| {
+                    let known = Field::ALL.iter().map(|(n, _)| *n).collect::<Vec<_>>().join(", ");
+                    error(at, format!("unknown field `{name}`, expected one of: {known}"))
+                })?,
+            (token, at) => return Err(error(at, format!("expected a field, found {}", describe(&token)))),
+        };
+
+        match self.next("an operator")? {
+            (Token::Ident(kw), _) if kw == "in" => {
+                self.expect(Token::LBracket, "`[`")?;
+                let mut list = vec![self.literal(field)?];
+                while self.eat(&Token::Comma) {
+                    list.push(self.literal(field)?);
+                }
+                self.expect(Token::RBracket, "`]` or `,`")?;
+                Ok(Expr::In(field, list))
+            }
+            (Token::Op(op), at) => {
+                if matches!(self.peek(), Some((Token::Ident(kw), _)) if kw == "null") {
+                    self.pos += 1;
+                    return match op {
+                        Op::Eq => Ok(Expr::IsNull(field, true)),
+                        Op::Ne => Ok(Expr::IsNull(field, false)),
+                        _ => Err(error(at, "`null` can only be compared with `==` or `!=`")),
+                    };
+                }
+                if !matches!(op, Op::Eq | Op::Ne) && !field.is_numeric() {
+                    return Err(error(at, "ordering comparisons need a numeric field (nodes, latitude, longitude)"));
+                }
+                Ok(Expr::Compare(field, op, self.literal(field)?))
+            }
+            (token, at) => Err(error(at, format!("expected an operator or `in`, found {}", describe(&token)))),
+        }
+    }
+
+    /// A literal of the type of `field`.
+    fn literal(&mut self, field: Field) -> Result<Literal, FilterError> {
+        match (self.next("a value")?, field.is_numeric()) {
+            ((Token::Number(n), _), true) => Ok(Literal::Number(n)),
+            ((Token::Text(s), _), false) => Ok(Literal::Text(s)),
+            ((Token::Number(_), at), false) => Err(error(at, "expected a quoted string for a text field")),
+            ((Token::Text(_), at), true) => Err(error(at, "expected a number for a numeric field")),
+            ((token, at), _) => Err(error(at, format!("expected a value, found {}", describe(&token)))),
+        }
+    }
+}
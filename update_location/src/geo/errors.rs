@@ -0,0 +1,16 @@
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Failure while loading the offline geocoding dataset.
+#[derive(Debug, Error)]
+pub enum GeoError {
+    #[error("failed to read geo dataset {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("malformed geo dataset line {line}: {reason}")]
+    Parse { line: usize, reason: String },
+}
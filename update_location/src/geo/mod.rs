@@ -0,0 +1,207 @@
+//! # Offline coordinate enrichment
+//!
+//! Resolves record locations to latitude/longitude using a GeoNames cities dump
+//! (`cities500.txt`, `cities15000.txt`, ... from <https://download.geonames.org/export/dump/>),
+//! configured via `geo.dataset`. No network access is involved.
+//!
+//! Cities are looked up by country and canonical name (see
+//! [`normalize_name`](crate::matching::normalize_name)), including GeoNames
+//! alternate names. When several places share a name, the most populous wins.
+//! Records without a resolvable city keep `None` coordinates.
+
+mod errors;
+
+pub use errors::GeoError;
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+use crate::matching::normalize_name;
+use crate::unified::LocationRecord;
+
+// Column indices of the GeoNames "geoname" table.
+const COL_NAME: usize = 1;
+const COL_ASCII_NAME: usize = 2;
+const COL_ALTERNATE_NAMES: usize = 3;
+const COL_LATITUDE: usize = 4;
+const COL_LONGITUDE: usize = 5;
+const COL_COUNTRY: usize = 8;
+const COL_POPULATION: usize = 14;
+
+#[derive(Clone, Copy)]
+struct Place {
+    latitude: f64,
+    longitude: f64,
+    population: u64,
+}
+
+/// City coordinates keyed by (country, canonical name).
+pub struct GeoIndex {
+    places: HashMap<(String, String), Place>,
+}
+
+impl GeoIndex {
+    /// Loads places of the given countries (ISO 3166-1 alpha-2) from a GeoNames dump.
+    pub fn load(path: &Path, countries: &HashSet<&str>) -> Result<Self, GeoError> {
+        let file = File::open(path).map_err(|source| GeoError::Io { path: path.to_path_buf(), source })?;
+        Self::read(BufReader::new(file), path, countries)
+    }
+
+    /// Parses a GeoNames dump read from `path`.
+    fn read(reader: impl BufRead, path: &Path, countries: &HashSet<&str>) -> Result<Self, GeoError> {
+        let io_err = |source| GeoError::Io { path: path.to_path_buf(), source };
+
+        let mut places = HashMap::new();
+        for (idx, line) in reader.lines().enumerate() {
+            let line = line.map_err(io_err)?;
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let cols: Vec<&str> = line.split('\t').collect();
+            if cols.len() <= COL_POPULATION {
+                return Err(GeoError::Parse {
+                    line: idx + 1,
+                    reason: format!("expected at least {} columns, got {}", COL_POPULATION + 1, cols.len()),
+                });
+            }
+            if !countries.contains(cols[COL_COUNTRY]) {
+                continue;
+            }
+
+            let coord = |col: usize| {
+                cols[col].parse::<f64>().map_err(|e| GeoError::Parse {
+                    line: idx + 1,
+                    reason: format!("invalid coordinate {:?}: {e}", cols[col]),
+                })
+            };
+            let place = Place {
+                latitude: coord(COL_LATITUDE)?,
+                longitude: coord(COL_LONGITUDE)?,
+                population: cols[COL_POPULATION].parse().unwrap_or(0),
+            };
+
+            let names = [cols[COL_NAME], cols[COL_ASCII_NAME]]
+                .into_iter()
+                .chain(cols[COL_ALTERNATE_NAMES].split(','));
+            for name in names.filter(|n| !n.is_empty()) {
+                let key = (cols[COL_COUNTRY].to_string(), normalize_name(name));
+                places
+                    .entry(key)
+                    .and_modify(|p: &mut Place| {
+                        if place.population > p.population {
+                            *p = place;
+                        }
+                    })
+                    .or_insert(place);
+            }
+        }
+
+        Ok(Self { places })
+    }
+
+    /// Coordinates of a city, as `(latitude, longitude)`.
+    pub fn lookup(&self, country: &str, city: &str) -> Option<(f64, f64)> {
+        self.places
+            .get(&(country.to_string(), normalize_name(city)))
+            .map(|p| (p.latitude, p.longitude))
+    }
+}
+
+/// Fills in coordinates of normalized records from the dataset at `path`.
+///
+/// Returns the number of records that were resolved.
+pub fn enrich(records: &mut [LocationRecord], path: &Path) -> Result<usize, GeoError> {
+    let countries: HashSet<&str> = records.iter().map(|r| r.country.as_str()).collect();
+    let index = GeoIndex::load(path, &countries)?;
+
+    let mut resolved = 0;
+    for record in records.iter_mut() {
+        let Some(city) = record.city.as_deref() else {
+            continue;
+        };
+        if let Some((lat, lon)) = index.lookup(&record.country, city) {
+            record.latitude = Some(lat);
+            record.longitude = Some(lon);
+            resolved += 1;
+        }
+    }
+
+    Ok(resolved)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::unified::Provider;
+    use super::*;
+
+    /// A GeoNames row with the columns the index reads; the others stay empty.
+    fn row(name: &str, alternate: &str, lat: f64, lon: f64, country: &str, population: u64) -> String {
+        let mut cols = vec![String::new(); COL_POPULATION + 5];
+        cols[COL_NAME] = name.to_string();
+        cols[COL_ASCII_NAME] = name.to_string();
+        cols[COL_ALTERNATE_NAMES] = alternate.to_string();
+        cols[COL_LATITUDE] = lat.to_string();
+        cols[COL_LONGITUDE] = lon.to_string();
+        cols[COL_COUNTRY] = country.to_string();
+        cols[COL_POPULATION] = population.to_string();
+        cols.join("\t")
+    }
+
+    fn index(rows: &[String], countries: &[&str]) -> Result<GeoIndex, GeoError> {
+        GeoIndex::read(rows.join("\n").as_bytes(), Path::new("cities.txt"), &countries.iter().copied().collect())
+    }
+
+    #[test]
+    fn short_lines_are_rejected_with_their_number() {
+        let rows = [row("Berlin", "", 52.5, 13.4, "DE", 1), "# comment".to_string(), "a\tb\tc".to_string()];
+        let err = index(&rows, &["DE"]).err().unwrap();
+        assert!(
+            matches!(&err, GeoError::Parse { line: 3, reason } if reason == "expected at least 15 columns, got 3"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn alternate_names_resolve_to_the_place() {
+        let index = index(&[row("Munich", "München,Monaco di Baviera", 48.1, 11.6, "DE", 1)], &["DE"]).unwrap();
+        assert_eq!(index.lookup("DE", "munchen"), Some((48.1, 11.6)));
+        assert_eq!(index.lookup("DE", "Monaco di Baviera"), Some((48.1, 11.6)));
+        assert_eq!(index.lookup("AT", "Munich"), None);
+    }
+
+    #[test]
+    fn the_most_populous_place_wins_a_shared_name() {
+        let rows = [
+            row("Frankfurt", "", 52.3, 14.5, "DE", 57_000),
+            row("Frankfurt", "", 50.1, 8.7, "DE", 750_000),
+            row("Frankfurt", "", 51.0, 9.0, "DE", 100),
+        ];
+        assert_eq!(index(&rows, &["DE"]).unwrap().lookup("DE", "Frankfurt"), Some((50.1, 8.7)));
+    }
+
+    #[test]
+    fn other_countries_are_skipped() {
+        let rows = [row("Paris", "", 48.9, 2.4, "FR", 1), row("Bad", "", f64::NAN, 0.0, "US", 1)];
+        let index = index(&rows, &["DE"]).unwrap();
+        assert!(index.places.is_empty());
+    }
+
+    #[test]
+    fn enrich_fills_in_the_coordinates_of_known_cities() {
+        let path = std::env::temp_dir().join(format!("update_location_geo_{}.tsv", std::process::id()));
+        std::fs::write(&path, row("Berlin", "", 52.5, 13.4, "DE", 1)).unwrap();
+        let mut records = vec![
+            LocationRecord { city: Some("berlin".to_string()), ..LocationRecord::sample(Provider::IPRoyal, "DE") },
+            LocationRecord { city: Some("Atlantis".to_string()), ..LocationRecord::sample(Provider::IPRoyal, "DE") },
+            LocationRecord::sample(Provider::Infatica, "DE"),
+        ];
+
+        let resolved = enrich(&mut records, &path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(resolved.unwrap(), 1);
+        assert_eq!((records[0].latitude, records[0].longitude), (Some(52.5), Some(13.4)));
+        assert_eq!(records[1].latitude, None);
+    }
+}
@@ -0,0 +1,47 @@
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Failure to set up the outbound HTTP client.
+#[derive(Debug, Error)]
+pub enum HttpClientError {
+    #[error("failed to read {path}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("invalid certificate or key in {path}: {source}")]
+    Tls {
+        path: PathBuf,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    #[error("invalid header `{name}`: {reason}")]
+    Header { name: String, reason: String },
+
+    #[error("failed to build HTTP client: {0}")]
+    Build(#[from] reqwest::Error),
+}
+
+/// Failure to read a response body.
+#[derive(Debug, Error)]
+pub enum BodyError {
+    #[error("request error: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("response larger than the {limit} byte limit (http.max_response_bytes)")]
+    TooLarge { limit: u64 },
+}
+
+/// Failure to send a request through a [`Stack`](super::Stack).
+#[derive(Debug, Error)]
+pub enum SendError {
+    #[error("request error: {0}")]
+    Request(#[from] reqwest::Error),
+
+    /// A middleware failed, e.g. to obtain credentials, with an error of its own type.
+    #[error(transparent)]
+    Middleware(Box<dyn std::error::Error + Send + Sync>),
+}
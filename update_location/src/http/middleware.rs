@@ -0,0 +1,239 @@
+//! Composable layers around the base HTTP client.
+//!
+//! A [`Stack`] sends each request through its [`Middleware`] in the order they
+//! were added, the first being the outermost, and finally through its
+//! [`HttpSend`] transport: the client, or a stand-in in unit tests.
+//! Each layer may change the request, answer it itself, or call [`Next::run`]
+//! any number of times, e.g. to retry with fresh credentials.
+//!
+//! The generic layers are defined here; provider-specific ones, such as the
+//! iproyal bearer token, live with their provider.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use reqwest::header::{HeaderValue, CONTENT_TYPE};
+use reqwest::{Client, Method, Request, RequestBuilder, Response};
+use tokio::time::Instant;
+use url::Url;
+use crate::quota;
+use crate::unified::Provider;
+use crate::verbose;
+use super::SendError;
+
+/// Future returned by [`Middleware::handle`], boxed so layers can be trait objects.
+pub type HttpFuture<'a> = Pin<Box<dyn Future<Output = Result<Response, SendError>> + Send + 'a>>;
+
+/// The bottom of a [`Stack`], which actually sends requests.
+pub trait HttpSend: Send + Sync {
+    fn send(&self, request: Request) -> HttpFuture<'_>;
+}
+
+impl HttpSend for Client {
+    fn send(&self, request: Request) -> HttpFuture<'_> {
+        Box::pin(async move { Ok(self.execute(request).await?) })
+    }
+}
+
+/// A layer of a [`Stack`].
+pub trait Middleware: Send + Sync {
+    /// Handles `request`, normally by passing it on to `next`.
+    fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> HttpFuture<'a>;
+}
+
+/// The layers below a [`Middleware`], ending with the transport.
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    transport: &'a dyn HttpSend,
+    layers: &'a [Arc<dyn Middleware>],
+}
+
+impl<'a> Next<'a> {
+    /// Sends `request` through the remaining layers.
+    pub fn run(self, request: Request) -> HttpFuture<'a> {
+        match self.layers.split_first() {
+            Some((layer, layers)) => layer.handle(request, Next { transport: self.transport, layers }),
+            None => self.transport.send(request),
+        }
+    }
+}
+
+/// An HTTP client with its middleware; cheap to clone.
+#[derive(Clone)]
+pub struct Stack {
+    client: Client,
+    transport: Arc<dyn HttpSend>,
+    layers: Vec<Arc<dyn Middleware>>,
+}
+
+impl Stack {
+    /// A stack without middleware around `client`.
+    pub fn new(client: Client) -> Self {
+        Self { transport: Arc::new(client.clone()), client, layers: Vec::new() }
+    }
+
+    /// Sends requests through `transport` instead of the client, which then only builds them.
+    pub fn transport(mut self, transport: Arc<dyn HttpSend>) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Adds `layer` below the layers added so far.
+    pub fn layer(mut self, layer: impl Middleware + 'static) -> Self {
+        self.layers.push(Arc::new(layer));
+        self
+    }
+
+    /// The base client, which builds the requests.
+    pub fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// Starts a request, to be sent with [`send`](Self::send).
+    pub fn request(&self, method: Method, url: Url) -> RequestBuilder {
+        self.client.request(method, url)
+    }
+
+    /// Sends `request` through every layer.
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response, SendError> {
+        let next = Next { transport: self.transport.as_ref(), layers: &self.layers };
+        next.run(request.build()?).await
+    }
+}
+
+/// Retries a layer made, recorded in the response extensions so callers can
+/// account for them, e.g. in [`EndpointStats`](super::EndpointStats).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Retries(pub u32);
+
+impl Retries {
+    /// Retries recorded on `resp`.
+    pub fn of(resp: &Response) -> u32 {
+        resp.extensions().get::<Retries>().map_or(0, |retries| retries.0)
+    }
+
+    /// Adds `retries` to those recorded on `resp`.
+    pub fn add(resp: &mut Response, retries: u32) {
+        let total = Self::of(resp) + retries;
+        resp.extensions_mut().insert(Retries(total));
+    }
+}
+
+/// Applies its duration to requests without a timeout of their own.
+pub struct Timeout(pub Duration);
+
+impl Middleware for Timeout {
+    fn handle<'a>(&'a self, mut request: Request, next: Next<'a>) -> HttpFuture<'a> {
+        request.timeout_mut().get_or_insert(self.0);
+        next.run(request)
+    }
+}
+
+/// Counts every request against the provider's [quota](crate::quota).
+pub struct Quota(pub Provider);
+
+impl Middleware for Quota {
+    fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> HttpFuture<'a> {
+        quota::count(self.0);
+        next.run(request)
+    }
+}
+
+/// Prints each request, its status and duration with `--verbose`.
+///
+/// Only the URL path is printed, so credentials in queries or bodies stay out of the output.
+pub struct Logging(pub Provider);
+
+impl Middleware for Logging {
+    fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> HttpFuture<'a> {
+        Box::pin(async move {
+            let (method, path) = (request.method().clone(), request.url().path().to_string());
+            let started = Instant::now();
+            let result = next.run(request).await;
+            let outcome = match &result {
+                Ok(resp) => resp.status().to_string(),
+                Err(err) => format!("failed: {err}"),
+            };
+            verbose!("{}: {method} {path} -> {outcome} in {:.2}s", self.0, started.elapsed().as_secs_f64());
+            result
+        })
+    }
+}
+
+/// Adds credential fields to form bodies, so requests can be built without them.
+///
+/// Requests without a body get a form body of just these fields.
+pub struct FormCredentials(pub Vec<(String, String)>);
+
+impl Middleware for FormCredentials {
+    fn handle<'a>(&'a self, mut request: Request, next: Next<'a>) -> HttpFuture<'a> {
+        let existing = request.body().and_then(|body| body.as_bytes()).unwrap_or_default();
+        let mut form = url::form_urlencoded::Serializer::new(String::from_utf8_lossy(existing).into_owned());
+        form.extend_pairs(&self.0);
+        let body = form.finish();
+
+        request.headers_mut().insert(CONTENT_TYPE, HeaderValue::from_static("application/x-www-form-urlencoded"));
+        *request.body_mut() = Some(body.into());
+        next.run(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use axum::extract::RawForm;
+    use axum::routing::post;
+    use axum::Router;
+    use super::*;
+
+    /// Serves `router` on a local port, returning its base URL.
+    async fn serve(router: Router) -> Url {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        format!("http://{addr}/").parse().unwrap()
+    }
+
+    /// Counts the requests passing through it.
+    struct Counter(Arc<AtomicU32>);
+
+    impl Middleware for Counter {
+        fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> HttpFuture<'a> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            next.run(request)
+        }
+    }
+
+    /// Sends every request twice, recording the retry.
+    struct Twice;
+
+    impl Middleware for Twice {
+        fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> HttpFuture<'a> {
+            Box::pin(async move {
+                next.run(request.try_clone().unwrap()).await?;
+                let mut resp = next.run(request).await?;
+                Retries::add(&mut resp, 1);
+                Ok(resp)
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn layers_run_in_order_around_the_client() {
+        let url = serve(Router::new().route("/", post(|RawForm(form): RawForm| async move { form }))).await;
+        let (outer, inner) = (Arc::new(AtomicU32::new(0)), Arc::new(AtomicU32::new(0)));
+        let stack = Stack::new(Client::new())
+            .layer(Counter(outer.clone()))
+            .layer(Twice)
+            .layer(Counter(inner.clone()))
+            .layer(FormCredentials(vec![("password".to_string(), "p&w".to_string())]));
+
+        let request = stack.request(Method::POST, url).form(&[("country", "DE")]);
+        let resp = stack.send(request).await.unwrap();
+
+        assert_eq!((outer.load(Ordering::SeqCst), inner.load(Ordering::SeqCst)), (1, 2));
+        assert_eq!(Retries::of(&resp), 1);
+        assert_eq!(resp.text().await.unwrap(), "country=DE&password=p%26w");
+    }
+}
@@ -0,0 +1,143 @@
+//! # Outbound HTTP client
+//!
+//! Every provider request goes through a client built by [`client`] from the
+//! provider's effective [`HttpConfig`], so proxy, TLS, header and timeout settings
+//! apply uniformly. Providers wrap it in a [`Stack`] of [`Middleware`] for what
+//! applies per request, such as timeouts, quota accounting, logging and
+//! credentials, so a new provider composes these layers instead of
+//! re-implementing them. Response bodies are read with [`read_body`], which enforces
+//! `http.max_response_bytes`. Provider queries account their requests in an
+//! [`EndpointStats`] per dataset.
+//! Without a configured proxy, reqwest honours the standard `HTTPS_PROXY` /
+//! `HTTP_PROXY` / `ALL_PROXY` / `NO_PROXY` variables.
+
+mod errors;
+mod middleware;
+mod stats;
+#[cfg(test)]
+pub(crate) mod testing;
+
+use std::path::Path;
+use std::sync::Once;
+use std::time::Duration;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Certificate, Client, ClientBuilder, Identity, NoProxy, Proxy, Response};
+use crate::models::{HttpConfig, ProxyConfig};
+
+pub use errors::{BodyError, HttpClientError, SendError};
+pub use middleware::{FormCredentials, HttpFuture, HttpSend, Logging, Middleware, Next, Quota, Retries, Stack, Timeout};
+pub use stats::EndpointStats;
+
+/// Connect timeout used when `http.connect_timeout` is not set.
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Response size limit used when `http.max_response_bytes` is not set.
+pub const DEFAULT_MAX_RESPONSE_BYTES: u64 = 256 * 1024 * 1024;
+
+/// `User-Agent` used when `http.user_agent` is not set.
+const DEFAULT_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Builds an HTTP client from `cfg`.
+pub fn client(cfg: &HttpConfig) -> Result<Client, HttpClientError> {
+    let mut builder = Client::builder()
+        .user_agent(cfg.get_user_agent().unwrap_or(DEFAULT_USER_AGENT))
+        .default_headers(headers(cfg)?)
+        .connect_timeout(cfg.get_connect_timeout().unwrap_or(DEFAULT_CONNECT_TIMEOUT));
+    if let Some(timeout) = cfg.get_read_timeout() {
+        builder = builder.read_timeout(timeout);
+    }
+
+    if let Some(proxy) = cfg.get_proxy() {
+        builder = builder.proxy(proxy_for(proxy)?);
+    }
+    builder = tls(builder, cfg)?;
+
+    Ok(builder.build()?)
+}
+
+/// Reads a response body, failing as soon as it exceeds `cfg`'s size limit.
+///
+/// An advertised `Content-Length` above the limit is rejected before reading.
+pub async fn read_body(cfg: &HttpConfig, mut resp: Response) -> Result<Vec<u8>, BodyError> {
+    let limit = cfg.get_max_response_bytes().unwrap_or(DEFAULT_MAX_RESPONSE_BYTES);
+    if resp.content_length().is_some_and(|len| len > limit) {
+        return Err(BodyError::TooLarge { limit });
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = resp.chunk().await? {
+        if (body.len() + chunk.len()) as u64 > limit {
+            return Err(BodyError::TooLarge { limit });
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(body)
+}
+
+/// Body of an error response for diagnostics; empty when it cannot be read within the limit.
+pub async fn error_body(cfg: &HttpConfig, resp: Response) -> String {
+    read_body(cfg, resp)
+        .await
+        .map(|body| String::from_utf8_lossy(&body).into_owned())
+        .unwrap_or_default()
+}
+
+/// Static headers from `http.extra_headers` and the provider's `headers`.
+fn headers(cfg: &HttpConfig) -> Result<HeaderMap, HttpClientError> {
+    let header_err = |name: &str, reason: String| HttpClientError::Header { name: name.to_string(), reason };
+
+    let mut headers = HeaderMap::new();
+    for (name, value) in cfg.get_extra_headers() {
+        let key = HeaderName::try_from(name.as_str()).map_err(|e| header_err(name, e.to_string()))?;
+        let mut value = HeaderValue::try_from(value.as_str()).map_err(|e| header_err(name, e.to_string()))?;
+        value.set_sensitive(true);
+        headers.insert(key, value);
+    }
+    Ok(headers)
+}
+
+fn proxy_for(cfg: &ProxyConfig) -> Result<Proxy, HttpClientError> {
+    let mut proxy = Proxy::all(cfg.get_url().clone())?;
+    if let Some((username, password)) = cfg.get_credentials() {
+        proxy = proxy.basic_auth(username, password);
+    }
+    Ok(proxy.no_proxy(cfg.get_no_proxy().and_then(NoProxy::from_string)))
+}
+
+/// Applies the CA bundle, client certificate and verification settings.
+fn tls(mut builder: ClientBuilder, cfg: &HttpConfig) -> Result<ClientBuilder, HttpClientError> {
+    if let Some(path) = cfg.get_ca_bundle() {
+        let pem = read(path)?;
+        let certs = Certificate::from_pem_bundle(&pem).map_err(|source| tls_err(path, source))?;
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    if let Some((cert_path, key_path)) = cfg.get_client_identity() {
+        let identity = Identity::from_pkcs8_pem(&read(cert_path)?, &read(key_path)?)
+            .map_err(|source| tls_err(cert_path, source))?;
+        builder = builder.identity(identity);
+    }
+
+    if cfg.get_insecure_skip_verify() {
+        static WARNING: Once = Once::new();
+        WARNING.call_once(|| {
+            crate::ui::suspend(|| eprintln!(
+                "WARNING: http.insecure_skip_verify is set; TLS certificates are NOT verified \
+                 and vendor credentials can be intercepted. Use http.ca_bundle instead."
+            ));
+        });
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+
+    Ok(builder)
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, HttpClientError> {
+    std::fs::read(path).map_err(|source| HttpClientError::Io { path: path.to_path_buf(), source })
+}
+
+fn tls_err(path: &Path, source: reqwest::Error) -> HttpClientError {
+    HttpClientError::Tls { path: path.to_path_buf(), source }
+}
@@ -0,0 +1,45 @@
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Wall time, requests and body bytes spent fetching one provider dataset.
+///
+/// Paginated datasets accumulate all of their pages. Only filled in by actual
+/// fetches: data served from the cache carries no statistics.
+#[derive(Debug, Clone)]
+pub struct EndpointStats {
+    /// Endpoint path relative to the provider's base URL.
+    pub endpoint: &'static str,
+    pub elapsed: Duration,
+    pub requests: u32,
+    /// Requests repeated after a recoverable failure, e.g. an expired token.
+    pub retries: u32,
+    /// Response body bytes received, as transferred (before decoding).
+    pub bytes: u64,
+    started: Instant,
+}
+
+impl EndpointStats {
+    /// Starts timing requests to `endpoint`.
+    pub fn start(endpoint: &'static str) -> Self {
+        Self { endpoint, elapsed: Duration::ZERO, requests: 0, retries: 0, bytes: 0, started: Instant::now() }
+    }
+
+    /// Counts one response of `bytes` body bytes.
+    pub fn record(&mut self, bytes: usize) {
+        self.requests += 1;
+        self.bytes += bytes as u64;
+    }
+
+    /// Adds the requests, retries and bytes of `part`, fetched concurrently with this one.
+    pub fn merge(&mut self, part: &EndpointStats) {
+        self.requests += part.requests;
+        self.retries += part.retries;
+        self.bytes += part.bytes;
+    }
+
+    /// Stops the clock once all requests of the dataset are done.
+    pub fn finish(mut self) -> Self {
+        self.elapsed = self.started.elapsed();
+        self
+    }
+}
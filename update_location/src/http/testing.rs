@@ -0,0 +1,99 @@
+//! Stand-ins for the network in unit tests.
+
+use std::collections::{HashMap, VecDeque};
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll};
+use axum::body::Bytes;
+use http_body::{Frame, SizeHint};
+use reqwest::{Method, Request, StatusCode};
+use super::{HttpFuture, HttpSend};
+
+/// A request as seen by [`Scripted`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sent {
+    pub method: Method,
+    pub path: String,
+    pub query: Option<String>,
+    pub authorization: Option<String>,
+    pub body: String,
+}
+
+/// Answers requests with scripted responses, in order, recording each request.
+///
+/// Running out of responses panics, so unexpected requests fail the test.
+pub struct Scripted {
+    responses: Mutex<VecDeque<(StatusCode, String)>>,
+    sent: Mutex<Vec<Sent>>,
+    /// Lengths advertised by responses, by their position, see [`Scripted::advertise`].
+    advertised: HashMap<usize, u64>,
+}
+
+impl Scripted {
+    pub fn new<S: Into<String>>(responses: impl IntoIterator<Item = (StatusCode, S)>) -> Self {
+        Self {
+            responses: Mutex::new(responses.into_iter().map(|(status, body)| (status, body.into())).collect()),
+            sent: Mutex::new(Vec::new()),
+            advertised: HashMap::new(),
+        }
+    }
+
+    /// Makes response `n`, counting from zero, advertise a length of `length`
+    /// bytes, as a connection dropped before the end of the body would.
+    pub fn advertise(mut self, n: usize, length: u64) -> Self {
+        self.advertised.insert(n, length);
+        self
+    }
+
+    /// The requests answered so far.
+    pub fn sent(&self) -> Vec<Sent> {
+        self.sent.lock().unwrap().clone()
+    }
+}
+
+impl HttpSend for Scripted {
+    fn send(&self, request: Request) -> HttpFuture<'_> {
+        let header = |name| request.headers().get(name).and_then(|v| v.to_str().ok()).map(str::to_string);
+        let mut sent = self.sent.lock().unwrap();
+        let advertised = self.advertised.get(&sent.len()).copied();
+        sent.push(Sent {
+            method: request.method().clone(),
+            path: request.url().path().to_string(),
+            query: request.url().query().map(str::to_string),
+            authorization: header(reqwest::header::AUTHORIZATION),
+            body: request
+                .body()
+                .and_then(|body| body.as_bytes())
+                .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+                .unwrap_or_default(),
+        });
+        drop(sent);
+        let (status, body) = self.responses.lock().unwrap().pop_front().expect("no scripted response left");
+        let body = match advertised {
+            Some(length) => reqwest::Body::wrap(Short { data: Some(body.into()), length }),
+            None => body.into(),
+        };
+        let response = axum::http::Response::builder().status(status).body(body).unwrap();
+        Box::pin(async move { Ok(response.into()) })
+    }
+}
+
+/// A body advertising `length` bytes, whatever it holds.
+struct Short {
+    data: Option<Bytes>,
+    length: u64,
+}
+
+impl http_body::Body for Short {
+    type Data = Bytes;
+    type Error = Infallible;
+
+    fn poll_frame(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Result<Frame<Bytes>, Infallible>>> {
+        Poll::Ready(self.data.take().map(|data| Ok(Frame::data(data))))
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::with_exact(self.length)
+    }
+}
@@ -1,5 +1,9 @@
 use thiserror::Error;
+use crate::http::BodyError;
+use crate::infatica::internal::consts::{GEO_NODES_ENDPOINT, ISP_CODES_ENDPOINT, REGION_CODES_ENDPOINT, ZIP_CODES_ENDPOINT};
 use crate::infatica::internal::errors::HTTPError;
+use crate::models::{ProviderError, ProviderErrorKind};
+use crate::unified::Provider;
 
 /// Aggregated error type for top-level Infatica queries.
 ///
@@ -9,17 +13,74 @@ use crate::infatica::internal::errors::HTTPError;
 pub enum InfaticaQueryError {
 	/// Failure during `geo_nodes.php` query (network, timeout, or parse).
 	#[error("geo_nodes request failed: {0}")]
-	GeoNodes(HTTPError),
+	GeoNodes(#[source] HTTPError),
 
 	/// Failure during `subdivision_codes.php` query.
 	#[error("region_codes request failed: {0}")]
-	RegionCodes(HTTPError),
+	RegionCodes(#[source] HTTPError),
 
 	/// Failure during `zip-codes.php` query.
 	#[error("zip_codes request failed: {0}")]
-	ZipCodes(HTTPError),
+	ZipCodes(#[source] HTTPError),
 
 	/// Failure during `isp_codes.php` query.
 	#[error("isp_codes request failed: {0}")]
-	IspCodes(HTTPError),
-}
\ No newline at end of file
+	IspCodes(#[source] HTTPError),
+}
+impl From<InfaticaQueryError> for ProviderError {
+	fn from(err: InfaticaQueryError) -> Self {
+		let (endpoint, inner) = match &err {
+			InfaticaQueryError::GeoNodes(e) => (GEO_NODES_ENDPOINT, e),
+			InfaticaQueryError::RegionCodes(e) => (REGION_CODES_ENDPOINT, e),
+			InfaticaQueryError::ZipCodes(e) => (ZIP_CODES_ENDPOINT, e),
+			InfaticaQueryError::IspCodes(e) => (ISP_CODES_ENDPOINT, e),
+		};
+		let (kind, retryable) = inner.classify();
+		ProviderError::new(kind, Provider::Infatica, Some(endpoint), err).retryable(retryable)
+	}
+}
+
+impl HTTPError {
+	/// Classifies the failure, also telling whether repeating the request may help.
+	fn classify(&self) -> (ProviderErrorKind, bool) {
+		match self {
+			Self::URLError(err) | Self::BodyError(BodyError::Request(err)) => (ProviderErrorKind::from_reqwest(err), true),
+			Self::AuthError { .. } => (ProviderErrorKind::Auth, false),
+			Self::BadStatus { status, .. } => {
+				(ProviderErrorKind::from_status(*status), status.is_server_error() || *status == reqwest::StatusCode::TOO_MANY_REQUESTS)
+			}
+			Self::CountryError { source, .. } => source.classify(),
+			Self::DecodeError(_) => (ProviderErrorKind::Decode, false),
+			Self::BodyError(BodyError::TooLarge { .. }) => (ProviderErrorKind::Server, false),
+			Self::JoinURLError(_) | Self::ClientError(_) | Self::Middleware(_) => (ProviderErrorKind::Network, false),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use reqwest::StatusCode;
+	use super::*;
+
+	fn classify(err: HTTPError) -> (ProviderErrorKind, bool, Option<&'static str>) {
+		let err = ProviderError::from(InfaticaQueryError::GeoNodes(err));
+		(err.kind, err.retryable, err.endpoint)
+	}
+
+	#[test]
+	fn classifies_statuses() {
+		let status = |status| HTTPError::BadStatus { status, body: String::new() };
+		assert_eq!(classify(status(StatusCode::TOO_MANY_REQUESTS)), (ProviderErrorKind::RateLimited, true, Some(GEO_NODES_ENDPOINT)));
+		assert_eq!(classify(status(StatusCode::BAD_GATEWAY)).0, ProviderErrorKind::Server);
+		assert!(!classify(status(StatusCode::NOT_FOUND)).1);
+		assert_eq!(classify(HTTPError::AuthError { status: StatusCode::FORBIDDEN }).0, ProviderErrorKind::Auth);
+		assert!(!classify(HTTPError::AuthError { status: StatusCode::FORBIDDEN }).1);
+	}
+
+	#[test]
+	fn country_failures_take_the_kind_of_their_cause() {
+		let source = Box::new(HTTPError::BadStatus { status: StatusCode::SERVICE_UNAVAILABLE, body: String::new() });
+		let err = HTTPError::CountryError { country: "DE".to_string(), failed: 1, total: 2, source };
+		assert_eq!(classify(err), (ProviderErrorKind::Server, true, Some(GEO_NODES_ENDPOINT)));
+	}
+}
@@ -4,6 +4,7 @@ use crate::infatica::internal::isp_codes::isp_codes;
 use crate::infatica::internal::region_codes::region_codes;
 use crate::infatica::internal::zip_codes::zip_codes;
 use crate::infatica::models::InfaticaQueryResults;
+use crate::infatica::zip_validation;
 use crate::models::InfaticaConfig;
 
 /// Executes **all four Infatica queries concurrently**.
@@ -12,15 +13,17 @@ use crate::models::InfaticaConfig;
 /// - Runs [`geo_nodes`], [`region_codes`], [`zip_codes`], and [`isp_codes`] using [`tokio::join!`].
 /// - Aggregates all encountered errors into a single `Vec<InfaticaQueryError>`.
 /// - If any query fails, returns `Err(Vec<...>)` containing **all** errors (no early return).
-/// - If all succeed, returns [`InfaticaQueryResults`] containing the fetched datasets.
+/// - If all succeed, returns [`InfaticaQueryResults`] containing the fetched datasets
+///   and the [`EndpointStats`](crate::http::EndpointStats) of each query, with the ZIP
+///   codes checked per `zip_patterns` and `zip_action`, see [`zip_validation`].
 ///
 /// ### Errors
 /// - Network failures, deserialization errors, or invalid URLs bubble up through [`HTTPError`].
 ///
 /// ### Example
 /// ```no_run
-/// # use crate::infatica::get_all;
-/// # use crate::models::InfaticaConfig;
+/// # use update_location::infatica::get_all;
+/// # use update_location::models::InfaticaConfig;
 /// # async fn example(cfg: InfaticaConfig) {
 /// match get_all(&cfg).await {
 ///     Ok(results) => println!("Fetched {} geo-nodes", results.geo_nodes().len()),
@@ -50,33 +53,47 @@ pub async fn get_all(cfg: &InfaticaConfig) -> Result<InfaticaQueryResults, Vec<I
 		region_codes,
 		zip_codes,
 		isp_codes,
+		endpoints,
 	) = {
 		let mut g = Vec::new();
 		let mut r = Vec::new();
 		let mut z = Vec::new();
 		let mut i = Vec::new();
+		let mut endpoints = Vec::new();
 
 		match geo_res {
-			Ok(v) => g = v,
+			Ok((v, stats)) => {
+				g = v;
+				endpoints.push(stats);
+			}
 			Err(e) => errors.push(InfaticaQueryError::GeoNodes(e)),
 		}
 
 		match region_res {
-			Ok(v) => r = v,
+			Ok((v, stats)) => {
+				r = v;
+				endpoints.push(stats);
+			}
 			Err(e) => errors.push(InfaticaQueryError::RegionCodes(e)),
 		}
 
 		match zip_res {
-			Ok(v) => z = v,
+			Ok((v, stats)) => {
+				z = v;
+				endpoints.push(stats);
+			}
 			Err(e) => errors.push(InfaticaQueryError::ZipCodes(e)),
 		}
 
 		match isp_res {
-			Ok(v) => i = v,
+			Ok((v, stats)) => {
+				i = v;
+				endpoints.push(stats);
+			}
 			Err(e) => errors.push(InfaticaQueryError::IspCodes(e)),
 		}
 
-		(g, r, z, i)
+		(g, r, z, i, endpoints)
 	};
 
 	// If any of the four failed, propagate all failures together.
@@ -84,13 +101,17 @@ pub async fn get_all(cfg: &InfaticaConfig) -> Result<InfaticaQueryResults, Vec<I
 		return Err(errors);
 	}
 
-	// Otherwise, all succeeded — return a grouped result.
+	// Otherwise, all succeeded — check the ZIP codes and return a grouped result.
+	let mut zip_codes = zip_codes;
+	let zip_quality = zip_validation::validate(&mut zip_codes, cfg.get_zip_patterns(), cfg.get_zip_action());
 	Ok(
 		InfaticaQueryResults::new(
 			geo_nodes,
 			region_codes,
 			zip_codes,
+			zip_quality,
 			isp_codes,
+			endpoints,
 		)
 	)
 }
\ No newline at end of file
@@ -2,6 +2,7 @@
 
 use thiserror::Error;
 use url::ParseError;
+use crate::http::{BodyError, HttpClientError, SendError};
 
 /// Generic HTTP-level error type shared by all Infatica calls.
 #[derive(Debug, Error)]
@@ -10,7 +11,44 @@ pub enum HTTPError {
 	#[error("failed to join URL: {0}")]
 	JoinURLError(#[from] ParseError),
 
-	/// `reqwest` network, timeout, or deserialization error.
+	/// The HTTP client could not be set up (proxy or TLS settings).
+	#[error(transparent)]
+	ClientError(#[from] HttpClientError),
+
+	/// `reqwest` network, timeout, or body read error.
 	#[error("request error: {0}")]
 	URLError(#[from] reqwest::Error),
+
+	/// The body could not be read or exceeded `http.max_response_bytes`.
+	#[error(transparent)]
+	BodyError(#[from] BodyError),
+
+	/// Infatica rejected the credentials (HTTP 401/403).
+	#[error("authentication failed (HTTP {status}): check infatica.email / infatica.password")]
+	AuthError { status: reqwest::StatusCode },
+
+	/// Any other non-success HTTP status.
+	#[error("unexpected HTTP status {status}: {body}")]
+	BadStatus { status: reqwest::StatusCode, body: String },
+
+	/// Some countries of a per-country fetch failed, even when retried; `source` is the first.
+	#[error("{failed} of {total} countries failed, first {country}: {source}")]
+	CountryError { country: String, failed: usize, total: usize, source: Box<HTTPError> },
+
+	/// The body was received but does not match the expected schema.
+	#[error("failed to decode response: {0}")]
+	DecodeError(#[from] serde_json::Error),
+
+	/// A layer of the HTTP stack failed with an error of another type.
+	#[error("request middleware failed: {0}")]
+	Middleware(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl From<SendError> for HTTPError {
+	fn from(err: SendError) -> Self {
+		match err {
+			SendError::Request(err) => Self::URLError(err),
+			SendError::Middleware(err) => err.downcast::<Self>().map_or_else(Self::Middleware, |err| *err),
+		}
+	}
 }
\ No newline at end of file
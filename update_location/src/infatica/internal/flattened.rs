@@ -0,0 +1,85 @@
+//! Deserialization of Infatica's “array-of-arrays” payloads into one flat list.
+
+use std::fmt;
+use std::marker::PhantomData;
+use serde::de::{DeserializeSeed, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+
+/// Records of a `[[record, ...], ...]` response, flattened while deserializing.
+///
+/// Each inner array is appended to one `Vec` as it is read, so neither the
+/// nested `Vec<Vec<T>>` nor a second flat copy of it is ever materialized.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Flattened<T>(Vec<T>);
+
+impl<T> Flattened<T> {
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Flattened<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(Outer(PhantomData)).map(Flattened)
+    }
+}
+
+/// Visits the outer array, feeding each inner one to [`Inner`].
+struct Outer<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for Outer<T> {
+    type Value = Vec<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an array of arrays of records")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut records = Vec::new();
+        while seq.next_element_seed(Inner(&mut records))?.is_some() {}
+        Ok(records)
+    }
+}
+
+/// Appends the records of one inner array to the flat list.
+struct Inner<'a, T>(&'a mut Vec<T>);
+
+impl<'de, T: Deserialize<'de>> DeserializeSeed<'de> for Inner<'_, T> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for Inner<'_, T> {
+    type Value = ();
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("an array of records")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        self.0.reserve(seq.size_hint().unwrap_or(0));
+        while let Some(record) = seq.next_element()? {
+            self.0.push(record);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concatenates_inner_arrays() {
+        let flat: Flattened<u32> = serde_json::from_str("[[1, 2], [], [3]]").unwrap();
+        assert_eq!(flat.into_inner(), [1, 2, 3]);
+    }
+
+    #[test]
+    fn rejects_a_flat_array() {
+        assert!(serde_json::from_str::<Flattened<u32>>("[1, 2]").is_err());
+    }
+}
@@ -3,34 +3,111 @@
 //! Returns a list of nodes grouped by (country, region, city, ISP).
 //! Each record contains location identifiers, ISP metadata, ASN,
 //! ZIP code, and node counts.
+//!
+//! With `infatica.countries` set, the dataset is fetched as one request per
+//! country (passing the `country` form field), at most
+//! `infatica.country_concurrency` at a time. Responses stay small, and a failed
+//! country is retried on its own instead of refetching everything.
 
-use reqwest::Client;
-use super::consts::GEO_NODES_ENDPOINT;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
+use crate::http::{EndpointStats, Stack};
+use super::consts::{COUNTRY_FIELD, GEO_NODES_ENDPOINT};
 use super::helpers::extras_exclude_corporate;
 use super::errors::HTTPError;
 use super::models::{InfaticaGeoNodeRecord, InfaticaRecords};
-use super::query_infatica::{query_infatica};
+use super::query_infatica::{query_infatica, stack};
 use crate::models::InfaticaConfig;
 
 /// Fetches geo-node dataset from Infatica.
 /// Automatically adds `excludeCorporate=1` to filter out corporate nodes.
 ///
-/// On success, flattens the double array format (`Vec<Vec<Record>>`)
+/// The double array format (`[[record, ...], ...]`) is flattened while parsing
 /// into a single `Vec<InfaticaGeoNodeRecord>`.
-pub async fn geo_nodes(cfg: &InfaticaConfig) -> Result<Vec<InfaticaGeoNodeRecord>, HTTPError> {
-    let http_client = Client::new();
+pub async fn geo_nodes(cfg: &InfaticaConfig) -> Result<(Vec<InfaticaGeoNodeRecord>, EndpointStats), HTTPError> {
+    let http_client = stack(cfg)?;
+
+    let mut stats = EndpointStats::start(GEO_NODES_ENDPOINT);
+    let records = if cfg.get_countries().is_empty() {
+        query(&http_client, cfg, None, &mut stats).await?.into_inner()
+    } else {
+        by_country(&http_client, cfg, &mut stats).await?
+    };
+
+    Ok((records, stats.finish()))
+}
+
+/// Fetches the geo nodes of `country`, or of all countries.
+async fn query(
+    client: &Stack,
+    cfg: &InfaticaConfig,
+    country: Option<&str>,
+    stats: &mut EndpointStats,
+) -> Result<InfaticaRecords, HTTPError> {
+    let mut extras = extras_exclude_corporate();
+    if let Some(country) = country {
+        extras.push((COUNTRY_FIELD.to_string(), country.to_string()));
+    }
 
-    let resp = query_infatica::<InfaticaRecords>(
-            &http_client,
+    query_infatica::<InfaticaRecords>(
+            client,
             cfg.get_endpoint(),
             GEO_NODES_ENDPOINT,
             cfg,
-            extras_exclude_corporate(),
-        ).await?;
+            extras,
+            stats,
+        ).await
+}
+
+/// Fetches each configured country separately, merging the records in configuration order.
+///
+/// A failed country is retried once, unless the credentials were rejected. If it
+/// fails again, the whole dataset fails, reporting the first such country.
+async fn by_country(
+    client: &Stack,
+    cfg: &InfaticaConfig,
+    stats: &mut EndpointStats,
+) -> Result<Vec<InfaticaGeoNodeRecord>, HTTPError> {
+    let countries = cfg.get_countries();
+    let shared = Arc::new(cfg.clone());
+    let permits = Arc::new(Semaphore::new(cfg.get_country_concurrency()));
+
+    // Dropping the set on cancellation aborts the requests still running
+    let mut tasks = JoinSet::new();
+    for (index, country) in countries.iter().cloned().enumerate() {
+        let (client, cfg, permits) = (client.clone(), shared.clone(), permits.clone());
+        tasks.spawn(async move {
+            let _permit = permits.acquire_owned().await.expect("the semaphore is never closed");
+            let mut stats = EndpointStats::start(GEO_NODES_ENDPOINT);
+            let mut result = query(&client, &cfg, Some(&country), &mut stats).await;
+            if let Err(err) = &result
+                && !matches!(err, HTTPError::AuthError { .. })
+            {
+                stats.retries += 1;
+                result = query(&client, &cfg, Some(&country), &mut stats).await;
+            }
+            (index, country, result, stats)
+        });
+    }
+
+    let mut parts = Vec::with_capacity(countries.len());
+    let mut failures = Vec::new();
+    while let Some(joined) = tasks.join_next().await {
+        let (index, country, result, part) = joined.expect("country query panicked");
+        stats.merge(&part);
+        match result {
+            Ok(records) => parts.push((index, records.into_inner())),
+            Err(err) => failures.push((index, country, err)),
+        }
+    }
 
-    let parsed = resp.into_iter()
-        .flatten()
-        .collect::<Vec<InfaticaGeoNodeRecord>>();
+    failures.sort_by_key(|(index, ..)| *index);
+    let failed = failures.len();
+    if let Some((_, country, source)) = failures.into_iter().next() {
+        return Err(HTTPError::CountryError { country, failed, total: countries.len(), source: Box::new(source) });
+    }
 
-    Ok(parsed)
-}
\ No newline at end of file
+    parts.sort_by_key(|(index, _)| *index);
+    Ok(parts.into_iter().flat_map(|(_, records)| records).collect())
+}
@@ -0,0 +1,98 @@
+//! Field deserializers tolerating Infatica's inconsistent JSON types.
+//!
+//! The same field is sometimes sent as a number and sometimes as a string
+//! (`"nodes": 12` vs `"nodes": "12"`, `"zip": 10115` vs `"zip": "10115"`).
+//! Used with `#[serde(deserialize_with = ...)]`, these accept both, so a few
+//! odd records do not fail the whole endpoint.
+
+use std::fmt;
+use serde::de::{Error, Unexpected, Visitor};
+use serde::Deserializer;
+
+/// A `u32` sent as a number or a numeric string; surrounding whitespace is ignored.
+pub fn u32_from_any<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    deserializer.deserialize_any(U32Visitor)
+}
+
+/// A string, or a number kept as its decimal text.
+pub fn string_from_any<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    deserializer.deserialize_any(StringVisitor)
+}
+
+struct U32Visitor;
+
+impl Visitor<'_> for U32Visitor {
+    type Value = u32;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a non-negative integer or a string holding one")
+    }
+
+    fn visit_u64<E: Error>(self, v: u64) -> Result<u32, E> {
+        u32::try_from(v).map_err(|_| E::invalid_value(Unexpected::Unsigned(v), &self))
+    }
+
+    fn visit_i64<E: Error>(self, v: i64) -> Result<u32, E> {
+        u32::try_from(v).map_err(|_| E::invalid_value(Unexpected::Signed(v), &self))
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<u32, E> {
+        v.trim().parse().map_err(|_| E::invalid_value(Unexpected::Str(v), &self))
+    }
+}
+
+struct StringVisitor;
+
+impl Visitor<'_> for StringVisitor {
+    type Value = String;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a string or an integer")
+    }
+
+    fn visit_str<E: Error>(self, v: &str) -> Result<String, E> {
+        Ok(v.to_string())
+    }
+
+    fn visit_string<E: Error>(self, v: String) -> Result<String, E> {
+        Ok(v)
+    }
+
+    fn visit_u64<E: Error>(self, v: u64) -> Result<String, E> {
+        Ok(v.to_string())
+    }
+
+    fn visit_i64<E: Error>(self, v: i64) -> Result<String, E> {
+        Ok(v.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Record {
+        #[serde(deserialize_with = "super::u32_from_any")]
+        nodes: u32,
+        #[serde(deserialize_with = "super::string_from_any")]
+        zip: String,
+    }
+
+    fn parse(json: &str) -> Result<(u32, String), serde_json::Error> {
+        serde_json::from_str::<Record>(json).map(|r| (r.nodes, r.zip))
+    }
+
+    #[test]
+    fn accepts_numbers_and_strings() {
+        assert_eq!(parse(r#"{"nodes": 12, "zip": "01067"}"#).unwrap(), (12, "01067".to_string()));
+        assert_eq!(parse(r#"{"nodes": " 12 ", "zip": 10115}"#).unwrap(), (12, "10115".to_string()));
+    }
+
+    #[test]
+    fn rejects_what_is_not_a_count() {
+        assert!(parse(r#"{"nodes": "many", "zip": ""}"#).is_err());
+        assert!(parse(r#"{"nodes": -1, "zip": ""}"#).is_err());
+        assert!(parse(r#"{"nodes": 4294967296, "zip": ""}"#).is_err());
+    }
+}
@@ -5,16 +5,20 @@
 //! - [`region_codes`] — returns the dictionary of region/subdivision codes
 //! - [`zip_codes`] — returns the dictionary of postal/ZIP codes
 //! - [`isp_codes`] — returns the dictionary of ISP codes
+//! - [`preflight`] — checks credentials without fetching a dataset
 //!
 //! Each function performs a single API query using `reqwest` with form-encoded fields,
 //! deserializes the JSON result into strongly-typed Rust structs, and flattens the legacy
-//! “array-of-arrays” Infatica format into a simple `Vec<T>`.
+//! “array-of-arrays” Infatica format into a simple `Vec<T>` while parsing, see [`flattened`].
 
+pub mod flattened;
 pub mod geo_nodes;
 pub mod models;
 pub mod isp_codes;
+mod lenient;
+pub mod preflight;
 mod query_infatica;
-mod consts;
+pub(super) mod consts;
 mod helpers;
 pub mod errors;
 pub mod region_codes;
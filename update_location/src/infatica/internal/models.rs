@@ -1,14 +1,16 @@
 //! Data model definitions for Infatica API responses.
 use serde::{Deserialize, Serialize};
+use super::flattened::Flattened;
+use super::lenient::{string_from_any, u32_from_any};
 
 /// Extra form fields passed to Infatica HTTP queries.
 pub type InfaticaFormFields = Vec<(String, String)>;
 
-/// Root-level type: Infatica returns an array of records.
-pub type InfaticaRecords = Vec<Vec<InfaticaGeoNodeRecord>>;
+/// Root-level type: Infatica returns an array of arrays of records.
+pub type InfaticaRecords = Flattened<InfaticaGeoNodeRecord>;
 
 /// Geo-node record combining country, region, city, ISP, and node stats.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InfaticaGeoNodeRecord {
     /// ISO 3166-1 alpha-2 country code (e.g. "US", "DE")
     pub country: String,
@@ -22,21 +24,24 @@ pub struct InfaticaGeoNodeRecord {
     /// Internet Service Provider name
     pub isp: String,
 
-    /// Autonomous System Number
+    /// Autonomous System Number; sent as a number or a numeric string
+    #[serde(deserialize_with = "u32_from_any")]
     pub asn: u32,
 
-    /// Postal / ZIP code (may contain non-numeric text)
+    /// Postal / ZIP code (may contain non-numeric text, or be sent as a number)
+    #[serde(deserialize_with = "string_from_any")]
     pub zip: String,
 
-    /// Number of nodes available in this region/city/ISP
+    /// Number of nodes available in this region/city/ISP; sent as a number or a numeric string
+    #[serde(deserialize_with = "u32_from_any")]
     pub nodes: u32,
 }
 
 /// ISP dictionary — Infatica wraps in `Vec<Vec<_>>`.
-pub type InfaticaIspRecords = Vec<Vec<InfaticaIspRecord>>;
+pub type InfaticaIspRecords = Flattened<InfaticaIspRecord>;
 
 /// ISP record mapping name to numeric code.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InfaticaIspRecord {
     /// The ISP’s name or descriptive label.
     /// May contain quotes, punctuation, or Unicode characters.
@@ -47,10 +52,10 @@ pub struct InfaticaIspRecord {
 }
 
 /// Region dictionary — Infatica wraps in `Vec<Vec<_>>`.
-pub type InfaticaRegionRecords = Vec<Vec<InfaticaRegionRecord>>;
+pub type InfaticaRegionRecords = Flattened<InfaticaRegionRecord>;
 
 /// Region/subdivision record.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InfaticaRegionRecord {
 	/// Internal Infatica region code.
 	pub code: u32,
@@ -61,10 +66,10 @@ pub struct InfaticaRegionRecord {
 }
 
 /// ZIP dictionary — Infatica wraps in `Vec<Vec<_>>`.
-pub type InfaticaZipRecords = Vec<Vec<InfaticaZipRecord>>;
+pub type InfaticaZipRecords = Flattened<InfaticaZipRecord>;
 
 /// Postal code record.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct InfaticaZipRecord {
 	/// ISO 3166-1 alpha-2 country code (e.g. "US", "JP").
 	pub country: String,
@@ -75,6 +80,12 @@ pub struct InfaticaZipRecord {
 	/// City name (may include Unicode, spaces, or punctuation).
 	pub city: String,
 
-	/// Postal / ZIP code (may include letters, hyphens, etc.).
+	/// Postal / ZIP code (may include letters, hyphens, etc., or be sent as a number).
+	#[serde(deserialize_with = "string_from_any")]
 	pub zip: String,
+
+	/// Set by [`zip_validation`](crate::infatica::zip_validation) when `zip` does not
+	/// match the format of `country`.
+	#[serde(default, skip_serializing_if = "std::ops::Not::not")]
+	pub malformed: bool,
 }
\ No newline at end of file
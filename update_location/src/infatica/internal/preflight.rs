@@ -0,0 +1,25 @@
+//! Credential check against Infatica without downloading a dataset.
+//!
+//! Posts the configured credentials to the small `subdivision_codes.php` dictionary
+//! and only inspects the response status; the body is dropped unread.
+
+use super::consts::REGION_CODES_ENDPOINT;
+use super::errors::HTTPError;
+use super::helpers::extras_empty;
+use super::query_infatica::{send_infatica, stack};
+use crate::models::InfaticaConfig;
+
+/// Verifies that Infatica accepts the configured email and password.
+pub async fn preflight(cfg: &InfaticaConfig) -> Result<(), HTTPError> {
+    let http_client = stack(cfg)?;
+
+    send_infatica(
+            &http_client,
+            cfg.get_endpoint(),
+            REGION_CODES_ENDPOINT,
+            cfg,
+            extras_empty(),
+        ).await?;
+
+    Ok(())
+}
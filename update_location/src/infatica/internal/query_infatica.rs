@@ -1,27 +1,65 @@
 //! Core query helper used by all Infatica API calls.
 //!
 //! Responsible for:
+//! - Building the HTTP [`Stack`] of every call: logging, the request timeout,
+//!   the email and password form fields, and counting each request against the
+//!   [quota](crate::quota)
 //! - Ensuring trailing `/` in base URL before joining endpoint
-//! - Constructing POST form fields (email, password, extras)
-//! - Executing HTTP request with timeout
+//! - Constructing POST form fields (extras)
+//! - Enforcing a response size limit
+//! - Rejecting authentication failures and non-success statuses
 //! - Deserializing JSON response into a generic `T`
+//! - Counting requests and body bytes in the dataset's [`EndpointStats`]
 
-use std::collections::HashMap;
-use reqwest::Client;
+use reqwest::{Method, Response, StatusCode};
+use crate::http::{self, EndpointStats, FormCredentials, Logging, Quota, Stack, Timeout};
+use crate::models::InfaticaConfig;
+use crate::unified::Provider;
 use super::consts::{DEFAULT_TIMEOUT, EMAIL_FIELD, PASSWORD_FIELD};
 use super::errors::HTTPError;
 use super::models::InfaticaFormFields;
 
+/// The HTTP stack of Infatica calls with `cfg`.
+pub fn stack(cfg: &InfaticaConfig) -> Result<Stack, HTTPError> {
+    let credentials = vec![
+        (EMAIL_FIELD.to_string(), cfg.get_email().to_string()),
+        (PASSWORD_FIELD.to_string(), cfg.get_password().to_string()),
+    ];
+    Ok(Stack::new(http::client(cfg.get_http())?)
+        .layer(Logging(Provider::Infatica))
+        .layer(Timeout(cfg.get_timeout().copied().unwrap_or(DEFAULT_TIMEOUT)))
+        .layer(FormCredentials(credentials))
+        .layer(Quota(Provider::Infatica)))
+}
+
 pub async fn query_infatica<T>(
-    client: &Client,
+    http: &Stack,
     base: &url::Url,
     endpoint: &str,
-    cfg: &crate::models::InfaticaConfig,
+    cfg: &InfaticaConfig,
     extra_form_fields: InfaticaFormFields,
+    stats: &mut EndpointStats,
 ) -> Result<T, HTTPError>
 where
     T: serde::de::DeserializeOwned,
 {
+    let resp = send_infatica(http, base, endpoint, cfg, extra_form_fields).await?;
+
+    let body = http::read_body(cfg.get_http(), resp).await?;
+    stats.record(body.len());
+    let parsed = serde_json::from_slice::<T>(&body)?;
+
+    Ok(parsed)
+}
+
+/// Sends the credential-bearing POST and returns the successful response with its body unread.
+pub async fn send_infatica(
+    http: &Stack,
+    base: &url::Url,
+    endpoint: &str,
+    cfg: &InfaticaConfig,
+    extra_form_fields: InfaticaFormFields,
+) -> Result<Response, HTTPError> {
 	// Ensure base URL ends with a slash, otherwise `join()` drops last path segment.
     let mut sanitized = base.clone();
     if !sanitized.path().ends_with('/') {
@@ -29,25 +67,44 @@ where
     }
 
     let url = sanitized.join(endpoint)?;
-    let timeout = cfg.get_timeout().unwrap_or(&DEFAULT_TIMEOUT).to_owned();
-
-	// Prepare POST form data
-    let mut form: HashMap<String, String> = HashMap::new();
-    form.insert(EMAIL_FIELD.to_string(), cfg.get_email().to_string());
-    form.insert(PASSWORD_FIELD.to_string(), cfg.get_password().to_string());
-    for (k, v) in extra_form_fields {
-        form.insert(k, v);
+
+	// Execute and check the status; the stack adds the credentials
+    let resp = http.send(http.request(Method::POST, url).form(&extra_form_fields)).await?;
+
+    let status = resp.status();
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        return Err(HTTPError::AuthError { status });
+    }
+    if !status.is_success() {
+        let body = http::error_body(cfg.get_http(), resp).await;
+        return Err(HTTPError::BadStatus { status, body });
     }
 
-	// Execute and decode
-    let resp =client
-        .post(url)
-        .timeout(timeout)
-        .form(&form)
-        .send()
-        .await?;
+    Ok(resp)
+}
 
-    let parsed = resp.json::<T>().await?;
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use crate::http::testing::Scripted;
+    use super::*;
 
-    Ok(parsed)
-}
\ No newline at end of file
+    #[tokio::test]
+    async fn credentials_are_added_to_the_form_and_statuses_checked() {
+        let cfg = InfaticaConfig::new("https://infatica.example/api".parse().unwrap(), "me@example.com", "s3cret");
+        let transport = Arc::new(Scripted::new([(StatusCode::TOO_MANY_REQUESTS, "slow down"), (StatusCode::OK, "[]")]));
+        let http = stack(&cfg).unwrap().transport(transport.clone());
+        let extras = vec![("country".to_string(), "DE".to_string())];
+        let mut stats = EndpointStats::start("isp_codes.php");
+
+        let err = send_infatica(&http, cfg.get_endpoint(), "isp_codes.php", &cfg, extras.clone()).await.unwrap_err();
+        assert!(matches!(err, HTTPError::BadStatus { status: StatusCode::TOO_MANY_REQUESTS, ref body } if body == "slow down"));
+        let records: Vec<serde_json::Value> =
+            query_infatica(&http, cfg.get_endpoint(), "isp_codes.php", &cfg, extras, &mut stats).await.unwrap();
+        assert!(records.is_empty());
+
+        let sent = transport.sent();
+        assert_eq!(sent[1].path, "/api/isp_codes.php");
+        assert_eq!(sent[1].body, "country=DE&email=me%40example.com&password=s3cret");
+    }
+}
@@ -1,28 +1,26 @@
 //! Queries Infatica’s region/subdivision dictionary (`subdivision_codes.php`).
 
-use reqwest::Client;
+use crate::http::EndpointStats;
 use super::consts::{REGION_CODES_ENDPOINT};
 use super::helpers::extras_empty;
 use super::errors::HTTPError;
 use super::models::{InfaticaRegionRecord, InfaticaRegionRecords};
-use super::query_infatica::query_infatica;
+use super::query_infatica::{query_infatica, stack};
 use crate::models::InfaticaConfig;
 
 /// Fetches the region/subdivision dictionary from Infatica.
-pub async fn region_codes(cfg: &InfaticaConfig) -> Result<Vec<InfaticaRegionRecord>, HTTPError> {
-	let http_client = Client::new();
+pub async fn region_codes(cfg: &InfaticaConfig) -> Result<(Vec<InfaticaRegionRecord>, EndpointStats), HTTPError> {
+	let http_client = stack(cfg)?;
 
+	let mut stats = EndpointStats::start(REGION_CODES_ENDPOINT);
 	let resp = query_infatica::<InfaticaRegionRecords>(
 		&http_client,
 		cfg.get_endpoint(),
 		REGION_CODES_ENDPOINT,
 		cfg,
 		extras_empty(),
+		&mut stats,
 	).await?;
 
-	let parsed = resp.into_iter()
-		.flatten()
-		.collect::<Vec<InfaticaRegionRecord>>();
-
-	Ok(parsed)
+	Ok((resp.into_inner(), stats.finish()))
 }
\ No newline at end of file
@@ -1,28 +1,26 @@
 //! Queries Infatica’s ZIP/postal code dictionary (`zip-codes.php`).
 
-use reqwest::Client;
+use crate::http::EndpointStats;
 use super::consts::{ZIP_CODES_ENDPOINT};
 use super::helpers::extras_empty;
 use super::errors::HTTPError;
 use super::models::{InfaticaZipRecord, InfaticaZipRecords};
-use super::query_infatica::query_infatica;
+use super::query_infatica::{query_infatica, stack};
 use crate::models::InfaticaConfig;
 
 /// Fetches the ZIP/postal dictionary from Infatica.
-pub async fn zip_codes(cfg: &InfaticaConfig) -> Result<Vec<InfaticaZipRecord>, HTTPError> {
-	let http_client = Client::new();
+pub async fn zip_codes(cfg: &InfaticaConfig) -> Result<(Vec<InfaticaZipRecord>, EndpointStats), HTTPError> {
+	let http_client = stack(cfg)?;
 
+	let mut stats = EndpointStats::start(ZIP_CODES_ENDPOINT);
 	let resp = query_infatica::<InfaticaZipRecords>(
 		&http_client,
 		cfg.get_endpoint(),
 		ZIP_CODES_ENDPOINT,
 		cfg,
 		extras_empty(),
+		&mut stats,
 	).await?;
 
-	let parsed = resp.into_iter()
-		.flatten()
-		.collect::<Vec<InfaticaZipRecord>>();
-
-	Ok(parsed)
+	Ok((resp.into_inner(), stats.finish()))
 }
\ No newline at end of file
@@ -10,7 +10,9 @@
 //! - [`zip_codes`] — postal/ZIP code dictionary
 //! - [`isp_codes`] — ISP dictionary
 //!
-//! On success, it returns an [`InfaticaQueryResults`] struct containing all four datasets.
+//! On success, it returns an [`InfaticaQueryResults`] struct containing all four datasets,
+//! with the ZIP codes checked by [`zip_validation`]. Its dictionaries resolve geo
+//! nodes to proxy [`targeting`] parameters.
 //! On failure, it returns a vector of [`InfaticaQueryError`] values, one per failed endpoint.
 //!
 //! The module isolates all HTTP and schema details inside [`internal`],
@@ -20,5 +22,13 @@ mod internal;
 mod get_all;
 mod errors;
 mod models;
+pub mod zip_validation;
+pub mod targeting;
 
-pub use get_all::get_all;
\ No newline at end of file
+pub use get_all::get_all;
+pub use errors::InfaticaQueryError;
+pub use internal::preflight::preflight;
+pub use internal::flattened::Flattened;
+pub use internal::models::{InfaticaGeoNodeRecord, InfaticaZipRecord};
+pub use models::InfaticaQueryResults;
+pub use targeting::{InfaticaTargeting, TargetingResolver};
\ No newline at end of file
@@ -1,24 +1,50 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use crate::http::EndpointStats;
+use crate::infatica::internal::consts::GEO_NODES_ENDPOINT;
 use crate::infatica::internal::models::{InfaticaGeoNodeRecord, InfaticaIspRecord, InfaticaRegionRecord, InfaticaZipRecord};
+use crate::infatica::zip_validation::ZipQuality;
 
+/// Grouped results of [`get_all`](crate::infatica::get_all).
+///
+/// Equality compares the datasets only, not the query statistics.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct InfaticaQueryResults{
 	geo_nodes: Vec<InfaticaGeoNodeRecord>,
 	region_codes: Vec<InfaticaRegionRecord>,
 	zip_codes: Vec<InfaticaZipRecord>,
 	isp_codes: Vec<InfaticaIspRecord>,
+	/// Result of validating `zip_codes`; empty for data cached before it was recorded.
+	#[serde(default)]
+	zip_quality: ZipQuality,
+	/// Statistics of the queries; not cached, so empty for cached results.
+	#[serde(skip)]
+	endpoints: Vec<EndpointStats>,
+	/// Unix seconds of the fetch, kept in the cache; unknown for older cache entries.
+	#[serde(default)]
+	fetched_at: Option<u64>,
 }
 
 impl InfaticaQueryResults {
+	/// Endpoint the unified records are parsed from.
+	pub const SOURCE_ENDPOINT: &str = GEO_NODES_ENDPOINT;
+
 	pub fn new(
 		geo_nodes: Vec<InfaticaGeoNodeRecord>,
 		region_codes: Vec<InfaticaRegionRecord>,
 		zip_codes: Vec<InfaticaZipRecord>,
+		zip_quality: ZipQuality,
 		isp_codes: Vec<InfaticaIspRecord>,
+		endpoints: Vec<EndpointStats>,
 	) -> Self {
 		Self {
 			geo_nodes,
 			region_codes,
 			zip_codes,
+			zip_quality,
 			isp_codes,
+			endpoints,
+			fetched_at: SystemTime::now().duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs()),
 		}
 	}
 
@@ -34,7 +60,57 @@ impl InfaticaQueryResults {
 		&self.zip_codes
 	}
 
+	pub fn zip_quality(&self) -> &ZipQuality {
+		&self.zip_quality
+	}
+
 	pub fn isp_codes(&self) -> &Vec<InfaticaIspRecord> {
 		&self.isp_codes
 	}
-}
\ No newline at end of file
+
+	pub fn endpoints(&self) -> &[EndpointStats] {
+		&self.endpoints
+	}
+
+	pub fn fetched_at(&self) -> Option<u64> {
+		self.fetched_at
+	}
+}
+
+impl PartialEq for InfaticaQueryResults {
+	fn eq(&self, other: &Self) -> bool {
+		self.geo_nodes == other.geo_nodes
+			&& self.region_codes == other.region_codes
+			&& self.zip_codes == other.zip_codes
+			&& self.isp_codes == other.isp_codes
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use crate::infatica::internal::models::{InfaticaIspRecords, InfaticaRecords, InfaticaRegionRecords, InfaticaZipRecords};
+	use super::*;
+
+	const GEO_NODES: &str = include_str!("../../fixtures/infatica_geo_nodes.json");
+	const REGION_CODES: &str = include_str!("../../fixtures/infatica_region_codes.json");
+	const ZIP_CODES: &str = include_str!("../../fixtures/infatica_zip_codes.json");
+	const ISP_CODES: &str = include_str!("../../fixtures/infatica_isp_codes.json");
+
+	#[test]
+	fn datasets_round_trip_losslessly() {
+		let results = InfaticaQueryResults::new(
+			serde_json::from_str::<InfaticaRecords>(GEO_NODES).unwrap().into_inner(),
+			serde_json::from_str::<InfaticaRegionRecords>(REGION_CODES).unwrap().into_inner(),
+			serde_json::from_str::<InfaticaZipRecords>(ZIP_CODES).unwrap().into_inner(),
+			ZipQuality::default(),
+			serde_json::from_str::<InfaticaIspRecords>(ISP_CODES).unwrap().into_inner(),
+			Vec::new(),
+		);
+		assert_eq!(results.zip_codes()[1].zip, "10115");
+
+		// The cache stores the flattened datasets; numbers sent as strings come back as numbers
+		let cached: InfaticaQueryResults = serde_json::from_slice(&serde_json::to_vec(&results).unwrap()).unwrap();
+		assert!(cached == results);
+		assert_eq!(serde_json::to_vec(&cached).unwrap(), serde_json::to_vec(&results).unwrap());
+	}
+}
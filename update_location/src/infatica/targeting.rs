@@ -0,0 +1,167 @@
+//! # Proxy targeting parameters
+//!
+//! Infatica selects the exit location of a proxy connection from parameters
+//! appended to the proxy username. Regions and ISPs are addressed by their codes
+//! in the `region_codes` and `isp_codes` dictionaries, postal codes as listed in
+//! `zip_codes`, countries by their ISO 3166-1 alpha-2 code:
+//!
+//! ```text
+//! _country-US                                   country only
+//! _country-US_region-1_isp-11                   region and ISP from the dictionaries
+//! _country-US_region-1_isp-11_zip-90001         down to a postal code
+//! _country-GB_zip-SW1A1AA                       spaces are dropped from postal codes
+//! ```
+//!
+//! A [`TargetingResolver`] looks the names of a geo node up in the dictionaries;
+//! whatever it cannot resolve (including postal codes tagged as malformed by
+//! [`zip_validation`](super::zip_validation)) is left out, widening the target
+//! instead of sending a parameter Infatica does not know.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use crate::infatica::internal::models::{InfaticaGeoNodeRecord, InfaticaIspRecord, InfaticaRegionRecord, InfaticaZipRecord};
+use crate::infatica::InfaticaQueryResults;
+
+/// Targeting parameters of one proxy connection; displays as the string to append
+/// to the proxy username.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InfaticaTargeting {
+	/// ISO 3166-1 alpha-2 country code.
+	pub country: String,
+	/// Code of the region in the `region_codes` dictionary.
+	pub region: Option<u32>,
+	/// Code of the ISP in the `isp_codes` dictionary.
+	pub isp: Option<u32>,
+	pub zip: Option<String>,
+}
+
+impl InfaticaTargeting {
+	/// `username` with the targeting parameters appended.
+	pub fn username(&self, username: &str) -> String {
+		format!("{username}{self}")
+	}
+}
+
+impl fmt::Display for InfaticaTargeting {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "_country-{}", self.country)?;
+		if let Some(region) = self.region {
+			write!(f, "_region-{region}")?;
+		}
+		if let Some(isp) = self.isp {
+			write!(f, "_isp-{isp}")?;
+		}
+		if let Some(zip) = &self.zip {
+			write!(f, "_zip-{zip}")?;
+		}
+		Ok(())
+	}
+}
+
+/// Lookup tables of the Infatica dictionaries, resolving geo nodes to [`InfaticaTargeting`]s.
+///
+/// Names are matched case-insensitively; a name listed twice resolves to its first code.
+#[derive(Debug, Default)]
+pub struct TargetingResolver {
+	regions: HashMap<String, u32>,
+	isps: HashMap<String, u32>,
+	/// `(country, postal code)` pairs of the valid entries of `zip_codes`.
+	zips: HashSet<(String, String)>,
+}
+
+impl TargetingResolver {
+	pub fn new(regions: &[InfaticaRegionRecord], isps: &[InfaticaIspRecord], zips: &[InfaticaZipRecord]) -> Self {
+		let mut resolver = Self::default();
+		for region in regions {
+			resolver.regions.entry(key(&region.name)).or_insert(region.code);
+		}
+		for isp in isps {
+			resolver.isps.entry(key(&isp.isp)).or_insert(isp.code);
+		}
+		resolver.zips = zips
+			.iter()
+			.filter(|z| !z.malformed)
+			.map(|z| (z.country.trim().to_uppercase(), zip(&z.zip)))
+			.collect();
+		resolver
+	}
+
+	/// Resolver over the dictionaries of `results`.
+	pub fn from_results(results: &InfaticaQueryResults) -> Self {
+		Self::new(results.region_codes(), results.isp_codes(), results.zip_codes())
+	}
+
+	pub fn resolve(&self, node: &InfaticaGeoNodeRecord) -> InfaticaTargeting {
+		let country = node.country.trim().to_uppercase();
+		let zip = Some(zip(&node.zip)).filter(|z| self.zips.contains(&(country.clone(), z.clone())));
+		InfaticaTargeting {
+			region: self.regions.get(&key(&node.subdivision)).copied(),
+			isp: self.isps.get(&key(&node.isp)).copied(),
+			zip,
+			country,
+		}
+	}
+}
+
+fn key(name: &str) -> String {
+	name.trim().to_lowercase()
+}
+
+/// Postal code as sent in a parameter: upper-cased, without whitespace.
+fn zip(code: &str) -> String {
+	code.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const REGIONS: &str = include_str!("../../fixtures/infatica_region_codes.json");
+	const ISPS: &str = include_str!("../../fixtures/infatica_isp_codes.json");
+	const ZIPS: &str = include_str!("../../fixtures/infatica_zip_codes.json");
+
+	fn resolver() -> TargetingResolver {
+		let regions: Vec<InfaticaRegionRecord> = serde_json::from_str::<Vec<Vec<_>>>(REGIONS).unwrap().concat();
+		let isps: Vec<InfaticaIspRecord> = serde_json::from_str::<Vec<Vec<_>>>(ISPS).unwrap().concat();
+		let mut zips: Vec<InfaticaZipRecord> = serde_json::from_str::<Vec<Vec<_>>>(ZIPS).unwrap().concat();
+		zips.push(InfaticaZipRecord {
+			country: "GB".to_string(),
+			subdivision: "England".to_string(),
+			city: "London".to_string(),
+			zip: "SW1A 1AA".to_string(),
+			malformed: false,
+		});
+		TargetingResolver::new(&regions, &isps, &zips)
+	}
+
+	fn node(country: &str, subdivision: &str, isp: &str, zip: &str) -> InfaticaGeoNodeRecord {
+		InfaticaGeoNodeRecord {
+			country: country.to_string(),
+			subdivision: subdivision.to_string(),
+			city: "XX".to_string(),
+			isp: isp.to_string(),
+			asn: 0,
+			zip: zip.to_string(),
+			nodes: 1,
+		}
+	}
+
+	#[test]
+	fn documented_examples() {
+		let resolver = resolver();
+		let target = |n: InfaticaGeoNodeRecord| resolver.resolve(&n).to_string();
+
+		assert_eq!(target(node("us", "", "Unknown ISP", "")), "_country-US");
+		assert_eq!(target(node("US", "california", "Comcast Cable", "")), "_country-US_region-1_isp-11");
+		assert_eq!(target(node("US", "California", "Comcast Cable", "90001")), "_country-US_region-1_isp-11_zip-90001");
+		assert_eq!(target(node("GB", "", "", "sw1a 1aa")), "_country-GB_zip-SW1A1AA");
+	}
+
+	#[test]
+	fn leaves_out_unlisted_postal_codes() {
+		let resolver = resolver();
+		// 90001 is listed for the US only
+		assert_eq!(resolver.resolve(&node("DE", "Berlin", "", "90001")).to_string(), "_country-DE_region-2");
+		assert_eq!(resolver.resolve(&node("DE", "", "", "10115")).username("user"), "user_country-DE_zip-10115");
+	}
+}
@@ -0,0 +1,197 @@
+//! # ZIP code validation
+//!
+//! Checks the postal codes of the `zip_codes` dictionary against the format of
+//! their country. Built-in formats cover common countries; `infatica.zip_patterns`
+//! adds to and overrides them. Malformed records are tagged or dropped per
+//! `infatica.zip_action`, and counted in a [`ZipQuality`] for the run report.
+//! Countries without a known format are left unchecked.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::LazyLock;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use crate::infatica::InfaticaZipRecord;
+
+/// Postal code formats of common countries, matched against the whole code.
+const DEFAULT_PATTERNS: &[(&str, &str)] = &[
+	("AT", r"\d{4}"),
+	("AU", r"\d{4}"),
+	("BE", r"\d{4}"),
+	("BR", r"\d{5}-?\d{3}"),
+	("CA", r"[A-Z]\d[A-Z] ?\d[A-Z]\d"),
+	("CH", r"\d{4}"),
+	("CZ", r"\d{3} ?\d{2}"),
+	("DE", r"\d{5}"),
+	("DK", r"\d{4}"),
+	("ES", r"\d{5}"),
+	("FI", r"\d{5}"),
+	("FR", r"\d{5}"),
+	("GB", r"[A-Z]{1,2}\d[A-Z\d]? ?\d[A-Z]{2}"),
+	("IN", r"\d{6}"),
+	("IT", r"\d{5}"),
+	("JP", r"\d{3}-?\d{4}"),
+	("MX", r"\d{5}"),
+	("NL", r"\d{4} ?[A-Z]{2}"),
+	("NO", r"\d{4}"),
+	("PL", r"\d{2}-?\d{3}"),
+	("PT", r"\d{4}(-\d{3})?"),
+	("RU", r"\d{6}"),
+	("SE", r"\d{3} ?\d{2}"),
+	("US", r"\d{5}(-\d{4})?"),
+];
+
+static DEFAULTS: LazyLock<BTreeMap<&'static str, ZipPattern>> = LazyLock::new(|| {
+	DEFAULT_PATTERNS
+		.iter()
+		.map(|(country, pattern)| (*country, pattern.parse().expect("built-in ZIP patterns are valid")))
+		.collect()
+});
+
+/// Format of a country's postal codes: a regular expression the whole code,
+/// trimmed and upper-cased, must match.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(try_from = "String")]
+pub struct ZipPattern(Regex);
+
+impl ZipPattern {
+	pub fn matches(&self, zip: &str) -> bool {
+		self.0.is_match(&zip.trim().to_uppercase())
+	}
+}
+
+impl FromStr for ZipPattern {
+	type Err = regex::Error;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		Regex::new(&format!("^(?:{s})$")).map(Self)
+	}
+}
+
+impl TryFrom<String> for ZipPattern {
+	type Error = regex::Error;
+
+	fn try_from(s: String) -> Result<Self, Self::Error> {
+		s.parse()
+	}
+}
+
+/// What happens to records whose code does not match their country's format.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ZipAction {
+	/// Keep them, with [`InfaticaZipRecord::malformed`] set.
+	#[default]
+	Tag,
+	/// Remove them from the dataset.
+	Drop,
+}
+
+/// Outcome of validating a `zip_codes` dictionary.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ZipQuality {
+	/// Records of countries with a known format.
+	pub checked: usize,
+	/// Records of countries without one.
+	pub unchecked: usize,
+	/// Malformed records per country.
+	pub malformed: BTreeMap<String, usize>,
+	pub action: ZipAction,
+}
+
+impl ZipQuality {
+	pub fn is_clean(&self) -> bool {
+		self.malformed.is_empty()
+	}
+}
+
+impl fmt::Display for ZipQuality {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "ZIP codes: {} checked, {} of countries without a known format", self.checked, self.unchecked)?;
+		if self.is_clean() {
+			return writeln!(f, "  all checked codes well-formed");
+		}
+		let verb = match self.action {
+			ZipAction::Tag => "tagged",
+			ZipAction::Drop => "dropped",
+		};
+		for (country, count) in &self.malformed {
+			writeln!(f, "  {country}: {count} malformed code(s) {verb}")?;
+		}
+		Ok(())
+	}
+}
+
+/// Checks `records` against the built-in formats overridden by `patterns`
+/// (keyed by ISO 3166-1 alpha-2 code), applying `action` to malformed ones.
+pub fn validate(records: &mut Vec<InfaticaZipRecord>, patterns: &BTreeMap<String, ZipPattern>, action: ZipAction) -> ZipQuality {
+	let mut quality = ZipQuality { action, ..ZipQuality::default() };
+	let pattern = |country: &str| {
+		patterns
+			.iter()
+			.find(|(c, _)| c.eq_ignore_ascii_case(country))
+			.map(|(_, p)| p)
+			.or_else(|| DEFAULTS.get(country.to_ascii_uppercase().as_str()))
+	};
+
+	records.retain_mut(|record| {
+		let Some(pattern) = pattern(&record.country) else {
+			quality.unchecked += 1;
+			return true;
+		};
+		quality.checked += 1;
+		record.malformed = !pattern.matches(&record.zip);
+		if record.malformed {
+			*quality.malformed.entry(record.country.to_ascii_uppercase()).or_default() += 1;
+		}
+		!(record.malformed && action == ZipAction::Drop)
+	});
+	quality
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn record(country: &str, zip: &str) -> InfaticaZipRecord {
+		InfaticaZipRecord {
+			country: country.to_string(),
+			subdivision: String::new(),
+			city: String::new(),
+			zip: zip.to_string(),
+			malformed: false,
+		}
+	}
+
+	fn records() -> Vec<InfaticaZipRecord> {
+		vec![
+			record("US", "90001-1234"),
+			record("us", "9001"),
+			record("GB", "sw1a 1aa"),
+			record("DE", "1011"),
+			record("ZZ", "anything"),
+		]
+	}
+
+	#[test]
+	fn tags_codes_not_matching_their_country() {
+		let mut tagged = records();
+		let quality = validate(&mut tagged, &BTreeMap::new(), ZipAction::Tag);
+
+		assert_eq!(tagged.iter().map(|r| r.malformed).collect::<Vec<_>>(), [false, true, false, true, false]);
+		assert_eq!((quality.checked, quality.unchecked), (4, 1));
+		assert_eq!(quality.malformed, BTreeMap::from([("DE".to_string(), 1), ("US".to_string(), 1)]));
+	}
+
+	#[test]
+	fn configured_patterns_override_the_defaults() {
+		let patterns = BTreeMap::from([("de".to_string(), r"\d{4,5}".parse().unwrap()), ("ZZ".to_string(), r"\d+".parse().unwrap())]);
+		let mut kept = records();
+		let quality = validate(&mut kept, &patterns, ZipAction::Drop);
+
+		assert_eq!(kept.iter().map(|r| r.zip.as_str()).collect::<Vec<_>>(), ["90001-1234", "sw1a 1aa", "1011"]);
+		assert_eq!(quality.malformed, BTreeMap::from([("US".to_string(), 1), ("ZZ".to_string(), 1)]));
+		assert_eq!(quality.unchecked, 0);
+	}
+}
@@ -1,27 +1,426 @@
-use config::{Config, Environment, File};
+use std::collections::hash_map::Entry;
+use std::path::{Path, PathBuf};
+use config::{Config, Environment, File, FileFormat, FileSourceFile, Map, Source, Value};
 use override_key_core::ApplyOverrides;
-use crate::models::{AppConfig, CLIArgs, constants::ENV_PREFIX, ConfigError};
+use crate::init::check_schema;
+use crate::{secrets, verbose};
+use crate::models::{read_secret_file, AppConfig, CLIArgs, ConfigError, ConfigFileFormat, MASK};
+use crate::models::constants::{DEPRECATED_KEYS, ENV_LIST_KEYS, ENV_LIST_SEPARATOR, ENV_PREFIX, ENV_SEPARATOR, PROFILE_ENV};
 
 /// Load configuration from file, environment, and CLI arguments.
-pub fn load_config(args: &CLIArgs) -> Result<AppConfig, ConfigError> {
-    let mut builder = Config::builder();
+///
+/// The merged values are checked with [`check_schema`] first, so every problem is
+/// reported at once. Secrets configured through `*_file` keys are read once the
+/// config is deserialized, and providers without their own proxy inherit `http.proxy`.
+/// With `--verbose`, the keys set by command-line flags are printed first, secrets masked.
+pub async fn load_config(args: &CLIArgs) -> Result<AppConfig, ConfigError> {
+    // `--extra-config` and `--set` are merged at the root as one table, so each
+    // leaf is printed, and masked, on its own
+    for (key, value) in args.active_overrides() {
+        for (leaf, value) in override_key_core::map::flatten(key, value) {
+            let shown = if CLIArgs::sensitive_keys().contains(&leaf.as_str()) { MASK.to_string() } else { value.to_string() };
+            verbose!("{leaf} = {shown} (from command line)");
+        }
+    }
+
+    let raw = load_raw_config(args).await?;
+    check_schema(&raw)?;
+
+    let mut cfg = raw
+        .try_deserialize::<AppConfig>()
+        .map_err(|source| ConfigError::DeserializeConfigError { source })?;
+
+    if let Some(iproyal) = cfg.iproyal.as_mut() {
+        iproyal.resolve_secret_files()?;
+        iproyal.inherit_http(&cfg.http);
+    }
+    if let Some(infatica) = cfg.infatica.as_mut() {
+        infatica.resolve_secret_files()?;
+        infatica.inherit_http(&cfg.http);
+    }
 
-    // Lowest priority: configuration file
-    if let Some(path) = &args.config {
-        builder = builder.add_source(File::with_name(path).required(false));
+    Ok(cfg)
+}
+
+/// Merge file, environment, and CLI sources without deserializing the result.
+///
+/// Precedence, lowest first: `--config` files in the order given (TOML, JSON, JSON5,
+/// YAML, INI or RON by extension, otherwise `--config-format`), the selected profile,
+/// `MYAPP_*` environment variables, then CLI flags, `--extra-config` among them, and
+/// finally `--set key=value` pairs, so ad hoc overrides win over everything else.
+///
+/// Deprecated keys are then moved to their replacement, see [`migrate_deprecated_keys`].
+/// `vault:` / `aws-sm:` references in the merged values are resolved here (see [`crate::secrets`]).
+pub async fn load_raw_config(args: &CLIArgs) -> Result<Config, ConfigError> {
+    // Process environment, completed by the `.env` file
+    let env = load_env(args.env_file.as_deref())?;
+
+    // Lowest priority: configuration files, later ones overriding earlier ones
+    let mut files = Config::builder();
+    for path in config_files(&args.config)? {
+        files = files.add_source(config_file(path, args.config_format.unwrap_or_default()));
+    }
+    let files = files.build()?;
+
+    // The selected profile is merged over the file defaults
+    let profile = args.profile.clone().or_else(|| env.get(PROFILE_ENV).cloned());
+    let mut builder = Config::builder().add_source(files.clone());
+    if let Some(name) = profile {
+        builder = builder.add_source(ProfileSource::select(&files, &name)?);
     }
 
     // Medium priority: environment variables
-    builder = builder.add_source(
-        Environment::with_prefix(ENV_PREFIX)
-        .separator("_")
-    );
+    builder = builder.add_source(environment(resolve_secret_file_vars(env)?, ENV_LIST_KEYS));
 
     builder = args.apply_overrides(builder)?;
 
-    // Build the final merged config and deserialize it
-    let cfg = builder.build()?;
+    // Build the final merged config, move deprecated keys and resolve secret references
+    let raw = migrate_deprecated_keys(builder.build()?, DEPRECATED_KEYS)?;
+    Ok(secrets::resolve(raw).await?)
+}
+
+/// Sets the replacement of each deprecated key of `renamed` found in `raw`, warning on stderr.
+///
+/// The replacement wins when both are set, whatever source set each of them, so a
+/// deprecated key left behind in one file cannot undo a migration made in another.
+/// The deprecated keys stay in the result for `validate` and `print-config`.
+fn migrate_deprecated_keys(raw: Config, renamed: &[(&str, &str)]) -> Result<Config, ConfigError> {
+    let mut builder = Config::builder().add_source(raw.clone());
+    for &(old, new) in renamed {
+        let Ok(value) = raw.get::<Value>(old) else { continue };
+        if raw.get::<Value>(new).is_ok() {
+            eprintln!("warning: config key `{old}` is deprecated and ignored, `{new}` is set");
+        } else {
+            eprintln!("warning: config key `{old}` is deprecated, use `{new}` instead");
+            builder = builder.set_override(new, value)?;
+        }
+    }
+    Ok(builder.build()?)
+}
+
+/// Environment source reading `MYAPP_*` variables from `env`.
+///
+/// Nested keys are separated by `__` (`MYAPP_IPROYAL__ENDPOINT` sets `iproyal.endpoint`)
+/// so single underscores stay part of key names. Booleans and numbers are parsed, and
+/// the values of `list_keys` are split on commas.
+fn environment(env: Map<String, String>, list_keys: &[&str]) -> Environment {
+    let mut source = Environment::with_prefix(ENV_PREFIX)
+        .prefix_separator("_")
+        .separator(ENV_SEPARATOR)
+        .try_parsing(true)
+        .source(Some(env));
+
+    // Without parse keys the source would split every string value
+    if !list_keys.is_empty() {
+        source = source.list_separator(ENV_LIST_SEPARATOR);
+        for key in list_keys {
+            source = source.with_list_parse_key(key);
+        }
+    }
+
+    source
+}
+
+/// `.env` file read from the working directory when `--env-file` is not given.
+const DEFAULT_ENV_FILE: &str = ".env";
+
+/// Process environment merged with the variables of a `.env` file.
+///
+/// Variables already set in the process environment win over the file. An explicit
+/// `--env-file` must exist; the default `.env` is optional.
+fn load_env(env_file: Option<&str>) -> Result<Map<String, String>, ConfigError> {
+    let path = Path::new(env_file.unwrap_or(DEFAULT_ENV_FILE));
+    let mut env = Map::new();
+
+    if env_file.is_some() || path.is_file() {
+        let env_err = |source| ConfigError::EnvFileError { path: path.to_path_buf(), source };
+        for item in dotenvy::from_path_iter(path).map_err(env_err)? {
+            let (key, value) = item.map_err(env_err)?;
+            env.insert(key, value);
+        }
+    }
+
+    env.extend(std::env::vars());
+    Ok(env)
+}
+
+/// Suffix of environment variables naming a file that holds the actual value.
+const FILE_ENV_SUFFIX: &str = "_FILE";
+
+/// Replaces `MYAPP_X_FILE=/path` with `MYAPP_X=<file contents>`, e.g.
+/// `MYAPP_INFATICA__PASSWORD_FILE` fills `MYAPP_INFATICA__PASSWORD`.
+///
+/// This lets credentials be mounted as Docker / Kubernetes secrets. A directly set
+/// `MYAPP_X` takes precedence over `MYAPP_X_FILE`.
+fn resolve_secret_file_vars(mut env: Map<String, String>) -> Result<Map<String, String>, ConfigError> {
+    let prefix = format!("{ENV_PREFIX}_");
+
+    let file_vars: Vec<String> = env
+        .keys()
+        .filter(|k| k.starts_with(&prefix) && k.ends_with(FILE_ENV_SUFFIX))
+        .cloned()
+        .collect();
+
+    for file_var in file_vars {
+        let path = env.remove(&file_var).unwrap_or_default();
+        let var = file_var.trim_end_matches(FILE_ENV_SUFFIX).to_string();
+        if let Entry::Vacant(entry) = env.entry(var) {
+            entry.insert(read_secret_file(Path::new(&path))?);
+        }
+    }
+
+    Ok(env)
+}
+
+/// Source for one config file.
+///
+/// The format follows the extension (see [`CONFIG_EXTENSIONS`]); files without a
+/// known extension are read as `format`.
+fn config_file(path: PathBuf, format: ConfigFileFormat) -> File<FileSourceFile, FileFormat> {
+    let known = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| CONFIG_EXTENSIONS.contains(&ext));
+
+    let file = File::from(path).required(false);
+    if known {
+        file
+    } else {
+        file.format(format.into())
+    }
+}
+
+/// Extensions of files picked up from configuration directories.
+const CONFIG_EXTENSIONS: &[&str] = &["toml", "json", "json5", "yaml", "yml", "ini", "ron"];
+
+/// Expands `--config` arguments into the ordered list of files to merge.
+///
+/// Arguments are layered in the order given. A directory contributes its
+/// configuration files sorted by file name. A file `x/config.toml` is followed
+/// by the contents of `x/config.d/`, if that directory exists, so per-environment
+/// overrides can be dropped next to a base config.
+fn config_files(args: &[String]) -> Result<Vec<PathBuf>, ConfigError> {
+    let mut files = Vec::new();
+
+    for arg in args {
+        let path = Path::new(arg);
+        if path.is_dir() {
+            files.extend(dir_files(path)?);
+            continue;
+        }
+
+        files.push(path.to_path_buf());
+
+        if let Some(stem) = path.file_stem() {
+            let drop_in = path.with_file_name(format!("{}.d", stem.to_string_lossy()));
+            if drop_in.is_dir() {
+                files.extend(dir_files(&drop_in)?);
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Configuration files directly inside `dir`, sorted by file name.
+fn dir_files(dir: &Path) -> Result<Vec<PathBuf>, ConfigError> {
+    let read_err = |source| ConfigError::ConfigDirError { path: dir.to_path_buf(), source };
+
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir).map_err(read_err)? {
+        let path = entry.map_err(read_err)?.path();
+        let supported = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| CONFIG_EXTENSIONS.contains(&ext));
+        if path.is_file() && supported {
+            files.push(path);
+        }
+    }
+
+    files.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+    Ok(files)
+}
+
+/// The `[profiles.<name>]` section of the config files, applied as its own layer.
+#[derive(Debug, Clone)]
+struct ProfileSource(Map<String, Value>);
+
+impl ProfileSource {
+    fn select(files: &Config, name: &str) -> Result<Self, ConfigError> {
+        files
+            .get::<Map<String, Value>>(&format!("profiles.{name}"))
+            .map(Self)
+            .map_err(|_| ConfigError::UnknownProfile { name: name.to_string() })
+    }
+}
+
+impl Source for ProfileSource {
+    fn clone_into_box(&self) -> Box<dyn Source + Send + Sync> {
+        Box::new(self.clone())
+    }
+
+    fn collect(&self) -> Result<Map<String, Value>, config::ConfigError> {
+        Ok(self.0.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(vars: &[(&str, &str)], list_keys: &[&str]) -> Config {
+        let env = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        Config::builder()
+            .add_source(environment(env, list_keys))
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn double_underscore_reaches_nested_keys() {
+        let cfg = collect(&[
+            ("MYAPP_IPROYAL__ENDPOINT", "https://example.com"),
+            ("MYAPP_IPROYAL__CLIENT_ID", "abc"),
+        ], &[]);
+
+        assert_eq!(cfg.get_string("iproyal.endpoint").unwrap(), "https://example.com");
+        assert_eq!(cfg.get_string("iproyal.client_id").unwrap(), "abc");
+    }
+
+    #[test]
+    fn single_underscore_stays_in_key() {
+        let cfg = collect(&[("MYAPP_CACHE__MAX_AGE", "6h")], &[]);
+
+        assert_eq!(cfg.get_string("cache.max_age").unwrap(), "6h");
+        assert!(cfg.get_string("cache.max.age").is_err());
+    }
+
+    #[test]
+    fn ignores_unprefixed_variables() {
+        let cfg = collect(&[("OTHER_IPROYAL__ENDPOINT", "x")], &[]);
+
+        assert!(cfg.get_string("iproyal.endpoint").is_err());
+    }
+
+    #[test]
+    fn parses_numbers_and_booleans() {
+        let cfg = collect(&[
+            ("MYAPP_MATCHING__THRESHOLD", "0.66"),
+            ("MYAPP_INFATICA__RETRIES", "3"),
+            ("MYAPP_CACHE__ENABLED", "true"),
+        ], &[]);
+
+        assert_eq!(cfg.get_float("matching.threshold").unwrap(), 0.66);
+        assert_eq!(cfg.get_int("infatica.retries").unwrap(), 3);
+        assert!(cfg.get_bool("cache.enabled").unwrap());
+    }
+
+    #[test]
+    fn splits_only_list_keys() {
+        let cfg = collect(&[
+            ("MYAPP_OUTPUT__FORMATS", "json,csv"),
+            ("MYAPP_INFATICA__PASSWORD", "a,b"),
+        ], &["output.formats"]);
+
+        assert_eq!(cfg.get::<Vec<String>>("output.formats").unwrap(), ["json", "csv"]);
+        assert_eq!(cfg.get_string("infatica.password").unwrap(), "a,b");
+    }
+
+    fn load_file(name: &str, contents: &str, format: ConfigFileFormat) -> Config {
+        let path = std::env::temp_dir().join(format!("update_location_{}_{name}", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+
+        let cfg = Config::builder().add_source(config_file(path.clone(), format)).build();
+        std::fs::remove_file(&path).unwrap();
+        cfg.unwrap()
+    }
+
+    #[test]
+    fn reads_yaml_files() {
+        let cfg = load_file("config.yaml", "iproyal:\n  endpoint: https://example.com\n  page_size: 50\n", ConfigFileFormat::Toml);
+
+        assert_eq!(cfg.get_string("iproyal.endpoint").unwrap(), "https://example.com");
+        assert_eq!(cfg.get_int("iproyal.page_size").unwrap(), 50);
+    }
+
+    #[test]
+    fn reads_json5_files() {
+        let cfg = load_file("config.json5", "{ iproyal: { endpoint: 'https://example.com', }, // comment\n }", ConfigFileFormat::Toml);
+
+        assert_eq!(cfg.get_string("iproyal.endpoint").unwrap(), "https://example.com");
+    }
+
+    #[test]
+    fn reads_ini_files() {
+        let cfg = load_file("config.ini", "[infatica]\nemail = ops@example.com\n", ConfigFileFormat::Toml);
+
+        assert_eq!(cfg.get_string("infatica.email").unwrap(), "ops@example.com");
+    }
+
+    #[test]
+    fn extension_less_files_use_the_given_format() {
+        let cfg = load_file("config", "cache:\n  max_age: 6h\n", ConfigFileFormat::Yaml);
+
+        assert_eq!(cfg.get_string("cache.max_age").unwrap(), "6h");
+    }
+
+    #[test]
+    fn extension_wins_over_the_given_format() {
+        let cfg = load_file("config.toml", "[cache]\nmax_age = \"6h\"\n", ConfigFileFormat::Yaml);
+
+        assert_eq!(cfg.get_string("cache.max_age").unwrap(), "6h");
+    }
+
+    #[test]
+    fn file_vars_fill_nested_keys() {
+        let path = std::env::temp_dir().join(format!("update_location_env_{}", std::process::id()));
+        std::fs::write(&path, "secret\n").unwrap();
+
+        let env = Map::from([(
+            "MYAPP_INFATICA__PASSWORD_FILE".to_string(),
+            path.to_string_lossy().into_owned(),
+        )]);
+        let env = resolve_secret_file_vars(env).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let cfg = Config::builder().add_source(environment(env, &[])).build().unwrap();
+        assert_eq!(cfg.get_string("infatica.password").unwrap(), "secret");
+    }
+
+    #[test]
+    fn set_pairs_win_over_flags_and_environment() {
+        use clap::Parser;
+
+        let args = CLIArgs::try_parse_from([
+            "update_location",
+            "--set", "iproyal.endpoint=https://set.example.com",
+            "--iproyal-endpoint", "https://flag.example.com",
+            "--set", "cache.max_age=2h",
+        ]).unwrap();
+        let env = Map::from([("MYAPP_CACHE__MAX_AGE".to_string(), "6h".to_string())]);
 
-    cfg.try_deserialize::<AppConfig>()
-        .map_err(|source| ConfigError::DeserializeConfigError { source })
-}
\ No newline at end of file
+        let builder = Config::builder().add_source(environment(env, &[]));
+        let cfg = args.apply_overrides(builder).unwrap().build().unwrap();
+
+        assert_eq!(cfg.get_string("iproyal.endpoint").unwrap(), "https://set.example.com");
+        assert_eq!(cfg.get_string("cache.max_age").unwrap(), "2h");
+    }
+
+    #[test]
+    fn deprecated_keys_are_moved_unless_replaced() {
+        let raw = Config::builder()
+            .add_source(File::from_str(
+                "[export]\ndir = \"old\"\nformats = [\"csv\"]\n[output]\nformats = [\"ndjson\"]\n",
+                FileFormat::Toml,
+            ))
+            .build()
+            .unwrap();
+
+        let cfg = migrate_deprecated_keys(raw, DEPRECATED_KEYS).unwrap().try_deserialize::<AppConfig>().unwrap();
+
+        assert_eq!(cfg.output.get_dir(), Path::new("old"));
+        assert_eq!(cfg.output.get_formats(), [crate::models::ExportFormat::Ndjson]);
+        assert_eq!(cfg.output.get_compression(), crate::models::ExportCompression::None);
+    }
+}
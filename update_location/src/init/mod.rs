@@ -1,3 +1,7 @@
 mod load;
+mod schema;
+mod template;
 
-pub use load::load_config;
\ No newline at end of file
+pub use load::{load_config, load_raw_config};
+pub use schema::{check_schema, ConfigReport};
+pub use template::{config_template, ConfigKey, Kind, CONFIG_KEYS};
@@ -0,0 +1,638 @@
+//! Schema validation of the merged configuration.
+//!
+//! Deserializing into [`AppConfig`](crate::models::AppConfig) stops at the first
+//! problem. This pass walks the merged values first and collects every missing
+//! required field, malformed URL and out-of-range timeout of the enabled
+//! sections into one [`ConfigReport`]. Which keys exist, their kinds and which
+//! are required come from [`CONFIG_KEYS`], the table the template and `validate`
+//! use too. Each problem names the config file key, environment variable and
+//! CLI flag that can supply the value.
+
+use std::fmt;
+use std::time::Duration;
+use config::Config;
+use override_key_core::ApplyOverrides;
+use serde_json::{Map, Value};
+use url::Url;
+use crate::filter::Filter;
+use crate::infatica::zip_validation::ZipPattern;
+use crate::models::CLIArgs;
+use crate::models::constants::{ENV_PREFIX, ENV_SEPARATOR};
+use super::template::{ConfigKey, Kind, CONFIG_KEYS};
+
+/// Accepted range of provider request timeouts.
+const MIN_TIMEOUT: Duration = Duration::from_secs(1);
+const MAX_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// A single invalid or missing configuration value.
+#[derive(Debug)]
+pub struct ConfigProblem {
+    /// Dotted configuration key, e.g. `iproyal.endpoint`.
+    pub key: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.key, self.message)?;
+        match self.sources() {
+            Some(sources) => write!(f, " ({sources})"),
+            None => Ok(()),
+        }
+    }
+}
+
+impl ConfigProblem {
+    /// Where a value for the key can come from: config file, environment and CLI flag.
+    ///
+    /// `None` for problems with a whole section, which no single value fixes.
+    pub fn sources(&self) -> Option<String> {
+        let key = &self.key;
+        if !key.contains('.') {
+            return None;
+        }
+        let env = env_var(key);
+        let flag = cli_flag(key).map(|flag| format!(", or {flag}")).unwrap_or_default();
+
+        Some(format!("set `{key}` in a config file, {env}{flag}"))
+    }
+}
+
+/// Environment variable setting the dotted config `key`, e.g. `MYAPP_IPROYAL__ENDPOINT`.
+pub(super) fn env_var(key: &str) -> String {
+    format!("{ENV_PREFIX}_{}", key.to_uppercase().replace('.', ENV_SEPARATOR))
+}
+
+/// CLI flag overriding the dotted config `key`, e.g. `--iproyal-endpoint`, if there is one.
+pub(super) fn cli_flag(key: &str) -> Option<String> {
+    CLIArgs::flag_key_map()
+        .iter()
+        .find(|(_, k)| *k == key)
+        .map(|(flag, _)| format!("--{flag}"))
+}
+
+/// Every problem found in the merged configuration.
+#[derive(Debug)]
+pub struct ConfigReport {
+    pub problems: Vec<ConfigProblem>,
+}
+
+impl fmt::Display for ConfigReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} problem(s):", self.problems.len())?;
+        for problem in &self.problems {
+            write!(f, "\n  - {problem}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigReport {}
+
+/// Checks the merged configuration, reporting all problems at once.
+///
+/// Each key of [`CONFIG_KEYS`] in a present section is checked against its kind and
+/// `required` flag; the rules spanning several keys follow.
+pub fn check_schema(raw: &Config) -> Result<(), ConfigReport> {
+    // Values that cannot be represented at all are reported by deserialization
+    let Ok(Value::Object(root)) = raw.clone().try_deserialize::<Value>() else {
+        return Ok(());
+    };
+
+    let mut checker = Checker::default();
+
+    for keys in CONFIG_KEYS.chunk_by(|a, b| a.section() == b.section()) {
+        let name = keys[0].section();
+        let Some(section) = checker.section(&root, name) else { continue };
+        // That of a section's first key applies to the whole section
+        let section_feature = keys[0].feature;
+        if let Some(feature) = section_feature {
+            checker.feature(name, feature);
+        }
+        for key in keys {
+            checker.key(section, name, key);
+            if let Some(feature) = key.feature
+                && key.feature != section_feature
+                && is_on(section.get(key.name()))
+            {
+                checker.feature(key.key, feature);
+            }
+        }
+    }
+
+    if let Some(section) = table(&root, "iproyal") {
+        if !checker.any_set(section, &["token", "token_file"]) {
+            checker.required(section, "iproyal", "client_id");
+            checker.required_one_of(section, "iproyal", &["client_secret", "client_secret_file"]);
+            checker.required(section, "iproyal", "token_endpoint");
+        }
+        checker.retry_delays(section, "iproyal");
+        checker.proxy(section, "iproyal");
+        checker.headers(section, "iproyal", "headers");
+    }
+
+    if let Some(section) = table(&root, "infatica") {
+        checker.required_one_of(section, "infatica", &["password", "password_file"]);
+        checker.retry_delays(section, "infatica");
+        checker.proxy(section, "infatica");
+        checker.headers(section, "infatica", "headers");
+        checker.zip_patterns(section, "infatica", "zip_patterns");
+    }
+
+    if let Some(section) = table(&root, "http") {
+        checker.together(section, "http", &["client_cert", "client_key"]);
+        checker.headers(section, "http", "extra_headers");
+    }
+
+    if let Some(email) = table(&root, "notify.email") {
+        checker.together(email, "notify.email", &["username", "password"]);
+        checker.template(email, "notify.email", "subject");
+        checker.template(email, "notify.email", "body");
+    }
+
+    if let Some(section) = table(&root, "output") {
+        checker.filter(section, "output", "filter");
+    }
+
+    if let Some(section) = table(&root, "dedup") {
+        checker.distinct(section, "dedup", "prefer");
+    }
+
+    if checker.problems.is_empty() {
+        Ok(())
+    } else {
+        Err(ConfigReport { problems: checker.problems })
+    }
+}
+
+/// Cargo features configuration keys may need, and whether this build includes them.
+const FEATURES: &[(&str, bool)] = &[
+    ("kafka", cfg!(feature = "kafka")),
+    ("nats", cfg!(feature = "nats")),
+    ("translit", cfg!(feature = "translit")),
+    ("email", cfg!(feature = "email")),
+];
+
+/// Whether this build includes the cargo `feature`.
+fn has_feature(feature: &str) -> bool {
+    FEATURES.iter().any(|(name, enabled)| *name == feature && *enabled)
+}
+
+/// The table at the dotted `path`, the root for an empty one; `None` when any part is not a table.
+fn table<'a>(root: &'a Map<String, Value>, path: &str) -> Option<&'a Map<String, Value>> {
+    path.split('.').filter(|part| !part.is_empty()).try_fold(root, |table, part| match table.get(part)? {
+        Value::Object(inner) => Some(inner),
+        _ => None,
+    })
+}
+
+#[derive(Default)]
+struct Checker {
+    problems: Vec<ConfigProblem>,
+}
+
+impl Checker {
+    fn push(&mut self, section: &str, field: &str, message: impl Into<String>) {
+        self.problems.push(ConfigProblem {
+            key: if section.is_empty() { field.to_string() } else { format!("{section}.{field}") },
+            message: message.into(),
+        });
+    }
+
+    /// Returns the section at the dotted `name` if it is present; a missing section disables what it configures.
+    ///
+    /// Reports a value in its place that is not a table, unless an enclosing section was already reported.
+    fn section<'a>(&mut self, root: &'a Map<String, Value>, name: &str) -> Option<&'a Map<String, Value>> {
+        let mut section = root;
+        let mut path = String::new();
+        for part in name.split('.').filter(|part| !part.is_empty()) {
+            if !path.is_empty() {
+                path.push('.');
+            }
+            path.push_str(part);
+            match section.get(part)? {
+                Value::Object(inner) => section = inner,
+                Value::Null => return None,
+                _ => {
+                    if !self.problems.iter().any(|problem| problem.key == path) {
+                        self.problems.push(ConfigProblem {
+                            key: path,
+                            message: "expected a table".to_string(),
+                        });
+                    }
+                    return None;
+                }
+            }
+        }
+        Some(section)
+    }
+
+    /// Reports a section or key that needs a cargo feature this build does not include.
+    fn feature(&mut self, key: &str, feature: &str) {
+        if !has_feature(feature) {
+            self.problems.push(ConfigProblem {
+                key: key.to_string(),
+                message: format!("needs the `{feature}` feature, which this build does not include"),
+            });
+        }
+    }
+
+    /// Checks the value of `key` in `section`, named `name`, against its kind.
+    fn key(&mut self, section: &Map<String, Value>, name: &str, key: &ConfigKey) {
+        let field = key.name();
+        let value = section.get(field);
+        if !is_set(value) {
+            if key.required {
+                self.push(name, field, "missing");
+            }
+            return;
+        }
+        let Some(value) = value else { return };
+        match key.kind {
+            Kind::String | Kind::Path => {
+                if value.is_object() || value.is_array() {
+                    self.push(name, field, format!("expected a string, found `{value}`"));
+                }
+            }
+            Kind::Url(schemes) => self.url(name, field, value, schemes),
+            Kind::Duration => {
+                self.duration(name, field, value);
+            }
+            Kind::Timeout => self.timeout(name, field, value),
+            Kind::Integer { min, max } => self.integer(name, field, value, min, max),
+            Kind::Number => {
+                let number = match value {
+                    Value::Number(_) => true,
+                    Value::String(s) => s.parse::<f64>().is_ok(),
+                    _ => false,
+                };
+                if !number {
+                    self.push(name, field, format!("expected a number, found `{value}`"));
+                }
+            }
+            Kind::Bool => {
+                if !matches!(value, Value::Bool(_)) && !matches!(value.as_str(), Some("true" | "false")) {
+                    self.push(name, field, format!("expected true or false, found `{value}`"));
+                }
+            }
+            Kind::Choice(values) => self.choice(name, field, value, values),
+            Kind::List(values) => self.list(name, field, value, key.required, values),
+            Kind::Table => {
+                if !value.is_object() {
+                    self.push(name, field, "expected a table");
+                }
+            }
+            Kind::Tables => {
+                if !value.as_array().is_some_and(|items| items.iter().all(Value::is_object)) {
+                    self.push(name, field, "expected a list of tables");
+                }
+            }
+        }
+    }
+
+    fn any_set(&self, section: &Map<String, Value>, fields: &[&str]) -> bool {
+        fields.iter().any(|field| is_set(section.get(*field)))
+    }
+
+    fn required(&mut self, section: &Map<String, Value>, name: &str, field: &str) {
+        if !is_set(section.get(field)) {
+            self.push(name, field, "missing");
+        }
+    }
+
+    fn required_one_of(&mut self, section: &Map<String, Value>, name: &str, fields: &[&str]) {
+        if !self.any_set(section, fields) {
+            let alternatives = fields[1..].iter().map(|f| format!("`{name}.{f}`")).collect::<Vec<_>>();
+            self.push(name, fields[0], format!("missing, alternatively set {}", alternatives.join(", ")));
+        }
+    }
+
+    /// Reports the unset fields when only some of `fields` are set.
+    fn together(&mut self, section: &Map<String, Value>, name: &str, fields: &[&str]) {
+        if !self.any_set(section, fields) {
+            return;
+        }
+        for field in fields {
+            if !is_set(section.get(*field)) {
+                let others = fields.iter().filter(|f| *f != field).map(|f| format!("`{name}.{f}`")).collect::<Vec<_>>();
+                self.push(name, field, format!("missing, required together with {}", others.join(", ")));
+            }
+        }
+    }
+
+    fn url(&mut self, name: &str, field: &str, value: &Value, schemes: &[&str]) {
+        match value {
+            Value::String(s) => match Url::parse(s) {
+                Ok(url) if schemes.contains(&url.scheme()) => {}
+                Ok(url) => self.push(name, field, format!(
+                    "unsupported URL scheme `{}`, expected {}",
+                    url.scheme(),
+                    schemes.join(" or "),
+                )),
+                Err(err) => self.push(name, field, format!("invalid URL `{s}`: {err}")),
+            },
+            other => self.push(name, field, format!("expected a URL, found `{other}`")),
+        }
+    }
+
+    fn duration(&mut self, name: &str, field: &str, value: &Value) -> Option<Duration> {
+        match value {
+            Value::String(s) => match humantime::parse_duration(s) {
+                Ok(duration) => Some(duration),
+                Err(err) => {
+                    self.push(name, field, format!("invalid duration `{s}`: {err}"));
+                    None
+                }
+            },
+            other => {
+                self.push(name, field, format!("expected a duration like `30s`, found `{other}`"));
+                None
+            }
+        }
+    }
+
+    fn timeout(&mut self, name: &str, field: &str, value: &Value) {
+        if let Some(timeout) = self.duration(name, field, value)
+            && !(MIN_TIMEOUT..=MAX_TIMEOUT).contains(&timeout)
+        {
+            self.push(name, field, format!(
+                "timeout {} out of range ({} to {})",
+                humantime::format_duration(timeout),
+                humantime::format_duration(MIN_TIMEOUT),
+                humantime::format_duration(MAX_TIMEOUT),
+            ));
+        }
+    }
+
+    /// Checks an integer in `min..=max`.
+    fn integer(&mut self, name: &str, field: &str, value: &Value, min: u64, max: u64) {
+        let in_range = |n: u64| (min..=max).contains(&n);
+        let valid = match value {
+            Value::Number(n) => n.as_u64().is_some_and(in_range),
+            Value::String(s) => s.parse::<u64>().is_ok_and(in_range),
+            _ => false,
+        };
+        if !valid {
+            let expected = if max == u64::MAX {
+                format!("an integer of at least {min}")
+            } else {
+                format!("an integer from {min} to {max}")
+            };
+            self.push(name, field, format!("expected {expected}, found `{value}`"));
+        }
+    }
+
+    fn choice(&mut self, name: &str, field: &str, value: &Value, values: &[&str]) {
+        match value.as_str() {
+            Some(s) if values.contains(&s) => {}
+            Some(s) => self.push(name, field, format!("unknown value `{s}`, expected one of {}", values.join(", "))),
+            None => self.push(name, field, format!("expected one of {}, found `{value}`", values.join(", "))),
+        }
+    }
+
+    /// Checks a list of non-blank strings, each one of `values` if given.
+    fn list(&mut self, name: &str, field: &str, value: &Value, required: bool, values: Option<&[&str]>) {
+        let items = match value {
+            Value::Array(items) => items.as_slice(),
+            // A single value, e.g. from an environment variable without a list separator
+            Value::String(_) => std::slice::from_ref(value),
+            other => return self.push(name, field, format!("expected a list, found `{other}`")),
+        };
+        if items.is_empty() && required {
+            return self.push(name, field, "must not be empty");
+        }
+        for item in items {
+            match (item.as_str(), values) {
+                (Some(s), _) if s.trim().is_empty() => self.push(name, field, "expected a list of non-empty strings"),
+                (Some(s), Some(values)) if !values.contains(&s) => {
+                    self.push(name, field, format!("unknown value `{s}`, expected one of {}", values.join(", ")));
+                }
+                (Some(_), _) => {}
+                (None, _) => self.push(name, field, format!("expected a list of strings, found `{item}`")),
+            }
+        }
+    }
+
+    /// Checks the optional `<name>.proxy` table against the keys of `[http.proxy]`.
+    fn proxy(&mut self, section: &Map<String, Value>, name: &str) {
+        let name = format!("{name}.proxy");
+        if let Some(Value::Object(proxy)) = section.get("proxy") {
+            for key in CONFIG_KEYS.iter().filter(|key| key.section() == "http.proxy") {
+                self.key(proxy, &name, key);
+            }
+        }
+    }
+
+    /// Checks that `retry_max_delay` is not shorter than `retry_backoff`.
+    fn retry_delays(&mut self, section: &Map<String, Value>, name: &str) {
+        let parse = |field| section.get(field)?.as_str().and_then(|s| humantime::parse_duration(s).ok());
+        if let (Some(backoff), Some(max_delay)) = (parse("retry_backoff"), parse("retry_max_delay"))
+            && max_delay < backoff
+        {
+            self.push(name, "retry_max_delay", format!(
+                "{} is shorter than retry_backoff ({})",
+                humantime::format_duration(max_delay),
+                humantime::format_duration(backoff),
+            ));
+        }
+    }
+
+    /// Checks a table of header names to string values.
+    fn headers(&mut self, section: &Map<String, Value>, name: &str, field: &str) {
+        if let Some(Value::Object(headers)) = section.get(field) {
+            for (header, value) in headers {
+                if reqwest::header::HeaderName::try_from(header.as_str()).is_err() {
+                    self.push(name, field, format!("invalid header name `{header}`"));
+                } else if value.as_str().is_none_or(|v| reqwest::header::HeaderValue::try_from(v).is_err()) {
+                    self.push(name, field, format!("invalid value of header `{header}`"));
+                }
+            }
+        }
+    }
+
+    /// Checks an optional table of country codes to postal code patterns.
+    fn zip_patterns(&mut self, section: &Map<String, Value>, name: &str, field: &str) {
+        if let Some(Value::Object(patterns)) = section.get(field) {
+            for (country, pattern) in patterns {
+                match pattern.as_str().map(str::parse::<ZipPattern>) {
+                    Some(Ok(_)) => {}
+                    Some(Err(err)) => self.push(name, field, format!("invalid pattern of `{country}`: {err}")),
+                    None => self.push(name, field, format!("expected a pattern for `{country}`, found `{pattern}`")),
+                }
+            }
+        }
+    }
+
+    /// Checks an optional filter expression.
+    fn filter(&mut self, section: &Map<String, Value>, name: &str, field: &str) {
+        if let Some(Value::String(s)) = section.get(field)
+            && let Err(err) = s.parse::<Filter>()
+        {
+            self.push(name, field, err.to_string());
+        }
+    }
+
+    /// Checks a message template, see [`crate::notify::template`].
+    fn template(&mut self, section: &Map<String, Value>, name: &str, field: &str) {
+        if let Some(Value::String(template)) = section.get(field)
+            && let Err(err) = crate::notify::template::check(template)
+        {
+            self.push(name, field, err);
+        }
+    }
+
+    /// Reports values listed more than once in an optional list.
+    fn distinct(&mut self, section: &Map<String, Value>, name: &str, field: &str) {
+        let Some(Value::Array(items)) = section.get(field) else { return };
+        for (position, item) in items.iter().enumerate() {
+            if let Some(s) = item.as_str()
+                && items[..position].iter().any(|earlier| earlier.as_str() == Some(s))
+            {
+                self.push(name, field, format!("`{s}` is listed more than once"));
+            }
+        }
+    }
+}
+
+/// Whether a flag is present and turned on.
+fn is_on(value: Option<&Value>) -> bool {
+    match value {
+        Some(Value::Bool(on)) => *on,
+        Some(Value::String(s)) => s == "true",
+        _ => false,
+    }
+}
+
+/// Whether a value is present and not blank.
+fn is_set(value: Option<&Value>) -> bool {
+    match value {
+        None | Some(Value::Null) => false,
+        Some(Value::String(s)) => !s.trim().is_empty(),
+        Some(_) => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use config::{File, FileFormat};
+    use super::*;
+
+    fn check(toml: &str) -> Vec<ConfigProblem> {
+        let raw = Config::builder()
+            .add_source(File::from_str(toml, FileFormat::Toml))
+            .build()
+            .unwrap();
+        check_schema(&raw).err().map(|report| report.problems).unwrap_or_default()
+    }
+
+    fn messages(problems: &[ConfigProblem]) -> Vec<(&str, &str)> {
+        problems.iter().map(|p| (p.key.as_str(), p.message.as_str())).collect()
+    }
+
+    #[test]
+    fn reports_every_missing_required_field() {
+        let problems = check("[iproyal]\ntimeout = \"30s\"\n[infatica]\ncountries = [\"US\"]\n");
+
+        assert_eq!(messages(&problems), [
+            ("iproyal.endpoint", "missing"),
+            ("infatica.endpoint", "missing"),
+            ("infatica.email", "missing"),
+            ("iproyal.client_id", "missing"),
+            ("iproyal.client_secret", "missing, alternatively set `iproyal.client_secret_file`"),
+            ("iproyal.token_endpoint", "missing"),
+            ("infatica.password", "missing, alternatively set `infatica.password_file`"),
+        ]);
+        for problem in &problems {
+            let sources = problem.sources().unwrap();
+            assert!(sources.contains(&env_var(&problem.key)), "{sources}");
+            assert!(sources.contains(&cli_flag(&problem.key).unwrap()), "{sources}");
+        }
+    }
+
+    #[test]
+    fn sources_name_the_file_key_env_var_and_flag() {
+        let problem = ConfigProblem { key: "iproyal.endpoint".to_string(), message: "missing".to_string() };
+        assert_eq!(
+            problem.sources().unwrap(),
+            "set `iproyal.endpoint` in a config file, MYAPP_IPROYAL__ENDPOINT, or --iproyal-endpoint",
+        );
+
+        let problem = ConfigProblem { key: "nats.subject_prefix".to_string(), message: "missing".to_string() };
+        assert_eq!(problem.sources().unwrap(), "set `nats.subject_prefix` in a config file, MYAPP_NATS__SUBJECT_PREFIX");
+
+        let problem = ConfigProblem { key: "kafka".to_string(), message: "expected a table".to_string() };
+        assert_eq!(problem.sources(), None);
+    }
+
+    #[test]
+    fn report_lists_every_problem() {
+        let report = ConfigReport { problems: check("[infatica]\nemail = \"me@example.com\"\npassword = \"secret\"\n[cache]\nmax_age = \"soon\"\n") };
+
+        assert_eq!(report.to_string(), format!(
+            "2 problem(s):\n  - {}\n  - {}",
+            "infatica.endpoint: missing (set `infatica.endpoint` in a config file, MYAPP_INFATICA__ENDPOINT, or --infatica-endpoint)",
+            "cache.max_age: invalid duration `soon`: expected number at 0 \
+             (set `cache.max_age` in a config file, MYAPP_CACHE__MAX_AGE, or --max-age)",
+        ));
+    }
+
+    #[test]
+    fn a_token_replaces_the_client_credentials() {
+        let problems = check("[iproyal]\nendpoint = \"https://iproyal.example/api/\"\ntoken_file = \"/run/secrets/token\"\n");
+        assert!(problems.is_empty(), "{problems:?}");
+    }
+
+    #[test]
+    fn one_of_the_alternatives_is_enough() {
+        let problems = check(
+            "[infatica]\nendpoint = \"https://infatica.example/\"\nemail = \"me@example.com\"\npassword_file = \"/run/secrets/pw\"\n",
+        );
+        assert!(problems.is_empty(), "{problems:?}");
+    }
+
+    #[test]
+    fn keys_set_together_need_each_other() {
+        let problems = check("[http]\nclient_cert = \"client.pem\"\n");
+        assert_eq!(messages(&problems), [("http.client_key", "missing, required together with `http.client_cert`")]);
+
+        let problems = check("[http]\nclient_cert = \"client.pem\"\nclient_key = \"client.key\"\n");
+        assert!(problems.is_empty(), "{problems:?}");
+    }
+
+    #[test]
+    fn values_are_checked_against_the_kind_of_their_key() {
+        let problems = check(
+            "[nats]\nurl = \"http://nats.example\"\n\
+             [http]\nconnect_timeout = \"2h\"\nmax_response_bytes = 0\ninsecure_skip_verify = \"yes\"\n\
+             [output]\ncompression = \"brotli\"\nsinks = [\"kafka\", \"redis\"]\n\
+             [dedup]\nprefer = [\"infatica\", \"infatica\"]\n",
+        );
+        let messages = messages(&problems);
+
+        for expected in [
+            ("http.connect_timeout", "timeout 2h out of range (1s to 1h)"),
+            ("http.max_response_bytes", "expected an integer of at least 1, found `0`"),
+            ("http.insecure_skip_verify", "expected true or false, found `\"yes\"`"),
+            ("output.compression", "unknown value `brotli`, expected one of none, gzip, zstd"),
+            ("output.sinks", "unknown value `redis`, expected one of kafka, nats"),
+            ("nats.url", "unsupported URL scheme `http`, expected nats or tls or ws or wss"),
+            ("dedup.prefer", "`infatica` is listed more than once"),
+        ] {
+            assert!(messages.contains(&expected), "{expected:?} not in {messages:?}");
+        }
+    }
+
+    #[test]
+    fn provider_proxies_follow_the_keys_of_http_proxy() {
+        let problems = check(
+            "[iproyal]\nendpoint = \"https://iproyal.example/api/\"\ntoken = \"t\"\nproxy = { username = \"me\" }\n",
+        );
+        assert_eq!(messages(&problems), [("iproyal.proxy.url", "missing")]);
+    }
+
+    #[test]
+    fn a_section_that_is_not_a_table_is_reported_once() {
+        let problems = check("notify = \"on\"\n");
+        assert_eq!(messages(&problems), [("notify", "expected a table")]);
+    }
+}
@@ -0,0 +1,14 @@
+use crate::http::EndpointStats;
+use crate::iproyal::errors::IPRoyalGetCountryError;
+use crate::iproyal::models::IPRoyalAvailabilityRecord;
+use crate::iproyal::query_iproyal::query_iproyal;
+use crate::iproyal::session::IPRoyalSession;
+
+pub(crate) const ENDPOINT: &str = "access/availability";
+
+/// Fetches IP availability keyed by location code.
+pub async fn availability(session: &IPRoyalSession<'_>) -> Result<(Vec<IPRoyalAvailabilityRecord>, EndpointStats), IPRoyalGetCountryError> {
+    let mut stats = EndpointStats::start(ENDPOINT);
+    let records = query_iproyal::<Vec<IPRoyalAvailabilityRecord>>(session, ENDPOINT, &[], &mut stats).await?;
+    Ok((records, stats.finish()))
+}
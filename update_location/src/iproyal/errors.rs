@@ -0,0 +1,123 @@
+use thiserror::Error;
+use url::ParseError;
+use crate::http::{BodyError, HttpClientError, SendError};
+use crate::iproyal::{availability, get_raw_data, isps};
+use crate::models::{ProviderError, ProviderErrorKind};
+use crate::unified::Provider;
+
+/// HTTP-level error shared by all iproyal endpoint calls.
+#[derive(Debug, Error)]
+pub enum IPRoyalGetCountryError {
+    #[error("failed to join URL: {0}")]
+    JoinURLError(#[source] ParseError),
+    /// The HTTP client could not be set up (proxy or TLS settings).
+    #[error(transparent)]
+    ClientError(#[from] HttpClientError),
+    /// Network failure, timeout, or error while reading the body.
+    #[error("request error: {0}")]
+    URLError(#[source] reqwest::Error),
+    /// The API rejected the token or credentials (HTTP 401/403).
+    #[error("authentication failed (HTTP {status}): check iproyal.token or iproyal.client_id / iproyal.client_secret")]
+    AuthError { status: reqwest::StatusCode },
+    /// The body could not be read or exceeded `http.max_response_bytes`.
+    #[error(transparent)]
+    BodyError(#[from] BodyError),
+    /// Neither a static token nor a complete client-credentials set is configured.
+    #[error("no iproyal credentials: set iproyal.token, or iproyal.client_id, iproyal.client_secret and iproyal.token_endpoint")]
+    MissingCredentials,
+    /// Any other non-success HTTP status.
+    #[error("unexpected HTTP status {status}: {body}")]
+    BadStatus { status: reqwest::StatusCode, body: String },
+    /// The body was received in full but does not match the expected schema.
+    #[error("failed to decode response: {0}")]
+    DecodeError(#[source] serde_json::Error),
+    /// The body ended before the advertised `Content-Length` was received.
+    #[error("response truncated: expected {expected} bytes, received {received}")]
+    Truncated { expected: u64, received: usize },
+    /// A page reported another page number than the one requested.
+    #[error("unexpected page: requested page {requested}, received page {received}")]
+    UnexpectedPage { requested: u32, received: u32 },
+    /// Pagination finished but fewer countries were collected than the API reported.
+    #[error("incomplete country tree: expected {expected} countries, received {received}")]
+    IncompleteTree { expected: usize, received: usize },
+    /// A layer of the HTTP stack failed with an error of another type.
+    #[error("request middleware failed: {0}")]
+    Middleware(#[source] Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl From<SendError> for IPRoyalGetCountryError {
+    /// Unwraps errors of the iproyal layers, such as a failed token exchange.
+    fn from(err: SendError) -> Self {
+        match err {
+            SendError::Request(err) => Self::URLError(err),
+            SendError::Middleware(err) => err.downcast::<Self>().map_or_else(Self::Middleware, |err| *err),
+        }
+    }
+}
+
+/// Aggregated error type for top-level iproyal queries.
+///
+/// Each variant corresponds to a specific iproyal endpoint.
+/// When using [`get_all`](super::get_all), multiple variants can appear simultaneously in the returned `Vec`.
+#[derive(Debug, Error)]
+pub enum IPRoyalQueryError {
+    /// The HTTP client could not be built (e.g. invalid proxy settings); nothing was requested.
+    #[error("failed to set up HTTP client: {0}")]
+    Client(#[source] IPRoyalGetCountryError),
+
+    /// Failure during `access/countries` query.
+    #[error("countries request failed: {0}")]
+    Countries(#[source] IPRoyalGetCountryError),
+
+    /// Failure during `access/isps` query.
+    #[error("isps request failed: {0}")]
+    Isps(#[source] IPRoyalGetCountryError),
+
+    /// Failure during `access/availability` query.
+    #[error("availability request failed: {0}")]
+    Availability(#[source] IPRoyalGetCountryError),
+}
+
+impl IPRoyalGetCountryError {
+    /// Classifies the failure, also telling whether repeating the request may help.
+    fn classify(&self) -> (ProviderErrorKind, bool) {
+        match self {
+            Self::URLError(err) | Self::BodyError(BodyError::Request(err)) => (ProviderErrorKind::from_reqwest(err), true),
+            Self::AuthError { .. } | Self::MissingCredentials => (ProviderErrorKind::Auth, false),
+            Self::BadStatus { status, .. } => {
+                (ProviderErrorKind::from_status(*status), status.is_server_error() || *status == reqwest::StatusCode::TOO_MANY_REQUESTS)
+            }
+            Self::Truncated { .. } => (ProviderErrorKind::Network, true),
+            Self::IncompleteTree { .. } | Self::UnexpectedPage { .. } => (ProviderErrorKind::Server, true),
+            Self::DecodeError(_) => (ProviderErrorKind::Decode, false),
+            Self::BodyError(BodyError::TooLarge { .. }) => (ProviderErrorKind::Server, false),
+            Self::JoinURLError(_) | Self::ClientError(_) | Self::Middleware(_) => (ProviderErrorKind::Network, false),
+        }
+    }
+}
+
+impl From<IPRoyalQueryError> for ProviderError {
+    fn from(err: IPRoyalQueryError) -> Self {
+        let (endpoint, inner) = match &err {
+            IPRoyalQueryError::Client(e) => (None, e),
+            IPRoyalQueryError::Countries(e) => (Some(get_raw_data::ENDPOINT), e),
+            IPRoyalQueryError::Isps(e) => (Some(isps::ENDPOINT), e),
+            IPRoyalQueryError::Availability(e) => (Some(availability::ENDPOINT), e),
+        };
+        let (kind, retryable) = inner.classify();
+        ProviderError::new(kind, Provider::IPRoyal, endpoint, err).retryable(retryable)
+    }
+}
+
+/// Invalid parameters passed to a [`TargetingBuilder`](super::TargetingBuilder).
+#[derive(Debug, Error)]
+pub enum TargetingError {
+    #[error("invalid session id \"{0}\": expected {len} ASCII letters and digits", len = super::targeting::SESSION_ID_LEN)]
+    InvalidSession(String),
+    #[error("a sticky session needs a lifetime")]
+    MissingLifetime,
+    #[error("a session lifetime needs a session id")]
+    LifetimeWithoutSession,
+    #[error("invalid session lifetime {}: expected whole seconds from 1s to 7d", humantime::format_duration(*.0))]
+    InvalidLifetime(std::time::Duration),
+}
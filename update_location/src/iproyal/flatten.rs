@@ -0,0 +1,26 @@
+use crate::iproyal::ip_availability::Availability;
+use crate::iproyal::models::{IPRoyalLocation, Root};
+use crate::iproyal::tree::Leaf;
+
+/// Flattens the iproyal location tree into one record per leaf.
+///
+/// A leaf is the deepest node on a path: usually an ISP, but a city, state or
+/// country without children is emitted as a leaf of its own so no location is
+/// lost. Countries may carry `states` (with nested cities and state-level ISPs)
+/// and/or `cities` directly; both branches are traversed, see [`Root::iter_leaves`].
+pub fn flatten(root: &Root) -> Vec<IPRoyalLocation> {
+    root.iter_leaves().map(|leaf| location(leaf, &root.prefix)).collect()
+}
+
+fn location(leaf: Leaf<'_>, country_prefix: &str) -> IPRoyalLocation {
+    let ip_availability = leaf.ip_availability();
+    IPRoyalLocation {
+        code: leaf.code(country_prefix),
+        country: leaf.country.code.clone(),
+        state: leaf.state.map(|s| s.name.clone()),
+        city: leaf.city.map(|c| c.name.clone()),
+        isp: leaf.isp.map(|i| i.name.clone()),
+        ip_availability: ip_availability.cloned(),
+        availability: ip_availability.map_or(Availability::Unknown, |a| Availability::parse(a)),
+    }
+}
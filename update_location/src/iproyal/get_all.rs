@@ -0,0 +1,68 @@
+use crate::iproyal::availability::availability;
+use crate::iproyal::errors::IPRoyalQueryError;
+use crate::iproyal::get_raw_data::get_raw_data;
+use crate::iproyal::isps::isps;
+use crate::iproyal::models::IPRoyalQueryResults;
+use crate::iproyal::session::IPRoyalSession;
+use crate::models::IPRoyalConfig;
+
+/// Executes **all iproyal queries concurrently**.
+///
+/// ### Behavior
+/// - Runs [`get_raw_data`], [`isps`], and [`availability`] using [`tokio::join!`].
+/// - Aggregates all encountered errors into a single `Vec<IPRoyalQueryError>`.
+/// - If any query fails, returns `Err(Vec<...>)` containing **all** errors (no early return).
+/// - If all succeed, returns [`IPRoyalQueryResults`] containing the fetched datasets.
+/// - All queries share one [`IPRoyalSession`], so credentials are exchanged at most once.
+pub async fn get_all(cfg: &IPRoyalConfig) -> Result<IPRoyalQueryResults, Vec<IPRoyalQueryError>> {
+    let session = IPRoyalSession::new(cfg).map_err(|e| vec![IPRoyalQueryError::Client(e)])?;
+
+    // Run all endpoint calls concurrently.
+    let (countries_res, isps_res, availability_res) = tokio::join!(
+        get_raw_data(&session),
+        isps(&session),
+        availability(&session),
+    );
+
+    let mut errors = Vec::new();
+    let mut endpoints = Vec::new();
+
+    let countries = match countries_res {
+        Ok((v, stats)) => {
+            endpoints.push(stats);
+            Some(v)
+        }
+        Err(e) => {
+            errors.push(IPRoyalQueryError::Countries(e));
+            None
+        }
+    };
+
+    let isps = match isps_res {
+        Ok((v, stats)) => {
+            endpoints.push(stats);
+            v
+        }
+        Err(e) => {
+            errors.push(IPRoyalQueryError::Isps(e));
+            Vec::new()
+        }
+    };
+
+    let availability = match availability_res {
+        Ok((v, stats)) => {
+            endpoints.push(stats);
+            v
+        }
+        Err(e) => {
+            errors.push(IPRoyalQueryError::Availability(e));
+            Vec::new()
+        }
+    };
+
+    // If any query failed, propagate all failures together.
+    match countries {
+        Some(countries) if errors.is_empty() => Ok(IPRoyalQueryResults::new(countries, isps, availability, endpoints)),
+        _ => Err(errors),
+    }
+}
@@ -1,43 +1,134 @@
-use std::time::Duration;
-use reqwest::{{Client}};
-use thiserror::Error;
-use url::ParseError;
+use crate::http::EndpointStats;
+use crate::iproyal::errors::IPRoyalGetCountryError;
 use crate::iproyal::models::Root;
+use crate::iproyal::query_iproyal::query_iproyal;
+use crate::iproyal::session::IPRoyalSession;
 use crate::models::IPRoyalConfig;
 
-#[derive(Debug, Error)]
-pub enum IPRoyalGetCountryError {
-    #[error("failed to join URL: {0}")]
-    JoinURLError(ParseError),
-    #[error("request error: {0}")]
-    URLError(reqwest::Error),
+pub(crate) const ENDPOINT: &str = "access/countries";
+const PAGE_PARAM: &str = "page";
+const PER_PAGE_PARAM: &str = "per_page";
+
+/// Fetches the full country → state/city → ISP tree.
+///
+/// ### Pagination
+/// - When `iproyal.page_size` is configured, pages are requested explicitly via
+///   `page` / `per_page` query parameters.
+/// - Whenever the response carries a `meta` block, pages are followed until
+///   `current_page == last_page`, concatenating `countries` from every page.
+/// - Responses without `meta` are treated as the complete, unpaginated tree.
+///
+/// The returned statistics cover all pages.
+///
+/// ### Errors
+/// - [`IPRoyalGetCountryError::Truncated`] if any page body is shorter than advertised.
+/// - [`IPRoyalGetCountryError::UnexpectedPage`] if a page reports another `current_page`
+///   than the one requested.
+/// - [`IPRoyalGetCountryError::IncompleteTree`] if the collected country count does not
+///   match the `total` reported by the API.
+pub async fn get_raw_data(session: &IPRoyalSession<'_>) -> Result<(Root, EndpointStats), IPRoyalGetCountryError> {
+    let cfg = session.cfg();
+    let mut stats = EndpointStats::start(ENDPOINT);
+
+    let mut page: u32 = 1;
+    let mut root = query_iproyal::<Root>(session, ENDPOINT, &page_query(cfg, page), &mut stats).await?;
+
+    while let Some(meta) = root.meta.as_ref().filter(|m| m.current_page < m.last_page) {
+        page = meta.current_page + 1;
+
+        let next = query_iproyal::<Root>(session, ENDPOINT, &page_query(cfg, page), &mut stats).await?;
+        // A server repeating or skipping pages would otherwise be followed forever
+        if let Some(received) = next.meta.as_ref().map(|m| m.current_page).filter(|&p| p != page) {
+            return Err(IPRoyalGetCountryError::UnexpectedPage { requested: page, received });
+        }
+        root.countries.extend(next.countries);
+        root.meta = next.meta;
+    }
+
+    if let Some(expected) = root.meta.as_ref().and_then(|m| m.total)
+        && expected != root.countries.len()
+    {
+        return Err(IPRoyalGetCountryError::IncompleteTree {
+            expected,
+            received: root.countries.len(),
+        });
+    }
+
+    Ok((root, stats.finish()))
 }
 
-const ENDPOINT: &str = "access/countries";
-const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
-
-pub async fn get_raw_data(cfg: &IPRoyalConfig) -> Result<Root, IPRoyalGetCountryError> {
-    let http_client = Client::new();
-
-    let mut sanitized_url = cfg.get_endpoint().to_owned();
-    if !sanitized_url.path().ends_with('/'){
-        sanitized_url.path_segments_mut().unwrap().push("");
-    }
-    sanitized_url = sanitized_url.join(ENDPOINT).map_err(IPRoyalGetCountryError::JoinURLError)?;
-
-    let token = cfg.get_token().to_owned();
-    let timeout = cfg.get_timeout().unwrap_or_else(|| &DEFAULT_TIMEOUT).to_owned();
-
-    Ok(
-        http_client
-            .get(sanitized_url)
-            .bearer_auth(token)
-            .timeout(timeout)
-            .send()
-            .await
-            .map_err(IPRoyalGetCountryError::URLError)?
-            .json::<Root>()
-            .await
-            .map_err(IPRoyalGetCountryError::URLError)?
-    )
-}
\ No newline at end of file
+/// Builds the query string for a given page.
+///
+/// The first page is requested without parameters unless a page size is configured,
+/// so unpaginated deployments see exactly the same request as before.
+fn page_query(cfg: &IPRoyalConfig, page: u32) -> Vec<(&'static str, String)> {
+    let mut query = Vec::new();
+    if page > 1 || cfg.get_page_size().is_some() {
+        query.push((PAGE_PARAM, page.to_string()));
+    }
+    if let Some(size) = cfg.get_page_size() {
+        query.push((PER_PAGE_PARAM, size.to_string()));
+    }
+    query
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use reqwest::{Client, StatusCode};
+    use crate::clock::ManualClock;
+    use crate::http::testing::Scripted;
+    use super::*;
+
+    fn page(codes: &[&str], current_page: u32, last_page: u32, total: usize) -> (StatusCode, String) {
+        let countries: Vec<_> = codes
+            .iter()
+            .map(|code| serde_json::json!({ "code": code, "name": code, "ip_availability": null }))
+            .collect();
+        let meta = serde_json::json!({ "current_page": current_page, "last_page": last_page, "total": total });
+        (StatusCode::OK, serde_json::json!({ "prefix": "_country-", "countries": countries, "meta": meta }).to_string())
+    }
+
+    async fn fetch(transport: Arc<Scripted>) -> Result<(Root, EndpointStats), IPRoyalGetCountryError> {
+        let cfg = IPRoyalConfig::new("https://iproyal.example/v1".parse().unwrap(), "static");
+        let session = IPRoyalSession::with(&cfg, Client::new(), transport, Arc::new(ManualClock::new()));
+        get_raw_data(&session).await
+    }
+
+    #[tokio::test]
+    async fn pages_are_followed_until_the_last() {
+        let transport = Arc::new(Scripted::new([page(&["us", "de"], 1, 2, 3), page(&["fr"], 2, 2, 3)]));
+        let (root, stats) = fetch(transport.clone()).await.unwrap();
+
+        let codes: Vec<_> = root.countries.iter().map(|c| c.code.as_str()).collect();
+        assert_eq!(codes, ["us", "de", "fr"]);
+        let queries: Vec<_> = transport.sent().into_iter().map(|s| s.query).collect();
+        assert_eq!(queries, [None, Some("page=2".to_string())]);
+        assert_eq!(stats.requests, 2);
+    }
+
+    #[tokio::test]
+    async fn a_repeated_page_fails_instead_of_looping() {
+        let transport = Arc::new(Scripted::new([page(&["us"], 1, 3, 3), page(&["us"], 1, 3, 3)]));
+        let err = fetch(transport.clone()).await.unwrap_err();
+
+        assert!(matches!(err, IPRoyalGetCountryError::UnexpectedPage { requested: 2, received: 1 }), "{err}");
+        assert_eq!(transport.sent().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn missing_countries_fail_the_tree() {
+        let transport = Arc::new(Scripted::new([page(&["us"], 1, 2, 3), page(&["de"], 2, 2, 3)]));
+        let err = fetch(transport).await.unwrap_err();
+        assert!(matches!(err, IPRoyalGetCountryError::IncompleteTree { expected: 3, received: 2 }), "{err}");
+    }
+
+    #[tokio::test]
+    async fn a_short_page_is_reported_as_truncated() {
+        let (status, body) = page(&["us"], 1, 1, 1);
+        let advertised = body.len() as u64 + 10;
+        let transport = Arc::new(Scripted::new([(status, body)]).advertise(0, advertised));
+        let err = fetch(transport).await.unwrap_err();
+        assert!(matches!(err, IPRoyalGetCountryError::Truncated { expected, .. } if expected == advertised), "{err}");
+    }
+}
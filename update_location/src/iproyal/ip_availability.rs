@@ -0,0 +1,119 @@
+//! Parsing of iproyal's free-form `ip_availability` strings.
+//!
+//! The API reports availability as display strings such as `"10K+"`, `"1K-5K"`,
+//! `"<100"`, `"1,500"` or `"no data"`. [`Availability::parse`] turns them into
+//! numbers so thresholds and diffs can compare them; anything it does not
+//! recognize becomes [`Availability::Unknown`] instead of an error.
+
+use serde::{Deserialize, Serialize};
+
+/// Numeric interpretation of an `ip_availability` string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Availability {
+    /// A single (possibly lower-bound) figure, e.g. `"10K+"` → `10_000`.
+    Approx(u64),
+    /// An explicit range, e.g. `"1K-5K"` → `1_000..=5_000`, `"<100"` → `0..=100`.
+    Range { min: u64, max: u64 },
+    /// Missing, `"no data"`, or not parseable.
+    Unknown,
+}
+
+impl Availability {
+    /// Parses an availability string, never failing.
+    pub fn parse(raw: &str) -> Self {
+        let s = raw.trim().to_ascii_lowercase().replace([',', ' ', '~'], "");
+
+        if let Some(rest) = s.strip_prefix('<') {
+            return parse_quantity(rest.trim_start_matches('=')).map_or(Self::Unknown, |max| Self::Range { min: 0, max });
+        }
+
+        let s = s.trim_start_matches(['>', '=']).trim_end_matches('+');
+
+        if let Some((lo, hi)) = s.split_once('-') {
+            // "1-5k" carries the unit only on the upper bound, while in "500-2k" it
+            // would put the lower bound above the upper one
+            let unit = hi.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.');
+            let max = parse_quantity(hi);
+            let with_unit = if lo.chars().all(|c| c.is_ascii_digit() || c == '.') {
+                parse_quantity(&format!("{lo}{unit}")).filter(|min| max.is_some_and(|max| *min <= max))
+            } else {
+                None
+            };
+            let min = with_unit.or_else(|| parse_quantity(lo));
+            return match (min, max) {
+                (Some(min), Some(max)) if min <= max => Self::Range { min, max },
+                _ => Self::Unknown,
+            };
+        }
+
+        parse_quantity(s).map_or(Self::Unknown, Self::Approx)
+    }
+
+    /// Smallest number of IPs this availability guarantees, if known.
+    pub fn lower_bound(&self) -> Option<u64> {
+        match self {
+            Self::Approx(n) => Some(*n),
+            Self::Range { min, .. } => Some(*min),
+            Self::Unknown => None,
+        }
+    }
+}
+
+/// Parses `"1500"`, `"1.5k"`, `"2m"` style quantities.
+fn parse_quantity(s: &str) -> Option<u64> {
+    let (number, multiplier) = match s.chars().last()? {
+        'k' => (&s[..s.len() - 1], 1_000.0),
+        'm' => (&s[..s.len() - 1], 1_000_000.0),
+        'b' => (&s[..s.len() - 1], 1_000_000_000.0),
+        _ => (s, 1.0),
+    };
+
+    let value: f64 = number.parse().ok()?;
+    (value.is_finite() && value >= 0.0).then(|| (value * multiplier).round() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_figures_and_ranges() {
+        let cases = [
+            ("1500", Availability::Approx(1_500)),
+            ("  7 ", Availability::Approx(7)),
+            ("1,500", Availability::Approx(1_500)),
+            ("1.5K", Availability::Approx(1_500)),
+            ("2m", Availability::Approx(2_000_000)),
+            ("10K+", Availability::Approx(10_000)),
+            ("5k+", Availability::Approx(5_000)),
+            (">1k", Availability::Approx(1_000)),
+            ("~2k", Availability::Approx(2_000)),
+            ("<100", Availability::Range { min: 0, max: 100 }),
+            ("<=1k", Availability::Range { min: 0, max: 1_000 }),
+            ("1K-5K", Availability::Range { min: 1_000, max: 5_000 }),
+            // The unit of the upper bound applies to a bare lower bound
+            ("1-5k", Availability::Range { min: 1_000, max: 5_000 }),
+            ("1.5-2k", Availability::Range { min: 1_500, max: 2_000 }),
+            ("500-2k", Availability::Range { min: 500, max: 2_000 }),
+            ("100 - 1K", Availability::Range { min: 100, max: 1_000 }),
+        ];
+        for (raw, expected) in cases {
+            assert_eq!(Availability::parse(raw), expected, "{raw:?}");
+        }
+    }
+
+    #[test]
+    fn unrecognized_values_are_unknown() {
+        let cases = ["", "   ", "no data", "n/a", "-5", "-5k", "5k-1k", "k", "1x", "nan", "inf", "<"];
+        for raw in cases {
+            assert_eq!(Availability::parse(raw), Availability::Unknown, "{raw:?}");
+        }
+    }
+
+    #[test]
+    fn lower_bounds() {
+        assert_eq!(Availability::Approx(10).lower_bound(), Some(10));
+        assert_eq!(Availability::Range { min: 1, max: 5 }.lower_bound(), Some(1));
+        assert_eq!(Availability::Unknown.lower_bound(), None);
+    }
+}
@@ -0,0 +1,14 @@
+use crate::http::EndpointStats;
+use crate::iproyal::errors::IPRoyalGetCountryError;
+use crate::iproyal::models::IPRoyalIspRecord;
+use crate::iproyal::query_iproyal::query_iproyal;
+use crate::iproyal::session::IPRoyalSession;
+
+pub(crate) const ENDPOINT: &str = "access/isps";
+
+/// Fetches the flat list of ISPs available for targeting.
+pub async fn isps(session: &IPRoyalSession<'_>) -> Result<(Vec<IPRoyalIspRecord>, EndpointStats), IPRoyalGetCountryError> {
+    let mut stats = EndpointStats::start(ENDPOINT);
+    let records = query_iproyal::<Vec<IPRoyalIspRecord>>(session, ENDPOINT, &[], &mut stats).await?;
+    Ok((records, stats.finish()))
+}
@@ -1,4 +1,35 @@
+//! # iproyal Public Module
+//!
+//! High-level API for fetching iproyal's residential access data in parallel.
+//!
+//! - [`get_raw_data`] — the country → state/city → ISP tree (`access/countries`)
+//! - [`isps`] — the flat ISP listing (`access/isps`)
+//! - [`availability`] — IP availability per location code (`access/availability`)
+//!
+//! [`get_all`] runs all of them concurrently and returns an [`IPRoyalQueryResults`],
+//! or every [`IPRoyalQueryError`] encountered.
+//!
+//! The location tree is walked with [`Root::iter_cities`](models::Root::iter_cities),
+//! [`iter_isps`](models::Root::iter_isps) and [`iter_leaves`](models::Root::iter_leaves),
+//! which hide whether a country lists its cities under states or directly. A
+//! [`Targeting`] turns a flattened leaf into the parameters selecting it on a proxy connection.
+
 pub mod get_raw_data;
 pub mod models;
+pub mod errors;
+mod flatten;
+mod tree;
+pub mod targeting;
+pub mod ip_availability;
+mod query_iproyal;
+pub mod session;
+mod isps;
+mod availability;
+mod get_all;
+mod preflight;
 
-pub use get_raw_data::get_raw_data;
\ No newline at end of file
+pub use get_all::get_all;
+pub use preflight::preflight;
+pub use flatten::flatten;
+pub use tree::{compose, CityItem, IspItem, Leaf};
+pub use targeting::{Targeting, TargetingBuilder};
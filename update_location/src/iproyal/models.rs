@@ -1,51 +1,208 @@
-use serde::Deserialize;
 
-#[derive(Debug, Deserialize)]
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use crate::http::EndpointStats;
+use crate::iproyal::ip_availability::Availability;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Root {
     pub prefix: String,
     pub countries: Vec<Country>,
+    /// Pagination info; absent when the API returns the whole tree at once.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<PageMeta>,
+}
+
+/// Pagination block of a paged `access/countries` response.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PageMeta {
+    pub current_page: u32,
+    pub last_page: u32,
+    /// Total number of countries across all pages, if reported.
+    #[serde(default)]
+    pub total: Option<usize>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Country {
     pub code: String,
     pub name: String,
     pub ip_availability: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub cities: Option<Container<City>>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub states: Option<Container<State>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct State {
     pub code: String,
     pub name: String,
     pub ip_availability: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub cities: Option<Container<City>>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub isps: Option<Container<Isp>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct City {
     pub code: String,
     pub name: String,
     pub ip_availability: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub isps: Option<Container<Isp>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Isp {
     pub code: String,
     pub name: String,
     pub ip_availability: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Container<T> {
     pub prefix: String,
     pub options: Vec<T>,
-}
\ No newline at end of file
+}
+
+/// A single leaf of the iproyal location tree, with its ancestry resolved.
+///
+/// Produced by [`flatten`](super::flatten). The tree has two shapes per country
+/// (country → states → cities → ISPs, or country → cities → ISPs), so `state`,
+/// `city` and `isp` are `None` whenever that level is absent for the leaf.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IPRoyalLocation {
+    /// Full location code of the leaf, its prefixes included (e.g.
+    /// `_country-us_state-texas_city-austin`), as iproyal's targeting syntax expects.
+    pub code: String,
+    /// Country code as reported by iproyal (e.g. "us", "de").
+    pub country: String,
+    /// State name, for countries that are split into states.
+    pub state: Option<String>,
+    /// City name, if the leaf is at city level or below.
+    pub city: Option<String>,
+    /// ISP name, if the leaf is an ISP.
+    pub isp: Option<String>,
+    /// Availability string of the leaf node itself (e.g. "10K+"), as reported.
+    pub ip_availability: Option<String>,
+    /// Numeric interpretation of `ip_availability`.
+    pub availability: Availability,
+}
+
+/// Entry of the `access/isps` listing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IPRoyalIspRecord {
+    pub code: String,
+    pub name: String,
+    /// Country code the ISP operates in.
+    pub country: String,
+    pub ip_availability: Option<String>,
+}
+
+/// Entry of the `access/availability` listing.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IPRoyalAvailabilityRecord {
+    /// Location code (country, state or city) the availability applies to.
+    pub location: String,
+    pub ip_availability: Option<String>,
+}
+
+/// Grouped results of [`get_all`](super::get_all).
+///
+/// Equality compares the datasets only, not the query statistics.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct IPRoyalQueryResults {
+    countries: Root,
+    isps: Vec<IPRoyalIspRecord>,
+    availability: Vec<IPRoyalAvailabilityRecord>,
+    /// Statistics of the queries; not cached, so empty for cached results.
+    #[serde(skip)]
+    endpoints: Vec<EndpointStats>,
+    /// Unix seconds of the fetch, kept in the cache; unknown for older cache entries.
+    #[serde(default)]
+    fetched_at: Option<u64>,
+}
+
+impl IPRoyalQueryResults {
+    /// Endpoint the unified records are parsed from.
+    pub const SOURCE_ENDPOINT: &str = crate::iproyal::get_raw_data::ENDPOINT;
+
+    pub fn new(
+        countries: Root,
+        isps: Vec<IPRoyalIspRecord>,
+        availability: Vec<IPRoyalAvailabilityRecord>,
+        endpoints: Vec<EndpointStats>,
+    ) -> Self {
+        Self {
+            countries,
+            isps,
+            availability,
+            endpoints,
+            fetched_at: SystemTime::now().duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs()),
+        }
+    }
+
+    pub fn countries(&self) -> &Root {
+        &self.countries
+    }
+
+    /// Takes the location tree, dropping the other datasets.
+    pub fn into_countries(self) -> Root {
+        self.countries
+    }
+
+    pub fn isps(&self) -> &Vec<IPRoyalIspRecord> {
+        &self.isps
+    }
+
+    pub fn availability(&self) -> &Vec<IPRoyalAvailabilityRecord> {
+        &self.availability
+    }
+
+    pub fn endpoints(&self) -> &[EndpointStats] {
+        &self.endpoints
+    }
+
+    pub fn fetched_at(&self) -> Option<u64> {
+        self.fetched_at
+    }
+}
+
+impl PartialEq for IPRoyalQueryResults {
+    fn eq(&self, other: &Self) -> bool {
+        self.countries == other.countries && self.isps == other.isps && self.availability == other.availability
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::de::DeserializeOwned;
+    use serde_json::Value;
+    use super::*;
+
+    const COUNTRIES: &str = include_str!("../../fixtures/iproyal_countries.json");
+    const ISPS: &str = include_str!("../../fixtures/iproyal_isps.json");
+    const AVAILABILITY: &str = include_str!("../../fixtures/iproyal_availability.json");
+
+    /// Parses `fixture`, checking that serializing it again reproduces the payload exactly.
+    fn round_trip<T: Serialize + DeserializeOwned + PartialEq + std::fmt::Debug>(fixture: &str) -> T {
+        let parsed: T = serde_json::from_str(fixture).unwrap();
+        let json = serde_json::to_string(&parsed).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&json).unwrap(), serde_json::from_str::<Value>(fixture).unwrap());
+        assert_eq!(serde_json::from_str::<T>(&json).unwrap(), parsed);
+        parsed
+    }
+
+    #[test]
+    fn payloads_round_trip_losslessly() {
+        let countries: Root = round_trip(COUNTRIES);
+        let isps: Vec<IPRoyalIspRecord> = round_trip(ISPS);
+        let availability: Vec<IPRoyalAvailabilityRecord> = round_trip(AVAILABILITY);
+
+        let results = IPRoyalQueryResults::new(countries, isps, availability, Vec::new());
+        let cached: IPRoyalQueryResults = serde_json::from_slice(&serde_json::to_vec(&results).unwrap()).unwrap();
+        assert!(cached == results);
+    }
+}
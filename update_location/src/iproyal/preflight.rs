@@ -0,0 +1,17 @@
+use reqwest::Method;
+use crate::http::EndpointStats;
+use crate::iproyal::errors::IPRoyalGetCountryError;
+use crate::iproyal::get_raw_data::ENDPOINT;
+use crate::iproyal::query_iproyal::send_iproyal;
+use crate::iproyal::session::IPRoyalSession;
+use crate::models::IPRoyalConfig;
+
+/// Verifies that iproyal accepts the configured credentials without downloading data.
+///
+/// Exchanges client credentials when configured, then issues an authenticated `HEAD`
+/// against `access/countries`.
+pub async fn preflight(cfg: &IPRoyalConfig) -> Result<(), IPRoyalGetCountryError> {
+    let session = IPRoyalSession::new(cfg)?;
+    send_iproyal(&session, Method::HEAD, ENDPOINT, &[], &mut EndpointStats::start(ENDPOINT)).await?;
+    Ok(())
+}
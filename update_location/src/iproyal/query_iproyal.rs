@@ -0,0 +1,72 @@
+use reqwest::{Method, Response, StatusCode};
+use crate::http::{self, EndpointStats, Retries};
+use crate::iproyal::errors::IPRoyalGetCountryError;
+use crate::iproyal::session::IPRoyalSession;
+
+/// Performs an authenticated GET against an iproyal endpoint and decodes the JSON body.
+///
+/// The body is read in full (up to `http.max_response_bytes`) and checked against
+/// `Content-Length` before decoding, so
+/// a connection dropped mid-transfer surfaces as [`IPRoyalGetCountryError::Truncated`]
+/// rather than as a confusing decode error. The request is counted in `stats`.
+pub async fn query_iproyal<T>(
+    session: &IPRoyalSession<'_>,
+    endpoint: &str,
+    query: &[(&str, String)],
+    stats: &mut EndpointStats,
+) -> Result<T, IPRoyalGetCountryError>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let resp = send_iproyal(session, Method::GET, endpoint, query, stats).await?;
+
+    let expected = resp.content_length();
+    let body = http::read_body(session.cfg().get_http(), resp).await?;
+    stats.record(body.len());
+
+    if let Some(expected) = expected
+        && (body.len() as u64) < expected
+    {
+        return Err(IPRoyalGetCountryError::Truncated { expected, received: body.len() });
+    }
+
+    serde_json::from_slice::<T>(&body).map_err(IPRoyalGetCountryError::DecodeError)
+}
+
+/// Sends an authenticated request to an iproyal endpoint and returns the successful response.
+///
+/// The base URL is sanitized to end with `/` so `join()` keeps its last path segment.
+/// Authentication failures and other non-success statuses are reported as errors.
+///
+/// The request goes through the session's [`Stack`](crate::http::Stack); a retry
+/// after refreshing the token is counted in `stats.retries`.
+pub async fn send_iproyal(
+    session: &IPRoyalSession<'_>,
+    method: Method,
+    endpoint: &str,
+    query: &[(&str, String)],
+    stats: &mut EndpointStats,
+) -> Result<Response, IPRoyalGetCountryError> {
+    let cfg = session.cfg();
+
+    let mut sanitized_url = cfg.get_endpoint().to_owned();
+    if !sanitized_url.path().ends_with('/'){
+        sanitized_url.path_segments_mut().unwrap().push("");
+    }
+    sanitized_url = sanitized_url.join(endpoint).map_err(IPRoyalGetCountryError::JoinURLError)?;
+
+    let request = session.http().request(method, sanitized_url).query(query);
+    let resp = session.http().send(request).await?;
+    stats.retries += Retries::of(&resp);
+
+    let status = resp.status();
+    if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+        return Err(IPRoyalGetCountryError::AuthError { status });
+    }
+    if !status.is_success() {
+        let body = http::error_body(cfg.get_http(), resp).await;
+        return Err(IPRoyalGetCountryError::BadStatus { status, body });
+    }
+
+    Ok(resp)
+}
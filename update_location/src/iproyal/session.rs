@@ -0,0 +1,281 @@
+//! Per-run iproyal session: the shared HTTP stack and its bearer token middleware.
+//!
+//! Two authentication modes are supported:
+//! - **Static token** — `iproyal.token` is sent as-is on every request.
+//! - **Client credentials** — `iproyal.client_id` / `iproyal.client_secret` are exchanged
+//!   at `iproyal.token_endpoint` for a short-lived bearer token, which is cached for the
+//!   session and refreshed when it expires or when the API answers `401`.
+//!
+//! A static token takes precedence when both are configured.
+
+use std::sync::Arc;
+use std::time::Duration;
+use reqwest::header::{HeaderValue, AUTHORIZATION};
+use reqwest::{Client, Request, StatusCode};
+use serde::Deserialize;
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use crate::clock::{self, Clock};
+use crate::http::{self, HttpFuture, HttpSend, Logging, Middleware, Next, Quota, Retries, SendError, Stack, Timeout};
+use crate::iproyal::errors::IPRoyalGetCountryError;
+use crate::models::IPRoyalConfig;
+use crate::unified::Provider;
+
+/// Per-request timeout used when `iproyal.timeout` is not set.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Refresh tokens this long before their advertised expiry to absorb clock skew and latency.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(30);
+
+/// Shared state for all iproyal calls made during one run.
+pub struct IPRoyalSession<'a> {
+    cfg: &'a IPRoyalConfig,
+    http: Stack,
+}
+
+impl<'a> IPRoyalSession<'a> {
+    /// Creates the session and its HTTP stack (see [`crate::http::client`]): requests
+    /// are logged, limited by `iproyal.timeout`, authenticated with [`BearerToken`]
+    /// and counted against the quota, a refreshed retry included.
+    pub fn new(cfg: &'a IPRoyalConfig) -> Result<Self, IPRoyalGetCountryError> {
+        let client = http::client(cfg.get_http())?;
+        Ok(Self::with(cfg, client.clone(), Arc::new(client), clock::system()))
+    }
+
+    /// Like [`new`](Self::new), sending every request, token exchanges included,
+    /// through `transport`, and expiring tokens on `clock`.
+    pub fn with(cfg: &'a IPRoyalConfig, client: Client, transport: Arc<dyn HttpSend>, clock: Arc<dyn Clock>) -> Self {
+        let http = Stack::new(client.clone())
+            .transport(transport.clone())
+            .layer(Logging(Provider::IPRoyal))
+            .layer(Timeout(cfg.get_timeout().copied().unwrap_or(DEFAULT_TIMEOUT)))
+            .layer(BearerToken::new(cfg.clone(), client, transport, clock))
+            .layer(Quota(Provider::IPRoyal));
+        Self { cfg, http }
+    }
+
+    pub fn cfg(&self) -> &IPRoyalConfig {
+        self.cfg
+    }
+
+    pub fn http(&self) -> &Stack {
+        &self.http
+    }
+}
+
+/// Authenticates requests with the static token or an exchanged one.
+///
+/// When the credentials are exchanged, a `401` drops the cached token and the
+/// request is retried once with a fresh one, recorded as [`Retries`].
+struct BearerToken {
+    cfg: IPRoyalConfig,
+    /// Client and transport of the token exchange, which bypasses the stack.
+    client: Client,
+    transport: Arc<dyn HttpSend>,
+    clock: Arc<dyn Clock>,
+    token: Mutex<Option<CachedToken>>,
+}
+
+struct CachedToken {
+    value: String,
+    expires_at: Option<Instant>,
+}
+
+/// OAuth2 client-credentials token response.
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    #[serde(default)]
+    expires_in: Option<u64>,
+}
+
+impl Middleware for BearerToken {
+    fn handle<'a>(&'a self, request: Request, next: Next<'a>) -> HttpFuture<'a> {
+        Box::pin(async move {
+            let retry = if self.can_refresh() { request.try_clone() } else { None };
+            let resp = next.run(self.authorize(request).await?).await?;
+            match retry {
+                Some(retry) if resp.status() == StatusCode::UNAUTHORIZED => {
+                    self.invalidate().await;
+                    let mut resp = next.run(self.authorize(retry).await?).await?;
+                    Retries::add(&mut resp, 1);
+                    Ok(resp)
+                }
+                _ => Ok(resp),
+            }
+        })
+    }
+}
+
+impl BearerToken {
+    fn new(cfg: IPRoyalConfig, client: Client, transport: Arc<dyn HttpSend>, clock: Arc<dyn Clock>) -> Self {
+        Self { cfg, client, transport, clock, token: Mutex::new(None) }
+    }
+
+    /// Whether a `401` can be recovered from by exchanging credentials again.
+    fn can_refresh(&self) -> bool {
+        self.cfg.get_token().is_none()
+    }
+
+    /// Adds the `Authorization` header to `request`.
+    async fn authorize(&self, mut request: Request) -> Result<Request, SendError> {
+        let token = self.token().await.map_err(|err| SendError::Middleware(Box::new(err)))?;
+        let mut value = HeaderValue::try_from(format!("Bearer {token}"))
+            .map_err(|err| SendError::Middleware(Box::new(err)))?;
+        value.set_sensitive(true);
+        request.headers_mut().insert(AUTHORIZATION, value);
+        Ok(request)
+    }
+
+    /// Returns the bearer token to send, exchanging credentials if needed.
+    async fn token(&self) -> Result<String, IPRoyalGetCountryError> {
+        if let Some(token) = self.cfg.get_token() {
+            return Ok(token.to_owned());
+        }
+
+        let mut cached = self.token.lock().await;
+        if let Some(token) = cached.as_ref()
+            && token.expires_at.is_none_or(|at| self.clock.now() < at)
+        {
+            return Ok(token.value.clone());
+        }
+
+        let fresh = self.exchange().await?;
+        let value = fresh.value.clone();
+        *cached = Some(fresh);
+        Ok(value)
+    }
+
+    /// Drops the cached token so the next [`token`](Self::token) call exchanges a new one.
+    async fn invalidate(&self) {
+        *self.token.lock().await = None;
+    }
+    async fn exchange(&self) -> Result<CachedToken, IPRoyalGetCountryError> {
+        let (Some(client_id), Some(client_secret), Some(endpoint)) = (
+            self.cfg.get_client_id(),
+            self.cfg.get_client_secret(),
+            self.cfg.get_token_endpoint(),
+        ) else {
+            return Err(IPRoyalGetCountryError::MissingCredentials);
+        };
+
+        let request = self
+            .client
+            .post(endpoint.clone())
+            .form(&[
+                ("grant_type", "client_credentials"),
+                ("client_id", client_id),
+                ("client_secret", client_secret),
+            ])
+            .build()
+            .map_err(IPRoyalGetCountryError::URLError)?;
+        let resp = self.transport.send(request).await?;
+
+        let status = resp.status();
+        if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            return Err(IPRoyalGetCountryError::AuthError { status });
+        }
+        if !status.is_success() {
+            let body = http::error_body(self.cfg.get_http(), resp).await;
+            return Err(IPRoyalGetCountryError::BadStatus { status, body });
+        }
+
+        let body = http::read_body(self.cfg.get_http(), resp).await?;
+        let token: TokenResponse = serde_json::from_slice(&body).map_err(IPRoyalGetCountryError::DecodeError)?;
+
+        Ok(CachedToken {
+            value: token.access_token,
+            expires_at: token
+                .expires_in
+                .map(|secs| self.clock.now() + Duration::from_secs(secs).saturating_sub(EXPIRY_MARGIN)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reqwest::Method;
+    use crate::clock::ManualClock;
+    use crate::http::testing::Scripted;
+    use crate::http::EndpointStats;
+    use crate::iproyal::query_iproyal::send_iproyal;
+    use super::*;
+
+    fn client_credentials() -> IPRoyalConfig {
+        toml::from_str(
+            r#"
+            endpoint = "https://iproyal.example/v1"
+            client_id = "id"
+            client_secret = "secret"
+            token_endpoint = "https://auth.example/token"
+            "#,
+        )
+        .unwrap()
+    }
+
+    fn token(value: &str, expires_in: u64) -> (StatusCode, String) {
+        (StatusCode::OK, format!(r#"{{"access_token":"{value}","expires_in":{expires_in}}}"#))
+    }
+
+    async fn get(session: &IPRoyalSession<'_>, stats: &mut EndpointStats) -> Result<(), IPRoyalGetCountryError> {
+        send_iproyal(session, Method::GET, "access/isps", &[], stats).await.map(drop)
+    }
+
+    #[tokio::test]
+    async fn rejected_tokens_are_refreshed_once() {
+        let cfg = client_credentials();
+        let transport = Arc::new(Scripted::new([
+            token("first", 3600),
+            (StatusCode::UNAUTHORIZED, String::new()),
+            token("second", 3600),
+            (StatusCode::OK, "[]".to_string()),
+        ]));
+        let session = IPRoyalSession::with(&cfg, Client::new(), transport.clone(), Arc::new(ManualClock::new()));
+
+        let mut stats = EndpointStats::start("access/isps");
+        get(&session, &mut stats).await.unwrap();
+
+        let sent = transport.sent();
+        let auth: Vec<_> = sent.iter().map(|s| s.authorization.as_deref()).collect();
+        assert_eq!(auth, [None, Some("Bearer first"), None, Some("Bearer second")]);
+        assert_eq!(sent[0].path, "/token");
+        assert_eq!(stats.retries, 1);
+    }
+
+    #[tokio::test]
+    async fn tokens_are_exchanged_again_once_expired() {
+        let cfg = client_credentials();
+        let clock = ManualClock::new();
+        let transport = Arc::new(Scripted::new([
+            token("first", 90),
+            (StatusCode::OK, "[]".to_string()),
+            (StatusCode::OK, "[]".to_string()),
+            token("second", 90),
+            (StatusCode::OK, "[]".to_string()),
+        ]));
+        let session = IPRoyalSession::with(&cfg, Client::new(), transport.clone(), Arc::new(clock.clone()));
+        let mut stats = EndpointStats::start("access/isps");
+
+        get(&session, &mut stats).await.unwrap();
+        clock.advance(Duration::from_secs(59));
+        get(&session, &mut stats).await.unwrap();
+        // 90s minus the expiry margin
+        clock.advance(Duration::from_secs(1));
+        get(&session, &mut stats).await.unwrap();
+
+        let auth: Vec<_> = transport.sent().iter().map(|s| s.authorization.clone()).collect();
+        assert_eq!(auth.iter().filter(|a| a.is_none()).count(), 2);
+        assert_eq!(auth.last().unwrap().as_deref(), Some("Bearer second"));
+    }
+
+    #[tokio::test]
+    async fn a_static_token_is_not_refreshed() {
+        let cfg = IPRoyalConfig::new("https://iproyal.example/v1".parse().unwrap(), "static");
+        let transport = Arc::new(Scripted::new([(StatusCode::UNAUTHORIZED, "")]));
+        let session = IPRoyalSession::with(&cfg, Client::new(), transport.clone(), Arc::new(ManualClock::new()));
+
+        let err = get(&session, &mut EndpointStats::start("access/isps")).await.unwrap_err();
+        assert!(matches!(err, IPRoyalGetCountryError::AuthError { status: StatusCode::UNAUTHORIZED }));
+        assert_eq!(transport.sent().len(), 1);
+    }
+}
@@ -0,0 +1,154 @@
+//! Proxy targeting strings.
+//!
+//! iproyal selects the exit location of a proxy connection from parameters
+//! appended to the proxy credentials: the prefixed location code of a leaf (see
+//! [`IPRoyalLocation::code`]), optionally followed by a sticky session that keeps
+//! the same exit IP for its lifetime:
+//!
+//! ```text
+//! _country-us_state-texas_city-austin_isp-comcast_session-a1b2c3d4_lifetime-30m
+//! ```
+
+use std::fmt;
+use std::time::Duration;
+use crate::iproyal::errors::TargetingError;
+use crate::iproyal::models::IPRoyalLocation;
+
+/// Length of a session id.
+pub const SESSION_ID_LEN: usize = 8;
+
+/// Longest lifetime of a sticky session.
+pub const MAX_LIFETIME: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Targeting parameters of one proxy connection; displays as the string to append
+/// to the proxy username.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Targeting {
+    location: String,
+    session: Option<(String, Duration)>,
+}
+
+/// Builder of a [`Targeting`], see [`Targeting::builder`].
+#[derive(Debug, Clone)]
+pub struct TargetingBuilder {
+    location: String,
+    session: Option<String>,
+    lifetime: Option<Duration>,
+}
+
+impl Targeting {
+    /// Targets the leaf `location`, with a rotating IP unless a session is set.
+    pub fn builder(location: &IPRoyalLocation) -> TargetingBuilder {
+        TargetingBuilder { location: location.code.clone(), session: None, lifetime: None }
+    }
+
+    /// `username` with the targeting parameters appended.
+    pub fn username(&self, username: &str) -> String {
+        format!("{username}{self}")
+    }
+}
+
+impl TargetingBuilder {
+    /// Keeps the exit IP for the session `id`, made of [`SESSION_ID_LEN`] ASCII letters and digits.
+    pub fn session(mut self, id: impl Into<String>) -> Self {
+        self.session = Some(id.into());
+        self
+    }
+
+    /// Lifetime of the session, from one second up to [`MAX_LIFETIME`]; whole seconds only.
+    pub fn lifetime(mut self, lifetime: Duration) -> Self {
+        self.lifetime = Some(lifetime);
+        self
+    }
+
+    pub fn build(self) -> Result<Targeting, TargetingError> {
+        let session = match (self.session, self.lifetime) {
+            (None, None) => None,
+            (None, Some(_)) => return Err(TargetingError::LifetimeWithoutSession),
+            (Some(id), _) if id.len() != SESSION_ID_LEN || !id.bytes().all(|b| b.is_ascii_alphanumeric()) => {
+                return Err(TargetingError::InvalidSession(id));
+            }
+            (Some(_), None) => return Err(TargetingError::MissingLifetime),
+            (Some(_), Some(lifetime))
+                if lifetime < Duration::from_secs(1) || lifetime > MAX_LIFETIME || lifetime.subsec_nanos() != 0 =>
+            {
+                return Err(TargetingError::InvalidLifetime(lifetime));
+            }
+            (Some(id), Some(lifetime)) => Some((id, lifetime)),
+        };
+        Ok(Targeting { location: self.location, session })
+    }
+}
+
+impl fmt::Display for Targeting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.location)?;
+        if let Some((id, lifetime)) = &self.session {
+            write!(f, "_session-{id}_lifetime-{}", format_lifetime(*lifetime))?;
+        }
+        Ok(())
+    }
+}
+
+/// `lifetime` in the largest of days, hours, minutes or seconds that divides it.
+fn format_lifetime(lifetime: Duration) -> String {
+    let secs = lifetime.as_secs();
+    let (value, unit) = [(86_400, "d"), (3_600, "h"), (60, "m")]
+        .into_iter()
+        .find(|(unit, _)| secs.is_multiple_of(*unit))
+        .map_or((secs, "s"), |(unit, suffix)| (secs / unit, suffix));
+    format!("{value}{unit}")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::iproyal::ip_availability::Availability;
+    use super::*;
+
+    fn location() -> IPRoyalLocation {
+        IPRoyalLocation {
+            code: "_country-us_state-texas_city-austin_isp-comcast".to_string(),
+            country: "us".to_string(),
+            state: Some("Texas".to_string()),
+            city: Some("Austin".to_string()),
+            isp: Some("Comcast".to_string()),
+            ip_availability: None,
+            availability: Availability::Unknown,
+        }
+    }
+
+    #[test]
+    fn formats_location_and_session() {
+        let rotating = Targeting::builder(&location()).build().unwrap();
+        assert_eq!(rotating.username("user"), "user_country-us_state-texas_city-austin_isp-comcast");
+
+        let sticky = Targeting::builder(&location())
+            .session("a1b2C3d4")
+            .lifetime(Duration::from_secs(30 * 60))
+            .build()
+            .unwrap();
+        assert_eq!(sticky.to_string(), "_country-us_state-texas_city-austin_isp-comcast_session-a1b2C3d4_lifetime-30m");
+
+        let lifetime = |secs| format_lifetime(Duration::from_secs(secs));
+        assert_eq!((lifetime(90).as_str(), lifetime(7200).as_str(), lifetime(172_800).as_str()), ("90s", "2h", "2d"));
+    }
+
+    #[test]
+    fn rejects_invalid_sessions() {
+        let build = |id: Option<&str>, secs: Option<u64>| {
+            let mut builder = Targeting::builder(&location());
+            if let Some(id) = id {
+                builder = builder.session(id);
+            }
+            if let Some(secs) = secs {
+                builder = builder.lifetime(Duration::from_secs(secs));
+            }
+            builder.build().unwrap_err()
+        };
+        assert!(matches!(build(Some("short"), Some(60)), TargetingError::InvalidSession(_)));
+        assert!(matches!(build(Some("a1b2-c3d"), Some(60)), TargetingError::InvalidSession(_)));
+        assert!(matches!(build(Some("a1b2c3d4"), None), TargetingError::MissingLifetime));
+        assert!(matches!(build(Some("a1b2c3d4"), Some(8 * 86_400)), TargetingError::InvalidLifetime(_)));
+        assert!(matches!(build(None, Some(60)), TargetingError::LifetimeWithoutSession));
+    }
+}
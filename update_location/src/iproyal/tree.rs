@@ -0,0 +1,234 @@
+//! Traversal of the iproyal location tree.
+//!
+//! A country lists its cities either under `states` or directly under `cities`
+//! (sometimes both), and ISPs hang off cities as well as states. The iterators
+//! of [`Root`] and [`Country`] hide that asymmetry: they visit states first, in
+//! order, each with its cities before its own ISPs, then the cities listed on the
+//! country itself, and yield every item with the path leading to it.
+
+use crate::iproyal::models::{City, Container, Country, Isp, Root, State};
+
+/// A city and the country (and state, if any) it is listed under.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CityItem<'a> {
+    pub country: &'a Country,
+    pub state: Option<&'a State>,
+    pub city: &'a City,
+}
+
+/// An ISP and the path to it; `city` is `None` for ISPs attached to a state.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IspItem<'a> {
+    pub country: &'a Country,
+    pub state: Option<&'a State>,
+    pub city: Option<&'a City>,
+    pub isp: &'a Isp,
+}
+
+/// The deepest node on a path: usually an ISP, but a country, state or city
+/// without children is a leaf of its own. Levels absent from the path are `None`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Leaf<'a> {
+    pub country: &'a Country,
+    pub state: Option<&'a State>,
+    pub city: Option<&'a City>,
+    pub isp: Option<&'a Isp>,
+}
+
+impl<'a> Leaf<'a> {
+    /// Availability reported for the leaf node itself.
+    pub fn ip_availability(&self) -> Option<&'a String> {
+        match (self.isp, self.city, self.state) {
+            (Some(isp), ..) => isp.ip_availability.as_ref(),
+            (None, Some(city), _) => city.ip_availability.as_ref(),
+            (None, None, Some(state)) => state.ip_availability.as_ref(),
+            (None, None, None) => self.country.ip_availability.as_ref(),
+        }
+    }
+
+    /// Full location code of the leaf as used in iproyal's targeting syntax, e.g.
+    /// `_country-us_state-texas_city-austin_isp-comcast`: the code of every node on
+    /// the path, each behind the prefix of the container listing it. `country_prefix`
+    /// is the [`Root::prefix`] the country is listed under.
+    pub fn code(&self, country_prefix: &str) -> String {
+        let mut parts = vec![(country_prefix, self.country.code.as_str())];
+        if let Some(state) = self.state {
+            parts.push((prefix(&self.country.states), &state.code));
+        }
+        if let Some(city) = self.city {
+            let cities = self.state.map_or(&self.country.cities, |s| &s.cities);
+            parts.push((prefix(cities), &city.code));
+        }
+        if let Some(isp) = self.isp {
+            let isps = match (self.city, self.state) {
+                (Some(city), _) => &city.isps,
+                (None, Some(state)) => &state.isps,
+                (None, None) => &None,
+            };
+            parts.push((prefix(isps), &isp.code));
+        }
+        compose(&parts)
+    }
+}
+
+/// Concatenates `(prefix, code)` pairs into a full location code.
+pub fn compose(parts: &[(&str, &str)]) -> String {
+    parts.iter().flat_map(|(prefix, code)| [*prefix, *code]).collect()
+}
+
+impl Root {
+    /// Every city of every country.
+    pub fn iter_cities(&self) -> impl Iterator<Item = CityItem<'_>> {
+        self.countries.iter().flat_map(Country::iter_cities)
+    }
+
+    /// Every ISP of every country, whether listed under a city or a state.
+    pub fn iter_isps(&self) -> impl Iterator<Item = IspItem<'_>> {
+        self.countries.iter().flat_map(Country::iter_isps)
+    }
+
+    /// Every leaf of every country.
+    pub fn iter_leaves(&self) -> impl Iterator<Item = Leaf<'_>> {
+        self.countries.iter().flat_map(Country::iter_leaves)
+    }
+}
+
+impl Country {
+    /// The cities of the country, those of its states included.
+    pub fn iter_cities(&self) -> impl Iterator<Item = CityItem<'_>> {
+        let in_states = options(&self.states).iter().flat_map(move |state| {
+            options(&state.cities).iter().map(move |city| CityItem { country: self, state: Some(state), city })
+        });
+        let direct = options(&self.cities).iter().map(move |city| CityItem { country: self, state: None, city });
+        in_states.chain(direct)
+    }
+
+    /// The ISPs of the country, those attached to its states included.
+    pub fn iter_isps(&self) -> impl Iterator<Item = IspItem<'_>> {
+        let in_states = options(&self.states).iter().flat_map(move |state| {
+            let cities = options(&state.cities).iter().flat_map(move |city| city_isps(self, Some(state), city));
+            let own = options(&state.isps).iter().map(move |isp| IspItem { country: self, state: Some(state), city: None, isp });
+            cities.chain(own)
+        });
+        let direct = options(&self.cities).iter().flat_map(move |city| city_isps(self, None, city));
+        in_states.chain(direct)
+    }
+
+    /// The leaves of the country; the country itself when it has no states or cities.
+    pub fn iter_leaves(&self) -> impl Iterator<Item = Leaf<'_>> {
+        let bare = options(&self.states).is_empty() && options(&self.cities).is_empty();
+        let itself = bare.then_some(Leaf { country: self, state: None, city: None, isp: None });
+        let in_states = options(&self.states).iter().flat_map(move |state| state_leaves(self, state));
+        let direct = options(&self.cities).iter().flat_map(move |city| city_leaves(self, None, city));
+        itself.into_iter().chain(in_states).chain(direct)
+    }
+}
+
+fn city_isps<'a>(country: &'a Country, state: Option<&'a State>, city: &'a City) -> impl Iterator<Item = IspItem<'a>> {
+    options(&city.isps).iter().map(move |isp| IspItem { country, state, city: Some(city), isp })
+}
+
+fn state_leaves<'a>(country: &'a Country, state: &'a State) -> impl Iterator<Item = Leaf<'a>> {
+    let bare = options(&state.cities).is_empty() && options(&state.isps).is_empty();
+    let itself = bare.then_some(Leaf { country, state: Some(state), city: None, isp: None });
+    let cities = options(&state.cities).iter().flat_map(move |city| city_leaves(country, Some(state), city));
+    // ISPs attached directly to the state, without a city in between
+    let isps = options(&state.isps).iter().map(move |isp| Leaf { country, state: Some(state), city: None, isp: Some(isp) });
+    itself.into_iter().chain(cities).chain(isps)
+}
+
+fn city_leaves<'a>(country: &'a Country, state: Option<&'a State>, city: &'a City) -> impl Iterator<Item = Leaf<'a>> {
+    let itself = options(&city.isps).is_empty().then_some(Leaf { country, state, city: Some(city), isp: None });
+    let isps = options(&city.isps).iter().map(move |isp| Leaf { country, state, city: Some(city), isp: Some(isp) });
+    itself.into_iter().chain(isps)
+}
+
+/// Returns the prefix of an optional container, or an empty string.
+fn prefix<T>(container: &Option<Container<T>>) -> &str {
+    container.as_ref().map_or("", |c| c.prefix.as_str())
+}
+
+/// Returns the options of an optional container, or an empty slice.
+fn options<T>(container: &Option<Container<T>>) -> &[T] {
+    container.as_ref().map(|c| c.options.as_slice()).unwrap_or(&[])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node<T>(prefix: &str, options: Vec<T>) -> Option<Container<T>> {
+        Some(Container { prefix: prefix.to_string(), options })
+    }
+
+    fn isp(name: &str) -> Isp {
+        Isp { code: name.to_lowercase(), name: name.to_string(), ip_availability: None }
+    }
+
+    fn city(name: &str, isps: Vec<Isp>) -> City {
+        City { code: name.to_lowercase(), name: name.to_string(), ip_availability: None, isps: node("_isp-", isps) }
+    }
+
+    fn country(code: &str, states: Option<Container<State>>, cities: Option<Container<City>>) -> Country {
+        Country { code: code.to_string(), name: code.to_string(), ip_availability: Some("1K+".to_string()), cities, states }
+    }
+
+    fn root() -> Root {
+        let texas = State {
+            code: "tx".to_string(),
+            name: "Texas".to_string(),
+            ip_availability: None,
+            cities: node("_city-", vec![city("Austin", vec![isp("Comcast")]), city("Dallas", vec![])]),
+            isps: node("_isp-", vec![isp("AT&T")]),
+        };
+        Root {
+            prefix: "_country-".to_string(),
+            countries: vec![
+                country("us", node("_state-", vec![texas]), None),
+                country("de", None, node("_city-", vec![city("Berlin", vec![isp("Telekom")])])),
+                country("fr", None, None),
+            ],
+            meta: None,
+        }
+    }
+
+    #[test]
+    fn cities_and_isps_of_both_shapes() {
+        let root = root();
+        let cities: Vec<_> = root.iter_cities().map(|c| (c.country.code.as_str(), c.state.map(|s| s.name.as_str()), c.city.name.as_str())).collect();
+        assert_eq!(cities, [("us", Some("Texas"), "Austin"), ("us", Some("Texas"), "Dallas"), ("de", None, "Berlin")]);
+
+        let isps: Vec<_> = root.iter_isps().map(|i| (i.city.map(|c| c.name.as_str()), i.isp.name.as_str())).collect();
+        assert_eq!(isps, [(Some("Austin"), "Comcast"), (None, "AT&T"), (Some("Berlin"), "Telekom")]);
+    }
+
+    #[test]
+    fn childless_nodes_are_leaves() {
+        let root = root();
+        let leaves: Vec<_> = root
+            .iter_leaves()
+            .map(|l| (l.country.code.as_str(), l.city.map(|c| c.name.as_str()), l.isp.map(|i| i.name.as_str())))
+            .collect();
+        assert_eq!(leaves, [
+            ("us", Some("Austin"), Some("Comcast")),
+            ("us", Some("Dallas"), None),
+            ("us", None, Some("AT&T")),
+            ("de", Some("Berlin"), Some("Telekom")),
+            ("fr", None, None),
+        ]);
+        assert_eq!(root.iter_leaves().last().unwrap().ip_availability().map(String::as_str), Some("1K+"));
+    }
+
+    #[test]
+    fn codes_chain_the_container_prefixes() {
+        let root = root();
+        let codes: Vec<String> = root.iter_leaves().map(|l| l.code(&root.prefix)).collect();
+        assert_eq!(codes, [
+            "_country-us_state-tx_city-austin_isp-comcast",
+            "_country-us_state-tx_city-dallas",
+            "_country-us_state-tx_isp-at&t",
+            "_country-de_city-berlin_isp-telekom",
+            "_country-fr",
+        ]);
+    }
+}
@@ -0,0 +1,37 @@
+//! # update_location
+//!
+//! Provider clients, the unified location pipeline and the subcommands of the
+//! `update_location` binary, which only parses arguments and dispatches. Being a
+//! library also lets `benches/` exercise the pipeline stages directly.
+
+pub mod alerts;
+pub mod cache;
+pub mod changes;
+pub mod clock;
+pub mod commands;
+pub mod export;
+pub mod filter;
+pub mod geo;
+pub mod http;
+pub mod infatica;
+pub mod init;
+pub mod iproyal;
+pub mod lock;
+pub mod matching;
+pub mod models;
+pub mod normalize;
+pub mod notify;
+pub mod pipeline;
+pub mod quality;
+pub mod quota;
+pub mod run_id;
+pub mod secrets;
+pub mod server;
+pub mod shutdown;
+pub mod sinks;
+pub mod stats;
+pub mod ui;
+pub mod unified;
+#[cfg(feature = "vcr")]
+pub mod vcr;
+
@@ -0,0 +1,15 @@
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Failure to take the run lock.
+#[derive(Debug, Error)]
+pub enum LockError {
+    #[error("run in progress{}", .holder.map(|pid| format!(" (pid {pid})")).unwrap_or_default())]
+    InProgress { holder: Option<u32> },
+
+    #[error("interrupted while waiting for the run in progress")]
+    Interrupted,
+
+    #[error("failed to lock {}: {source}", .path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+}
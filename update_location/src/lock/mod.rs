@@ -0,0 +1,131 @@
+//! # Run lock
+//!
+//! `fetch` and `export` both write the cache and the change log, and `fetch`
+//! publishes to the sinks, so two runs overlapping (such as a slow cron run still
+//! going when the next one starts) would publish the same changes twice. Each
+//! takes an exclusive lock on `<cache.dir>/run.lock` first; a second invocation
+//! waits up to `run.lock_wait` (`--lock-wait`) for it, then exits with
+//! [`EXIT_RUN_IN_PROGRESS`] without doing anything.
+//!
+//! The lock is an OS file lock, released when the process exits however it exits,
+//! so a crashed run never leaves a stale lock behind. The file holds the PID of the
+//! last holder, reported to the runs turned away.
+//!
+//! Only runs sharing a cache directory exclude each other. There is no Postgres
+//! advisory lock for runs on different hosts, since no sink writes to Postgres;
+//! hosts sharing a cache directory on a network filesystem need one that
+//! supports `flock`.
+
+mod errors;
+
+pub use errors::LockError;
+
+use std::fs::{File, OpenOptions, TryLockError};
+use std::io::{Read, Seek, Write};
+use std::path::Path;
+use std::time::Duration;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+/// Exit code of a run turned away by one in progress (`EX_TEMPFAIL`).
+pub const EXIT_RUN_IN_PROGRESS: u8 = 75;
+
+const LOCK_FILE: &str = "run.lock";
+
+/// Interval between attempts while waiting for the lock.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Exclusive lock on a cache directory, held until dropped.
+#[derive(Debug)]
+pub struct RunLock {
+    _file: File,
+}
+
+impl RunLock {
+    /// Locks `dir`, waiting up to `wait` for a run holding it, unless cancelled.
+    pub async fn acquire(dir: &Path, wait: Duration, cancel: &CancellationToken) -> Result<Self, LockError> {
+        let path = dir.join(LOCK_FILE);
+        let io = |source| LockError::Io { path: path.clone(), source };
+
+        std::fs::create_dir_all(dir).map_err(io)?;
+        // Not truncated here, as that would erase the PID of a current holder
+        let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(&path).map_err(io)?;
+
+        let deadline = Instant::now() + wait;
+        loop {
+            match file.try_lock() {
+                Ok(()) => break,
+                Err(TryLockError::WouldBlock) if Instant::now() < deadline => {
+                    tokio::select! {
+                        () = tokio::time::sleep(POLL_INTERVAL.min(deadline - Instant::now())) => {}
+                        () = cancel.cancelled() => return Err(LockError::Interrupted),
+                    }
+                }
+                Err(TryLockError::WouldBlock) => return Err(LockError::InProgress { holder: holder(&mut file) }),
+                Err(TryLockError::Error(err)) => return Err(io(err)),
+            }
+        }
+
+        file.set_len(0).map_err(io)?;
+        file.rewind().map_err(io)?;
+        write!(file, "{}", std::process::id()).map_err(io)?;
+        Ok(Self { _file: file })
+    }
+}
+
+/// PID recorded by the current holder, if readable.
+fn holder(file: &mut File) -> Option<u32> {
+    let mut pid = String::new();
+    file.rewind().ok()?;
+    file.read_to_string(&mut pid).ok()?;
+    pid.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn second_lock_is_turned_away_until_the_first_is_dropped() {
+        let dir = std::env::temp_dir().join(format!("update_location-lock-{}", std::process::id()));
+        let cancel = CancellationToken::new();
+
+        let first = RunLock::acquire(&dir, Duration::ZERO, &cancel).await.unwrap();
+        let second = RunLock::acquire(&dir, Duration::from_millis(300), &cancel).await;
+        assert!(matches!(second, Err(LockError::InProgress { holder: Some(pid) }) if pid == std::process::id()));
+
+        drop(first);
+        assert!(RunLock::acquire(&dir, Duration::ZERO, &cancel).await.is_ok());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_waiting_run_takes_the_lock_once_the_holder_drops_it() {
+        let dir = std::env::temp_dir().join(format!("update_location-lock-wait-{}", std::process::id()));
+        let cancel = CancellationToken::new();
+
+        let first = RunLock::acquire(&dir, Duration::ZERO, &cancel).await.unwrap();
+        let release = async {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            drop(first);
+        };
+        let ((), second) = tokio::join!(release, RunLock::acquire(&dir, Duration::from_secs(10), &cancel));
+        assert!(second.is_ok(), "{second:?}");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn cancelling_a_waiting_run_interrupts_it() {
+        let dir = std::env::temp_dir().join(format!("update_location-lock-cancel-{}", std::process::id()));
+        let cancel = CancellationToken::new();
+
+        let _first = RunLock::acquire(&dir, Duration::ZERO, &cancel).await.unwrap();
+        let interrupt = async {
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            cancel.cancel();
+        };
+        let ((), second) = tokio::join!(interrupt, RunLock::acquire(&dir, Duration::from_secs(10), &cancel));
+        assert!(matches!(second, Err(LockError::Interrupted)), "{second:?}");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,92 +1,41 @@
-mod infatica;
-mod init;
-mod iproyal;
-mod models;
-
-use crate::init::load_config;
-use crate::models::CLIArgs;
-use clap::Parser;
-use tokio;
+use update_location::init::load_config;
+use update_location::models::{CLIArgs, Command};
+use update_location::{commands, run_id, shutdown, ui, verbose};
+use std::process::ExitCode;
 
 #[tokio::main]
-async fn main() {
-    let args = CLIArgs::parse();
+async fn main() -> ExitCode {
+    let args = CLIArgs::parse_with_config_keys();
+    ui::init(ui::Verbosity::from_flags(args.quiet, args.verbose));
+    run_id::init(args.run_id.clone().unwrap_or_else(run_id::generate));
+
+    // Commands that inspect the configuration itself run before it is deserialized
+    match &args.command {
+        Some(Command::Validate) => return commands::validate::run(&args).await,
+        Some(Command::PrintConfig(print)) => return commands::print_config::run(&args, print).await,
+        Some(Command::Init(init)) => return commands::init::run(init),
+        _ => {}
+    }
 
-    let cfg = match load_config(&args) {
+    let cfg = match load_config(&args).await {
         Ok(c) => c,
         Err(e) => {
             eprintln!("{e}");
-            std::process::exit(1);
+            return ExitCode::FAILURE;
         }
     };
-
-    match iproyal::get_raw_data(&cfg.iproyal).await {
-        Ok(r) => {
-            println!("iproyal request succeeded");
-            println!("iproyal countries {}", r.countries.len());
-            println!(
-                "iproyal first country: {{ code: \"{}\", name: \"{}\", cities: \"{}\", states: \"{}\", ip_availability: \"{}\" }}",
-                &r.countries[0].code,
-                &r.countries[0].name,
-                &r.countries[0]
-                    .cities
-                    .as_ref()
-                    .map(|c| c.options.len())
-                    .unwrap_or(0),
-                &r.countries[0]
-                    .states
-                    .as_ref()
-                    .map(|c| c.options.len())
-                    .unwrap_or(0),
-                &r.countries[0]
-                    .ip_availability
-                    .as_deref()
-                    .map(|c| c)
-                    .unwrap_or("no data"),
-            );
-            println!();
-        }
-        Err(e) => eprintln!("iproyal request failed: {e:?}"),
-    }
-
-    match infatica::get_all(&cfg.infatica).await {
-        Ok(results) => {
-            println!("Infatica queries succeeded");
-
-            println!("--- GEO NODES ---");
-            println!("Records: {}", results.geo_nodes().len());
-            if let Some(first) = results.geo_nodes().first() {
-                println!("First record: {:?}", first);
-            }
-            println!();
-
-            println!("--- REGION CODES ---");
-            println!("Records: {}", results.region_codes().len());
-            if let Some(first) = results.region_codes().first() {
-                println!("First record: {:?}", first);
-            }
-            println!();
-
-            println!("--- ZIP CODES ---");
-            println!("Records: {}", results.zip_codes().len());
-            if let Some(first) = results.zip_codes().first() {
-                println!("First record: {:?}", first);
-            }
-            println!();
-
-            println!("--- ISP CODES ---");
-            println!("Records: {}", results.isp_codes().len());
-            if let Some(first) = results.isp_codes().first() {
-                println!("First record: {:?}", first);
-            }
-            println!();
-        }
-
-        Err(errors) => {
-            eprintln!("Infatica query failed with {} error(s):", errors.len());
-            for err in errors {
-                eprintln!("  - {err}");
-            }
-        }
+    verbose!("{cfg:#?}");
+
+    let cancel = shutdown::cancel_on_signal();
+
+    match &args.command {
+        None => commands::fetch::run(&cfg, args.dry_run, &cancel).await,
+        Some(Command::Compare(compare)) => commands::compare::run(&cfg, compare, &cancel).await,
+        Some(Command::Serve(serve)) => commands::serve::run(&cfg, serve, &cancel).await,
+        Some(Command::Export(export)) => commands::export::run(&cfg, export, &cancel).await,
+        Some(Command::Prune(prune)) => commands::prune::run(&cfg, prune),
+        Some(Command::Stats(stats)) => commands::stats::run(&cfg, stats),
+        Some(Command::Health(health)) => commands::health::run(&cfg, health, &cancel).await,
+        Some(Command::Validate | Command::PrintConfig(_) | Command::Init(_)) => unreachable!("handled before loading the config"),
     }
 }
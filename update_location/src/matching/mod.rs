@@ -0,0 +1,164 @@
+//! # Cross-provider city matching
+//!
+//! Links the same city across vendors even when spelled differently
+//! (`"Munich"` / `"München"`, `"Saint Louis"` / `"St Louis"`), producing a
+//! coverage view of which providers serve which locations.
+//!
+//! Names are first compared by their canonical form (see [`normalize_name`]),
+//! taken from the ASCII transliteration of cities in other scripts if present;
+//! only when no exact match exists in the same country is a fuzzy comparison
+//! made against the existing clusters, accepting the best candidate scoring at
+//! least the configured threshold (`matching.threshold`).
+
+mod names;
+
+pub use names::{normalize_name, similarity};
+
+use std::collections::{BTreeMap, HashMap};
+use serde::Serialize;
+use crate::unified::{LocationRecord, Provider};
+
+/// Per-provider figures for one matched city.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct ProviderCoverage {
+    /// Number of records (ISP leaves) for this city.
+    pub records: usize,
+    /// Sum of node estimates across those records.
+    pub nodes: u64,
+}
+
+/// One city, possibly reported by several providers under different spellings.
+#[derive(Debug, Clone, Serialize)]
+pub struct CoverageEntry {
+    pub country: String,
+    /// Display name: the first spelling encountered.
+    pub city: String,
+    /// Every distinct spelling merged into this entry.
+    pub names: Vec<String>,
+    pub providers: BTreeMap<Provider, ProviderCoverage>,
+}
+
+impl CoverageEntry {
+    /// Whether only a single provider serves this city.
+    pub fn is_exclusive(&self) -> bool {
+        self.providers.len() == 1
+    }
+}
+
+/// Groups city-level records across providers into coverage entries.
+///
+/// Records without a city are ignored. Entries are sorted by country, then city.
+pub fn coverage(records: &[LocationRecord], threshold: f64) -> Vec<CoverageEntry> {
+    let mut entries: Vec<CoverageEntry> = Vec::new();
+    // (country, canonical name) → index into `entries`
+    let mut exact: HashMap<(String, String), usize> = HashMap::new();
+    // country → canonical names with their entry index, for fuzzy lookups
+    let mut by_country: HashMap<String, Vec<(String, usize)>> = HashMap::new();
+
+    for record in records {
+        let Some(city) = record.city.as_deref() else {
+            continue;
+        };
+        let canonical = normalize_name(record.ascii_city().unwrap_or(city));
+        let key = (record.country.clone(), canonical.clone());
+
+        let index = match exact.get(&key) {
+            Some(&i) => i,
+            None => {
+                let candidates = by_country.entry(record.country.clone()).or_default();
+                let fuzzy = candidates
+                    .iter()
+                    .map(|(name, i)| (similarity(name, &canonical), *i))
+                    .filter(|(score, _)| *score >= threshold)
+                    .max_by(|a, b| a.0.total_cmp(&b.0))
+                    .map(|(_, i)| i);
+
+                let i = fuzzy.unwrap_or_else(|| {
+                    entries.push(CoverageEntry {
+                        country: record.country.clone(),
+                        city: city.to_string(),
+                        names: Vec::new(),
+                        providers: BTreeMap::new(),
+                    });
+                    entries.len() - 1
+                });
+                candidates.push((canonical, i));
+                exact.insert(key, i);
+                i
+            }
+        };
+
+        let entry = &mut entries[index];
+        if !entry.names.iter().any(|n| n == city) {
+            entry.names.push(city.to_string());
+        }
+        let provider = entry.providers.entry(record.provider).or_default();
+        provider.records += 1;
+        provider.nodes += record.node_estimate().unwrap_or(0);
+    }
+
+    entries.sort_by(|a, b| (&a.country, &a.city).cmp(&(&b.country, &b.city)));
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(provider: Provider, city: &str, nodes: u64) -> LocationRecord {
+        LocationRecord {
+            city: Some(city.to_string()),
+            nodes: Some(nodes),
+            ..LocationRecord::sample(provider, "DE")
+        }
+    }
+
+    fn cities(entries: &[CoverageEntry]) -> Vec<(&str, usize)> {
+        entries.iter().map(|e| (e.city.as_str(), e.providers.len())).collect()
+    }
+
+    #[test]
+    fn names_are_canonicalized() {
+        assert_eq!(normalize_name("St. Louis City"), "st louis");
+        assert_eq!(normalize_name("  München "), "munchen");
+        assert_eq!(normalize_name("The City of London"), "london");
+        assert_eq!(normalize_name("Frankfurt-am-Main"), "frankfurt am main");
+        assert_eq!(similarity("berlin", "berlin"), 1.0);
+    }
+
+    #[test]
+    fn exact_spellings_merge_regardless_of_threshold() {
+        let records = [record(Provider::Infatica, "München", 3), record(Provider::IPRoyal, "munchen", 4)];
+        let entries = coverage(&records, 1.0);
+
+        assert_eq!(cities(&entries), [("München", 2)]);
+        assert_eq!(entries[0].names, ["München", "munchen"]);
+        assert_eq!(entries[0].providers[&Provider::IPRoyal].nodes, 4);
+    }
+
+    #[test]
+    fn fuzzy_matches_need_the_threshold() {
+        let records = [record(Provider::Infatica, "Munich", 3), record(Provider::IPRoyal, "Munchen", 4)];
+        let score = similarity("munich", "munchen");
+
+        // Reaching the threshold is enough
+        assert_eq!(cities(&coverage(&records, score)), [("Munich", 2)]);
+        assert_eq!(cities(&coverage(&records, score.next_up())), [("Munchen", 1), ("Munich", 1)]);
+        // Cities of different countries never match
+        let mut other = records.clone();
+        other[1].country = "AT".to_string();
+        assert_eq!(coverage(&other, 0.0).len(), 2);
+    }
+
+    #[test]
+    fn transliterations_are_matched_when_present() {
+        let mut moscow = record(Provider::IPRoyal, "Москва", 2);
+        let records = [record(Provider::Infatica, "Moskva", 1), moscow.clone()];
+        assert_eq!(coverage(&records, 0.92).len(), 2);
+
+        moscow.city_ascii = Some("Moskva".to_string());
+        let entries = coverage(&[records[0].clone(), moscow], 0.92);
+        assert_eq!(cities(&entries), [("Moskva", 2)]);
+        assert_eq!(entries[0].names, ["Moskva", "Москва"]);
+    }
+}
@@ -0,0 +1,29 @@
+use crate::normalize::fold_diacritic;
+
+/// Words that vendors add or drop around city names without changing the place.
+const NOISE_WORDS: &[&str] = &["city", "of", "the"];
+
+/// Canonical form used for city comparison.
+///
+/// Lowercases, folds diacritics, treats punctuation as word breaks, drops
+/// [`NOISE_WORDS`], and joins the remaining words with single spaces:
+/// `"St. Louis City"` → `"st louis"`, `"München"` → `"munchen"`.
+pub fn normalize_name(name: &str) -> String {
+    let folded: String = name
+        .chars()
+        .flat_map(char::to_lowercase)
+        .map(fold_diacritic)
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+
+    folded
+        .split_whitespace()
+        .filter(|w| !NOISE_WORDS.contains(w))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Similarity of two already-normalized names in `0.0..=1.0`.
+pub fn similarity(a: &str, b: &str) -> f64 {
+    strsim::jaro_winkler(a, b)
+}
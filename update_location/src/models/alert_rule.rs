@@ -0,0 +1,11 @@
+use serde::Deserialize;
+use crate::unified::Provider;
+
+#[derive(Deserialize, Debug, Clone)]
+/// A coverage threshold evaluated after each fetch: `provider` must report at
+/// least `min_nodes` nodes in `country` (ISO 3166-1 alpha-2).
+pub struct AlertRule {
+    pub provider: Provider,
+    pub country: String,
+    pub min_nodes: u64,
+}
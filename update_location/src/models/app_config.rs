@@ -1,9 +1,42 @@
 use serde::Deserialize;
 use crate::models::infatica_config::InfaticaConfig;
-use crate::models::IPRoyalConfig;
+use crate::models::{AlertRule, CacheConfig, DedupConfig, ExportConfig, GeoConfig, HttpConfig, IPRoyalConfig, KafkaConfig, MatchingConfig, NatsConfig, NotifyConfig, OutputConfig, QuotaConfig, RunConfig};
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Default, Debug)]
 pub struct AppConfig {
-    pub iproyal: IPRoyalConfig,
-    pub infatica: InfaticaConfig,
-}
\ No newline at end of file
+    /// Providers without a config section are disabled.
+    #[serde(default)]
+    pub iproyal: Option<IPRoyalConfig>,
+    #[serde(default)]
+    pub infatica: Option<InfaticaConfig>,
+    #[serde(default)]
+    pub http: HttpConfig,
+    #[serde(default)]
+    pub cache: CacheConfig,
+    #[serde(default)]
+    pub matching: MatchingConfig,
+    #[serde(default)]
+    pub geo: GeoConfig,
+    /// Resolution of locations reported by several providers; unset keeps every record.
+    #[serde(default)]
+    pub dedup: Option<DedupConfig>,
+    #[serde(default)]
+    pub alerts: Vec<AlertRule>,
+    #[serde(default)]
+    pub output: OutputConfig,
+    #[serde(default)]
+    pub export: ExportConfig,
+    #[serde(default)]
+    pub run: RunConfig,
+    /// Daily request budgets; see [`crate::quota`].
+    #[serde(default)]
+    pub quota: QuotaConfig,
+    /// Change event sinks; see [`crate::sinks`].
+    #[serde(default)]
+    pub kafka: Option<KafkaConfig>,
+    #[serde(default)]
+    pub nats: Option<NatsConfig>,
+    /// Notification channels; see [`crate::notify`].
+    #[serde(default)]
+    pub notify: NotifyConfig,
+}
@@ -0,0 +1,31 @@
+use std::path::PathBuf;
+use std::time::Duration;
+use serde::Deserialize;
+
+/// Default cache location when `cache.dir` is not configured.
+const DEFAULT_DIR: &str = "update_location";
+
+#[derive(Deserialize, Default, Debug)]
+/// Represents configuration for the local provider response cache.
+pub struct CacheConfig {
+    #[serde(default)]
+    dir: Option<PathBuf>,
+
+    /// Reuse cached datasets younger than this; unset always fetches fresh data.
+    #[serde(default, with = "humantime_serde::option")]
+    max_age: Option<Duration>,
+}
+
+impl CacheConfig {
+    /// Get the configured cache directory, defaulting to a folder in the system temp dir
+    pub fn get_dir(&self) -> PathBuf {
+        self.dir
+            .clone()
+            .unwrap_or_else(|| std::env::temp_dir().join(DEFAULT_DIR))
+    }
+
+    /// Get the configured maximum cache age
+    pub fn get_max_age(&self) -> Option<&Duration> {
+        self.max_age.as_ref()
+    }
+}
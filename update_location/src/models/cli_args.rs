@@ -1,40 +1,242 @@
-use clap::Parser;
+use clap::{CommandFactory, FromArgMatches, Parser};
+use crate::models::{CliDuration, Command, ConfigFileFormat};
+use override_key_core::ApplyOverrides;
 use override_key_derive::ApplyOverrides;
 
 /// Command-line arguments for update_location
 #[derive(Parser, ApplyOverrides)]
 #[command(name = "update_location", version, about = "location loading and updating script")]
-#[apply_overrides(infer_keys)]
+#[apply_overrides(infer_keys, emit_order_test = "conflicting")]
 pub struct CLIArgs {
-    /// Path to a configuration file
+    #[command(subcommand)]
+    #[override_key(skip)]
+    pub command: Option<Command>,
+
+    /// Configuration file or directory; repeat to layer several, later ones win.
+    /// Files are overridden by the profile, then MYAPP_* env vars, then flags
+    #[arg(long, global = true)]
+    #[override_key(skip)]
+    pub config: Vec<String>,
+
+    /// Format of config files without an extension; others are detected by extension
+    #[arg(long, global = true, value_enum)]
+    #[override_key(skip)]
+    pub config_format: Option<ConfigFileFormat>,
+
+    /// Config profile merged over the defaults, from [profiles.<name>] (env: MYAPP_PROFILE)
+    #[arg(long, global = true)]
+    #[override_key(skip)]
+    pub profile: Option<String>,
+
+    /// File of KEY=VALUE environment variables (default: .env, if present)
+    #[arg(long, global = true)]
+    #[override_key(skip)]
+    pub env_file: Option<String>,
+
+    /// Only print errors and the final report
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    #[override_key(skip)]
+    pub quiet: bool,
+
+    /// Also print sample records and the loaded configuration (secrets masked)
+    #[arg(short, long, global = true)]
+    #[override_key(skip)]
+    pub verbose: bool,
+
+    /// Id of this run in the report, export manifest, records and sink messages,
+    /// e.g. one assigned by an orchestrator (default: a new ULID)
+    #[arg(long, global = true, value_parser = crate::run_id::parse)]
+    #[override_key(skip)]
+    pub run_id: Option<String>,
+
+    /// Run the whole pipeline but only print what would be published to the sinks
     #[arg(long)]
-    pub config: Option<String>,
+    #[override_key(skip)]
+    pub dry_run: bool,
 
     /// IPRoyal API endpoint
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub iproyal_endpoint: Option<String>,
 
     /// IPRoyal token
-    #[arg(long)]
+    #[arg(long, global = true)]
+    #[override_key(sensitive)]
     pub iproyal_token: Option<String>,
 
+    /// IPRoyal OAuth client id (alternative to --iproyal-token)
+    #[arg(long, global = true)]
+    #[override_key = "iproyal.client_id"]
+    pub iproyal_client_id: Option<String>,
+
+    /// IPRoyal OAuth client secret
+    #[arg(long, global = true)]
+    #[override_key(key = "iproyal.client_secret", sensitive)]
+    pub iproyal_client_secret: Option<String>,
+
+    /// IPRoyal OAuth token endpoint
+    #[arg(long, global = true)]
+    #[override_key = "iproyal.token_endpoint"]
+    pub iproyal_token_endpoint: Option<String>,
+
     /// timeout (e.g. 5m, 10s)
-    #[arg(long)]
-    pub iproyal_timeout: Option<String>,
+    #[arg(long, global = true)]
+    pub iproyal_timeout: Option<CliDuration>,
 
     /// Infatica API endpoint
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub infatica_endpoint: Option<String>,
 
     /// IPRoyal token
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub infatica_email: Option<String>,
 
     /// IPRoyal token
-    #[arg(long)]
+    #[arg(long, global = true)]
+    #[override_key(sensitive)]
     pub infatica_password: Option<String>,
 
     /// timeout (e.g. 5m, 10s)
-    #[arg(long)]
-    pub infatica_timeout: Option<String>,
-}
\ No newline at end of file
+    #[arg(long, global = true)]
+    pub infatica_timeout: Option<CliDuration>,
+
+    /// Proxy for vendor requests, e.g. http://proxy:3128 (default: HTTPS_PROXY etc.)
+    #[arg(long, global = true)]
+    #[override_key = "http.proxy.url"]
+    pub proxy: Option<String>,
+
+    /// Username for proxy authentication
+    #[arg(long, global = true)]
+    #[override_key = "http.proxy.username"]
+    pub proxy_username: Option<String>,
+
+    /// Password for proxy authentication
+    #[arg(long, global = true)]
+    #[override_key(key = "http.proxy.password", sensitive)]
+    pub proxy_password: Option<String>,
+
+    /// PEM bundle of extra trusted CA certificates
+    #[arg(long, global = true)]
+    #[override_key = "http.ca_bundle"]
+    pub ca_bundle: Option<String>,
+
+    /// PEM client certificate for mutual TLS
+    #[arg(long, global = true)]
+    #[override_key = "http.client_cert"]
+    pub client_cert: Option<String>,
+
+    /// PKCS#8 PEM private key of --client-cert
+    #[arg(long, global = true)]
+    #[override_key = "http.client_key"]
+    pub client_key: Option<String>,
+
+    /// Directory for cached provider responses
+    #[arg(long, global = true)]
+    #[override_key = "cache.dir"]
+    pub cache_dir: Option<String>,
+
+    /// Reuse cached provider data younger than this (e.g. 6h, 30m)
+    #[arg(long, global = true)]
+    #[override_key = "cache.max_age"]
+    pub max_age: Option<CliDuration>,
+
+    /// Wait this long for a run in progress instead of exiting at once (e.g. 10m)
+    #[arg(long, global = true)]
+    #[override_key = "run.lock_wait"]
+    pub lock_wait: Option<CliDuration>,
+
+    /// GeoNames cities dump used to add coordinates to records
+    #[arg(long, global = true)]
+    #[override_key = "geo.dataset"]
+    pub geo_dataset: Option<String>,
+
+    /// Directory receiving export snapshots
+    #[arg(long, global = true)]
+    #[override_key = "output.dir"]
+    pub export_dir: Option<String>,
+
+    /// Only export and publish records matching this expression, e.g. 'country in ["US","DE"] && nodes > 10'
+    #[arg(long, global = true)]
+    #[override_key = "output.filter"]
+    pub filter: Option<String>,
+
+    /// Only publish to these sinks (comma-separated), e.g. to pause one
+    #[arg(long, global = true, value_delimiter = ',', value_parser = ["kafka", "nats"])]
+    #[override_key = "output.sinks"]
+    pub sinks: Option<Vec<String>>,
+
+    /// JSON object merged over the configuration, e.g. '{"cache":{"max_age":"1h"}}';
+    /// only the keys it contains are overridden
+    #[arg(long, global = true, value_parser = parse_json_object)]
+    #[override_key(json)]
+    pub extra_config: Option<serde_json::Value>,
+
+    /// Sets any config key, e.g. 'cache.max_age=1h'; repeatable, and applied after
+    /// all other flags
+    #[arg(long, global = true, value_name = "KEY=VALUE", value_parser = parse_key_value)]
+    #[override_key(dynamic)]
+    pub set: Vec<(String, String)>,
+}
+
+impl CLIArgs {
+    /// Like [`Parser::parse`], with each flag's help ending in the config key it
+    /// overrides, e.g. `--iproyal-endpoint ... [config: iproyal.endpoint]`.
+    pub fn parse_with_config_keys() -> Self {
+        let mut command = Self::command_with_config_keys();
+        let mut matches = command.clone().get_matches();
+        Self::from_arg_matches_mut(&mut matches).unwrap_or_else(|e| e.format(&mut command).exit())
+    }
+
+    /// The clap command, with config keys appended to the help of overriding flags.
+    pub fn command_with_config_keys() -> clap::Command {
+        Self::command().mut_args(|arg| {
+            let keys: Vec<&str> = Self::flag_key_map()
+                .iter()
+                .filter(|(flag, _)| Some(*flag) == arg.get_long())
+                .map(|(_, key)| *key)
+                .collect();
+            if keys.is_empty() {
+                return arg;
+            }
+
+            let suffix = format!("[config: {}]", keys.join(", "));
+            let help = arg.get_help().map(|help| format!("{help} {suffix}"));
+            let long_help = arg.get_long_help().map(|help| format!("{help}\n\n{suffix}"));
+            let arg = arg.help(help.unwrap_or(suffix));
+            match long_help {
+                Some(long_help) => arg.long_help(long_help),
+                None => arg,
+            }
+        })
+    }
+}
+
+/// Parses `--extra-config`, which must be a JSON object to be merged key by key.
+fn parse_json_object(raw: &str) -> Result<serde_json::Value, String> {
+    match serde_json::from_str(raw) {
+        Ok(value @ serde_json::Value::Object(_)) => Ok(value),
+        Ok(_) => Err("expected a JSON object, e.g. '{\"cache\":{\"max_age\":\"1h\"}}'".to_string()),
+        Err(err) => Err(format!("invalid JSON: {err}")),
+    }
+}
+
+/// Parses `--set`, a dotted config key and its value separated by the first `=`.
+fn parse_key_value(raw: &str) -> Result<(String, String), String> {
+    match raw.split_once('=') {
+        Some((key, value)) if !key.trim().is_empty() => Ok((key.trim().to_string(), value.to_string())),
+        Some(_) => Err("the key before '=' is empty".to_string()),
+        None => Err("expected KEY=VALUE, e.g. 'cache.max_age=1h'".to_string()),
+    }
+}
+
+/// Arguments setting keys through several flags, for the generated order test:
+/// `--extra-config` wins over the flags before it, and `--set` over everything.
+#[cfg(test)]
+fn conflicting() -> CLIArgs {
+    CLIArgs::parse_from([
+        "update_location",
+        "--iproyal-endpoint", "https://flag.example.com",
+        "--max-age", "2h",
+        "--extra-config", r#"{"cache":{"max_age":"3h"},"iproyal":{"endpoint":"https://json.example.com"}}"#,
+        "--set", "iproyal.endpoint=https://set.example.com",
+    ])
+}
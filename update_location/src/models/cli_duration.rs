@@ -0,0 +1,37 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+use config::ValueKind;
+
+/// Duration CLI flag (e.g. `5m`, `90s`, `1h 30m`), validated by clap when parsing arguments.
+///
+/// Converts into the canonical humantime string when applied as a config override,
+/// which the `humantime_serde` fields of the config models accept.
+#[derive(Debug, Clone, Copy)]
+pub struct CliDuration(Duration);
+
+impl FromStr for CliDuration {
+    type Err = humantime::DurationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        humantime::parse_duration(s).map(Self)
+    }
+}
+
+impl fmt::Display for CliDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        humantime::format_duration(self.0).fmt(f)
+    }
+}
+
+impl From<CliDuration> for ValueKind {
+    fn from(d: CliDuration) -> Self {
+        ValueKind::String(d.to_string())
+    }
+}
+
+impl From<CliDuration> for Duration {
+    fn from(d: CliDuration) -> Self {
+        d.0
+    }
+}
@@ -0,0 +1,164 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use clap::{Args, Subcommand, ValueEnum};
+use crate::models::{CliDuration, ExportCompression, ExportFormat};
+use crate::unified::Provider;
+
+/// Subcommands of update_location; without one, all providers are fetched and summarized.
+#[derive(Subcommand)]
+pub enum Command {
+    /// Compare location coverage between providers
+    Compare(CompareArgs),
+
+    /// Check the configuration and provider credentials without fetching data
+    Validate,
+
+    /// Print the merged configuration (file + env + CLI) with secrets masked
+    PrintConfig(PrintConfigArgs),
+
+    /// Serve the latest location data over an HTTP API
+    Serve(ServeArgs),
+
+    /// Fetch all providers and write the records as a new snapshot under the export directory
+    Export(ExportArgs),
+
+    /// Remove export snapshots no retention rule keeps
+    Prune(PruneArgs),
+
+    /// Summarize per-provider coverage of the latest export snapshot
+    Stats(StatsArgs),
+
+    /// Write a commented starter configuration listing every setting
+    Init(InitArgs),
+
+    /// Probe each enabled provider and report reachability, credentials and latency
+    Health(HealthArgs),
+}
+
+/// Arguments of `update_location compare`
+#[derive(Args)]
+pub struct CompareArgs {
+    /// Providers to compare (comma-separated)
+    #[arg(long, value_delimiter = ',', default_values_t = Provider::ALL)]
+    pub providers: Vec<Provider>,
+
+    /// Only show this country (ISO 3166-1 alpha-2, e.g. DE)
+    #[arg(long)]
+    pub country: Option<String>,
+
+    /// Only list cities served by a single provider
+    #[arg(long)]
+    pub only_exclusive: bool,
+}
+
+/// Arguments of `update_location print-config`
+#[derive(Args)]
+pub struct PrintConfigArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = ConfigFormat::Toml)]
+    pub format: ConfigFormat,
+}
+
+/// Serialization format for `print-config`
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+    /// Deserialized settings as Rust `Debug` output, secrets masked
+    Debug,
+}
+
+/// Arguments of `update_location serve`
+#[derive(Args)]
+pub struct ServeArgs {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    pub listen: SocketAddr,
+
+    /// Refetch provider data this often (e.g. 1h); unset serves the startup snapshot
+    #[arg(long)]
+    pub refresh: Option<CliDuration>,
+
+    /// Also serve the gRPC API on this address
+    #[cfg(feature = "grpc")]
+    #[arg(long)]
+    pub grpc_listen: Option<SocketAddr>,
+}
+
+/// Arguments of `update_location export`
+#[derive(Args)]
+pub struct ExportArgs {
+    /// Formats to write (comma-separated); defaults to `output.formats`
+    #[arg(long, value_enum, value_delimiter = ',')]
+    pub format: Vec<ExportFormat>,
+
+    /// Compress the artifacts; defaults to `output.compression`
+    #[arg(long, value_enum)]
+    pub compress: Option<ExportCompression>,
+
+    /// Where the records come from
+    #[arg(long, value_enum, default_value_t = ExportSource::Fetch)]
+    pub from: ExportSource,
+}
+
+/// Record source of `export`
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportSource {
+    /// Fetch the providers, honouring `cache.max_age`
+    Fetch,
+    /// The datasets last stored in the cache, however old, without calling any vendor
+    Cache,
+    /// The records of the latest export snapshot, e.g. to write it in other formats
+    Snapshot,
+}
+
+/// Arguments of `update_location prune`
+#[derive(Args)]
+pub struct PruneArgs {
+    /// List the snapshots that would be removed without removing them
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+/// Arguments of `update_location stats`
+#[derive(Args)]
+pub struct StatsArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = StatsFormat::Table)]
+    pub format: StatsFormat,
+
+    /// Number of ISPs listed per provider
+    #[arg(long, default_value_t = 5)]
+    pub top: usize,
+
+    /// Instead, show node-count changes per country over the exports of this period (e.g. 30d)
+    #[arg(long)]
+    pub history: Option<CliDuration>,
+}
+
+/// Output format of `stats`
+#[derive(Clone, Copy, ValueEnum)]
+pub enum StatsFormat {
+    Table,
+    Json,
+}
+
+/// Arguments of `update_location init`
+#[derive(Args)]
+pub struct InitArgs {
+    /// File to write; prints to stdout when unset
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+
+    /// Overwrite an existing --output file
+    #[arg(long)]
+    pub force: bool,
+}
+
+/// Arguments of `update_location health`
+#[derive(Args)]
+pub struct HealthArgs {
+    /// Probes per provider
+    #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u32).range(1..=100))]
+    pub probes: u32,
+}
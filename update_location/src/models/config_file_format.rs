@@ -0,0 +1,27 @@
+use clap::ValueEnum;
+use config::FileFormat;
+
+/// Format of configuration files, for `--config-format`
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+pub enum ConfigFileFormat {
+    #[default]
+    Toml,
+    Json,
+    Json5,
+    Yaml,
+    Ini,
+    Ron,
+}
+
+impl From<ConfigFileFormat> for FileFormat {
+    fn from(format: ConfigFileFormat) -> Self {
+        match format {
+            ConfigFileFormat::Toml => FileFormat::Toml,
+            ConfigFileFormat::Json => FileFormat::Json,
+            ConfigFileFormat::Json5 => FileFormat::Json5,
+            ConfigFileFormat::Yaml => FileFormat::Yaml,
+            ConfigFileFormat::Ini => FileFormat::Ini,
+            ConfigFileFormat::Ron => FileFormat::Ron,
+        }
+    }
+}
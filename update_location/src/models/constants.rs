@@ -1 +1,26 @@
 pub const ENV_PREFIX: &str = "MYAPP";
+
+/// Environment variable selecting the config profile when `--profile` is not given.
+pub const PROFILE_ENV: &str = "MYAPP_PROFILE";
+
+/// Separator between the sections of a nested key in environment variables,
+/// e.g. `MYAPP_IPROYAL__CLIENT_ID` sets `iproyal.client_id`.
+pub const ENV_SEPARATOR: &str = "__";
+
+/// Separator of list values in environment variables.
+pub const ENV_LIST_SEPARATOR: &str = ",";
+
+/// Keys whose environment values are split on [`ENV_LIST_SEPARATOR`] into lists.
+///
+/// Every other value is kept as a single scalar, so commas in tokens or
+/// passwords survive untouched.
+pub const ENV_LIST_KEYS: &[&str] = &["kafka.brokers", "output.sinks", "dedup.prefer", "notify.email.to"];
+
+/// Keys moved to another name, with their replacement.
+///
+/// A deprecated key still applies, with a warning, unless its replacement is also set.
+pub const DEPRECATED_KEYS: &[(&str, &str)] = &[
+    ("export.dir", "output.dir"),
+    ("export.formats", "output.formats"),
+    ("export.compression", "output.compression"),
+];
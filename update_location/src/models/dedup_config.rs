@@ -0,0 +1,44 @@
+use serde::Deserialize;
+use crate::unified::Provider;
+
+#[derive(Deserialize, Default, Debug, Clone)]
+/// Represents how records several providers report for the same location are resolved.
+pub struct DedupConfig {
+    /// Providers in order of preference; unlisted ones follow in their usual order.
+    #[serde(default)]
+    prefer: Vec<Provider>,
+
+    /// Folds the figures of the other providers into the kept record; unset keeps them as reported.
+    #[serde(default)]
+    merge: Option<DedupMerge>,
+}
+
+/// How the kept record of a duplicate location combines the figures of the others.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DedupMerge {
+    /// Node estimates of all providers added up.
+    SumNodes,
+    /// The largest node count and availability of any provider.
+    MaxAvailability,
+}
+
+impl DedupConfig {
+    pub fn new(prefer: Vec<Provider>, merge: Option<DedupMerge>) -> Self {
+        Self { prefer, merge }
+    }
+
+    /// Get the rank of `provider`, lower being preferred
+    pub fn rank(&self, provider: Provider) -> usize {
+        let fallback = Provider::ALL.iter().position(|p| *p == provider).unwrap_or_default();
+        match self.prefer.iter().position(|p| *p == provider) {
+            Some(rank) => rank,
+            None => self.prefer.len() + fallback,
+        }
+    }
+
+    /// Get how figures of duplicates are merged
+    pub fn get_merge(&self) -> Option<DedupMerge> {
+        self.merge
+    }
+}
@@ -1,13 +1,145 @@
+use std::fmt;
+use std::path::PathBuf;
 use thiserror::Error;
+use crate::unified::Provider;
 
 #[derive(Error, Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum ConfigError {
     #[error("failed to build config: {0}")]
     BuildConfigError(#[from] config::ConfigError),
 
+    #[error("invalid configuration, {0}")]
+    InvalidConfig(#[from] crate::init::ConfigReport),
+
     #[error("failed to deserialize config: {source}")]
     DeserializeConfigError {
         #[source]
         source: config::ConfigError,
     },
-}
\ No newline at end of file
+
+    #[error("failed to read config directory {path}: {source}")]
+    ConfigDirError {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("profile `{name}` not found: expected a [profiles.{name}] section in the config files")]
+    UnknownProfile { name: String },
+
+    #[error("failed to read secret file {path}: {source}")]
+    SecretFileError {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    #[error("failed to resolve secret reference: {0}")]
+    SecretRefError(#[from] crate::secrets::SecretError),
+
+    #[error("failed to read env file {path}: {source}")]
+    EnvFileError {
+        path: PathBuf,
+        #[source]
+        source: dotenvy::Error,
+    },
+}
+
+/// What went wrong in a provider query, independently of the vendor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderErrorKind {
+    /// Credentials missing or rejected.
+    Auth,
+    /// Connection, proxy, TLS or transfer failure.
+    Network,
+    /// A request timed out.
+    Timeout,
+    /// The response does not match the expected schema.
+    Decode,
+    /// The vendor asked to slow down (HTTP 429).
+    RateLimited,
+    /// The vendor answered with an error status or an inconsistent dataset.
+    Server,
+}
+
+impl ProviderErrorKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProviderErrorKind::Auth => "auth",
+            ProviderErrorKind::Network => "network",
+            ProviderErrorKind::Timeout => "timeout",
+            ProviderErrorKind::Decode => "decode",
+            ProviderErrorKind::RateLimited => "rate limited",
+            ProviderErrorKind::Server => "server",
+        }
+    }
+
+    /// Classifies an HTTP status the vendor answered with.
+    pub fn from_status(status: reqwest::StatusCode) -> Self {
+        match status {
+            reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => ProviderErrorKind::Auth,
+            reqwest::StatusCode::TOO_MANY_REQUESTS => ProviderErrorKind::RateLimited,
+            reqwest::StatusCode::REQUEST_TIMEOUT | reqwest::StatusCode::GATEWAY_TIMEOUT => ProviderErrorKind::Timeout,
+            _ => ProviderErrorKind::Server,
+        }
+    }
+
+    /// Classifies a `reqwest` failure.
+    pub fn from_reqwest(err: &reqwest::Error) -> Self {
+        if err.is_timeout() {
+            ProviderErrorKind::Timeout
+        } else if err.is_decode() {
+            ProviderErrorKind::Decode
+        } else if let Some(status) = err.status() {
+            Self::from_status(status)
+        } else {
+            ProviderErrorKind::Network
+        }
+    }
+}
+
+impl fmt::Display for ProviderErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A failed query of any provider, classified so reports, retries and exit codes
+/// can treat all vendors alike. Displays as the vendor-specific error it wraps.
+///
+/// Built from each vendor's query error, e.g. `IPRoyalQueryError` or
+/// `InfaticaQueryError`, which know the endpoint they failed on.
+#[derive(Debug, Error)]
+#[error("{source}")]
+pub struct ProviderError {
+    pub kind: ProviderErrorKind,
+    pub provider: Provider,
+    /// Endpoint path relative to the provider's base URL; `None` when the failure
+    /// precedes any request, such as an invalid proxy setting.
+    pub endpoint: Option<&'static str>,
+    /// Whether repeating the query may succeed.
+    pub retryable: bool,
+    source: Box<dyn std::error::Error + Send + Sync>,
+}
+
+impl ProviderError {
+    pub fn new(
+        kind: ProviderErrorKind,
+        provider: Provider,
+        endpoint: Option<&'static str>,
+        source: impl Into<Box<dyn std::error::Error + Send + Sync>>,
+    ) -> Self {
+        let retryable = matches!(
+            kind,
+            ProviderErrorKind::Network | ProviderErrorKind::Timeout | ProviderErrorKind::RateLimited | ProviderErrorKind::Server
+        );
+        Self { kind, provider, endpoint, retryable, source: source.into() }
+    }
+
+    /// Overrides whether the query may succeed when repeated.
+    pub fn retryable(mut self, retryable: bool) -> Self {
+        self.retryable = retryable;
+        self
+    }
+}
@@ -0,0 +1,60 @@
+use std::time::Duration;
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize, Default, Debug)]
+/// Represents the retention of `export` snapshots; what is written, and where,
+/// is configured under `[output]`, see [`crate::models::OutputConfig`].
+///
+/// Without `keep_last` and `keep_days`, every snapshot is kept. With either set,
+/// a snapshot is pruned once no rule keeps it; the latest one is always kept.
+pub struct ExportConfig {
+    /// Keep this many of the newest snapshots.
+    #[serde(default)]
+    keep_last: Option<usize>,
+
+    /// Keep snapshots younger than this many days.
+    #[serde(default)]
+    keep_days: Option<u64>,
+}
+
+/// File format of an exported artifact.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// One JSON array of unified records
+    Json,
+    /// One unified record per line
+    Ndjson,
+    /// Flat table with a header row
+    Csv,
+    /// IPRoyal's location tree as returned by its API (country → state/city → ISP, with prefixes)
+    #[serde(rename = "tree-json")]
+    TreeJson,
+    /// Records added, changed or removed since the previous snapshot
+    #[serde(rename = "delta-json")]
+    DeltaJson,
+}
+
+/// Compression applied to exported artifacts.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportCompression {
+    #[default]
+    None,
+    /// `.gz`, readable by every toolchain
+    Gzip,
+    /// `.zst`, smaller and faster
+    Zstd,
+}
+
+impl ExportConfig {
+    pub fn get_keep_last(&self) -> Option<usize> {
+        self.keep_last
+    }
+
+    /// Get the maximum age of kept snapshots
+    pub fn get_keep_age(&self) -> Option<Duration> {
+        self.keep_days.map(|days| Duration::from_secs(days * 24 * 60 * 60))
+    }
+}
@@ -0,0 +1,17 @@
+use std::path::{Path, PathBuf};
+use serde::Deserialize;
+
+#[derive(Deserialize, Default, Debug)]
+/// Represents configuration for offline coordinate enrichment.
+pub struct GeoConfig {
+    /// GeoNames cities dump; enrichment is skipped when unset.
+    #[serde(default)]
+    dataset: Option<PathBuf>,
+}
+
+impl GeoConfig {
+    /// Get the configured GeoNames dataset path
+    pub fn get_dataset(&self) -> Option<&Path> {
+        self.dataset.as_deref()
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use serde::Deserialize;
+use crate::models::{ProxyConfig, Redacted};
+
+#[derive(Deserialize, Default, Clone)]
+/// Represents HTTP client settings shared by all providers.
+///
+/// Provider sections may override the proxy (`iproyal.proxy` replaces `http.proxy`
+/// for iproyal requests) and add headers (`iproyal.headers` is merged over
+/// `http.extra_headers`). `Debug` masks header values, which often carry API keys.
+pub struct HttpConfig {
+    /// Proxy for vendor requests; unset falls back to the `HTTPS_PROXY` / `HTTP_PROXY` /
+    /// `ALL_PROXY` and `NO_PROXY` environment variables.
+    #[serde(default)]
+    proxy: Option<ProxyConfig>,
+
+    /// PEM file of additional trusted root certificates, e.g. a corporate interception CA.
+    #[serde(default)]
+    ca_bundle: Option<PathBuf>,
+
+    /// PEM client certificate (chain) for vendors requiring mutual TLS.
+    #[serde(default)]
+    client_cert: Option<PathBuf>,
+
+    /// PKCS#8 PEM private key of `client_cert`.
+    #[serde(default)]
+    client_key: Option<PathBuf>,
+
+    /// `User-Agent` sent with every request; defaults to `update_location/<version>`.
+    #[serde(default)]
+    user_agent: Option<String>,
+
+    /// Static headers sent with every request.
+    #[serde(default)]
+    extra_headers: BTreeMap<String, String>,
+
+    /// Limit for connection setup (TCP and TLS handshake) per request.
+    #[serde(default, with = "humantime_serde::option")]
+    connect_timeout: Option<Duration>,
+
+    /// Limit for each read from the connection; the provider `timeout` bounds the whole request.
+    #[serde(default, with = "humantime_serde::option")]
+    read_timeout: Option<Duration>,
+
+    /// Largest accepted response body; larger responses fail instead of being buffered.
+    #[serde(default)]
+    max_response_bytes: Option<u64>,
+
+    /// Accept any server certificate. Only for debugging; prefer `ca_bundle`.
+    #[serde(default)]
+    insecure_skip_verify: bool,
+}
+
+impl HttpConfig {
+    /// Settings for one provider: these, with its own proxy and extra headers
+    pub fn for_provider(&self, proxy: Option<&ProxyConfig>, headers: &BTreeMap<String, String>) -> Self {
+        let mut http = self.clone();
+        if let Some(proxy) = proxy {
+            http.proxy = Some(proxy.clone());
+        }
+        http.extra_headers.extend(headers.iter().map(|(k, v)| (k.clone(), v.clone())));
+        http
+    }
+
+    /// Get the configured proxy
+    pub fn get_proxy(&self) -> Option<&ProxyConfig> {
+        self.proxy.as_ref()
+    }
+
+    /// Get the configured CA bundle
+    pub fn get_ca_bundle(&self) -> Option<&Path> {
+        self.ca_bundle.as_deref()
+    }
+
+    /// Get the client certificate and key paths, when both are configured
+    pub fn get_client_identity(&self) -> Option<(&Path, &Path)> {
+        Some((self.client_cert.as_deref()?, self.client_key.as_deref()?))
+    }
+
+    /// Get the configured User-Agent
+    pub fn get_user_agent(&self) -> Option<&str> {
+        self.user_agent.as_deref()
+    }
+
+    /// Get the headers sent with every request
+    pub fn get_extra_headers(&self) -> &BTreeMap<String, String> {
+        &self.extra_headers
+    }
+
+    /// Get the configured connect timeout
+    pub fn get_connect_timeout(&self) -> Option<Duration> {
+        self.connect_timeout
+    }
+
+    /// Get the configured read timeout
+    pub fn get_read_timeout(&self) -> Option<Duration> {
+        self.read_timeout
+    }
+
+    /// Get the configured response size limit
+    pub fn get_max_response_bytes(&self) -> Option<u64> {
+        self.max_response_bytes
+    }
+
+    /// Whether server certificate verification is disabled
+    pub fn get_insecure_skip_verify(&self) -> bool {
+        self.insecure_skip_verify
+    }
+}
+
+impl fmt::Debug for HttpConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let headers: BTreeMap<&str, Redacted> = self
+            .extra_headers
+            .iter()
+            .map(|(name, value)| (name.as_str(), Redacted(Some(value))))
+            .collect();
+
+        f.debug_struct("HttpConfig")
+            .field("proxy", &self.proxy)
+            .field("ca_bundle", &self.ca_bundle)
+            .field("client_cert", &self.client_cert)
+            .field("client_key", &self.client_key)
+            .field("user_agent", &self.user_agent)
+            .field("extra_headers", &headers)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("read_timeout", &self.read_timeout)
+            .field("max_response_bytes", &self.max_response_bytes)
+            .field("insecure_skip_verify", &self.insecure_skip_verify)
+            .finish()
+    }
+}
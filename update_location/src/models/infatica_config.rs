@@ -1,18 +1,124 @@
 use url::Url;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
 use std::time::Duration;
 use serde::Deserialize;
+use crate::infatica::zip_validation::{ZipAction, ZipPattern};
+use crate::cache;
+use crate::models::{read_secret_file, ConfigError, HttpConfig, ProxyConfig, Redacted, RetryPolicy};
 
-#[derive(Deserialize)]
+/// Country requests in flight at once when `country_concurrency` is not configured.
+const DEFAULT_COUNTRY_CONCURRENCY: usize = 4;
+
+#[derive(Deserialize, Clone)]
 /// Represents configuration for interacting with the IPRoyal API.
+///
+/// The password may instead be read from `password_file` (e.g. a mounted secret).
+/// `Debug` masks the password, so the config can be logged.
 pub struct InfaticaConfig {
     endpoint: Url,
     email: String,
+    #[serde(default)]
     password: String,
+    #[serde(default)]
+    password_file: Option<PathBuf>,
     #[serde(default, with = "humantime_serde::option")]
     timeout: Option<Duration>,
+
+    /// Upper bound of the whole fetch, all queries and pages included; unset waits indefinitely.
+    #[serde(default, with = "humantime_serde::option")]
+    fetch_timeout: Option<Duration>,
+
+    /// Refetches after a failure whose errors are all retryable; none by default.
+    #[serde(default)]
+    retries: Option<u32>,
+
+    /// Delay before the first retry, doubled for each further one.
+    #[serde(default, with = "humantime_serde::option")]
+    retry_backoff: Option<Duration>,
+
+    /// Upper bound of the delay between retries.
+    #[serde(default, with = "humantime_serde::option")]
+    retry_max_delay: Option<Duration>,
+
+    /// Countries (ISO 3166-1 alpha-2) to fetch geo nodes for, one request each;
+    /// empty fetches every country in a single request.
+    #[serde(default)]
+    countries: Vec<String>,
+
+    /// Country requests in flight at once.
+    #[serde(default)]
+    country_concurrency: Option<usize>,
+
+    /// Postal code formats by country, added to and overriding the built-in ones.
+    #[serde(default)]
+    zip_patterns: BTreeMap<String, ZipPattern>,
+
+    /// What happens to ZIP codes not matching their country's format.
+    #[serde(default)]
+    zip_action: ZipAction,
+
+    /// Proxy for this provider; unset inherits `http.proxy`.
+    #[serde(default)]
+    proxy: Option<ProxyConfig>,
+
+    /// Headers for this provider's requests, merged over `http.extra_headers`.
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+
+    /// Effective HTTP settings, set by [`Self::inherit_http`].
+    #[serde(skip)]
+    http: HttpConfig,
 }
 
 impl InfaticaConfig {
+    /// Config with the given credentials, everything else at its default
+    pub fn new(endpoint: Url, email: impl Into<String>, password: impl Into<String>) -> Self {
+        Self {
+            endpoint,
+            email: email.into(),
+            password: password.into(),
+            password_file: None,
+            timeout: None,
+            fetch_timeout: None,
+            retries: None,
+            retry_backoff: None,
+            retry_max_delay: None,
+            countries: Vec::new(),
+            country_concurrency: None,
+            zip_patterns: BTreeMap::new(),
+            zip_action: ZipAction::default(),
+            proxy: None,
+            headers: BTreeMap::new(),
+            http: HttpConfig::default(),
+        }
+    }
+
+    /// Read `password_file` when no password is set directly
+    pub fn resolve_secret_files(&mut self) -> Result<(), ConfigError> {
+        if self.password.is_empty()
+            && let Some(path) = &self.password_file
+        {
+            self.password = read_secret_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// Apply the shared `http` settings with this provider's proxy and headers
+    pub fn inherit_http(&mut self, http: &HttpConfig) {
+        self.http = http.for_provider(self.proxy.as_ref(), &self.headers);
+    }
+
+    /// Check that credentials are not blank
+    pub fn check(&self) -> Result<(), String> {
+        match (self.email.trim().is_empty(), self.password.trim().is_empty()) {
+            (false, false) => Ok(()),
+            (true, _) => Err("`email` is empty".to_string()),
+            (false, true) => Err("`password` is empty".to_string()),
+        }
+    }
+
     /// Get the configured endpoint
     pub fn get_endpoint(&self) -> &Url {
         &self.endpoint
@@ -32,4 +138,80 @@ impl InfaticaConfig {
     pub fn get_timeout(&self) -> Option<&Duration> {
         self.timeout.as_ref()
     }
-}
\ No newline at end of file
+
+    /// Limit the whole fetch, overriding `fetch_timeout`
+    pub(crate) fn set_fetch_timeout(&mut self, limit: Duration) {
+        self.fetch_timeout = Some(limit);
+    }
+
+    /// Get the configured limit of the whole fetch
+    pub fn get_fetch_timeout(&self) -> Option<Duration> {
+        self.fetch_timeout
+    }
+
+    /// Retry failed fetches up to `retries` times, overriding `retries`
+    pub(crate) fn set_retries(&mut self, retries: u32) {
+        self.retries = Some(retries);
+    }
+
+    /// Get how failed fetches are retried
+    pub fn get_retry_policy(&self) -> RetryPolicy {
+        RetryPolicy::new(self.retries.unwrap_or_default(), self.retry_backoff, self.retry_max_delay)
+    }
+
+    /// Get the countries whose geo nodes are fetched separately
+    pub fn get_countries(&self) -> &[String] {
+        &self.countries
+    }
+
+    /// Fetch the geo nodes of `countries` separately, overriding `countries`
+    pub(crate) fn set_countries(&mut self, countries: Vec<String>) {
+        self.countries = countries;
+    }
+
+    /// Get the number of country requests in flight at once
+    pub fn get_country_concurrency(&self) -> usize {
+        self.country_concurrency.unwrap_or(DEFAULT_COUNTRY_CONCURRENCY)
+    }
+
+    /// Get the configured postal code formats by country
+    pub fn get_zip_patterns(&self) -> &BTreeMap<String, ZipPattern> {
+        &self.zip_patterns
+    }
+
+    /// Get what happens to malformed ZIP codes
+    pub fn get_zip_action(&self) -> ZipAction {
+        self.zip_action
+    }
+
+    /// Get the effective HTTP settings for this provider
+    pub fn get_http(&self) -> &HttpConfig {
+        &self.http
+    }
+
+    /// Get the cache key of the settings that change what a fetch returns
+    pub fn get_cache_key(&self) -> String {
+        cache::key(&(self.endpoint.as_str(), &self.email, &self.password, self.get_countries()))
+    }
+}
+
+impl fmt::Debug for InfaticaConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InfaticaConfig")
+            .field("endpoint", &self.endpoint.as_str())
+            .field("email", &self.email)
+            .field("password", &Redacted(Some(&self.password)))
+            .field("password_file", &self.password_file)
+            .field("timeout", &self.timeout)
+            .field("fetch_timeout", &self.fetch_timeout)
+            .field("retries", &self.retries)
+            .field("retry_backoff", &self.retry_backoff)
+            .field("retry_max_delay", &self.retry_max_delay)
+            .field("countries", &self.countries)
+            .field("country_concurrency", &self.country_concurrency)
+            .field("zip_patterns", &self.zip_patterns)
+            .field("zip_action", &self.zip_action)
+            .field("http", &self.http)
+            .finish()
+    }
+}
@@ -1,30 +1,226 @@
 use url::Url;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::PathBuf;
 use std::time::Duration;
 use serde::Deserialize;
+use crate::cache;
+use crate::models::{read_secret_file, ConfigError, HttpConfig, ProxyConfig, Redacted, RetryPolicy};
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 /// Represents configuration for interacting with the IPRoyal API.
+///
+/// Authenticate either with a long-lived `token`, or with `client_id` / `client_secret`
+/// exchanged at `token_endpoint` for short-lived bearer tokens. Secrets may instead be
+/// read from `token_file` / `client_secret_file` (e.g. mounted secrets).
+///
+/// `Debug` masks `token` and `client_secret`, so the config can be logged.
 pub struct IPRoyalConfig {
     endpoint: Url,
-    token: String,
+    #[serde(default)]
+    token: Option<String>,
+    #[serde(default)]
+    token_file: Option<PathBuf>,
+
+    #[serde(default)]
+    client_id: Option<String>,
+    #[serde(default)]
+    client_secret: Option<String>,
+    #[serde(default)]
+    client_secret_file: Option<PathBuf>,
+    #[serde(default)]
+    token_endpoint: Option<Url>,
 
     #[serde(default, with = "humantime_serde::option")]
     timeout: Option<Duration>,
+
+    /// Upper bound of the whole fetch, all queries and pages included; unset waits indefinitely.
+    #[serde(default, with = "humantime_serde::option")]
+    fetch_timeout: Option<Duration>,
+
+    /// Refetches after a failure whose errors are all retryable; none by default.
+    #[serde(default)]
+    retries: Option<u32>,
+
+    /// Delay before the first retry, doubled for each further one.
+    #[serde(default, with = "humantime_serde::option")]
+    retry_backoff: Option<Duration>,
+
+    /// Upper bound of the delay between retries.
+    #[serde(default, with = "humantime_serde::option")]
+    retry_max_delay: Option<Duration>,
+
+    /// Proxy for this provider; unset inherits `http.proxy`.
+    #[serde(default)]
+    proxy: Option<ProxyConfig>,
+
+    /// Headers for this provider's requests, merged over `http.extra_headers`.
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+
+    /// Effective HTTP settings, set by [`Self::inherit_http`].
+    #[serde(skip)]
+    http: HttpConfig,
+
+    /// Countries per page for `access/countries`; unset requests the whole tree at once.
+    #[serde(default)]
+    page_size: Option<u32>,
 }
 
 impl IPRoyalConfig {
+    /// Config authenticating with a static `token`, everything else at its default
+    pub fn new(endpoint: Url, token: impl Into<String>) -> Self {
+        Self {
+            endpoint,
+            token: Some(token.into()),
+            token_file: None,
+            client_id: None,
+            client_secret: None,
+            client_secret_file: None,
+            token_endpoint: None,
+            timeout: None,
+            fetch_timeout: None,
+            retries: None,
+            retry_backoff: None,
+            retry_max_delay: None,
+            proxy: None,
+            headers: BTreeMap::new(),
+            http: HttpConfig::default(),
+            page_size: None,
+        }
+    }
+
+    /// Read `token_file` / `client_secret_file` for secrets not set directly
+    pub fn resolve_secret_files(&mut self) -> Result<(), ConfigError> {
+        if self.token.is_none()
+            && let Some(path) = &self.token_file
+        {
+            self.token = Some(read_secret_file(path)?);
+        }
+        if self.client_secret.is_none()
+            && let Some(path) = &self.client_secret_file
+        {
+            self.client_secret = Some(read_secret_file(path)?);
+        }
+        Ok(())
+    }
+
+    /// Apply the shared `http` settings with this provider's proxy and headers
+    pub fn inherit_http(&mut self, http: &HttpConfig) {
+        self.http = http.for_provider(self.proxy.as_ref(), &self.headers);
+    }
+
+    /// Check that a complete authentication method is configured
+    pub fn check(&self) -> Result<(), String> {
+        if self.token.is_some() {
+            return Ok(());
+        }
+
+        let missing: Vec<&str> = [
+            ("client_id", self.client_id.is_none()),
+            ("client_secret", self.client_secret.is_none()),
+            ("token_endpoint", self.token_endpoint.is_none()),
+        ]
+        .into_iter()
+        .filter_map(|(name, missing)| missing.then_some(name))
+        .collect();
+
+        match missing.as_slice() {
+            [] => Ok(()),
+            _ => Err(format!("set `token`, or `client_id`, `client_secret` and `token_endpoint` (missing: {})", missing.join(", "))),
+        }
+    }
+
     /// Get the configured endpoint
     pub fn get_endpoint(&self) -> &Url {
         &self.endpoint
     }
 
-    /// Get the configured token
-    pub fn get_token(&self) -> &str {
-        &self.token
+    /// Get the configured static token
+    pub fn get_token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    /// Get the configured OAuth client id
+    pub fn get_client_id(&self) -> Option<&str> {
+        self.client_id.as_deref()
+    }
+
+    /// Get the configured OAuth client secret
+    pub fn get_client_secret(&self) -> Option<&str> {
+        self.client_secret.as_deref()
+    }
+
+    /// Get the configured token exchange endpoint
+    pub fn get_token_endpoint(&self) -> Option<&Url> {
+        self.token_endpoint.as_ref()
     }
 
     /// Get the configured timeout
     pub fn get_timeout(&self) -> Option<&Duration> {
         self.timeout.as_ref()
     }
-}
\ No newline at end of file
+
+    /// Limit the whole fetch, overriding `fetch_timeout`
+    pub(crate) fn set_fetch_timeout(&mut self, limit: Duration) {
+        self.fetch_timeout = Some(limit);
+    }
+
+    /// Get the configured limit of the whole fetch
+    pub fn get_fetch_timeout(&self) -> Option<Duration> {
+        self.fetch_timeout
+    }
+
+    /// Retry failed fetches up to `retries` times, overriding `retries`
+    pub(crate) fn set_retries(&mut self, retries: u32) {
+        self.retries = Some(retries);
+    }
+
+    /// Get how failed fetches are retried
+    pub fn get_retry_policy(&self) -> RetryPolicy {
+        RetryPolicy::new(self.retries.unwrap_or_default(), self.retry_backoff, self.retry_max_delay)
+    }
+
+    /// Get the configured page size
+    pub fn get_page_size(&self) -> Option<u32> {
+        self.page_size
+    }
+
+    /// Get the effective HTTP settings for this provider
+    pub fn get_http(&self) -> &HttpConfig {
+        &self.http
+    }
+
+    /// Get the cache key of the settings that change what a fetch returns
+    pub fn get_cache_key(&self) -> String {
+        cache::key(&(
+            self.endpoint.as_str(),
+            &self.token,
+            &self.client_id,
+            &self.client_secret,
+            self.token_endpoint.as_ref().map(Url::as_str),
+            self.page_size,
+        ))
+    }
+}
+
+impl fmt::Debug for IPRoyalConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IPRoyalConfig")
+            .field("endpoint", &self.endpoint.as_str())
+            .field("token", &Redacted(self.token.as_deref()))
+            .field("token_file", &self.token_file)
+            .field("client_id", &self.client_id)
+            .field("client_secret", &Redacted(self.client_secret.as_deref()))
+            .field("client_secret_file", &self.client_secret_file)
+            .field("token_endpoint", &self.token_endpoint.as_ref().map(Url::as_str))
+            .field("timeout", &self.timeout)
+            .field("fetch_timeout", &self.fetch_timeout)
+            .field("retries", &self.retries)
+            .field("retry_backoff", &self.retry_backoff)
+            .field("retry_max_delay", &self.retry_max_delay)
+            .field("http", &self.http)
+            .field("page_size", &self.page_size)
+            .finish()
+    }
+}
@@ -0,0 +1,66 @@
+// Only read by the Kafka sink; builds without the `kafka` feature reject the section during validation
+#![cfg_attr(not(feature = "kafka"), allow(dead_code))]
+
+use std::time::Duration;
+use serde::Deserialize;
+
+/// Default bound on connecting and producing one run's change events.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Deserialize, Debug, Clone)]
+/// Represents configuration for publishing change events to Kafka (feature `kafka`).
+pub struct KafkaConfig {
+    /// Bootstrap brokers as `host:port`.
+    brokers: Vec<String>,
+
+    topic: String,
+
+    /// What the message key is derived from, which decides partitioning.
+    #[serde(default)]
+    key: KafkaKey,
+
+    #[serde(default)]
+    client_id: Option<String>,
+
+    #[serde(default, with = "humantime_serde::option")]
+    timeout: Option<Duration>,
+}
+
+/// Message key scheme. Keys are partitioned like the Java client's default
+/// partitioner, so events of one key stay ordered within a partition.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum KafkaKey {
+    /// `provider/country/subdivision/city/isp`: events of one location stay in order.
+    #[default]
+    Location,
+    /// `provider`
+    Provider,
+    /// `provider/country`
+    Country,
+    /// No key; events are spread across partitions.
+    None,
+}
+
+impl KafkaConfig {
+    pub fn get_brokers(&self) -> &[String] {
+        &self.brokers
+    }
+
+    pub fn get_topic(&self) -> &str {
+        &self.topic
+    }
+
+    pub fn get_key(&self) -> KafkaKey {
+        self.key
+    }
+
+    pub fn get_client_id(&self) -> Option<&str> {
+        self.client_id.as_deref()
+    }
+
+    /// Get the configured timeout, defaulting to 30 seconds
+    pub fn get_timeout(&self) -> Duration {
+        self.timeout.unwrap_or(DEFAULT_TIMEOUT)
+    }
+}
@@ -0,0 +1,27 @@
+use serde::Deserialize;
+
+/// Default minimum Jaro-Winkler similarity for two city names to be considered the same.
+const DEFAULT_THRESHOLD: f64 = 0.92;
+
+#[derive(Deserialize, Default, Debug)]
+/// Represents configuration for cross-provider city matching.
+pub struct MatchingConfig {
+    #[serde(default)]
+    threshold: Option<f64>,
+
+    /// Match and export cities in other scripts by their ASCII transliteration; needs the `translit` feature.
+    #[serde(default)]
+    transliterate: bool,
+}
+
+impl MatchingConfig {
+    /// Get the configured similarity threshold
+    pub fn get_threshold(&self) -> f64 {
+        self.threshold.unwrap_or(DEFAULT_THRESHOLD)
+    }
+
+    /// Get whether city names are transliterated to ASCII
+    pub fn get_transliterate(&self) -> bool {
+        self.transliterate
+    }
+}
@@ -2,11 +2,53 @@ mod app_config;
 mod iproyal_config;
 mod errors;
 mod cli_args;
+mod cli_duration;
+mod command;
 pub mod constants;
 mod infatica_config;
+mod cache_config;
+mod matching_config;
+mod alert_rule;
+mod geo_config;
+mod secret_file;
+mod redacted;
+mod config_file_format;
+mod proxy_config;
+mod http_config;
+mod kafka_config;
+mod nats_config;
+mod export_config;
+mod output_config;
+mod run_config;
+mod quota_config;
+mod retry_policy;
+mod dedup_config;
+mod notify_config;
 
-pub use crate::models::errors::ConfigError;
+pub use crate::models::errors::{ConfigError, ProviderError, ProviderErrorKind};
 pub use app_config::AppConfig;
 pub use iproyal_config::IPRoyalConfig;
 pub use infatica_config::InfaticaConfig;
 pub use cli_args::CLIArgs;
+pub use cli_duration::CliDuration;
+pub use command::{Command, CompareArgs, ConfigFormat, ExportArgs, ExportSource, HealthArgs, InitArgs, PrintConfigArgs, PruneArgs, ServeArgs, StatsArgs, StatsFormat};
+pub use cache_config::CacheConfig;
+pub use matching_config::MatchingConfig;
+pub use alert_rule::AlertRule;
+pub use geo_config::GeoConfig;
+pub use secret_file::read_secret_file;
+pub use redacted::{Redacted, MASK};
+pub use config_file_format::ConfigFileFormat;
+pub use proxy_config::ProxyConfig;
+pub use http_config::HttpConfig;
+pub use kafka_config::KafkaConfig;
+pub use nats_config::NatsConfig;
+pub use export_config::{ExportCompression, ExportConfig, ExportFormat};
+pub use output_config::{OutputConfig, SinkKind};
+pub use run_config::RunConfig;
+pub use quota_config::{ProviderQuota, QuotaAction, QuotaConfig};
+pub use retry_policy::RetryPolicy;
+pub use dedup_config::{DedupConfig, DedupMerge};
+pub use notify_config::{EmailConfig, EmailTls, NotifyConfig, SlackConfig, TelegramConfig, WebhookConfig};
+#[cfg(feature = "kafka")]
+pub use kafka_config::KafkaKey;
@@ -0,0 +1,272 @@
+use std::fmt;
+use std::time::Duration;
+use serde::Deserialize;
+use url::Url;
+use crate::models::{Redacted, MASK};
+
+/// Default aggregation window of `serve --refresh`.
+const DEFAULT_WINDOW: Duration = Duration::from_secs(15 * 60);
+
+/// Default number of changes listed per message.
+const DEFAULT_TOP: usize = 10;
+
+/// Default bound on delivering one message.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default Telegram Bot API endpoint.
+const DEFAULT_TELEGRAM_API: &str = "https://api.telegram.org/";
+
+/// Default subject of email notifications; see [`crate::notify::template`].
+const DEFAULT_EMAIL_SUBJECT: &str =
+    "Coverage changes: {added} added, {removed} removed, {count_changed} changed, {violations} alert(s) violated";
+
+/// Default body of email notifications.
+const DEFAULT_EMAIL_BODY: &str = "{summary}";
+
+#[derive(Deserialize, Default, Debug, Clone)]
+/// Represents the notification channels of coverage changes; each configured channel is enabled.
+pub struct NotifyConfig {
+    /// Period over which `serve --refresh` collects changes into one message.
+    #[serde(default, with = "humantime_serde::option")]
+    window: Option<Duration>,
+
+    /// Changes listed per message, largest node difference first.
+    #[serde(default)]
+    top: Option<usize>,
+
+    #[serde(default, with = "humantime_serde::option")]
+    timeout: Option<Duration>,
+
+    /// Print messages on stdout.
+    #[serde(default)]
+    stdout: bool,
+
+    #[serde(default)]
+    webhook: Option<WebhookConfig>,
+
+    #[serde(default)]
+    slack: Option<SlackConfig>,
+
+    #[serde(default)]
+    telegram: Option<TelegramConfig>,
+
+    /// Needs the `email` feature; builds without it reject the section during validation.
+    #[serde(default)]
+    email: Option<EmailConfig>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+/// Webhook receiving each message as JSON.
+pub struct WebhookConfig {
+    url: Url,
+}
+
+#[derive(Deserialize, Clone)]
+/// Slack incoming webhook.
+pub struct SlackConfig {
+    /// The webhook URL embeds its credentials.
+    webhook_url: Url,
+}
+
+#[derive(Deserialize, Clone)]
+/// Telegram bot posting to one chat.
+pub struct TelegramConfig {
+    bot_token: String,
+    /// Chat id, or `@channelname` for public channels.
+    chat_id: String,
+    /// Bot API endpoint, e.g. a local Bot API server.
+    #[serde(default)]
+    api_url: Option<Url>,
+}
+
+#[derive(Deserialize, Clone)]
+/// SMTP relay sending each message as a plain-text email (feature `email`).
+pub struct EmailConfig {
+    host: String,
+
+    #[serde(default)]
+    port: Option<u16>,
+
+    #[serde(default)]
+    tls: EmailTls,
+
+    #[serde(default)]
+    username: Option<String>,
+
+    #[serde(default)]
+    password: Option<String>,
+
+    /// Sender mailbox, e.g. `Coverage <coverage@example.com>`.
+    from: String,
+
+    /// Recipient mailboxes.
+    to: Vec<String>,
+
+    /// Templates of the subject and body; see [`crate::notify::template`].
+    #[serde(default)]
+    subject: Option<String>,
+
+    #[serde(default)]
+    body: Option<String>,
+}
+
+/// How the connection to the SMTP relay is secured.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EmailTls {
+    /// TLS from the start of the connection (SMTPS), on port 465 by default.
+    Implicit,
+    /// Plain connection upgraded with `STARTTLS`, which the relay must offer; port 587 by default.
+    #[default]
+    Starttls,
+    /// Unencrypted, for trusted local relays only; port 25 by default.
+    None,
+}
+
+impl NotifyConfig {
+    /// Whether any channel is configured.
+    pub fn is_enabled(&self) -> bool {
+        self.stdout || self.webhook.is_some() || self.slack.is_some() || self.telegram.is_some() || self.email.is_some()
+    }
+
+    /// Get the aggregation window, defaulting to 15 minutes
+    pub fn get_window(&self) -> Duration {
+        self.window.unwrap_or(DEFAULT_WINDOW)
+    }
+
+    /// Get the number of changes listed per message, defaulting to 10
+    pub fn get_top(&self) -> usize {
+        self.top.unwrap_or(DEFAULT_TOP)
+    }
+
+    /// Get the delivery timeout, defaulting to 30 seconds
+    pub fn get_timeout(&self) -> Duration {
+        self.timeout.unwrap_or(DEFAULT_TIMEOUT)
+    }
+
+    pub fn get_stdout(&self) -> bool {
+        self.stdout
+    }
+
+    pub fn get_webhook(&self) -> Option<&WebhookConfig> {
+        self.webhook.as_ref()
+    }
+
+    pub fn get_slack(&self) -> Option<&SlackConfig> {
+        self.slack.as_ref()
+    }
+
+    pub fn get_telegram(&self) -> Option<&TelegramConfig> {
+        self.telegram.as_ref()
+    }
+
+    pub fn get_email(&self) -> Option<&EmailConfig> {
+        self.email.as_ref()
+    }
+}
+
+impl WebhookConfig {
+    pub fn get_url(&self) -> &Url {
+        &self.url
+    }
+}
+
+impl SlackConfig {
+    pub fn get_webhook_url(&self) -> &Url {
+        &self.webhook_url
+    }
+}
+
+impl TelegramConfig {
+    pub fn get_bot_token(&self) -> &str {
+        &self.bot_token
+    }
+
+    pub fn get_chat_id(&self) -> &str {
+        &self.chat_id
+    }
+
+    /// Get the Bot API endpoint, defaulting to `https://api.telegram.org/`
+    pub fn get_api_url(&self) -> Url {
+        self.api_url.clone().unwrap_or_else(|| DEFAULT_TELEGRAM_API.parse().expect("default Telegram API URL is valid"))
+    }
+}
+
+// Only read by the email notifier
+#[cfg_attr(not(feature = "email"), allow(dead_code))]
+impl EmailConfig {
+    pub fn get_host(&self) -> &str {
+        &self.host
+    }
+
+    /// Get the port, defaulting to that of the TLS mode
+    pub fn get_port(&self) -> u16 {
+        self.port.unwrap_or(match self.tls {
+            EmailTls::Implicit => 465,
+            EmailTls::Starttls => 587,
+            EmailTls::None => 25,
+        })
+    }
+
+    pub fn get_tls(&self) -> EmailTls {
+        self.tls
+    }
+
+    /// Get the credentials, when both the username and password are set
+    pub fn get_credentials(&self) -> Option<(&str, &str)> {
+        self.username.as_deref().zip(self.password.as_deref())
+    }
+
+    pub fn get_from(&self) -> &str {
+        &self.from
+    }
+
+    pub fn get_to(&self) -> &[String] {
+        &self.to
+    }
+
+    /// Get the subject template, defaulting to the change counts
+    pub fn get_subject(&self) -> &str {
+        self.subject.as_deref().unwrap_or(DEFAULT_EMAIL_SUBJECT)
+    }
+
+    /// Get the body template, defaulting to the message other notifiers send
+    pub fn get_body(&self) -> &str {
+        self.body.as_deref().unwrap_or(DEFAULT_EMAIL_BODY)
+    }
+}
+
+impl fmt::Debug for EmailConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EmailConfig")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("tls", &self.tls)
+            .field("username", &self.username)
+            .field("password", &Redacted(self.password.as_deref()))
+            .field("from", &self.from)
+            .field("to", &self.to)
+            .field("subject", &self.subject)
+            .field("body", &self.body)
+            .finish()
+    }
+}
+
+impl fmt::Debug for SlackConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Everything after the host identifies and authorizes the webhook
+        let mut url = self.webhook_url.clone();
+        url.set_path(MASK);
+        f.debug_struct("SlackConfig").field("webhook_url", &url.as_str()).finish()
+    }
+}
+
+impl fmt::Debug for TelegramConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TelegramConfig")
+            .field("bot_token", &Redacted(Some(&self.bot_token)))
+            .field("chat_id", &self.chat_id)
+            .field("api_url", &self.api_url.as_ref().map(Url::as_str))
+            .finish()
+    }
+}
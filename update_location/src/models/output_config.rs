@@ -0,0 +1,119 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use serde::Deserialize;
+use crate::filter::Filter;
+use crate::models::{ExportCompression, ExportFormat};
+
+/// Default export location when `output.dir` is not configured.
+const DEFAULT_DIR: &str = "exports";
+
+/// Default number of change events per sink batch.
+const DEFAULT_BATCH_SIZE: usize = 500;
+
+/// Default wait before a partial batch is written anyway.
+const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Deserialize, Debug)]
+/// Represents configuration shared by every output: exports and change sinks.
+pub struct OutputConfig {
+    /// Directory receiving export snapshots.
+    #[serde(default)]
+    dir: Option<PathBuf>,
+
+    /// Formats written by each export.
+    #[serde(default = "default_formats")]
+    formats: Vec<ExportFormat>,
+
+    /// Compression of the exported artifacts.
+    #[serde(default)]
+    compression: ExportCompression,
+
+    /// Only records matching this expression are exported or published; see [`crate::filter`].
+    #[serde(default)]
+    filter: Option<Filter>,
+
+    /// Sinks to publish to; every configured sink when unset.
+    #[serde(default)]
+    sinks: Option<Vec<SinkKind>>,
+
+    /// Change events handed to the sinks at once.
+    #[serde(default)]
+    batch_size: Option<usize>,
+
+    /// Longest wait for a batch to fill up before it is written anyway.
+    #[serde(default, with = "humantime_serde::option")]
+    flush_interval: Option<Duration>,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            formats: default_formats(),
+            compression: ExportCompression::default(),
+            filter: None,
+            sinks: None,
+            batch_size: None,
+            flush_interval: None,
+        }
+    }
+}
+
+fn default_formats() -> Vec<ExportFormat> {
+    vec![ExportFormat::Json]
+}
+
+/// A change sink, named like its config section.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SinkKind {
+    Kafka,
+    Nats,
+}
+
+impl SinkKind {
+    /// All supported sinks, in a stable order.
+    pub const ALL: [SinkKind; 2] = [SinkKind::Kafka, SinkKind::Nats];
+
+    /// Lowercase name used in config keys and CLI flags.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SinkKind::Kafka => "kafka",
+            SinkKind::Nats => "nats",
+        }
+    }
+}
+
+impl OutputConfig {
+    /// Get the export directory, defaulting to `exports` in the working directory
+    pub fn get_dir(&self) -> &Path {
+        self.dir.as_deref().unwrap_or(Path::new(DEFAULT_DIR))
+    }
+
+    pub fn get_formats(&self) -> &[ExportFormat] {
+        &self.formats
+    }
+
+    pub fn get_compression(&self) -> ExportCompression {
+        self.compression
+    }
+
+    pub fn get_filter(&self) -> Option<&Filter> {
+        self.filter.as_ref()
+    }
+
+    /// Whether a configured `sink` publishes: all do, unless `output.sinks` lists the ones to use.
+    pub fn sink_enabled(&self, sink: SinkKind) -> bool {
+        self.sinks.as_ref().is_none_or(|sinks| sinks.contains(&sink))
+    }
+
+    /// Get the sink batch size, defaulting to 500 events
+    pub fn get_batch_size(&self) -> usize {
+        self.batch_size.unwrap_or(DEFAULT_BATCH_SIZE).max(1)
+    }
+
+    /// Get the flush interval of partial batches, defaulting to 1 second
+    pub fn get_flush_interval(&self) -> Duration {
+        self.flush_interval.unwrap_or(DEFAULT_FLUSH_INTERVAL)
+    }
+}
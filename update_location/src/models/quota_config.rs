@@ -0,0 +1,52 @@
+use serde::Deserialize;
+use crate::unified::Provider;
+
+#[derive(Deserialize, Default, Debug, Clone)]
+/// Represents the daily API request budgets of the providers.
+pub struct QuotaConfig {
+    #[serde(default)]
+    iproyal: Option<ProviderQuota>,
+    #[serde(default)]
+    infatica: Option<ProviderQuota>,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+/// Daily request budget of one provider.
+pub struct ProviderQuota {
+    /// Requests allowed per UTC day, across all runs.
+    daily_limit: u64,
+
+    #[serde(default)]
+    on_exceed: QuotaAction,
+}
+
+/// What a run that would exceed the budget does.
+#[derive(Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum QuotaAction {
+    /// Skip the provider, reporting it as failed.
+    #[default]
+    Refuse,
+    /// Fetch anyway after printing a warning.
+    Warn,
+}
+
+impl QuotaConfig {
+    /// Get the budget of `provider`, if one is configured
+    pub fn get(&self, provider: Provider) -> Option<&ProviderQuota> {
+        match provider {
+            Provider::IPRoyal => self.iproyal.as_ref(),
+            Provider::Infatica => self.infatica.as_ref(),
+        }
+    }
+}
+
+impl ProviderQuota {
+    pub fn get_daily_limit(&self) -> u64 {
+        self.daily_limit
+    }
+
+    pub fn get_on_exceed(&self) -> QuotaAction {
+        self.on_exceed
+    }
+}
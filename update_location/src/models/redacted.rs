@@ -0,0 +1,20 @@
+use std::fmt;
+
+/// Replacement printed for secret values.
+pub const MASK: &str = "********";
+
+/// Debug wrapper printing [`MASK`] instead of a secret.
+///
+/// Unset (`None`) and empty secrets are shown as such, so a missing credential
+/// can still be told apart from a configured one.
+pub struct Redacted<'a>(pub Option<&'a str>);
+
+impl fmt::Debug for Redacted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            None => f.write_str("None"),
+            Some("") => f.write_str("\"\""),
+            Some(_) => f.write_str(MASK),
+        }
+    }
+}
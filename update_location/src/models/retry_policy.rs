@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+/// Delay before the first retry when `retry_backoff` is not configured.
+const DEFAULT_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Longest delay between retries when `retry_max_delay` is not configured.
+const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(30);
+
+/// How a provider's failed fetch is repeated, from its `retries`, `retry_backoff`
+/// and `retry_max_delay` settings.
+///
+/// A fetch is only repeated while all of its errors are retryable. The delay
+/// starts at `backoff` and doubles for each further retry, up to `max_delay`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    retries: u32,
+    backoff: Duration,
+    max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    /// No retries.
+    fn default() -> Self {
+        Self::new(0, None, None)
+    }
+}
+
+impl RetryPolicy {
+    /// Policy of up to `retries` retries; unset delays take their defaults.
+    pub fn new(retries: u32, backoff: Option<Duration>, max_delay: Option<Duration>) -> Self {
+        Self {
+            retries,
+            backoff: backoff.unwrap_or(DEFAULT_BACKOFF),
+            max_delay: max_delay.unwrap_or(DEFAULT_MAX_DELAY),
+        }
+    }
+
+    /// Get the number of retries after the first attempt
+    pub fn get_retries(&self) -> u32 {
+        self.retries
+    }
+
+    /// Delay before retry `retry`, counted from 1.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        self.backoff.saturating_mul(factor).min(self.max_delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delays_double_up_to_the_maximum() {
+        let policy = RetryPolicy::new(6, Some(Duration::from_millis(500)), Some(Duration::from_secs(3)));
+        let delays: Vec<_> = (1..=6).map(|retry| policy.delay(retry)).collect();
+        assert_eq!(delays, [500, 1000, 2000, 3000, 3000, 3000].map(Duration::from_millis));
+        assert_eq!(policy.delay(u32::MAX), Duration::from_secs(3));
+    }
+}
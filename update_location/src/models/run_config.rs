@@ -0,0 +1,17 @@
+use std::time::Duration;
+use serde::Deserialize;
+
+#[derive(Deserialize, Default, Debug)]
+/// Represents configuration for coordinating overlapping runs.
+pub struct RunConfig {
+    /// How long to wait for a run already in progress; unset exits immediately.
+    #[serde(default, with = "humantime_serde::option")]
+    lock_wait: Option<Duration>,
+}
+
+impl RunConfig {
+    /// Get how long to wait for the run lock, zero if not configured
+    pub fn get_lock_wait(&self) -> Duration {
+        self.lock_wait.unwrap_or_default()
+    }
+}
@@ -0,0 +1,9 @@
+use std::path::Path;
+use crate::models::ConfigError;
+
+/// Reads a mounted secret (Docker / Kubernetes style), trimming surrounding whitespace.
+pub fn read_secret_file(path: &Path) -> Result<String, ConfigError> {
+    std::fs::read_to_string(path)
+        .map(|s| s.trim().to_string())
+        .map_err(|source| ConfigError::SecretFileError { path: path.to_path_buf(), source })
+}
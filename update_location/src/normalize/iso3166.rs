@@ -0,0 +1,139 @@
+//! ISO 3166 reference data: country codes, vendor aliases, and a curated
+//! subdivision table for the countries we see most often.
+
+/// Officially assigned ISO 3166-1 alpha-2 codes, plus `XK` (Kosovo, user-assigned
+/// but used by every vendor we consume).
+pub const COUNTRY_CODES: &[&str] = &[
+    "AD", "AE", "AF", "AG", "AI", "AL", "AM", "AO", "AQ", "AR", "AS", "AT", "AU", "AW", "AX", "AZ",
+    "BA", "BB", "BD", "BE", "BF", "BG", "BH", "BI", "BJ", "BL", "BM", "BN", "BO", "BQ", "BR", "BS",
+    "BT", "BV", "BW", "BY", "BZ", "CA", "CC", "CD", "CF", "CG", "CH", "CI", "CK", "CL", "CM", "CN",
+    "CO", "CR", "CU", "CV", "CW", "CX", "CY", "CZ", "DE", "DJ", "DK", "DM", "DO", "DZ", "EC", "EE",
+    "EG", "EH", "ER", "ES", "ET", "FI", "FJ", "FK", "FM", "FO", "FR", "GA", "GB", "GD", "GE", "GF",
+    "GG", "GH", "GI", "GL", "GM", "GN", "GP", "GQ", "GR", "GS", "GT", "GU", "GW", "GY", "HK", "HM",
+    "HN", "HR", "HT", "HU", "ID", "IE", "IL", "IM", "IN", "IO", "IQ", "IR", "IS", "IT", "JE", "JM",
+    "JO", "JP", "KE", "KG", "KH", "KI", "KM", "KN", "KP", "KR", "KW", "KY", "KZ", "LA", "LB", "LC",
+    "LI", "LK", "LR", "LS", "LT", "LU", "LV", "LY", "MA", "MC", "MD", "ME", "MF", "MG", "MH", "MK",
+    "ML", "MM", "MN", "MO", "MP", "MQ", "MR", "MS", "MT", "MU", "MV", "MW", "MX", "MY", "MZ", "NA",
+    "NC", "NE", "NF", "NG", "NI", "NL", "NO", "NP", "NR", "NU", "NZ", "OM", "PA", "PE", "PF", "PG",
+    "PH", "PK", "PL", "PM", "PN", "PR", "PS", "PT", "PW", "PY", "QA", "RE", "RO", "RS", "RU", "RW",
+    "SA", "SB", "SC", "SD", "SE", "SG", "SH", "SI", "SJ", "SK", "SL", "SM", "SN", "SO", "SR", "SS",
+    "ST", "SV", "SX", "SY", "SZ", "TC", "TD", "TF", "TG", "TH", "TJ", "TK", "TL", "TM", "TN", "TO",
+    "TR", "TT", "TV", "TW", "TZ", "UA", "UG", "UM", "US", "UY", "UZ", "VA", "VC", "VE", "VG", "VI",
+    "VN", "VU", "WF", "WS", "XK", "YE", "YT", "ZA", "ZM", "ZW",
+];
+
+/// Non-ISO country codes used by vendors, mapped to their ISO 3166-1 equivalent.
+pub const COUNTRY_ALIASES: &[(&str, &str)] = &[
+    ("UK", "GB"),
+    ("EL", "GR"),
+    ("EN", "GB"),
+    ("USA", "US"),
+    ("GBR", "GB"),
+    ("DEU", "DE"),
+    ("FRA", "FR"),
+    ("RUS", "RU"),
+];
+
+/// Curated ISO 3166-2 subdivisions: `(country, code suffix, names and aliases)`.
+///
+/// Only countries listed here get their subdivisions resolved and validated;
+/// extend the table as new markets show up in the validation report.
+pub const SUBDIVISIONS: &[(&str, &str, &[&str])] = &[
+    // United States
+    ("US", "AL", &["Alabama"]),
+    ("US", "AK", &["Alaska"]),
+    ("US", "AZ", &["Arizona"]),
+    ("US", "AR", &["Arkansas"]),
+    ("US", "CA", &["California"]),
+    ("US", "CO", &["Colorado"]),
+    ("US", "CT", &["Connecticut"]),
+    ("US", "DE", &["Delaware"]),
+    ("US", "DC", &["District of Columbia", "Washington, D.C.", "Washington DC"]),
+    ("US", "FL", &["Florida"]),
+    ("US", "GA", &["Georgia"]),
+    ("US", "HI", &["Hawaii"]),
+    ("US", "ID", &["Idaho"]),
+    ("US", "IL", &["Illinois"]),
+    ("US", "IN", &["Indiana"]),
+    ("US", "IA", &["Iowa"]),
+    ("US", "KS", &["Kansas"]),
+    ("US", "KY", &["Kentucky"]),
+    ("US", "LA", &["Louisiana"]),
+    ("US", "ME", &["Maine"]),
+    ("US", "MD", &["Maryland"]),
+    ("US", "MA", &["Massachusetts"]),
+    ("US", "MI", &["Michigan"]),
+    ("US", "MN", &["Minnesota"]),
+    ("US", "MS", &["Mississippi"]),
+    ("US", "MO", &["Missouri"]),
+    ("US", "MT", &["Montana"]),
+    ("US", "NE", &["Nebraska"]),
+    ("US", "NV", &["Nevada"]),
+    ("US", "NH", &["New Hampshire"]),
+    ("US", "NJ", &["New Jersey"]),
+    ("US", "NM", &["New Mexico"]),
+    ("US", "NY", &["New York"]),
+    ("US", "NC", &["North Carolina"]),
+    ("US", "ND", &["North Dakota"]),
+    ("US", "OH", &["Ohio"]),
+    ("US", "OK", &["Oklahoma"]),
+    ("US", "OR", &["Oregon"]),
+    ("US", "PA", &["Pennsylvania"]),
+    ("US", "RI", &["Rhode Island"]),
+    ("US", "SC", &["South Carolina"]),
+    ("US", "SD", &["South Dakota"]),
+    ("US", "TN", &["Tennessee"]),
+    ("US", "TX", &["Texas"]),
+    ("US", "UT", &["Utah"]),
+    ("US", "VT", &["Vermont"]),
+    ("US", "VA", &["Virginia"]),
+    ("US", "WA", &["Washington"]),
+    ("US", "WV", &["West Virginia"]),
+    ("US", "WI", &["Wisconsin"]),
+    ("US", "WY", &["Wyoming"]),
+    // Canada
+    ("CA", "AB", &["Alberta"]),
+    ("CA", "BC", &["British Columbia"]),
+    ("CA", "MB", &["Manitoba"]),
+    ("CA", "NB", &["New Brunswick"]),
+    ("CA", "NL", &["Newfoundland and Labrador", "Newfoundland"]),
+    ("CA", "NS", &["Nova Scotia"]),
+    ("CA", "NT", &["Northwest Territories"]),
+    ("CA", "NU", &["Nunavut"]),
+    ("CA", "ON", &["Ontario"]),
+    ("CA", "PE", &["Prince Edward Island"]),
+    ("CA", "QC", &["Quebec", "Québec"]),
+    ("CA", "SK", &["Saskatchewan"]),
+    ("CA", "YT", &["Yukon"]),
+    // Germany
+    ("DE", "BW", &["Baden-Württemberg"]),
+    ("DE", "BY", &["Bayern", "Bavaria"]),
+    ("DE", "BE", &["Berlin"]),
+    ("DE", "BB", &["Brandenburg"]),
+    ("DE", "HB", &["Bremen"]),
+    ("DE", "HH", &["Hamburg"]),
+    ("DE", "HE", &["Hessen", "Hesse"]),
+    ("DE", "MV", &["Mecklenburg-Vorpommern", "Mecklenburg-Western Pomerania"]),
+    ("DE", "NI", &["Niedersachsen", "Lower Saxony"]),
+    ("DE", "NW", &["Nordrhein-Westfalen", "North Rhine-Westphalia"]),
+    ("DE", "RP", &["Rheinland-Pfalz", "Rhineland-Palatinate"]),
+    ("DE", "SL", &["Saarland"]),
+    ("DE", "SN", &["Sachsen", "Saxony"]),
+    ("DE", "ST", &["Sachsen-Anhalt", "Saxony-Anhalt"]),
+    ("DE", "SH", &["Schleswig-Holstein"]),
+    ("DE", "TH", &["Thüringen", "Thuringia"]),
+    // United Kingdom
+    ("GB", "ENG", &["England"]),
+    ("GB", "SCT", &["Scotland"]),
+    ("GB", "WLS", &["Wales"]),
+    ("GB", "NIR", &["Northern Ireland"]),
+    // Australia
+    ("AU", "NSW", &["New South Wales"]),
+    ("AU", "QLD", &["Queensland"]),
+    ("AU", "SA", &["South Australia"]),
+    ("AU", "TAS", &["Tasmania"]),
+    ("AU", "VIC", &["Victoria"]),
+    ("AU", "WA", &["Western Australia"]),
+    ("AU", "ACT", &["Australian Capital Territory"]),
+    ("AU", "NT", &["Northern Territory"]),
+];
@@ -0,0 +1,210 @@
+//! # Country / region code normalization
+//!
+//! Vendors disagree on casing (`"us"` vs `"US"`), use non-ISO codes (`"UK"`),
+//! and report subdivisions as free text. [`normalize`] rewrites unified records
+//! in place so that:
+//!
+//! - `country` is an upper-case ISO 3166-1 alpha-2 code (aliases resolved),
+//! - `subdivision_code` holds the ISO 3166-2 code when the subdivision is known,
+//! - placeholder values (empty strings, Infatica's `"XX"` city) become `None`.
+//!
+//! Anything that cannot be mapped is left as reported and counted in the
+//! returned [`ValidationReport`]. With the `translit` feature, [`transliterate`]
+//! adds ASCII spellings of cities written in other scripts.
+
+mod iso3166;
+mod report;
+#[cfg(feature = "translit")]
+mod translit;
+
+pub use report::ValidationReport;
+#[cfg(feature = "translit")]
+pub use translit::transliterate;
+
+use iso3166::{COUNTRY_ALIASES, COUNTRY_CODES, SUBDIVISIONS};
+use crate::unified::LocationRecord;
+
+/// Placeholder used by vendors for "no value".
+const PLACEHOLDERS: &[&str] = &["", "XX", "-", "N/A"];
+
+/// Normalizes country and subdivision codes of all records in place.
+pub fn normalize(records: &mut [LocationRecord]) -> ValidationReport {
+    let mut report = ValidationReport::default();
+
+    for record in records.iter_mut() {
+        record.subdivision = clean(record.subdivision.take());
+        record.city = clean(record.city.take());
+        record.isp = clean(record.isp.take());
+
+        match normalize_country(&record.country) {
+            Some(code) => record.country = code.to_string(),
+            None => {
+                *report
+                    .unknown_countries
+                    .entry((record.provider, record.country.clone()))
+                    .or_default() += 1;
+                continue;
+            }
+        }
+
+        let Some(subdivision) = record.subdivision.as_deref() else {
+            continue;
+        };
+
+        if !has_curated_subdivisions(&record.country) {
+            continue;
+        }
+
+        match normalize_subdivision(&record.country, subdivision) {
+            Some(code) => record.subdivision_code = Some(code),
+            None => {
+                *report
+                    .unknown_subdivisions
+                    .entry((record.provider, record.country.clone(), subdivision.to_string()))
+                    .or_default() += 1;
+            }
+        }
+    }
+
+    report
+}
+
+/// Maps a vendor country code to ISO 3166-1 alpha-2, if it is known.
+pub fn normalize_country(raw: &str) -> Option<&'static str> {
+    let upper = raw.trim().to_ascii_uppercase();
+
+    if let Some((_, iso)) = COUNTRY_ALIASES.iter().find(|(alias, _)| *alias == upper) {
+        return Some(iso);
+    }
+
+    COUNTRY_CODES.binary_search(&upper.as_str()).ok().map(|i| COUNTRY_CODES[i])
+}
+
+/// Resolves a subdivision name, bare code (`"CA"`) or full code (`"US-CA"`) to ISO 3166-2.
+///
+/// `country` must already be normalized.
+pub fn normalize_subdivision(country: &str, raw: &str) -> Option<String> {
+    let upper = raw.trim().to_ascii_uppercase();
+    let suffix = upper
+        .strip_prefix(country)
+        .and_then(|rest| rest.strip_prefix('-'))
+        .unwrap_or(&upper);
+    let key = fold_key(raw);
+
+    SUBDIVISIONS
+        .iter()
+        .filter(|(c, _, _)| *c == country)
+        .find(|(_, code, names)| *code == suffix || names.iter().any(|n| fold_key(n) == key))
+        .map(|(c, code, _)| format!("{c}-{code}"))
+}
+
+fn has_curated_subdivisions(country: &str) -> bool {
+    SUBDIVISIONS.iter().any(|(c, _, _)| *c == country)
+}
+
+/// Comparison key for names: lowercase ASCII letters and digits only, common diacritics folded.
+fn fold_key(name: &str) -> String {
+    name.chars()
+        .flat_map(char::to_lowercase)
+        .map(fold_diacritic)
+        .filter(char::is_ascii_alphanumeric)
+        .collect()
+}
+
+/// Folds a lowercase Latin letter with a diacritic to its base letter.
+pub(crate) fn fold_diacritic(c: char) -> char {
+    match c {
+        'ä' | 'à' | 'á' | 'â' | 'ã' | 'å' => 'a',
+        'ö' | 'ò' | 'ó' | 'ô' | 'õ' | 'ø' => 'o',
+        'ü' | 'ù' | 'ú' | 'û' => 'u',
+        'é' | 'è' | 'ê' | 'ë' => 'e',
+        'í' | 'ì' | 'î' | 'ï' => 'i',
+        'ç' => 'c',
+        'ñ' => 'n',
+        'ß' => 's',
+        other => other,
+    }
+}
+
+fn clean(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !PLACEHOLDERS.iter().any(|p| p.eq_ignore_ascii_case(v)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::unified::Provider;
+    use super::*;
+
+    fn record(provider: Provider, country: &str, subdivision: &str, city: &str) -> LocationRecord {
+        LocationRecord {
+            subdivision: Some(subdivision.to_string()),
+            city: Some(city.to_string()),
+            ..LocationRecord::sample(provider, country)
+        }
+    }
+
+    #[test]
+    fn countries_are_case_folded_and_aliases_resolved() {
+        let cases = [
+            ("us", Some("US")),
+            (" De ", Some("DE")),
+            ("UK", Some("GB")),
+            ("usa", Some("US")),
+            ("xk", Some("XK")),
+            ("ZZ", None),
+            ("", None),
+        ];
+        for (raw, expected) in cases {
+            assert_eq!(normalize_country(raw), expected, "{raw:?}");
+        }
+    }
+
+    #[test]
+    fn subdivisions_resolve_by_code_name_or_alias() {
+        let cases = [
+            ("US", "CA", Some("US-CA")),
+            ("US", "us-ny", Some("US-NY")),
+            ("US", "new york", Some("US-NY")),
+            ("US", "Washington, D.C.", Some("US-DC")),
+            ("DE", "Bavaria", Some("DE-BY")),
+            ("DE", "THURINGEN", Some("DE-TH")),
+            ("DE", "Baden-Wurttemberg", Some("DE-BW")),
+            // A code of another country's table does not leak across
+            ("DE", "CA", None),
+            ("US", "Atlantis", None),
+        ];
+        for (country, raw, expected) in cases {
+            assert_eq!(normalize_subdivision(country, raw).as_deref(), expected, "{country} {raw:?}");
+        }
+    }
+
+    #[test]
+    fn reports_what_cannot_be_mapped() {
+        let mut records = vec![
+            record(Provider::IPRoyal, "uk", "Scotland", "Glasgow"),
+            record(Provider::IPRoyal, "ZZ", "Nowhere", "Nowhere"),
+            record(Provider::Infatica, "ZZ", "Nowhere", "Nowhere"),
+            record(Provider::Infatica, "ZZ", "Nowhere", "Nowhere"),
+            record(Provider::Infatica, "us", "Atlantis", "XX"),
+            // Subdivisions outside the curated table are kept without a code
+            record(Provider::Infatica, "fr", "Bretagne", "-"),
+        ];
+        let report = normalize(&mut records);
+
+        assert_eq!((records[0].country.as_str(), records[0].subdivision_code.as_deref()), ("GB", Some("GB-SCT")));
+        assert_eq!((records[4].country.as_str(), records[4].city.as_deref()), ("US", None));
+        assert_eq!((records[5].subdivision_code.as_deref(), records[5].city.as_deref()), (None, None));
+        assert_eq!(records[1].country, "ZZ");
+
+        let countries: Vec<_> = report.unknown_countries.iter().map(|((p, c), n)| (*p, c.as_str(), *n)).collect();
+        assert_eq!(countries, [(Provider::IPRoyal, "ZZ", 1), (Provider::Infatica, "ZZ", 2)]);
+        let subdivisions: Vec<_> =
+            report.unknown_subdivisions.iter().map(|((p, c, s), n)| (*p, c.as_str(), s.as_str(), *n)).collect();
+        assert_eq!(subdivisions, [(Provider::Infatica, "US", "Atlantis", 1)]);
+        assert!(!report.is_clean());
+        assert!(report.to_string().contains("infatica: unknown country code \"ZZ\" (2 records)"));
+        assert!(normalize(&mut records[..1]).is_clean());
+    }
+}
@@ -0,0 +1,37 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use crate::unified::Provider;
+
+/// Codes that normalization could not map, with occurrence counts.
+///
+/// Subdivisions are only reported for countries covered by the curated
+/// subdivision table; elsewhere they are kept as free text without a code.
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    /// `(provider, code as reported)` → number of records.
+    pub unknown_countries: BTreeMap<(Provider, String), usize>,
+    /// `(provider, country, subdivision as reported)` → number of records.
+    pub unknown_subdivisions: BTreeMap<(Provider, String, String), usize>,
+}
+
+impl ValidationReport {
+    pub fn is_clean(&self) -> bool {
+        self.unknown_countries.is_empty() && self.unknown_subdivisions.is_empty()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_clean() {
+            return writeln!(f, "all country and subdivision codes resolved");
+        }
+
+        for ((provider, code), count) in &self.unknown_countries {
+            writeln!(f, "  {provider}: unknown country code \"{code}\" ({count} records)")?;
+        }
+        for ((provider, country, name), count) in &self.unknown_subdivisions {
+            writeln!(f, "  {provider}: unknown subdivision \"{name}\" in {country} ({count} records)")?;
+        }
+        Ok(())
+    }
+}
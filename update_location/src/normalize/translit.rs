@@ -0,0 +1,32 @@
+use crate::unified::LocationRecord;
+
+/// Fills in [`city_ascii`](LocationRecord::city_ascii) for cities not written in
+/// plain ASCII, e.g. `"Москва"` → `"Moskva"`, `"São Paulo"` → `"Sao Paulo"`.
+///
+/// Transliteration goes character by character, so it serves alphabetic scripts
+/// well; CJK names come out in their Mandarin reading rather than their usual
+/// English spelling. Characters without a transliteration are dropped, and a
+/// name left empty gets none.
+pub fn transliterate(records: &mut [LocationRecord]) {
+    for record in records.iter_mut() {
+        record.city_ascii = record.city.as_deref().filter(|city| !city.is_ascii()).and_then(to_ascii);
+    }
+}
+
+fn to_ascii(name: &str) -> Option<String> {
+    let ascii = deunicode::deunicode_with_tofu(name, "");
+    let words: Vec<_> = ascii.split_whitespace().collect();
+    (!words.is_empty()).then(|| words.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transliterates_other_scripts_only() {
+        let names = ["Москва", "Αθήνα", "São Paulo", "Berlin", "\u{E000}"];
+        let ascii: Vec<_> = names.iter().map(|name| Some(*name).filter(|n| !n.is_ascii()).and_then(to_ascii)).collect();
+        assert_eq!(ascii, [Some("Moskva".to_string()), Some("Athena".to_string()), Some("Sao Paulo".to_string()), None, None]);
+    }
+}
@@ -0,0 +1,104 @@
+//! Notifications sent as plain-text emails through an SMTP relay.
+//!
+//! The subject and body are [templates](super::template); subjects are kept on
+//! one line.
+
+use std::time::Duration;
+use lettre::message::header::ContentType;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use crate::models::{EmailConfig, EmailTls};
+use crate::notify::{template, Notification, NotifyError, Notifier, NotifyFuture};
+
+pub(super) struct EmailNotifier {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+    subject: String,
+    body: String,
+    top: usize,
+}
+
+impl EmailNotifier {
+    pub(super) fn new(cfg: &EmailConfig, timeout: Duration, top: usize) -> Result<Self, NotifyError> {
+        let host = cfg.get_host();
+        let builder = match cfg.get_tls() {
+            EmailTls::Implicit => AsyncSmtpTransport::<Tokio1Executor>::relay(host)?,
+            EmailTls::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)?,
+            EmailTls::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+        };
+        let mut builder = builder.port(cfg.get_port()).timeout(Some(timeout));
+        if let Some((username, password)) = cfg.get_credentials() {
+            builder = builder.credentials(Credentials::new(username.to_string(), password.to_string()));
+        }
+
+        Ok(Self {
+            transport: builder.build(),
+            from: mailbox(cfg.get_from())?,
+            to: cfg.get_to().iter().map(|to| mailbox(to)).collect::<Result<_, _>>()?,
+            subject: cfg.get_subject().to_string(),
+            body: cfg.get_body().to_string(),
+            top,
+        })
+    }
+
+    fn message(&self, notification: &Notification) -> Result<Message, NotifyError> {
+        let subject = template::expand(&self.subject, notification, self.top);
+        let mut builder = Message::builder()
+            .from(self.from.clone())
+            .subject(subject.split_whitespace().collect::<Vec<_>>().join(" "))
+            .header(ContentType::TEXT_PLAIN);
+        for to in &self.to {
+            builder = builder.to(to.clone());
+        }
+        Ok(builder.body(template::expand(&self.body, notification, self.top))?)
+    }
+}
+
+fn mailbox(raw: &str) -> Result<Mailbox, NotifyError> {
+    raw.parse().map_err(|source| NotifyError::Mailbox { mailbox: raw.to_string(), source })
+}
+
+impl Notifier for EmailNotifier {
+    fn name(&self) -> &'static str {
+        "email"
+    }
+
+    fn send<'a>(&'a self, notification: &'a Notification) -> NotifyFuture<'a> {
+        Box::pin(async move {
+            let message = self.message(notification)?;
+            self.transport.send(message).await?;
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_templated_messages() {
+        let cfg: EmailConfig = serde_json::from_value(serde_json::json!({
+            "host": "smtp.example",
+            "from": "Coverage <coverage@example.com>",
+            "to": ["ops@example.com", "Sales <sales@example.com>"],
+            "subject": "[{run}]\n{added} added",
+        }))
+        .unwrap();
+        let notifier = EmailNotifier::new(&cfg, Duration::from_secs(5), 10).unwrap();
+        let notification = Notification::new("run-1", Vec::new(), Vec::new());
+
+        let message = String::from_utf8(notifier.message(&notification).unwrap().formatted()).unwrap();
+        assert!(message.contains("Subject: [run-1] 0 added\r\n"), "{message}");
+        assert!(message.contains("To: ops@example.com, Sales <sales@example.com>\r\n"), "{message}");
+        assert!(message.contains("Coverage changes (run run-1)"), "{message}");
+
+        let invalid: EmailConfig = serde_json::from_value(serde_json::json!({
+            "host": "smtp.example", "from": "not an address", "to": ["ops@example.com"],
+        }))
+        .unwrap();
+        assert!(matches!(EmailNotifier::new(&invalid, Duration::from_secs(5), 10), Err(NotifyError::Mailbox { .. })));
+    }
+}
@@ -0,0 +1,55 @@
+use reqwest::StatusCode;
+use thiserror::Error;
+
+/// Failure while delivering a notification.
+///
+/// Email variants only exist when the `email` feature is enabled.
+#[derive(Debug, Error)]
+pub enum NotifyError {
+    #[cfg(not(feature = "email"))]
+    #[error("the `{notifier}` notifier needs the `{feature}` feature, which this build does not include")]
+    Unsupported { notifier: &'static str, feature: &'static str },
+
+    #[error("request failed: {0}")]
+    Request(#[from] reqwest::Error),
+
+    #[error("rejected with status {status}: {body}")]
+    Status { status: StatusCode, body: String },
+
+    #[error("invalid endpoint: {0}")]
+    Url(#[from] url::ParseError),
+
+    #[error("failed to print the notification: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("the notifier task panicked")]
+    Panicked,
+
+    #[cfg(feature = "email")]
+    #[error("invalid mailbox `{mailbox}`: {source}")]
+    Mailbox { mailbox: String, source: lettre::address::AddressError },
+
+    #[cfg(feature = "email")]
+    #[error("failed to build the email: {0}")]
+    Email(#[from] lettre::error::Error),
+
+    #[cfg(feature = "email")]
+    #[error("smtp error: {0}")]
+    Smtp(#[from] lettre::transport::smtp::Error),
+}
+
+/// Longest part of a rejection body kept in [`NotifyError::Status`].
+const MAX_BODY: usize = 512;
+
+/// Fails with [`NotifyError::Status`] unless `resp` succeeded.
+pub(super) async fn check(resp: reqwest::Response) -> Result<(), NotifyError> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let mut body = resp.text().await.unwrap_or_default();
+    if let Some((end, _)) = body.char_indices().nth(MAX_BODY) {
+        body.truncate(end);
+    }
+    Err(NotifyError::Status { status, body })
+}
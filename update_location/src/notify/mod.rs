@@ -0,0 +1,438 @@
+//! # Notifications
+//!
+//! Human-readable messages about coverage changes, for people rather than the
+//! downstream systems the [sinks](crate::sinks) feed. Each channel configured
+//! under `[notify]` is a [`Notifier`]:
+//!
+//! - **stdout** (`notify.stdout = true`) — prints the message.
+//! - **webhook** (`[notify.webhook]`) — POSTs the message and its changes as JSON.
+//! - **Slack** (`[notify.slack]`) — posts the message through an incoming webhook.
+//! - **Telegram** (`[notify.telegram]`) — sends the message to a chat through a bot.
+//! - **email** (`[notify.email]`, feature `email`) — sends the message through an
+//!   SMTP relay, with a subject and body shaped by [templates](template).
+//!
+//! A message summarizes a [`Notification`]: the change counts, the
+//! `notify.top` changes with the largest node difference, the quality
+//! [anomalies](crate::quality::Anomaly) and the violated
+//! [alert rules](crate::alerts).
+//!
+//! The fetch command notifies once per run with changes, anomalies or violations. A
+//! `serve --refresh` daemon instead collects the changes of its refreshes in an
+//! [`Aggregator`] and sends them as one message once `notify.window` passed since
+//! the first of them, so frequent refreshes do not produce a message each. A
+//! location that changed several times within the window is reported once, from
+//! its state before the window to its state at the end of it (see
+//! [`changes::squash`]).
+//!
+//! Channels are notified concurrently; one that fails is reported with its error
+//! without affecting the others. Notifications are best effort: a failed one is
+//! not repeated.
+
+#[cfg(feature = "email")]
+mod email;
+mod errors;
+mod slack;
+mod stdout;
+mod telegram;
+pub mod template;
+mod webhook;
+
+pub use errors::NotifyError;
+
+use std::fmt::Write;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+use reqwest::Client;
+use serde::Serialize;
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+use crate::alerts::AlertViolation;
+use crate::changes::{self, Change, ChangeKind};
+use crate::models::AppConfig;
+use crate::quality::Anomaly;
+
+/// Coverage changes of one or more runs.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Notification {
+    /// Ids of the runs the changes were observed by, oldest first, see [`crate::run_id`].
+    pub run_ids: Vec<String>,
+    pub changes: Vec<Change>,
+    pub anomalies: Vec<Anomaly>,
+    /// Alert rules the runs violated.
+    pub violations: Vec<AlertViolation>,
+}
+
+impl Notification {
+    pub fn new(run_id: &str, changes: Vec<Change>, anomalies: Vec<Anomaly>) -> Self {
+        Self { run_ids: vec![run_id.to_string()], changes, anomalies, violations: Vec::new() }
+    }
+
+    /// Adds the alert rules the run violated.
+    pub fn with_violations(mut self, violations: Vec<AlertViolation>) -> Self {
+        self.violations = violations;
+        self
+    }
+
+    /// Whether there is nothing to notify about.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.anomalies.is_empty() && self.violations.is_empty()
+    }
+
+    /// Adds the changes of `later`, a subsequent run, squashing those of the same location.
+    pub fn merge(&mut self, later: Notification) {
+        for run_id in later.run_ids {
+            // A daemon's refreshes share its run id
+            if !self.run_ids.contains(&run_id) {
+                self.run_ids.push(run_id);
+            }
+        }
+        self.changes = changes::squash(std::mem::take(&mut self.changes), later.changes);
+        self.anomalies.extend(later.anomalies);
+        self.violations.extend(later.violations);
+    }
+
+    /// Number of changes of `kind`.
+    pub fn count(&self, kind: ChangeKind) -> usize {
+        self.changes.iter().filter(|c| c.kind == kind).count()
+    }
+
+    /// The message text, listing up to `top` changes, largest node difference first.
+    pub fn render(&self, top: usize) -> String {
+        let mut text = String::new();
+        let runs = match self.run_ids.as_slice() {
+            [] => String::new(),
+            [run] => format!(" (run {run})"),
+            [first, .., last] => format!(" ({} runs, {first} to {last})", self.run_ids.len()),
+        };
+        let _ = writeln!(text, "Coverage changes{runs}");
+        let _ = writeln!(
+            text,
+            "Added: {}, removed: {}, count changed: {}",
+            self.count(ChangeKind::Added),
+            self.count(ChangeKind::Removed),
+            self.count(ChangeKind::CountChanged),
+        );
+
+        let largest = self.top_changes(top);
+        if !largest.is_empty() {
+            let _ = writeln!(text, "Top changes:");
+            for line in largest {
+                let _ = writeln!(text, "  {line}");
+            }
+        }
+
+        if !self.anomalies.is_empty() {
+            let _ = writeln!(text, "Quality anomalies:");
+            for anomaly in &self.anomalies {
+                let _ = writeln!(text, "  {anomaly}");
+            }
+        }
+
+        if !self.violations.is_empty() {
+            let _ = writeln!(text, "Alert violations:");
+            for violation in &self.violations {
+                let _ = writeln!(text, "  {violation}");
+            }
+        }
+        text
+    }
+
+    /// One line for each of the `top` changes with the largest node difference, and
+    /// one counting the rest.
+    pub fn top_changes(&self, top: usize) -> Vec<String> {
+        let mut largest: Vec<&Change> = self.changes.iter().collect();
+        largest.sort_by_key(|c| std::cmp::Reverse(nodes(c.nodes_before).abs_diff(nodes(c.nodes_after))));
+        let mut lines: Vec<String> = largest.iter().take(top).map(|c| describe(c)).collect();
+        if top > 0 && largest.len() > top {
+            lines.push(format!("... and {} more", largest.len() - top));
+        }
+        lines
+    }
+}
+
+fn nodes(estimate: Option<u64>) -> u64 {
+    estimate.unwrap_or_default()
+}
+
+/// One line per change: `+ infatica DE / Berlin / Berlin: - -> 5 nodes`.
+fn describe(change: &Change) -> String {
+    let sign = match change.kind {
+        ChangeKind::Added => '+',
+        ChangeKind::Removed => '-',
+        ChangeKind::CountChanged => '~',
+    };
+    let record = &change.record;
+    let location = [Some(record.country.as_str()), record.subdivision.as_deref(), record.city.as_deref(), record.isp.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" / ");
+    let count = |estimate: Option<u64>| estimate.map_or_else(|| "-".to_string(), |n| n.to_string());
+    format!(
+        "{sign} {} {location}: {} -> {} nodes",
+        record.provider,
+        count(change.nodes_before),
+        count(change.nodes_after),
+    )
+}
+
+/// A notifier that failed, by name, and its error.
+pub type NotifyFailure = (&'static str, NotifyError);
+
+/// Future returned by [`Notifier::send`], boxed so notifiers can be used as trait objects.
+pub type NotifyFuture<'a> = Pin<Box<dyn Future<Output = Result<(), NotifyError>> + Send + 'a>>;
+
+/// A channel delivering notifications to people.
+pub trait Notifier: Send + Sync {
+    /// Name of the notifier, as its config key.
+    fn name(&self) -> &'static str;
+
+    /// Delivers `notification`.
+    fn send<'a>(&'a self, notification: &'a Notification) -> NotifyFuture<'a>;
+}
+
+/// Whether any notifier is configured.
+pub fn configured(cfg: &AppConfig) -> bool {
+    cfg.notify.is_enabled()
+}
+
+/// The notifiers configured in `cfg`, and the failures of those that cannot be set up.
+fn open(cfg: &AppConfig) -> (Vec<Arc<dyn Notifier>>, Vec<NotifyFailure>) {
+    let notify = &cfg.notify;
+    let top = notify.get_top();
+    let mut notifiers: Vec<Arc<dyn Notifier>> = Vec::new();
+    let mut failures = Vec::new();
+    if notify.get_stdout() {
+        notifiers.push(Arc::new(stdout::StdoutNotifier::new(top)));
+    }
+
+    let http = notify.get_webhook().is_some() || notify.get_slack().is_some() || notify.get_telegram().is_some();
+    if http {
+        match Client::builder().timeout(notify.get_timeout()).build() {
+            Ok(client) => {
+                if let Some(webhook) = notify.get_webhook() {
+                    notifiers.push(Arc::new(webhook::WebhookNotifier::new(client.clone(), webhook.get_url().clone(), top)));
+                }
+                if let Some(slack) = notify.get_slack() {
+                    notifiers.push(Arc::new(slack::SlackNotifier::new(client.clone(), slack.get_webhook_url().clone(), top)));
+                }
+                if let Some(telegram) = notify.get_telegram() {
+                    match telegram::TelegramNotifier::new(client, telegram, top) {
+                        Ok(notifier) => notifiers.push(Arc::new(notifier)),
+                        Err(err) => failures.push(("telegram", err)),
+                    }
+                }
+            }
+            // The client is shared by the webhook, Slack and Telegram notifiers
+            Err(err) => failures.push(("http", err.into())),
+        }
+    }
+
+    if let Some(email_cfg) = notify.get_email() {
+        #[cfg(feature = "email")]
+        match email::EmailNotifier::new(email_cfg, notify.get_timeout(), top) {
+            Ok(notifier) => notifiers.push(Arc::new(notifier)),
+            Err(err) => failures.push(("email", err)),
+        }
+        #[cfg(not(feature = "email"))]
+        {
+            let _ = email_cfg;
+            failures.push(("email", NotifyError::Unsupported { notifier: "email", feature: "email" }));
+        }
+    }
+
+    (notifiers, failures)
+}
+
+/// Sends `notification` through every notifier concurrently, returning the failures by name.
+pub async fn send(notifiers: &[Arc<dyn Notifier>], notification: Notification) -> Vec<NotifyFailure> {
+    let notification = Arc::new(notification);
+    let mut tasks = JoinSet::new();
+    for (i, notifier) in notifiers.iter().enumerate() {
+        let (notifier, notification) = (notifier.clone(), notification.clone());
+        tasks.spawn(async move { (i, notifier.send(&notification).await) });
+    }
+
+    // A panicked task leaves its notifier without a result
+    let mut results: Vec<Option<Result<(), NotifyError>>> = notifiers.iter().map(|_| None).collect();
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok((i, result)) = joined {
+            results[i] = Some(result);
+        }
+    }
+
+    notifiers
+        .iter()
+        .zip(results)
+        .filter_map(|(notifier, result)| match result {
+            Some(Ok(())) => None,
+            Some(Err(err)) => Some((notifier.name(), err)),
+            None => Some((notifier.name(), NotifyError::Panicked)),
+        })
+        .collect()
+}
+
+/// Sends `notification` through the notifiers of `cfg`, reporting failures on
+/// stderr; with `dry_run`, only prints the channels and the message.
+pub async fn deliver(cfg: &AppConfig, notification: Notification, dry_run: bool) {
+    let (notifiers, failures) = open(cfg);
+    for (notifier, err) in failures {
+        eprintln!("{notifier}: failed to notify: {err}");
+    }
+    if dry_run {
+        let names: Vec<_> = notifiers.iter().map(|n| n.name()).collect();
+        println!("notify: dry run, would send to {}:", names.join(", "));
+        print!("{}", notification.render(cfg.notify.get_top()));
+        return;
+    }
+    for (notifier, err) in send(&notifiers, notification).await {
+        eprintln!("{notifier}: failed to notify: {err}");
+    }
+}
+
+/// Collects notifications until `window` passed since the first one.
+#[derive(Debug)]
+pub struct Aggregator {
+    window: Duration,
+    pending: Option<(Instant, Notification)>,
+}
+
+impl Aggregator {
+    pub fn new(window: Duration) -> Self {
+        Self { window, pending: None }
+    }
+
+    /// Adds `notification`, opening a window unless one is open; empty ones are dropped.
+    pub fn push(&mut self, notification: Notification) {
+        if notification.is_empty() {
+            return;
+        }
+        match &mut self.pending {
+            Some((_, pending)) => pending.merge(notification),
+            None => self.pending = Some((Instant::now() + self.window, notification)),
+        }
+    }
+
+    /// When the open window closes; `None` while nothing is pending.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.pending.as_ref().map(|(deadline, _)| *deadline)
+    }
+
+    /// The collected notification, closing the window; `None` when there is nothing
+    /// left to notify, e.g. because every change was undone within the window.
+    pub fn take(&mut self) -> Option<Notification> {
+        self.pending.take().map(|(_, n)| n).filter(|n| !n.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::unified::{LocationRecord, Provider};
+
+    fn change(kind: ChangeKind, city: &str, before: Option<u64>, after: Option<u64>) -> Change {
+        let record = LocationRecord {
+            city: Some(city.to_string()),
+            nodes: after.or(before),
+            ..LocationRecord::sample(Provider::Infatica, "DE")
+        };
+        Change { kind, record, nodes_before: before, nodes_after: after }
+    }
+
+    #[test]
+    fn squashes_changes_of_a_location() {
+        let mut notification = Notification::new(
+            "run-1",
+            vec![
+                change(ChangeKind::Added, "Berlin", None, Some(5)),
+                change(ChangeKind::CountChanged, "Hamburg", Some(10), Some(20)),
+                change(ChangeKind::CountChanged, "Bremen", Some(3), Some(4)),
+                change(ChangeKind::Removed, "Kiel", Some(7), None),
+            ],
+            Vec::new(),
+        );
+        notification.merge(Notification::new(
+            "run-2",
+            vec![
+                change(ChangeKind::Removed, "Berlin", Some(5), None),
+                change(ChangeKind::CountChanged, "Hamburg", Some(20), Some(30)),
+                change(ChangeKind::CountChanged, "Bremen", Some(4), Some(3)),
+                change(ChangeKind::Added, "Kiel", None, Some(9)),
+                change(ChangeKind::Added, "Essen", None, Some(1)),
+            ],
+            Vec::new(),
+        ));
+
+        let squashed: Vec<_> = notification
+            .changes
+            .iter()
+            .map(|c| (c.kind, c.record.city.as_deref().unwrap(), c.nodes_before, c.nodes_after))
+            .collect();
+        assert_eq!(squashed, [
+            (ChangeKind::CountChanged, "Hamburg", Some(10), Some(30)),
+            (ChangeKind::CountChanged, "Kiel", Some(7), Some(9)),
+            (ChangeKind::Added, "Essen", None, Some(1)),
+        ]);
+        assert_eq!(notification.run_ids, ["run-1", "run-2"]);
+    }
+
+    #[test]
+    fn renders_largest_changes_first() {
+        let notification = Notification::new(
+            "run-1",
+            vec![
+                change(ChangeKind::CountChanged, "Hamburg", Some(10), Some(12)),
+                change(ChangeKind::Removed, "Kiel", Some(7), None),
+                change(ChangeKind::Added, "Berlin", None, Some(50)),
+            ],
+            Vec::new(),
+        );
+        assert_eq!(
+            notification.render(2),
+            "Coverage changes (run run-1)\n\
+             Added: 1, removed: 1, count changed: 1\n\
+             Top changes:\n  \
+             + infatica DE / Berlin: - -> 50 nodes\n  \
+             - infatica DE / Kiel: 7 -> - nodes\n  \
+             ... and 1 more\n"
+        );
+    }
+
+    #[test]
+    fn renders_violated_alert_rules() {
+        let violation = AlertViolation { provider: Provider::Infatica, country: "DE".to_string(), min_nodes: 100, nodes: 40 };
+        let notification = Notification::new("run-1", Vec::new(), Vec::new()).with_violations(vec![violation]);
+        assert!(!notification.is_empty());
+        assert_eq!(
+            notification.render(10),
+            "Coverage changes (run run-1)\n\
+             Added: 0, removed: 0, count changed: 0\n\
+             Alert violations:\n  \
+             infatica DE: 40 nodes, expected at least 100\n"
+        );
+    }
+
+    #[test]
+    fn aggregates_within_the_window() {
+        let mut aggregator = Aggregator::new(Duration::from_secs(60));
+        aggregator.push(Notification::new("run-0", Vec::new(), Vec::new()));
+        assert!(aggregator.deadline().is_none());
+
+        aggregator.push(Notification::new("run-1", vec![change(ChangeKind::Added, "Berlin", None, Some(5))], Vec::new()));
+        let deadline = aggregator.deadline().unwrap();
+        assert!(deadline > Instant::now() + Duration::from_secs(59));
+        aggregator.push(Notification::new("run-2", vec![change(ChangeKind::Added, "Essen", None, Some(1))], Vec::new()));
+        assert_eq!(aggregator.deadline(), Some(deadline));
+
+        let notification = aggregator.take().unwrap();
+        assert_eq!((notification.run_ids.len(), notification.changes.len()), (2, 2));
+        assert!(aggregator.deadline().is_none());
+
+        // Undone within the window: nothing to send
+        aggregator.push(Notification::new("run-3", vec![change(ChangeKind::Added, "Berlin", None, Some(5))], Vec::new()));
+        aggregator.push(Notification::new("run-4", vec![change(ChangeKind::Removed, "Berlin", Some(5), None)], Vec::new()));
+        assert!(aggregator.take().is_none());
+    }
+}
@@ -0,0 +1,34 @@
+//! Notifications posted to Slack through an incoming webhook.
+
+use reqwest::Client;
+use serde_json::json;
+use url::Url;
+use crate::notify::errors::check;
+use crate::notify::{Notification, Notifier, NotifyFuture};
+
+pub(super) struct SlackNotifier {
+    client: Client,
+    webhook_url: Url,
+    top: usize,
+}
+
+impl SlackNotifier {
+    pub(super) fn new(client: Client, webhook_url: Url, top: usize) -> Self {
+        Self { client, webhook_url, top }
+    }
+}
+
+impl Notifier for SlackNotifier {
+    fn name(&self) -> &'static str {
+        "slack"
+    }
+
+    fn send<'a>(&'a self, notification: &'a Notification) -> NotifyFuture<'a> {
+        Box::pin(async move {
+            let body = json!({ "text": notification.render(self.top) });
+            // The webhook URL is its credential; keep it out of error messages
+            let resp = self.client.post(self.webhook_url.clone()).json(&body).send().await.map_err(|e| e.without_url())?;
+            check(resp).await
+        })
+    }
+}
@@ -0,0 +1,30 @@
+//! Notifications printed on stdout.
+
+use std::io::Write;
+use crate::notify::{Notification, Notifier, NotifyFuture};
+
+pub(super) struct StdoutNotifier {
+    top: usize,
+}
+
+impl StdoutNotifier {
+    pub(super) fn new(top: usize) -> Self {
+        Self { top }
+    }
+}
+
+impl Notifier for StdoutNotifier {
+    fn name(&self) -> &'static str {
+        "stdout"
+    }
+
+    fn send<'a>(&'a self, notification: &'a Notification) -> NotifyFuture<'a> {
+        Box::pin(async move {
+            // One write, so concurrent output does not interleave with the message
+            let mut out = std::io::stdout().lock();
+            out.write_all(notification.render(self.top).as_bytes())?;
+            out.flush()?;
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,45 @@
+//! Notifications sent to a Telegram chat through the Bot API `sendMessage` method.
+
+use reqwest::Client;
+use serde_json::json;
+use url::Url;
+use crate::models::TelegramConfig;
+use crate::notify::errors::check;
+use crate::notify::{Notification, NotifyError, Notifier, NotifyFuture};
+
+/// Longest message Telegram accepts, in characters.
+const MAX_MESSAGE: usize = 4096;
+
+pub(super) struct TelegramNotifier {
+    client: Client,
+    /// `sendMessage` endpoint, embedding the bot token.
+    endpoint: Url,
+    chat_id: String,
+    top: usize,
+}
+
+impl TelegramNotifier {
+    pub(super) fn new(client: Client, cfg: &TelegramConfig, top: usize) -> Result<Self, NotifyError> {
+        let endpoint = cfg.get_api_url().join(&format!("bot{}/sendMessage", cfg.get_bot_token()))?;
+        Ok(Self { client, endpoint, chat_id: cfg.get_chat_id().to_string(), top })
+    }
+}
+
+impl Notifier for TelegramNotifier {
+    fn name(&self) -> &'static str {
+        "telegram"
+    }
+
+    fn send<'a>(&'a self, notification: &'a Notification) -> NotifyFuture<'a> {
+        Box::pin(async move {
+            let mut text = notification.render(self.top);
+            if let Some((end, _)) = text.char_indices().nth(MAX_MESSAGE) {
+                text.truncate(end);
+            }
+            let body = json!({ "chat_id": self.chat_id, "text": text });
+            // The endpoint embeds the bot token; keep it out of error messages
+            let resp = self.client.post(self.endpoint.clone()).json(&body).send().await.map_err(|e| e.without_url())?;
+            check(resp).await
+        })
+    }
+}
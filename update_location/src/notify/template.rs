@@ -0,0 +1,111 @@
+//! Message templates of notifiers that let users shape their messages, such as
+//! the subject and body of emails.
+//!
+//! A template is text with placeholders in braces; `{{` and `}}` stand for
+//! literal braces:
+//!
+//! | Placeholder | Value |
+//! |-------------|-------|
+//! | `{run}` | ids of the runs, comma-separated |
+//! | `{added}`, `{removed}`, `{count_changed}` | number of changes of each kind |
+//! | `{anomalies}` | number of quality anomalies |
+//! | `{violations}` | number of violated alert rules |
+//! | `{changes}` | the top changes, one per line |
+//! | `{alerts}` | the violated alert rules, one per line |
+//! | `{summary}` | the whole message as other notifiers send it |
+
+use crate::changes::ChangeKind;
+use crate::notify::Notification;
+
+/// Placeholders a template may use.
+pub const PLACEHOLDERS: [&str; 9] =
+    ["run", "added", "removed", "count_changed", "anomalies", "violations", "changes", "alerts", "summary"];
+
+/// One piece of a template.
+enum Part<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+/// Splits `template` into text and placeholders.
+fn parse(template: &str) -> Result<Vec<Part<'_>>, String> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(at) = rest.find(['{', '}']) {
+        parts.push(Part::Text(&rest[..at]));
+        let tail = &rest[at..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            parts.push(Part::Text(&tail[..1]));
+            rest = &tail[2..];
+        } else if tail.starts_with('}') {
+            return Err("unmatched `}`; write `}}` for a literal brace".to_string());
+        } else {
+            let end = tail.find('}').ok_or("unclosed `{`; write `{{` for a literal brace")?;
+            let name = &tail[1..end];
+            if !PLACEHOLDERS.contains(&name) {
+                return Err(format!("unknown placeholder `{{{name}}}`, expected one of {}", PLACEHOLDERS.join(", ")));
+            }
+            parts.push(Part::Placeholder(name));
+            rest = &tail[end + 1..];
+        }
+    }
+    parts.push(Part::Text(rest));
+    Ok(parts)
+}
+
+/// Checks that `template` only uses known placeholders.
+pub fn check(template: &str) -> Result<(), String> {
+    parse(template).map(|_| ())
+}
+
+/// `template` with its placeholders replaced by the values of `notification`,
+/// listing up to `top` changes. An invalid template is returned as is.
+pub fn expand(template: &str, notification: &Notification, top: usize) -> String {
+    let Ok(parts) = parse(template) else {
+        return template.to_string();
+    };
+    let mut out = String::new();
+    for part in parts {
+        match part {
+            Part::Text(text) => out.push_str(text),
+            Part::Placeholder("run") => out.push_str(&notification.run_ids.join(", ")),
+            Part::Placeholder("added") => out.push_str(&notification.count(ChangeKind::Added).to_string()),
+            Part::Placeholder("removed") => out.push_str(&notification.count(ChangeKind::Removed).to_string()),
+            Part::Placeholder("count_changed") => out.push_str(&notification.count(ChangeKind::CountChanged).to_string()),
+            Part::Placeholder("anomalies") => out.push_str(&notification.anomalies.len().to_string()),
+            Part::Placeholder("violations") => out.push_str(&notification.violations.len().to_string()),
+            Part::Placeholder("changes") => out.push_str(&notification.top_changes(top).join("\n")),
+            Part::Placeholder("alerts") => {
+                let alerts: Vec<String> = notification.violations.iter().map(ToString::to_string).collect();
+                out.push_str(&alerts.join("\n"));
+            }
+            // `summary`, the only placeholder left
+            Part::Placeholder(_) => out.push_str(&notification.render(top)),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::alerts::AlertViolation;
+    use crate::unified::Provider;
+    use super::*;
+
+    #[test]
+    fn expands_placeholders_and_escapes() {
+        let notification = Notification::new("run-1", Vec::new(), Vec::new());
+        let subject = expand("{{{run}}}: {added} added, {removed} removed", &notification, 10);
+        assert_eq!(subject, "{run-1}: 0 added, 0 removed");
+        assert_eq!(expand("{summary}", &notification, 10), notification.render(10));
+
+        let violation = AlertViolation { provider: Provider::IPRoyal, country: "FR".to_string(), min_nodes: 10, nodes: 2 };
+        let alerting = notification.clone().with_violations(vec![violation]);
+        assert_eq!(expand("{violations} violated:\n{alerts}", &alerting, 10), "1 violated:\niproyal FR: 2 nodes, expected at least 10");
+
+        assert!(check("{run} {changes}").is_ok());
+        assert!(check("{nodes}").unwrap_err().contains("unknown placeholder `{nodes}`"));
+        assert!(check("{run").is_err());
+        assert!(check("run}").is_err());
+    }
+}
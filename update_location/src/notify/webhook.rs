@@ -0,0 +1,50 @@
+//! Notifications POSTed as JSON to a webhook.
+//!
+//! The body holds the message text next to the notification itself, so a
+//! receiver can either show the text or process the changes:
+//!
+//! ```json
+//! {"text": "Coverage changes (run ...)\n...", "schema_version": 2, "run_ids": ["..."], "changes": [...], "anomalies": [...], "violations": [...]}
+//! ```
+
+use reqwest::Client;
+use serde::Serialize;
+use url::Url;
+use crate::notify::errors::check;
+use crate::notify::{Notification, Notifier, NotifyFuture};
+use crate::unified::SCHEMA_VERSION;
+
+pub(super) struct WebhookNotifier {
+    client: Client,
+    url: Url,
+    top: usize,
+}
+
+#[derive(Serialize)]
+struct Payload<'a> {
+    text: String,
+    /// Version of the record layout of the changes, see [`crate::unified::schema`].
+    schema_version: u32,
+    #[serde(flatten)]
+    notification: &'a Notification,
+}
+
+impl WebhookNotifier {
+    pub(super) fn new(client: Client, url: Url, top: usize) -> Self {
+        Self { client, url, top }
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+
+    fn send<'a>(&'a self, notification: &'a Notification) -> NotifyFuture<'a> {
+        Box::pin(async move {
+            let payload = Payload { text: notification.render(self.top), schema_version: SCHEMA_VERSION, notification };
+            let resp = self.client.post(self.url.clone()).json(&payload).send().await?;
+            check(resp).await
+        })
+    }
+}
@@ -0,0 +1,289 @@
+//! # Provider fetch pipeline
+//!
+//! Fetches the selected, enabled providers concurrently, each in its own task of
+//! a [`JoinSet`], so a run takes about as long as its slowest provider rather
+//! than the sum of all of them. Every task is bounded by its provider's
+//! `fetch_timeout` and stops when the run is cancelled; within that bound, a
+//! failed fetch is repeated per the provider's [`RetryPolicy`]. Whatever happens,
+//! each provider ends up as one [`ProviderRun`] the caller can report on. Responses
+//! go through the [`Cache`], so cached providers finish immediately. Providers
+//! that would exceed their daily request [quota](crate::quota) are refused, and
+//! the requests of the run are added to the quota ledger.
+//!
+//! Library users can configure such a run with [`FetchOptions`] instead of an
+//! [`AppConfig`].
+
+mod options;
+
+pub use options::{FetchOptions, Fetched};
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinSet;
+use tokio_util::sync::CancellationToken;
+use crate::cache::Cache;
+use crate::clock::{self, Clock};
+use crate::http::EndpointStats;
+use crate::infatica::{InfaticaQueryResults, TargetingResolver};
+use crate::iproyal::models::IPRoyalQueryResults;
+use crate::models::{AppConfig, ProviderError, ProviderErrorKind, RetryPolicy};
+use crate::quota::{Budget, QuotaError};
+use crate::unified::{self, LocationRecord, Provenance, Provider};
+use crate::{infatica, iproyal, status, ui};
+
+/// The datasets of one provider.
+pub enum Dataset {
+    IPRoyal(IPRoyalQueryResults),
+    Infatica(InfaticaQueryResults),
+}
+
+pub enum Outcome {
+    Fetched(Dataset),
+    /// Errors of the failed queries.
+    Failed(Vec<ProviderError>),
+    /// The fetch task panicked.
+    Panicked(String),
+    /// `fetch_timeout` elapsed first.
+    TimedOut(Duration),
+    Cancelled,
+}
+
+impl Dataset {
+    /// Per-endpoint statistics; empty when the data came from the cache.
+    pub fn endpoints(&self) -> &[EndpointStats] {
+        match self {
+            Dataset::IPRoyal(results) => results.endpoints(),
+            Dataset::Infatica(results) => results.endpoints(),
+        }
+    }
+
+    /// Provenance of the unified records of this dataset in run `run_id`;
+    /// `None` when `cfg` lacks the provider's section.
+    pub fn provenance(&self, cfg: &AppConfig, run_id: &str) -> Option<Provenance> {
+        match self {
+            Dataset::IPRoyal(results) => cfg.iproyal.as_ref().map(|c| {
+                Provenance::new(c.get_endpoint(), IPRoyalQueryResults::SOURCE_ENDPOINT, results.fetched_at(), run_id)
+            }),
+            Dataset::Infatica(results) => cfg.infatica.as_ref().map(|c| {
+                Provenance::new(c.get_endpoint(), InfaticaQueryResults::SOURCE_ENDPOINT, results.fetched_at(), run_id)
+            }),
+        }
+    }
+
+    /// The unified records of this dataset, with their provenance in run `run_id`.
+    pub fn records(&self, cfg: &AppConfig, run_id: &str) -> Vec<LocationRecord> {
+        let mut records = match self {
+            Dataset::IPRoyal(results) => unified::from_iproyal(&iproyal::flatten(results.countries())),
+            Dataset::Infatica(results) => {
+                unified::from_infatica(results.geo_nodes(), &TargetingResolver::from_results(results))
+            }
+        };
+        if let Some(provenance) = self.provenance(cfg, run_id) {
+            provenance.attach(&mut records);
+        }
+        records
+    }
+}
+
+/// How fetching one provider went.
+pub struct ProviderRun {
+    pub provider: Provider,
+    pub outcome: Outcome,
+    pub elapsed: Duration,
+}
+
+/// Fetches `providers` that are enabled in `cfg` concurrently, returning one run
+/// per fetched provider in [`Provider::ALL`] order.
+pub async fn fetch_all(
+    cfg: &AppConfig,
+    cache: &Cache,
+    providers: &[Provider],
+    cancel: &CancellationToken,
+) -> Vec<ProviderRun> {
+    let clock = clock::system();
+    let started = clock.now();
+    let budget = Arc::new(Budget::open(cfg));
+    let mut tasks = JoinSet::new();
+    let mut task_providers = HashMap::new();
+
+    if let Some(iproyal_cfg) = cfg.iproyal.as_ref().filter(|_| providers.contains(&Provider::IPRoyal)) {
+        let (iproyal_cfg, cache) = (iproyal_cfg.clone(), cache.clone());
+        let limit = iproyal_cfg.get_fetch_timeout();
+        let policy = iproyal_cfg.get_retry_policy();
+        let budget = budget.clone();
+        let fetch = retrying(Provider::IPRoyal, policy, clock.clone(), move || {
+            let (iproyal_cfg, cache, budget) = (iproyal_cfg.clone(), cache.clone(), budget.clone());
+            async move {
+                let fetch = async {
+                    budget.admit(Provider::IPRoyal).map_err(|err| vec![quota_error(Provider::IPRoyal, err)])?;
+                    iproyal::get_all(&iproyal_cfg)
+                        .await
+                        .map_err(|errors| errors.into_iter().map(ProviderError::from).collect())
+                };
+                cache.get_or_fetch("iproyal", &iproyal_cfg.get_cache_key(), fetch).await.map(Dataset::IPRoyal)
+            }
+        });
+        let task = tasks.spawn(run(Provider::IPRoyal, limit, clock.clone(), cancel.clone(), fetch));
+        task_providers.insert(task.id(), Provider::IPRoyal);
+    }
+
+    if let Some(infatica_cfg) = cfg.infatica.as_ref().filter(|_| providers.contains(&Provider::Infatica)) {
+        let (infatica_cfg, cache) = (infatica_cfg.clone(), cache.clone());
+        let limit = infatica_cfg.get_fetch_timeout();
+        let policy = infatica_cfg.get_retry_policy();
+        let budget = budget.clone();
+        let fetch = retrying(Provider::Infatica, policy, clock.clone(), move || {
+            let (infatica_cfg, cache, budget) = (infatica_cfg.clone(), cache.clone(), budget.clone());
+            async move {
+                let fetch = async {
+                    budget.admit(Provider::Infatica).map_err(|err| vec![quota_error(Provider::Infatica, err)])?;
+                    infatica::get_all(&infatica_cfg)
+                        .await
+                        .map_err(|errors| errors.into_iter().map(ProviderError::from).collect())
+                };
+                cache.get_or_fetch("infatica", &infatica_cfg.get_cache_key(), fetch).await.map(Dataset::Infatica)
+            }
+        });
+        let task = tasks.spawn(run(Provider::Infatica, limit, clock.clone(), cancel.clone(), fetch));
+        task_providers.insert(task.id(), Provider::Infatica);
+    }
+
+    let mut runs = Vec::new();
+    while let Some(joined) = tasks.join_next_with_id().await {
+        runs.push(match joined {
+            Ok((_, run)) => run,
+            Err(err) => ProviderRun {
+                provider: task_providers[&err.id()],
+                outcome: Outcome::Panicked(err.to_string()),
+                elapsed: clock.now() - started,
+            },
+        });
+    }
+    runs.sort_by_key(|run| run.provider);
+
+    // Every task has finished, so this is the last reference
+    if let Some(budget) = Arc::into_inner(budget) {
+        budget.record();
+    }
+    runs
+}
+
+/// Runs `fetch`, repeating it as `policy` allows while all of its errors are retryable,
+/// after the policy's delay on `clock`.
+async fn retrying<T, F>(
+    provider: Provider,
+    policy: RetryPolicy,
+    clock: Arc<dyn Clock>,
+    fetch: impl Fn() -> F,
+) -> Result<T, Vec<ProviderError>>
+where
+    F: Future<Output = Result<T, Vec<ProviderError>>>,
+{
+    let mut retry = 0;
+    loop {
+        match fetch().await {
+            Err(errors) if retry < policy.get_retries() && errors.iter().all(|err| err.retryable) => {
+                retry += 1;
+                let delay = policy.delay(retry);
+                let cause = errors.first().map(|err| format!(" after {err}")).unwrap_or_default();
+                status!("{provider}: retry {retry} of {} in {}{cause}", policy.get_retries(), humantime::format_duration(delay));
+                clock.sleep(delay).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// A fetch refused by its budget; refetching will not help before the next day.
+fn quota_error(provider: Provider, err: QuotaError) -> ProviderError {
+    ProviderError::new(ProviderErrorKind::RateLimited, provider, None, err).retryable(false)
+}
+
+/// Runs one provider's `fetch` under its spinner, bounded by `limit` on `clock` and `cancel`.
+async fn run(
+    provider: Provider,
+    limit: Option<Duration>,
+    clock: Arc<dyn Clock>,
+    cancel: CancellationToken,
+    fetch: impl Future<Output = Result<Dataset, Vec<ProviderError>>>,
+) -> ProviderRun {
+    let spinner = ui::spinner(provider.as_str());
+    let started = clock.now();
+
+    let bounded = async {
+        match limit {
+            Some(limit) => tokio::select! {
+                biased;
+                result = fetch => Ok(result),
+                () = clock.sleep(limit) => Err(limit),
+            },
+            None => Ok(fetch.await),
+        }
+    };
+    let outcome = match cancel.run_until_cancelled(bounded).await {
+        None => Outcome::Cancelled,
+        Some(Err(limit)) => Outcome::TimedOut(limit),
+        Some(Ok(Ok(dataset))) => Outcome::Fetched(dataset),
+        Some(Ok(Err(errors))) => Outcome::Failed(errors),
+    };
+
+    spinner.finish_with_message(match outcome {
+        Outcome::Fetched(_) => "done",
+        Outcome::Failed(_) | Outcome::Panicked(_) => "failed",
+        Outcome::TimedOut(_) => "timed out",
+        Outcome::Cancelled => "cancelled",
+    });
+    ProviderRun { provider, outcome, elapsed: clock.now() - started }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use crate::clock::ManualClock;
+    use super::*;
+
+    fn rate_limited() -> Vec<ProviderError> {
+        vec![ProviderError::new(ProviderErrorKind::RateLimited, Provider::Infatica, None, "429 Too Many Requests")]
+    }
+
+    #[tokio::test]
+    async fn rate_limited_fetches_are_retried_with_backoff() {
+        let clock = ManualClock::new();
+        let responses = Mutex::new(vec![Ok(()), Err(rate_limited()), Err(rate_limited())]);
+        let policy = RetryPolicy::new(3, Some(Duration::from_secs(1)), Some(Duration::from_secs(60)));
+
+        let fetch = || std::future::ready(responses.lock().unwrap().pop().unwrap());
+        retrying(Provider::Infatica, policy, Arc::new(clock.clone()), fetch).await.unwrap();
+
+        assert_eq!(clock.sleeps(), [Duration::from_secs(1), Duration::from_secs(2)]);
+    }
+
+    #[tokio::test]
+    async fn retries_stop_at_the_policy_limit_or_a_permanent_error() {
+        let clock = ManualClock::new();
+        let policy = RetryPolicy::new(2, None, None);
+        let fetch = || std::future::ready(Err::<(), _>(rate_limited()));
+        let errors = retrying(Provider::Infatica, policy, Arc::new(clock.clone()), fetch).await.unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(clock.sleeps().len(), 2);
+
+        let clock = ManualClock::new();
+        let fetch = || std::future::ready(Err::<(), _>(vec![rate_limited().remove(0).retryable(false)]));
+        retrying(Provider::Infatica, policy, Arc::new(clock.clone()), fetch).await.unwrap_err();
+        assert!(clock.sleeps().is_empty());
+    }
+
+    #[tokio::test]
+    async fn fetches_past_their_limit_time_out() {
+        let clock = ManualClock::new();
+        let limit = Duration::from_secs(600);
+        let fetch = std::future::pending();
+
+        let run = run(Provider::IPRoyal, Some(limit), Arc::new(clock.clone()), CancellationToken::new(), fetch).await;
+
+        assert!(matches!(run.outcome, Outcome::TimedOut(l) if l == limit));
+        assert_eq!(run.elapsed, limit);
+    }
+}
@@ -0,0 +1,272 @@
+//! Builder for fetching providers from library code, without a config file or CLI args.
+
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use crate::cache::Cache;
+use crate::models::{AppConfig, CacheConfig, DedupConfig, InfaticaConfig, IPRoyalConfig, ProviderError, ProviderErrorKind};
+use crate::normalize::{self, ValidationReport};
+use crate::pipeline::{self, Outcome};
+use crate::unified::{self, LocationRecord, Provider};
+use crate::run_id;
+
+/// Options of a programmatic fetch.
+///
+/// ```no_run
+/// # use std::time::Duration;
+/// # use update_location::models::InfaticaConfig;
+/// # use update_location::pipeline::FetchOptions;
+/// # use update_location::unified::Provider;
+/// # async fn example() {
+/// let infatica = InfaticaConfig::new("https://infatica.example/".parse().unwrap(), "me@example.com", "secret");
+/// let fetched = FetchOptions::new()
+///     .infatica(infatica)
+///     .providers([Provider::Infatica])
+///     .countries(["US", "DE"])
+///     .timeout(Duration::from_secs(120))
+///     .retries(2)
+///     .fetch()
+///     .await;
+/// println!("{} records, {} errors", fetched.records.len(), fetched.errors.len());
+/// # }
+/// ```
+///
+/// Progress goes through [`crate::ui`]; call `ui::init(Verbosity::Quiet)` first to silence it.
+#[derive(Clone)]
+pub struct FetchOptions {
+    iproyal: Option<IPRoyalConfig>,
+    infatica: Option<InfaticaConfig>,
+    providers: Vec<Provider>,
+    countries: Vec<String>,
+    timeout: Option<Duration>,
+    retries: Option<u32>,
+    dedup: Option<DedupConfig>,
+    cache: Cache,
+    run_id: Option<String>,
+}
+
+/// Result of [`FetchOptions::fetch`]: whatever could be fetched, and why the rest could not.
+pub struct Fetched {
+    /// Normalized records of the fetched providers.
+    pub records: Vec<LocationRecord>,
+    pub report: ValidationReport,
+    /// Errors of the providers still failing after all retries.
+    pub errors: Vec<ProviderError>,
+    /// Id of the fetch, recorded in the provenance of each record.
+    pub run_id: String,
+}
+
+impl Default for FetchOptions {
+    fn default() -> Self {
+        Self {
+            iproyal: None,
+            infatica: None,
+            providers: Provider::ALL.to_vec(),
+            countries: Vec::new(),
+            timeout: None,
+            retries: None,
+            dedup: None,
+            cache: Cache::new(CacheConfig::default().get_dir(), None),
+            run_id: None,
+        }
+    }
+}
+
+impl FetchOptions {
+    /// No providers configured, no timeout and no retries; responses are written to
+    /// the default cache directory but never read back.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables IPRoyal.
+    pub fn iproyal(mut self, cfg: IPRoyalConfig) -> Self {
+        self.iproyal = Some(cfg);
+        self
+    }
+
+    /// Enables Infatica.
+    pub fn infatica(mut self, cfg: InfaticaConfig) -> Self {
+        self.infatica = Some(cfg);
+        self
+    }
+
+    /// Only fetches these of the enabled providers.
+    pub fn providers(mut self, providers: impl IntoIterator<Item = Provider>) -> Self {
+        self.providers = providers.into_iter().collect();
+        self
+    }
+
+    /// Only keeps records of these countries (ISO 3166-1 alpha-2). Infatica geo nodes
+    /// are then fetched per country as well.
+    pub fn countries<S: Into<String>>(mut self, countries: impl IntoIterator<Item = S>) -> Self {
+        self.countries = countries.into_iter().map(|c| c.into().to_uppercase()).collect();
+        self
+    }
+
+    /// Bounds the fetch of each provider, all queries included.
+    pub fn timeout(mut self, limit: Duration) -> Self {
+        self.timeout = Some(limit);
+        self
+    }
+
+    /// Refetches a provider up to `retries` times while all of its errors are retryable,
+    /// overriding the retry policy of its config; [`timeout`](Self::timeout) bounds
+    /// all attempts together.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = Some(retries);
+        self
+    }
+
+    /// Keeps one record per location several providers report, per `policy`.
+    pub fn dedup(mut self, policy: DedupConfig) -> Self {
+        self.dedup = Some(policy);
+        self
+    }
+
+    /// Reads and writes provider responses through `cache`.
+    pub fn cache(mut self, cache: Cache) -> Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Records the fetch as run `id`, e.g. one assigned by an orchestrator, instead
+    /// of a new ULID per fetch.
+    pub fn run_id(mut self, id: impl Into<String>) -> Self {
+        self.run_id = Some(id.into());
+        self
+    }
+
+    /// Fetches the selected providers concurrently, retrying failed ones, and
+    /// returns their normalized records.
+    pub async fn fetch(&self) -> Fetched {
+        let cfg = self.app_config();
+        let run_id = self.run_id.clone().unwrap_or_else(run_id::generate);
+        let cancel = CancellationToken::new();
+        let mut records = Vec::new();
+        let mut errors = Vec::new();
+
+        for run in pipeline::fetch_all(&cfg, &self.cache, &self.providers, &cancel).await {
+            match run.outcome {
+                Outcome::Fetched(dataset) => records.extend(dataset.records(&cfg, &run_id)),
+                Outcome::Failed(failures) => errors.extend(failures),
+                Outcome::TimedOut(limit) => {
+                    let message = format!("no response within {}", humantime::format_duration(limit));
+                    errors.push(ProviderError::new(ProviderErrorKind::Timeout, run.provider, None, message));
+                }
+                Outcome::Panicked(err) => {
+                    let message = format!("fetch task failed: {err}");
+                    errors.push(ProviderError::new(ProviderErrorKind::Server, run.provider, None, message).retryable(false));
+                }
+                Outcome::Cancelled => unreachable!("the token is never cancelled"),
+            }
+        }
+
+        let report = normalize::normalize(&mut records);
+        if let Some(policy) = &self.dedup {
+            unified::dedup(&mut records, policy);
+        }
+        if !self.countries.is_empty() {
+            records.retain(|r| self.countries.contains(&r.country));
+        }
+        Fetched { records, report, errors, run_id }
+    }
+
+    /// The configuration `pipeline::fetch_all` expects, with these options applied.
+    fn app_config(&self) -> AppConfig {
+        let mut cfg = AppConfig {
+            iproyal: self.iproyal.clone(),
+            infatica: self.infatica.clone(),
+            ..AppConfig::default()
+        };
+        if let Some(iproyal) = cfg.iproyal.as_mut() {
+            iproyal.inherit_http(&cfg.http);
+            if let Some(limit) = self.timeout {
+                iproyal.set_fetch_timeout(limit);
+            }
+            if let Some(retries) = self.retries {
+                iproyal.set_retries(retries);
+            }
+        }
+        if let Some(infatica) = cfg.infatica.as_mut() {
+            infatica.inherit_http(&cfg.http);
+            if let Some(limit) = self.timeout {
+                infatica.set_fetch_timeout(limit);
+            }
+            if let Some(retries) = self.retries {
+                infatica.set_retries(retries);
+            }
+            if !self.countries.is_empty() {
+                infatica.set_countries(self.countries.clone());
+            }
+        }
+        cfg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn iproyal() -> IPRoyalConfig {
+        IPRoyalConfig::new("https://iproyal.example/api/".parse().unwrap(), "token")
+    }
+
+    fn infatica() -> InfaticaConfig {
+        InfaticaConfig::new("https://infatica.example/".parse().unwrap(), "me@example.com", "secret")
+    }
+
+    #[test]
+    fn options_apply_to_every_enabled_provider() {
+        let options = FetchOptions::new()
+            .iproyal(iproyal())
+            .infatica(infatica())
+            .providers([Provider::Infatica])
+            .countries(["us", "DE"])
+            .timeout(Duration::from_secs(120))
+            .retries(2);
+
+        assert_eq!(options.providers, [Provider::Infatica]);
+        assert_eq!(options.countries, ["US", "DE"]);
+
+        let cfg = options.app_config();
+        let (iproyal, infatica) = (cfg.iproyal.unwrap(), cfg.infatica.unwrap());
+        assert_eq!(iproyal.get_fetch_timeout(), Some(Duration::from_secs(120)));
+        assert_eq!(iproyal.get_retry_policy().get_retries(), 2);
+        assert_eq!(infatica.get_fetch_timeout(), Some(Duration::from_secs(120)));
+        assert_eq!(infatica.get_retry_policy().get_retries(), 2);
+        assert_eq!(infatica.get_countries(), ["US", "DE"]);
+    }
+
+    #[test]
+    fn unset_options_keep_the_defaults() {
+        let options = FetchOptions::new();
+        assert_eq!(options.providers, Provider::ALL);
+        assert!(options.countries.is_empty());
+        assert!(options.dedup.is_none());
+        let cfg = options.app_config();
+        assert!(cfg.iproyal.is_none() && cfg.infatica.is_none());
+
+        let cfg = FetchOptions::new().iproyal(iproyal()).infatica(infatica()).app_config();
+        let (iproyal, infatica) = (cfg.iproyal.unwrap(), cfg.infatica.unwrap());
+        assert_eq!(iproyal.get_fetch_timeout(), None);
+        assert_eq!(iproyal.get_retry_policy(), self::iproyal().get_retry_policy());
+        assert_eq!(infatica.get_fetch_timeout(), None);
+        assert_eq!(infatica.get_retry_policy(), self::infatica().get_retry_policy());
+        assert!(infatica.get_countries().is_empty());
+    }
+
+    #[test]
+    fn configs_keep_their_own_limits_unless_overridden() {
+        let mut cfg = infatica();
+        cfg.set_fetch_timeout(Duration::from_secs(30));
+        cfg.set_retries(5);
+
+        let kept = FetchOptions::new().infatica(cfg.clone()).app_config().infatica.unwrap();
+        assert_eq!(kept.get_fetch_timeout(), Some(Duration::from_secs(30)));
+        assert_eq!(kept.get_retry_policy().get_retries(), 5);
+
+        let overridden = FetchOptions::new().infatica(cfg).retries(1).app_config().infatica.unwrap();
+        assert_eq!(overridden.get_fetch_timeout(), Some(Duration::from_secs(30)));
+        assert_eq!(overridden.get_retry_policy().get_retries(), 1);
+    }
+}
@@ -0,0 +1,274 @@
+//! # Data-quality metrics
+//!
+//! Per-provider rates that catch vendor-side regressions a successful fetch
+//! does not reveal, such as a renamed field leaving every subdivision empty:
+//!
+//! - **empty subdivisions** — records without a subdivision,
+//! - **unknown codes** — records whose country or subdivision could not be mapped
+//!   to ISO 3166 by [`normalize`](crate::normalize::normalize),
+//! - **duplicates** — records repeating the provider, country, subdivision, city
+//!   and ISP of an earlier one.
+//!
+//! [`QualityLog`] keeps the metrics of the last run next to the provider cache:
+//!
+//! ```text
+//! <cache dir>/quality/metrics.json
+//! ```
+//!
+//! A rate that moved by at least [`MIN_SWING`] between two runs and whose change
+//! is significant under a two-proportion z-test (|z| ≥ [`Z_THRESHOLD`]) is an
+//! [`Anomaly`], so small providers do not flag noise and large ones do not flag
+//! negligible shifts.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use crate::cache::CacheError;
+use crate::normalize::ValidationReport;
+use crate::unified::{LocationRecord, Provider};
+
+const STATE_DIR: &str = "quality";
+const STATE_FILE: &str = "metrics.json";
+
+/// Smallest change of a rate, as a fraction of the records, reported as an anomaly.
+pub const MIN_SWING: f64 = 0.01;
+
+/// Smallest absolute z-score of a change reported as an anomaly.
+pub const Z_THRESHOLD: f64 = 3.0;
+
+/// Quality rates of one provider, as fractions of its records.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QualityMetrics {
+    pub provider: Provider,
+    pub records: usize,
+    pub empty_subdivisions: f64,
+    pub unknown_codes: f64,
+    pub duplicates: f64,
+}
+
+/// One rate of [`QualityMetrics`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Metric {
+    EmptySubdivisions,
+    UnknownCodes,
+    Duplicates,
+}
+
+impl Metric {
+    pub const ALL: [Metric; 3] = [Metric::EmptySubdivisions, Metric::UnknownCodes, Metric::Duplicates];
+
+    fn of(self, metrics: &QualityMetrics) -> f64 {
+        match self {
+            Metric::EmptySubdivisions => metrics.empty_subdivisions,
+            Metric::UnknownCodes => metrics.unknown_codes,
+            Metric::Duplicates => metrics.duplicates,
+        }
+    }
+}
+
+impl fmt::Display for Metric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Metric::EmptySubdivisions => "empty subdivisions",
+            Metric::UnknownCodes => "unknown codes",
+            Metric::Duplicates => "duplicates",
+        })
+    }
+}
+
+/// A rate that changed significantly since the previous run.
+#[derive(Debug, Clone, Serialize)]
+pub struct Anomaly {
+    pub provider: Provider,
+    pub metric: Metric,
+    pub before: f64,
+    pub after: f64,
+    /// z-score of the change.
+    pub z: f64,
+}
+
+impl fmt::Display for Anomaly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} {:.1}% -> {:.1}% (z = {:.1})",
+            self.provider,
+            self.metric,
+            self.before * 100.0,
+            self.after * 100.0,
+            self.z,
+        )
+    }
+}
+
+impl fmt::Display for QualityMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} records, {:.1}% empty subdivisions, {:.1}% unknown codes, {:.1}% duplicates",
+            self.provider,
+            self.records,
+            self.empty_subdivisions * 100.0,
+            self.unknown_codes * 100.0,
+            self.duplicates * 100.0,
+        )
+    }
+}
+
+/// Metrics of every provider present in `records`, in [`Provider::ALL`] order.
+/// `report` is the result of normalizing `records`.
+pub fn compute(records: &[LocationRecord], report: &ValidationReport) -> Vec<QualityMetrics> {
+    Provider::ALL
+        .into_iter()
+        .filter_map(|provider| {
+            let records: Vec<&LocationRecord> = records.iter().filter(|r| r.provider == provider).collect();
+            (!records.is_empty()).then(|| provider_metrics(provider, &records, report))
+        })
+        .collect()
+}
+
+fn provider_metrics(provider: Provider, records: &[&LocationRecord], report: &ValidationReport) -> QualityMetrics {
+    let unknown = report.unknown_countries.iter().filter(|((p, _), _)| *p == provider).map(|(_, n)| n).sum::<usize>()
+        + report.unknown_subdivisions.iter().filter(|((p, ..), _)| *p == provider).map(|(_, n)| n).sum::<usize>();
+    let empty = records.iter().filter(|r| r.subdivision.is_none()).count();
+    let mut seen = HashSet::new();
+    let duplicates = records
+        .iter()
+        .filter(|r| !seen.insert((&r.country, r.subdivision.as_deref(), r.city.as_deref(), r.isp.as_deref())))
+        .count();
+
+    let rate = |count: usize| count as f64 / records.len() as f64;
+    QualityMetrics {
+        provider,
+        records: records.len(),
+        empty_subdivisions: rate(empty),
+        unknown_codes: rate(unknown),
+        duplicates: rate(duplicates),
+    }
+}
+
+/// Rates of `current` that changed significantly since `previous`; providers
+/// missing from either side are skipped.
+pub fn anomalies(previous: &[QualityMetrics], current: &[QualityMetrics]) -> Vec<Anomaly> {
+    let mut anomalies = Vec::new();
+    for after in current {
+        let Some(before) = previous.iter().find(|m| m.provider == after.provider) else {
+            continue;
+        };
+        for metric in Metric::ALL {
+            let (p0, p1) = (metric.of(before), metric.of(after));
+            if (p1 - p0).abs() < MIN_SWING {
+                continue;
+            }
+            if let Some(z) = z_score(p0, before.records, p1, after.records)
+                && z.abs() >= Z_THRESHOLD
+            {
+                anomalies.push(Anomaly { provider: after.provider, metric, before: p0, after: p1, z });
+            }
+        }
+    }
+    anomalies
+}
+
+/// Two-proportion z-score of a rate going from `p0` over `n0` records to `p1` over `n1`.
+fn z_score(p0: f64, n0: usize, p1: f64, n1: usize) -> Option<f64> {
+    if n0 == 0 || n1 == 0 {
+        return None;
+    }
+    let (n0, n1) = (n0 as f64, n1 as f64);
+    let pooled = (p0 * n0 + p1 * n1) / (n0 + n1);
+    let error = (pooled * (1.0 - pooled) * (1.0 / n0 + 1.0 / n1)).sqrt();
+    (error > 0.0).then(|| (p1 - p0) / error)
+}
+
+/// Metrics of the last run, stored in the cache directory.
+pub struct QualityLog {
+    path: PathBuf,
+}
+
+impl QualityLog {
+    pub fn new(cache_dir: &Path) -> Self {
+        Self {
+            path: cache_dir.join(STATE_DIR).join(STATE_FILE),
+        }
+    }
+
+    /// Metrics of the last run; empty before the first one. An unreadable file counts as missing.
+    pub fn previous(&self) -> Vec<QualityMetrics> {
+        fs::read(&self.path)
+            .ok()
+            .and_then(|data| serde_json::from_slice(&data).ok())
+            .unwrap_or_default()
+    }
+
+    /// Stores `current` as the last run; providers missing from it keep their previous metrics.
+    pub fn record(&self, current: &[QualityMetrics]) -> Result<(), CacheError> {
+        let mut next: Vec<QualityMetrics> = self
+            .previous()
+            .into_iter()
+            .filter(|m| !current.iter().any(|c| c.provider == m.provider))
+            .collect();
+        next.extend(current.iter().cloned());
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, serde_json::to_vec(&next)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(subdivision: Option<&str>, city: &str) -> LocationRecord {
+        LocationRecord {
+            subdivision: subdivision.map(str::to_string),
+            city: Some(city.to_string()),
+            nodes: Some(1),
+            ..LocationRecord::sample(Provider::Infatica, "DE")
+        }
+    }
+
+    fn metrics(records: usize, empty_subdivisions: f64) -> QualityMetrics {
+        QualityMetrics { provider: Provider::Infatica, records, empty_subdivisions, unknown_codes: 0.0, duplicates: 0.0 }
+    }
+
+    #[test]
+    fn rates_per_provider() {
+        let records = [
+            record(Some("Berlin"), "Berlin"),
+            record(Some("Berlin"), "Berlin"),
+            record(None, "Hamburg"),
+            record(Some("Atlantis"), "Bremen"),
+        ];
+        let mut report = ValidationReport::default();
+        report.unknown_subdivisions.insert((Provider::Infatica, "DE".to_string(), "Atlantis".to_string()), 1);
+
+        let quality = compute(&records, &report);
+        assert_eq!(quality, [QualityMetrics {
+            provider: Provider::Infatica,
+            records: 4,
+            empty_subdivisions: 0.25,
+            unknown_codes: 0.25,
+            duplicates: 0.25,
+        }]);
+    }
+
+    #[test]
+    fn flags_significant_swings_only() {
+        // 2% to 40% of a thousand records: a regression
+        let found = anomalies(&[metrics(1000, 0.02)], &[metrics(1000, 0.4)]);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].metric, Metric::EmptySubdivisions);
+        assert!(found[0].z > Z_THRESHOLD);
+
+        // The same swing over a handful of records is noise
+        assert!(anomalies(&[metrics(5, 0.2)], &[metrics(5, 0.4)]).is_empty());
+        // A tiny shift over many records is not worth reporting
+        assert!(anomalies(&[metrics(1_000_000, 0.100)], &[metrics(1_000_000, 0.105)]).is_empty());
+    }
+}
@@ -0,0 +1,11 @@
+use thiserror::Error;
+use crate::unified::Provider;
+
+/// A fetch refused by the provider's daily request budget.
+#[derive(Debug, Error)]
+pub enum QuotaError {
+    #[error(
+        "daily quota of {limit} requests would be exceeded: {used} used today, the last fetch took {estimate}",
+    )]
+    Exceeded { provider: Provider, used: u64, estimate: u64, limit: u64 },
+}
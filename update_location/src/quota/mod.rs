@@ -0,0 +1,200 @@
+//! # API request quotas
+//!
+//! Vendor plans meter API calls, so every request sent to a provider is
+//! [counted](count), and [`pipeline::fetch_all`](crate::pipeline::fetch_all)
+//! adds the requests of each run to a ledger in `<cache.dir>/quota.json`:
+//!
+//! ```json
+//! { "day": "2026-10-17", "used": { "iproyal": 12 }, "last_run": { "iproyal": 4 } }
+//! ```
+//!
+//! `used` restarts every UTC day. With `quota.<provider>.daily_limit`
+//! configured, a provider is only fetched while the requests used today plus
+//! those of its last fetch, the estimate of the next one, stay within the
+//! limit. Otherwise it is refused with a [`QuotaError`], or with
+//! `on_exceed = "warn"` fetched after a warning. Data served from the cache
+//! costs nothing and is never refused.
+
+mod errors;
+
+pub use errors::QuotaError;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+use serde::{Deserialize, Serialize};
+use crate::models::{AppConfig, ProviderQuota, QuotaAction, QuotaConfig};
+use crate::unified::Provider;
+use crate::{status, ui};
+
+const LEDGER_FILE: &str = "quota.json";
+
+/// Requests sent by this process, indexed like [`Provider::ALL`].
+static SENT: [AtomicU64; Provider::ALL.len()] = [const { AtomicU64::new(0) }; Provider::ALL.len()];
+
+/// Counts one request sent to `provider`.
+pub fn count(provider: Provider) {
+    SENT[provider as usize].fetch_add(1, Ordering::Relaxed);
+}
+
+/// Requests sent to `provider` by this process so far.
+pub fn sent(provider: Provider) -> u64 {
+    SENT[provider as usize].load(Ordering::Relaxed)
+}
+
+/// Requests per provider, as persisted in the ledger file.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+struct Ledger {
+    /// UTC day `used` belongs to, as `YYYY-MM-DD`.
+    day: String,
+    #[serde(default)]
+    used: BTreeMap<Provider, u64>,
+    /// Requests of the last fetch that reached the provider, across days.
+    #[serde(default)]
+    last_run: BTreeMap<Provider, u64>,
+}
+
+impl Ledger {
+    /// Requests sent to `provider` on `day`.
+    fn used(&self, provider: Provider, day: &str) -> u64 {
+        if self.day == day { self.used.get(&provider).copied().unwrap_or(0) } else { 0 }
+    }
+
+    /// Checks whether a fetch of `provider` on `day` stays within `quota`.
+    fn check(&self, provider: Provider, quota: &ProviderQuota, day: &str) -> Result<(), QuotaError> {
+        let used = self.used(provider, day);
+        let estimate = self.last_run.get(&provider).copied().unwrap_or(0);
+        let limit = quota.get_daily_limit();
+        if used >= limit || used + estimate > limit {
+            return Err(QuotaError::Exceeded { provider, used, estimate, limit });
+        }
+        Ok(())
+    }
+
+    /// Adds the `requests` of one fetch of `provider` on `day`.
+    fn add(&mut self, provider: Provider, requests: u64, day: &str) {
+        if self.day != day {
+            self.day = day.to_string();
+            self.used.clear();
+        }
+        *self.used.entry(provider).or_default() += requests;
+        self.last_run.insert(provider, requests);
+    }
+}
+
+/// The ledger and budgets of one run.
+pub struct Budget {
+    path: PathBuf,
+    ledger: Ledger,
+    quotas: QuotaConfig,
+    /// [`sent`] per provider when the run started.
+    start: [u64; Provider::ALL.len()],
+}
+
+impl Budget {
+    /// Reads the ledger of `cfg.cache.dir`; a missing or unreadable one starts empty.
+    pub fn open(cfg: &AppConfig) -> Self {
+        let path = cfg.cache.get_dir().join(LEDGER_FILE);
+        let ledger = match fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|err| {
+                ui::suspend(|| eprintln!("ignoring unreadable quota ledger {}: {err}", path.display()));
+                Ledger::default()
+            }),
+            Err(_) => Ledger::default(),
+        };
+        Self { path, ledger, quotas: cfg.quota.clone(), start: Provider::ALL.map(sent) }
+    }
+
+    /// Checks whether `provider` may be fetched, warning instead of refusing when so configured.
+    pub fn admit(&self, provider: Provider) -> Result<(), QuotaError> {
+        let Some(quota) = self.quotas.get(provider) else {
+            return Ok(());
+        };
+        match self.ledger.check(provider, quota, &today()) {
+            Err(err) if quota.get_on_exceed() == QuotaAction::Warn => {
+                ui::suspend(|| eprintln!("{provider}: {err}; fetching anyway"));
+                Ok(())
+            }
+            result => result,
+        }
+    }
+
+    /// Adds the requests sent since [`Budget::open`] to the ledger.
+    pub fn record(mut self) {
+        let day = today();
+        let mut changed = false;
+        for provider in Provider::ALL {
+            let requests = sent(provider) - self.start[provider as usize];
+            if requests > 0 {
+                self.ledger.add(provider, requests, &day);
+                changed = true;
+            }
+            if let Some(quota) = self.quotas.get(provider) {
+                status!("{provider}: {} of {} daily requests used", self.ledger.used(provider, &day), quota.get_daily_limit());
+            }
+        }
+
+        if changed && let Err(err) = save(&self.path, &self.ledger) {
+            ui::suspend(|| eprintln!("failed to update quota ledger {}: {err}", self.path.display()));
+        }
+    }
+}
+
+fn save(path: &Path, ledger: &Ledger) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, serde_json::to_vec(ledger)?)
+}
+
+/// Current UTC day as `YYYY-MM-DD`.
+fn today() -> String {
+    humantime::format_rfc3339_seconds(SystemTime::now()).to_string()[..10].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quota(daily_limit: u64) -> ProviderQuota {
+        serde_json::from_value(serde_json::json!({ "daily_limit": daily_limit })).unwrap()
+    }
+
+    #[test]
+    fn refuses_fetches_the_last_run_says_would_exceed_the_limit() {
+        let mut ledger = Ledger::default();
+        let limit = quota(10);
+        assert!(ledger.check(Provider::IPRoyal, &limit, "2026-10-17").is_ok());
+
+        ledger.add(Provider::IPRoyal, 4, "2026-10-17");
+        assert!(ledger.check(Provider::IPRoyal, &limit, "2026-10-17").is_ok());
+
+        ledger.add(Provider::IPRoyal, 4, "2026-10-17");
+        let err = ledger.check(Provider::IPRoyal, &limit, "2026-10-17").unwrap_err();
+        assert!(matches!(err, QuotaError::Exceeded { used: 8, estimate: 4, limit: 10, .. }));
+        assert!(ledger.check(Provider::Infatica, &limit, "2026-10-17").is_ok());
+    }
+
+    #[test]
+    fn usage_restarts_every_day() {
+        let mut ledger = Ledger::default();
+        ledger.add(Provider::IPRoyal, 10, "2026-10-17");
+        assert!(ledger.check(Provider::IPRoyal, &quota(10), "2026-10-17").is_err());
+        assert!(ledger.check(Provider::IPRoyal, &quota(10), "2026-10-18").is_ok());
+
+        ledger.add(Provider::IPRoyal, 3, "2026-10-18");
+        assert_eq!(ledger.used(Provider::IPRoyal, "2026-10-18"), 3);
+        assert_eq!(ledger.last_run[&Provider::IPRoyal], 3);
+    }
+
+    #[test]
+    fn ledger_round_trips() {
+        let mut ledger = Ledger::default();
+        ledger.add(Provider::Infatica, 5, "2026-10-17");
+        let json = serde_json::to_string(&ledger).unwrap();
+        assert_eq!(json, r#"{"day":"2026-10-17","used":{"infatica":5},"last_run":{"infatica":5}}"#);
+        assert_eq!(serde_json::from_str::<Ledger>(&json).unwrap(), ledger);
+    }
+}
@@ -0,0 +1,66 @@
+//! Identifiers of runs, correlating the report, export manifests, sink messages
+//! and every unified record of one invocation.
+//!
+//! The id of the current run is chosen once, from `--run-id` when an orchestrator
+//! assigns its own, or as a new [ULID](https://github.com/ulid/spec) otherwise, and
+//! read through [`current`] so it does not have to be threaded through every call.
+
+use std::sync::OnceLock;
+use ulid::Ulid;
+
+/// Longest id accepted by [`parse`].
+pub const MAX_LEN: usize = 128;
+
+static CURRENT: OnceLock<String> = OnceLock::new();
+
+/// A new run id: a ULID, sortable by creation time.
+pub fn generate() -> String {
+    Ulid::new().to_string()
+}
+
+/// Sets the id of the current run; later calls are ignored.
+pub fn init(id: String) {
+    let _ = CURRENT.set(id);
+}
+
+/// Id of the current run, generated on first use unless [`init`] set one.
+pub fn current() -> &'static str {
+    CURRENT.get_or_init(generate)
+}
+
+/// Parses `--run-id`: up to [`MAX_LEN`] ASCII letters, digits, `-`, `_`, `.` or `:`,
+/// so the id can be used in file names, headers and log queries as is.
+pub fn parse(raw: &str) -> Result<String, String> {
+    if raw.is_empty() {
+        return Err("the run id is empty".to_string());
+    }
+    if raw.len() > MAX_LEN {
+        return Err(format!("the run id is longer than {MAX_LEN} characters"));
+    }
+    match raw.chars().find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))) {
+        Some(c) => Err(format!("invalid character {c:?}; use letters, digits, '-', '_', '.' or ':'")),
+        None => Ok(raw.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_ids_are_ulids() {
+        let id = generate();
+        assert_eq!(id.len(), 26);
+        assert!(Ulid::from_string(&id).is_ok());
+        assert_ne!(id, generate());
+    }
+
+    #[test]
+    fn orchestrator_ids_are_checked() {
+        assert_eq!(parse("airflow:daily_2026-10-17.1").as_deref(), Ok("airflow:daily_2026-10-17.1"));
+        assert!(parse("").is_err());
+        assert!(parse("two words").is_err());
+        assert!(parse("a/b").is_err());
+        assert!(parse(&"x".repeat(MAX_LEN + 1)).is_err());
+    }
+}
@@ -0,0 +1,160 @@
+//! AWS Secrets Manager backend.
+//!
+//! Calls `GetSecretValue`, signed with SigV4 by `aws-sigv4`, using the standard
+//! environment: `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`, optional
+//! `AWS_SESSION_TOKEN`, `AWS_REGION` (or `AWS_DEFAULT_REGION`) and optional
+//! `AWS_ENDPOINT_URL_SECRETS_MANAGER`. Only static credentials are supported.
+
+use std::time::SystemTime;
+use aws_credential_types::Credentials;
+use aws_sigv4::http_request::{sign, SignableBody, SignableRequest, SigningSettings};
+use aws_sigv4::sign::v4;
+use serde_json::{json, Value};
+use super::{field_value, SecretError, SecretRef};
+
+const SERVICE: &str = "secretsmanager";
+const TARGET: &str = "secretsmanager.GetSecretValue";
+const CONTENT_TYPE: &str = "application/x-amz-json-1.1";
+
+/// Static credentials and region of the signed calls.
+struct AwsEnv {
+    access_key: String,
+    secret_key: String,
+    session_token: Option<String>,
+    region: String,
+}
+
+pub async fn fetch(reference: &str, secret: &SecretRef<'_>) -> Result<String, SecretError> {
+    let env = |var: &'static str| {
+        std::env::var(var).map_err(|_| SecretError::MissingEnv { reference: reference.to_string(), var })
+    };
+    let aws = AwsEnv {
+        access_key: env("AWS_ACCESS_KEY_ID")?,
+        secret_key: env("AWS_SECRET_ACCESS_KEY")?,
+        session_token: std::env::var("AWS_SESSION_TOKEN").ok(),
+        region: env("AWS_REGION").or_else(|_| env("AWS_DEFAULT_REGION"))?,
+    };
+
+    let endpoint = std::env::var("AWS_ENDPOINT_URL_SECRETS_MANAGER")
+        .unwrap_or_else(|_| format!("https://{SERVICE}.{}.amazonaws.com", aws.region));
+    let url = url::Url::parse(&endpoint).map_err(|e| SecretError::InvalidRef {
+        reference: reference.to_string(),
+        reason: format!("invalid endpoint {endpoint}: {e}"),
+    })?;
+
+    let body = json!({ "SecretId": secret.location }).to_string();
+    let signing_err = |reason: String| SecretError::Signing { reference: reference.to_string(), reason };
+    let headers = signed_headers(&aws, url.as_str(), &body, SystemTime::now()).map_err(signing_err)?;
+
+    let mut request = reqwest::Client::new().post(url).body(body);
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+
+    let request_err = |source| SecretError::Request { reference: reference.to_string(), source };
+    let resp = request.send().await.map_err(request_err)?;
+    let status = resp.status();
+    if !status.is_success() {
+        let body = resp.text().await.unwrap_or_default();
+        return Err(SecretError::BadStatus { reference: reference.to_string(), status, body });
+    }
+
+    let decode_err = |source| SecretError::Decode { reference: reference.to_string(), source };
+    let bytes = resp.bytes().await.map_err(request_err)?;
+    let json: Value = serde_json::from_slice(&bytes).map_err(decode_err)?;
+    let Some(secret_string) = json["SecretString"].as_str() else {
+        return Err(SecretError::MissingField { reference: reference.to_string(), field: "SecretString".to_string() });
+    };
+
+    match secret.field {
+        None => Ok(secret_string.to_string()),
+        Some(field) => {
+            let object: Value = serde_json::from_str(secret_string).map_err(decode_err)?;
+            field_value(&object, reference, field)
+        }
+    }
+}
+
+/// The headers of a `GetSecretValue` call to `url` carrying `body`, SigV4-signed at `time`.
+fn signed_headers(aws: &AwsEnv, url: &str, body: &str, time: SystemTime) -> Result<Vec<(String, String)>, String> {
+    let identity = Credentials::new(&aws.access_key, &aws.secret_key, aws.session_token.clone(), None, "environment").into();
+    let params = v4::SigningParams::builder()
+        .identity(&identity)
+        .region(&aws.region)
+        .name(SERVICE)
+        .time(time)
+        .settings(SigningSettings::default())
+        .build()
+        .map_err(|e| e.to_string())?
+        .into();
+
+    let mut headers = vec![("content-type".to_string(), CONTENT_TYPE.to_string()), ("x-amz-target".to_string(), TARGET.to_string())];
+    let signable = SignableRequest::new(
+        "POST",
+        url,
+        headers.iter().map(|(name, value)| (name.as_str(), value.as_str())),
+        SignableBody::Bytes(body.as_bytes()),
+    )
+    .map_err(|e| e.to_string())?;
+    let (instructions, _) = sign(signable, &params).map_err(|e| e.to_string())?.into_parts();
+
+    headers.extend(instructions.headers().map(|(name, value)| (name.to_string(), value.to_string())));
+    Ok(headers)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use super::*;
+
+    fn aws(session_token: Option<&str>) -> AwsEnv {
+        // The example credentials of AWS's SigV4 test suite
+        AwsEnv {
+            access_key: "AKIDEXAMPLE".to_string(),
+            secret_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            session_token: session_token.map(str::to_string),
+            region: "us-east-1".to_string(),
+        }
+    }
+
+    fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+        headers.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, v)| v.as_str())
+    }
+
+    // 20150830T123600Z, the date of the test suite
+    const TIME: Duration = Duration::from_secs(1_440_938_160);
+
+    #[test]
+    fn signs_with_the_credential_scope_of_the_call() {
+        let url = "https://secretsmanager.us-east-1.amazonaws.com/";
+        let headers = signed_headers(&aws(None), url, r#"{"SecretId":"proxies"}"#, SystemTime::UNIX_EPOCH + TIME).unwrap();
+
+        assert_eq!(header(&headers, "x-amz-date"), Some("20150830T123600Z"));
+        assert_eq!(header(&headers, "x-amz-target"), Some(TARGET));
+        assert_eq!(header(&headers, "content-type"), Some(CONTENT_TYPE));
+        assert!(header(&headers, "x-amz-security-token").is_none());
+        let authorization = header(&headers, "authorization").unwrap();
+        assert!(authorization.starts_with(
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/secretsmanager/aws4_request, \
+             SignedHeaders=content-type;host;x-amz-date;x-amz-target, Signature="
+        ), "{authorization}");
+    }
+
+    #[test]
+    fn signature_covers_the_body_and_the_session_token() {
+        let url = "https://secretsmanager.us-east-1.amazonaws.com/";
+        let at = SystemTime::UNIX_EPOCH + TIME;
+        let signature = |aws: &AwsEnv, body: &str| {
+            let headers = signed_headers(aws, url, body, at).unwrap();
+            (header(&headers, "authorization").unwrap().to_string(), header(&headers, "x-amz-security-token").map(str::to_string))
+        };
+
+        let (plain, _) = signature(&aws(None), r#"{"SecretId":"a"}"#);
+        let (other_body, _) = signature(&aws(None), r#"{"SecretId":"b"}"#);
+        let (with_token, token) = signature(&aws(Some("session")), r#"{"SecretId":"a"}"#);
+
+        assert_ne!(plain, other_body);
+        assert_eq!(token.as_deref(), Some("session"));
+        assert!(with_token.contains("x-amz-security-token"), "{with_token}");
+    }
+}
@@ -0,0 +1,49 @@
+use thiserror::Error;
+
+/// Failure while resolving a `vault:` / `aws-sm:` config reference.
+///
+/// Backend-specific variants only exist when a backend feature is enabled.
+#[derive(Debug, Error)]
+pub enum SecretError {
+    #[error("failed to rebuild config with resolved secrets: {0}")]
+    Config(#[from] config::ConfigError),
+
+    #[error("`{reference}` needs the `{feature}` feature, which this build does not include")]
+    Unsupported { reference: String, feature: &'static str },
+
+    #[cfg(any(feature = "vault", feature = "aws-sm"))]
+    #[error("`{reference}` is invalid: {reason}")]
+    InvalidRef { reference: String, reason: String },
+
+    #[cfg(any(feature = "vault", feature = "aws-sm"))]
+    #[error("`{reference}`: environment variable {var} is not set")]
+    MissingEnv { reference: String, var: &'static str },
+
+    #[cfg(feature = "aws-sm")]
+    #[error("`{reference}`: failed to sign the request: {reason}")]
+    Signing { reference: String, reason: String },
+
+    #[cfg(any(feature = "vault", feature = "aws-sm"))]
+    #[error("`{reference}`: request failed: {source}")]
+    Request {
+        reference: String,
+        #[source]
+        source: reqwest::Error,
+    },
+
+    #[cfg(any(feature = "vault", feature = "aws-sm"))]
+    #[error("`{reference}`: unexpected HTTP status {status}: {body}")]
+    BadStatus { reference: String, status: reqwest::StatusCode, body: String },
+
+    #[cfg(any(feature = "vault", feature = "aws-sm"))]
+    #[error("`{reference}`: failed to decode response: {source}")]
+    Decode {
+        reference: String,
+        #[source]
+        source: serde_json::Error,
+    },
+
+    #[cfg(any(feature = "vault", feature = "aws-sm"))]
+    #[error("`{reference}`: field `{field}` not found in secret")]
+    MissingField { reference: String, field: String },
+}
@@ -0,0 +1,211 @@
+//! # External secret references
+//!
+//! String config values can reference a secret store instead of holding the
+//! secret itself:
+//!
+//! - `vault:<path>#<field>` — HashiCorp Vault, e.g. `vault:kv/data/proxies#iproyal_token`
+//!   (feature `vault`; see [`vault`] for the environment it reads).
+//! - `aws-sm:<name>[#<field>]` — AWS Secrets Manager; with `#field` the secret
+//!   string is parsed as JSON and the field is taken (feature `aws-sm`).
+//!
+//! References are resolved by [`resolve`] while loading the configuration, before
+//! it is deserialized, so the rest of the application only sees plain values.
+//! A reference to a backend that was not compiled in is an error rather than
+//! being passed through as a literal credential.
+
+mod errors;
+#[cfg(feature = "vault")]
+mod vault;
+#[cfg(feature = "aws-sm")]
+mod aws_sm;
+
+pub use errors::SecretError;
+
+use config::Config;
+use serde_json::Value;
+
+const VAULT_SCHEME: &str = "vault:";
+const AWS_SM_SCHEME: &str = "aws-sm:";
+
+/// A parsed `scheme:location[#field]` reference.
+#[cfg(any(feature = "vault", feature = "aws-sm"))]
+#[derive(Debug)]
+struct SecretRef<'a> {
+    location: &'a str,
+    field: Option<&'a str>,
+}
+
+#[cfg(any(feature = "vault", feature = "aws-sm"))]
+impl<'a> SecretRef<'a> {
+    fn parse(rest: &'a str) -> Self {
+        match rest.split_once('#') {
+            Some((location, field)) => Self { location, field: Some(field) },
+            None => Self { location: rest, field: None },
+        }
+    }
+}
+
+/// Replaces every secret reference in `cfg` with the value fetched from its store.
+pub async fn resolve(cfg: Config) -> Result<Config, SecretError> {
+    let tree = cfg.clone().try_deserialize::<Value>()?;
+    let mut refs = Vec::new();
+    collect_refs(&tree, String::new(), &mut refs);
+
+    if refs.is_empty() {
+        return Ok(cfg);
+    }
+
+    let mut values = Vec::with_capacity(refs.len());
+    for (key, reference) in refs {
+        values.push((key, fetch(&reference).await?));
+    }
+    replace(cfg, values)
+}
+
+/// `cfg` with the value at each `(key, value)` pair's key replaced.
+fn replace(cfg: Config, values: Vec<(String, String)>) -> Result<Config, SecretError> {
+    let mut builder = Config::builder().add_source(cfg);
+    for (key, value) in values {
+        builder = builder.set_override(key, value)?;
+    }
+    Ok(builder.build()?)
+}
+
+/// Collects `(key, reference)` pairs for string leaves using a known scheme, with
+/// dotted keys for tables and `[index]` for array items (`notify.email.to[1]`).
+fn collect_refs(node: &Value, key: String, refs: &mut Vec<(String, String)>) {
+    match node {
+        Value::Object(map) => {
+            for (name, child) in map {
+                let child_key = if key.is_empty() { name.clone() } else { format!("{key}.{name}") };
+                collect_refs(child, child_key, refs);
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                collect_refs(item, format!("{key}[{i}]"), refs);
+            }
+        }
+        Value::String(s) if s.starts_with(VAULT_SCHEME) || s.starts_with(AWS_SM_SCHEME) => {
+            refs.push((key, s.clone()));
+        }
+        _ => {}
+    }
+}
+
+async fn fetch(reference: &str) -> Result<String, SecretError> {
+    #[cfg(feature = "vault")]
+    if let Some(rest) = reference.strip_prefix(VAULT_SCHEME) {
+        return vault::fetch(reference, &SecretRef::parse(rest)).await;
+    }
+
+    #[cfg(feature = "aws-sm")]
+    if let Some(rest) = reference.strip_prefix(AWS_SM_SCHEME) {
+        return aws_sm::fetch(reference, &SecretRef::parse(rest)).await;
+    }
+
+    // Only reached for backends that were not compiled in
+    let feature = if reference.starts_with(VAULT_SCHEME) { "vault" } else { "aws-sm" };
+    Err(SecretError::Unsupported { reference: reference.to_string(), feature })
+}
+
+/// Extracts `field` from a JSON object, accepting string and scalar values.
+#[cfg(any(feature = "vault", feature = "aws-sm"))]
+fn field_value(object: &Value, reference: &str, field: &str) -> Result<String, SecretError> {
+    match object.get(field) {
+        Some(Value::String(s)) => Ok(s.clone()),
+        Some(v @ (Value::Number(_) | Value::Bool(_))) => Ok(v.to_string()),
+        _ => Err(SecretError::MissingField { reference: reference.to_string(), field: field.to_string() }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use config::{File, FileFormat};
+    use serde_json::json;
+    use super::*;
+
+    #[test]
+    fn finds_references_in_nested_tables_and_arrays() {
+        let tree = json!({
+            "iproyal": { "token": "vault:kv/data/proxies#iproyal_token", "endpoint": "https://api.iproyal.com" },
+            "notify": { "email": { "to": ["ops@example.com", "aws-sm:alerts#recipient"] } },
+            "infatica": { "password": "aws-sm:infatica", "retries": 3 },
+            "literal": "vault without a colon",
+        });
+        let mut refs = Vec::new();
+        collect_refs(&tree, String::new(), &mut refs);
+        refs.sort();
+
+        assert_eq!(refs, [
+            ("infatica.password".to_string(), "aws-sm:infatica".to_string()),
+            ("iproyal.token".to_string(), "vault:kv/data/proxies#iproyal_token".to_string()),
+            ("notify.email.to[1]".to_string(), "aws-sm:alerts#recipient".to_string()),
+        ]);
+    }
+
+    #[test]
+    fn replaces_nested_and_array_values() {
+        let cfg = Config::builder()
+            .add_source(File::from_str(
+                "[iproyal]\ntoken = \"vault:kv#t\"\n[notify.email]\nto = [\"ops@example.com\", \"aws-sm:a#r\"]\n",
+                FileFormat::Toml,
+            ))
+            .build()
+            .unwrap();
+
+        let cfg = replace(cfg, vec![
+            ("iproyal.token".to_string(), "secret".to_string()),
+            ("notify.email.to[1]".to_string(), "sales@example.com".to_string()),
+        ]).unwrap();
+
+        assert_eq!(cfg.get_string("iproyal.token").unwrap(), "secret");
+        assert_eq!(cfg.get::<Vec<String>>("notify.email.to").unwrap(), ["ops@example.com", "sales@example.com"]);
+    }
+
+    #[tokio::test]
+    async fn configs_without_references_are_kept() {
+        let cfg = Config::builder().set_override("iproyal.token", "plain").unwrap().build().unwrap();
+
+        assert_eq!(resolve(cfg).await.unwrap().get_string("iproyal.token").unwrap(), "plain");
+    }
+
+    #[cfg(not(feature = "vault"))]
+    #[tokio::test]
+    async fn references_to_missing_backends_are_errors() {
+        let cfg = Config::builder().set_override("iproyal.token", "vault:kv#t").unwrap().build().unwrap();
+
+        assert!(matches!(resolve(cfg).await, Err(SecretError::Unsupported { feature: "vault", .. })));
+    }
+
+    #[cfg(any(feature = "vault", feature = "aws-sm"))]
+    #[test]
+    fn references_split_at_the_field() {
+        let vault = SecretRef::parse("kv/data/x#field");
+        let aws = SecretRef::parse("name");
+        let empty = SecretRef::parse("kv/data/x#");
+
+        assert_eq!((vault.location, vault.field), ("kv/data/x", Some("field")));
+        assert_eq!((aws.location, aws.field), ("name", None));
+        assert_eq!((empty.location, empty.field), ("kv/data/x", Some("")));
+    }
+
+    #[cfg(feature = "vault")]
+    #[tokio::test]
+    async fn vault_references_need_a_field() {
+        let result = fetch("vault:kv/data/x").await;
+
+        assert!(matches!(result, Err(SecretError::InvalidRef { .. })), "{result:?}");
+    }
+
+    #[cfg(any(feature = "vault", feature = "aws-sm"))]
+    #[test]
+    fn fields_must_exist_and_be_scalars() {
+        let object = json!({ "token": "t", "port": 8080, "nested": { "a": 1 } });
+
+        assert_eq!(field_value(&object, "ref", "token").unwrap(), "t");
+        assert_eq!(field_value(&object, "ref", "port").unwrap(), "8080");
+        assert!(matches!(field_value(&object, "ref", "nested"), Err(SecretError::MissingField { .. })));
+        assert!(matches!(field_value(&object, "ref", "absent"), Err(SecretError::MissingField { .. })));
+    }
+}
@@ -0,0 +1,45 @@
+//! HashiCorp Vault backend.
+//!
+//! Reads `VAULT_ADDR`, `VAULT_TOKEN` and optionally `VAULT_NAMESPACE`, like the
+//! Vault CLI, and issues `GET {VAULT_ADDR}/v1/<path>`. Both KV v2 (`data.data`)
+//! and KV v1 (`data`) response shapes are accepted; a `#field` is required.
+
+use serde_json::Value;
+use super::{field_value, SecretError, SecretRef};
+
+pub async fn fetch(reference: &str, secret: &SecretRef<'_>) -> Result<String, SecretError> {
+    let env = |var: &'static str| {
+        std::env::var(var).map_err(|_| SecretError::MissingEnv { reference: reference.to_string(), var })
+    };
+    let Some(field) = secret.field else {
+        return Err(SecretError::InvalidRef {
+            reference: reference.to_string(),
+            reason: "expected vault:<path>#<field>".to_string(),
+        });
+    };
+
+    let url = format!("{}/v1/{}", env("VAULT_ADDR")?.trim_end_matches('/'), secret.location.trim_start_matches('/'));
+    let mut request = reqwest::Client::new()
+        .get(url)
+        .header("X-Vault-Token", env("VAULT_TOKEN")?);
+    if let Ok(namespace) = std::env::var("VAULT_NAMESPACE") {
+        request = request.header("X-Vault-Namespace", namespace);
+    }
+
+    let request_err = |source| SecretError::Request { reference: reference.to_string(), source };
+    let resp = request.send().await.map_err(request_err)?;
+    let status = resp.status();
+    if !status.is_success() {
+        let body = resp.text().await.unwrap_or_default();
+        return Err(SecretError::BadStatus { reference: reference.to_string(), status, body });
+    }
+
+    let body = resp.bytes().await.map_err(request_err)?;
+    let json: Value = serde_json::from_slice(&body)
+        .map_err(|source| SecretError::Decode { reference: reference.to_string(), source })?;
+
+    // KV v2 nests the secret one level deeper than KV v1
+    let data = &json["data"];
+    let object = if data["data"].is_object() { &data["data"] } else { data };
+    field_value(object, reference, field)
+}
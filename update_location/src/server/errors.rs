@@ -0,0 +1,21 @@
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Failure to save or reload a persisted serve snapshot.
+#[derive(Debug, Error)]
+pub enum PersistError {
+    #[error("failed to read snapshot {}: {source}", .path.display())]
+    Read { path: PathBuf, source: std::io::Error },
+
+    #[error("failed to write snapshot {}: {source}", .path.display())]
+    Write { path: PathBuf, source: std::io::Error },
+
+    #[error("{} is not a snapshot of this version", .path.display())]
+    Incompatible { path: PathBuf },
+
+    #[error("invalid snapshot {}: {source}", .path.display())]
+    Decode { path: PathBuf, source: postcard::Error },
+
+    #[error("failed to encode snapshot: {0}")]
+    Encode(#[source] postcard::Error),
+}
@@ -0,0 +1,157 @@
+//! Conversions between the unified model and the generated protobuf messages.
+
+use crate::iproyal::ip_availability::Availability;
+use crate::matching::CoverageEntry;
+use crate::unified::{LocationRecord, Provider};
+use super::proto;
+
+impl From<Provider> for proto::Provider {
+    fn from(provider: Provider) -> Self {
+        match provider {
+            Provider::IPRoyal => proto::Provider::Iproyal,
+            Provider::Infatica => proto::Provider::Infatica,
+        }
+    }
+}
+
+/// Provider filter of a request; `PROVIDER_UNSPECIFIED` (or an unknown value) matches all.
+pub fn provider_filter(provider: proto::Provider) -> Option<Provider> {
+    match provider {
+        proto::Provider::Unspecified => None,
+        proto::Provider::Iproyal => Some(Provider::IPRoyal),
+        proto::Provider::Infatica => Some(Provider::Infatica),
+    }
+}
+
+/// Text filter of a request; an empty string matches all.
+pub fn text_filter(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+impl From<&Availability> for proto::Availability {
+    fn from(availability: &Availability) -> Self {
+        let kind = match *availability {
+            Availability::Approx(n) => Some(proto::availability::Kind::Approx(n)),
+            Availability::Range { min, max } => Some(proto::availability::Kind::Range(proto::Range { min, max })),
+            Availability::Unknown => None,
+        };
+        Self { kind }
+    }
+}
+
+impl From<&LocationRecord> for proto::Location {
+    fn from(record: &LocationRecord) -> Self {
+        Self {
+            provider: proto::Provider::from(record.provider).into(),
+            country: record.country.clone(),
+            subdivision: record.subdivision.clone(),
+            subdivision_code: record.subdivision_code.clone(),
+            city: record.city.clone(),
+            isp: record.isp.clone(),
+            nodes: record.nodes,
+            availability: record.availability.as_ref().map(Into::into),
+            latitude: record.latitude,
+            longitude: record.longitude,
+        }
+    }
+}
+
+impl From<&CoverageEntry> for proto::CoverageEntry {
+    fn from(entry: &CoverageEntry) -> Self {
+        Self {
+            country: entry.country.clone(),
+            city: entry.city.clone(),
+            names: entry.names.clone(),
+            providers: entry
+                .providers
+                .iter()
+                .map(|(provider, coverage)| proto::ProviderCoverage {
+                    provider: proto::Provider::from(*provider).into(),
+                    records: coverage.records as u64,
+                    nodes: coverage.nodes,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use crate::matching::ProviderCoverage;
+    use super::*;
+
+    #[test]
+    fn providers_round_trip_through_the_request_filter() {
+        for provider in Provider::ALL {
+            assert_eq!(provider_filter(provider.into()), Some(provider));
+        }
+        assert_eq!(provider_filter(proto::Provider::Unspecified), None);
+    }
+
+    #[test]
+    fn blank_text_filters_match_all() {
+        assert_eq!(text_filter("  "), None);
+        assert_eq!(text_filter(" DE "), Some("DE".to_string()));
+    }
+
+    #[test]
+    fn availability_keeps_its_figures() {
+        let kind = |a: &Availability| proto::Availability::from(a).kind;
+        assert_eq!(kind(&Availability::Approx(10)), Some(proto::availability::Kind::Approx(10)));
+        assert_eq!(
+            kind(&Availability::Range { min: 1, max: 5 }),
+            Some(proto::availability::Kind::Range(proto::Range { min: 1, max: 5 }))
+        );
+        assert_eq!(kind(&Availability::Unknown), None);
+    }
+
+    #[test]
+    fn locations_carry_every_field_of_the_record() {
+        let record = LocationRecord {
+            subdivision: Some("Bavaria".to_string()),
+            subdivision_code: Some("BY".to_string()),
+            city: Some("Munich".to_string()),
+            isp: Some("Telekom".to_string()),
+            nodes: Some(12),
+            availability: Some(Availability::Approx(100)),
+            latitude: Some(48.1),
+            longitude: Some(11.6),
+            ..LocationRecord::sample(Provider::Infatica, "DE")
+        };
+
+        let location = proto::Location::from(&record);
+        assert_eq!(provider_filter(location.provider()), Some(record.provider));
+        assert_eq!(
+            (location.country.as_str(), location.subdivision, location.subdivision_code),
+            ("DE", record.subdivision, record.subdivision_code)
+        );
+        assert_eq!((location.city, location.isp, location.nodes), (record.city, record.isp, record.nodes));
+        assert_eq!((location.latitude, location.longitude), (record.latitude, record.longitude));
+        assert_eq!(location.availability.and_then(|a| a.kind), Some(proto::availability::Kind::Approx(100)));
+    }
+
+    #[test]
+    fn coverage_lists_each_provider_once() {
+        let entry = CoverageEntry {
+            country: "DE".to_string(),
+            city: "Munich".to_string(),
+            names: vec!["Munich".to_string(), "München".to_string()],
+            providers: BTreeMap::from([
+                (Provider::IPRoyal, ProviderCoverage { records: 2, nodes: 30 }),
+                (Provider::Infatica, ProviderCoverage { records: 1, nodes: 5 }),
+            ]),
+        };
+
+        let message = proto::CoverageEntry::from(&entry);
+        assert_eq!((message.country.as_str(), message.city.as_str()), ("DE", "Munich"));
+        assert_eq!(message.names, entry.names);
+        let providers: Vec<_> = message
+            .providers
+            .iter()
+            .map(|p| (provider_filter(p.provider()), p.records, p.nodes))
+            .collect();
+        assert_eq!(providers, [(Some(Provider::IPRoyal), 2, 30), (Some(Provider::Infatica), 1, 5)]);
+    }
+}
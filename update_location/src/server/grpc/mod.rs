@@ -0,0 +1,36 @@
+//! # gRPC API (feature `grpc`)
+//!
+//! Serves the same [`Snapshot`](super::Snapshot) data as the HTTP API through the
+//! `LocationService` of `proto/location.proto`:
+//!
+//! | RPC             | equivalent                                   |
+//! |-----------------|----------------------------------------------|
+//! | `ListLocations` | `GET /v1/locations`                          |
+//! | `GetCoverage`   | `GET /v1/coverage`, `GET /v1/coverage/diff`  |
+//! | `StreamChanges` | –, pushes the records changed by each refresh |
+//!
+//! The generated client, [`proto::location_service_client::LocationServiceClient`],
+//! is exported for services that consume the data from Rust.
+
+mod convert;
+mod service;
+
+/// Code generated from `proto/location.proto` by `build.rs`.
+#[allow(dead_code, clippy::all)]
+pub mod proto {
+    tonic::include_proto!("update_location.v1");
+}
+
+use std::net::SocketAddr;
+use tokio_util::sync::CancellationToken;
+use crate::server::SnapshotStore;
+use proto::location_service_server::LocationServiceServer;
+use service::LocationApi;
+
+/// Serves the gRPC API on `addr` until `cancel` fires; open change streams end with it.
+pub async fn serve(addr: SocketAddr, store: SnapshotStore, cancel: CancellationToken) -> Result<(), tonic::transport::Error> {
+    tonic::transport::Server::builder()
+        .add_service(LocationServiceServer::new(LocationApi::new(store, cancel.clone())))
+        .serve_with_shutdown(addr, cancel.cancelled_owned())
+        .await
+}
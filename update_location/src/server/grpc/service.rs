@@ -0,0 +1,248 @@
+use std::pin::Pin;
+use tokio::sync::mpsc;
+use tokio_stream::Stream;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::sync::CancellationToken;
+use tonic::{Request, Response, Status};
+use crate::changes::{self, ChangeKind};
+use crate::server::{Snapshot, SnapshotStore};
+use crate::unified::{LocationRecord, Provider};
+use super::convert::{provider_filter, text_filter};
+use super::proto;
+use super::proto::location_service_server::LocationService;
+
+/// Change sets buffered per stream before a slow client holds back its refresh.
+const STREAM_BUFFER: usize = 4;
+
+pub struct LocationApi {
+    store: SnapshotStore,
+    shutdown: CancellationToken,
+}
+
+impl LocationApi {
+    pub fn new(store: SnapshotStore, shutdown: CancellationToken) -> Self {
+        Self { store, shutdown }
+    }
+}
+
+/// Record filters shared by `ListLocations` and `StreamChanges`.
+struct RecordFilter {
+    provider: Option<Provider>,
+    country: Option<String>,
+    city: Option<String>,
+}
+
+impl RecordFilter {
+    fn accepts(&self, record: &LocationRecord) -> bool {
+        self.provider.is_none_or(|p| p == record.provider)
+            && matches(&self.country, Some(&record.country))
+            && matches(&self.city, record.city.as_deref())
+    }
+}
+
+/// Whether an optional filter accepts `value`, ignoring case.
+fn matches(filter: &Option<String>, value: Option<&str>) -> bool {
+    filter
+        .as_deref()
+        .is_none_or(|f| value.is_some_and(|v| v.eq_ignore_ascii_case(f)))
+}
+
+#[tonic::async_trait]
+impl LocationService for LocationApi {
+    async fn list_locations(
+        &self,
+        request: Request<proto::ListLocationsRequest>,
+    ) -> Result<Response<proto::ListLocationsResponse>, Status> {
+        let request = request.into_inner();
+        let filter = RecordFilter {
+            provider: provider_filter(request.provider()),
+            country: text_filter(&request.country),
+            city: text_filter(&request.city),
+        };
+
+        let snapshot = self.store.current();
+        Ok(Response::new(proto::ListLocationsResponse {
+            locations: snapshot
+                .find_records(filter.country.as_deref(), filter.city.as_deref())
+                .filter(|r| filter.provider.is_none_or(|p| p == r.provider))
+                .map(Into::into)
+                .collect(),
+            fetched_at: snapshot.fetched_at_secs(),
+        }))
+    }
+
+    async fn get_coverage(
+        &self,
+        request: Request<proto::GetCoverageRequest>,
+    ) -> Result<Response<proto::GetCoverageResponse>, Status> {
+        let request = request.into_inner();
+        let country = text_filter(&request.country);
+        let provider = provider_filter(request.provider());
+
+        let snapshot = self.store.current();
+        let entries = snapshot
+            .find_coverage(country.as_deref())
+            .filter(|e| !request.exclusive_only || e.is_exclusive())
+            .filter(|e| !request.exclusive_only || provider.is_none_or(|p| e.providers.contains_key(&p)))
+            .map(Into::into)
+            .collect();
+
+        Ok(Response::new(proto::GetCoverageResponse {
+            entries,
+            fetched_at: snapshot.fetched_at_secs(),
+        }))
+    }
+
+    type StreamChangesStream = Pin<Box<dyn Stream<Item = Result<proto::ChangeSet, Status>> + Send>>;
+
+    async fn stream_changes(
+        &self,
+        request: Request<proto::StreamChangesRequest>,
+    ) -> Result<Response<Self::StreamChangesStream>, Status> {
+        let request = request.into_inner();
+        let filter = RecordFilter {
+            provider: provider_filter(request.provider()),
+            country: text_filter(&request.country),
+            city: None,
+        };
+
+        let mut snapshots = self.store.subscribe();
+        let mut previous = snapshots.borrow_and_update().clone();
+        let shutdown = self.shutdown.clone();
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER);
+
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    () = shutdown.cancelled() => break,
+                    () = tx.closed() => break,
+                    changed = snapshots.changed() => if changed.is_err() { break },
+                }
+
+                let current = snapshots.borrow_and_update().clone();
+                let changes = changes(&previous, &current, &filter);
+                previous = current;
+                if is_empty(&changes) {
+                    continue;
+                }
+                if tx.send(Ok(changes)).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+}
+
+/// Records of `current` added, removed or updated relative to `previous`.
+fn changes(previous: &Snapshot, current: &Snapshot, filter: &RecordFilter) -> proto::ChangeSet {
+    let before = previous.records.iter().filter(|r| filter.accepts(r)).cloned().collect::<Vec<_>>();
+    let after = current.records.iter().filter(|r| filter.accepts(r)).cloned().collect::<Vec<_>>();
+
+    let mut set = proto::ChangeSet {
+        fetched_at: current.fetched_at_secs(),
+        ..Default::default()
+    };
+    for change in changes::diff(&before, &after) {
+        let location = (&change.record).into();
+        match change.kind {
+            ChangeKind::Added => set.added.push(location),
+            ChangeKind::Removed => set.removed.push(location),
+            ChangeKind::CountChanged => set.updated.push(location),
+        }
+    }
+
+    set
+}
+
+fn is_empty(set: &proto::ChangeSet) -> bool {
+    set.added.is_empty() && set.removed.is_empty() && set.updated.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio_stream::StreamExt;
+    use super::*;
+
+    fn record(provider: Provider, country: &str, city: &str, nodes: u64) -> LocationRecord {
+        LocationRecord {
+            city: Some(city.to_string()),
+            nodes: Some(nodes),
+            ..LocationRecord::sample(provider, country)
+        }
+    }
+
+    fn api(records: Vec<LocationRecord>) -> (LocationApi, SnapshotStore) {
+        let store = SnapshotStore::new(Snapshot::new(records, 0.9));
+        (LocationApi::new(store.clone(), CancellationToken::new()), store)
+    }
+
+    fn cities(locations: &[proto::Location]) -> Vec<&str> {
+        locations.iter().map(|l| l.city()).collect()
+    }
+
+    #[tokio::test]
+    async fn list_locations_applies_the_request_filters() {
+        let (api, _) = api(vec![
+            record(Provider::IPRoyal, "DE", "Berlin", 1),
+            record(Provider::Infatica, "DE", "Hamburg", 2),
+            record(Provider::Infatica, "US", "Boston", 3),
+        ]);
+
+        let request = proto::ListLocationsRequest {
+            provider: proto::Provider::Infatica.into(),
+            country: "de".to_string(),
+            city: String::new(),
+        };
+        let response = api.list_locations(Request::new(request)).await.unwrap().into_inner();
+        assert_eq!(cities(&response.locations), ["Hamburg"]);
+        assert!(response.fetched_at > 0);
+    }
+
+    #[tokio::test]
+    async fn get_coverage_keeps_exclusive_cities_of_the_provider() {
+        let (api, _) = api(vec![
+            record(Provider::IPRoyal, "DE", "Berlin", 1),
+            record(Provider::Infatica, "DE", "Berlin", 1),
+            record(Provider::Infatica, "DE", "Hamburg", 2),
+            record(Provider::IPRoyal, "DE", "Bonn", 3),
+        ]);
+
+        let request = proto::GetCoverageRequest {
+            country: "DE".to_string(),
+            exclusive_only: true,
+            provider: proto::Provider::Infatica.into(),
+        };
+        let response = api.get_coverage(Request::new(request)).await.unwrap().into_inner();
+        let cities: Vec<_> = response.entries.iter().map(|e| e.city.as_str()).collect();
+        assert_eq!(cities, ["Hamburg"]);
+    }
+
+    #[tokio::test]
+    async fn stream_changes_pushes_each_refresh() {
+        let (api, store) = api(vec![
+            record(Provider::IPRoyal, "DE", "Berlin", 1),
+            record(Provider::IPRoyal, "DE", "Bonn", 1),
+        ]);
+        let request = proto::StreamChangesRequest {
+            provider: proto::Provider::Iproyal.into(),
+            country: "DE".to_string(),
+        };
+        let mut stream = api.stream_changes(Request::new(request)).await.unwrap().into_inner();
+
+        store.replace(Snapshot::new(
+            vec![
+                record(Provider::IPRoyal, "DE", "Berlin", 5),
+                record(Provider::IPRoyal, "DE", "Hamburg", 1),
+                record(Provider::IPRoyal, "US", "Boston", 1),
+            ],
+            0.9,
+        ));
+
+        let set = stream.next().await.unwrap().unwrap();
+        assert_eq!(cities(&set.added), ["Hamburg"]);
+        assert_eq!(cities(&set.removed), ["Bonn"]);
+        assert_eq!(cities(&set.updated), ["Berlin"]);
+    }
+}
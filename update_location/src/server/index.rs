@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+use crate::matching::CoverageEntry;
+use crate::unified::LocationRecord;
+
+/// Lookup tables over the records and coverage of a [`Snapshot`](super::Snapshot),
+/// built once per snapshot so filtered requests do not scan every record.
+///
+/// Keys are ASCII-lowercased, matching the case-insensitive filters of the API;
+/// each list holds positions in ascending order, so results keep snapshot order.
+#[derive(Default)]
+pub struct SnapshotIndex {
+    countries: HashMap<String, Vec<usize>>,
+    cities: HashMap<(String, String), Vec<usize>>,
+    city_names: HashMap<String, Vec<usize>>,
+    coverage_countries: HashMap<String, Vec<usize>>,
+}
+
+impl SnapshotIndex {
+    pub fn new(records: &[LocationRecord], coverage: &[CoverageEntry]) -> Self {
+        let mut index = Self::default();
+        for (position, record) in records.iter().enumerate() {
+            let country = key(&record.country);
+            if let Some(city) = record.city.as_deref().map(key) {
+                index.cities.entry((country.clone(), city.clone())).or_default().push(position);
+                index.city_names.entry(city).or_default().push(position);
+            }
+            index.countries.entry(country).or_default().push(position);
+        }
+        for (position, entry) in coverage.iter().enumerate() {
+            index.coverage_countries.entry(key(&entry.country)).or_default().push(position);
+        }
+        index
+    }
+
+    /// Positions of the records in `country` and `city`; `None` when neither is
+    /// given, i.e. every record matches.
+    pub fn records(&self, country: Option<&str>, city: Option<&str>) -> Option<&[usize]> {
+        let positions = match (country, city) {
+            (None, None) => return None,
+            (Some(country), None) => self.countries.get(&key(country)),
+            (None, Some(city)) => self.city_names.get(&key(city)),
+            (Some(country), Some(city)) => self.cities.get(&(key(country), key(city))),
+        };
+        Some(positions.map_or(&[], Vec::as_slice))
+    }
+
+    /// Positions of the coverage entries of `country`; `None` when not given.
+    pub fn coverage(&self, country: Option<&str>) -> Option<&[usize]> {
+        country.map(|country| self.coverage_countries.get(&key(country)).map_or(&[][..], Vec::as_slice))
+    }
+}
+
+fn key(value: &str) -> String {
+    value.to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::matching::coverage;
+    use crate::unified::Provider;
+    use super::*;
+
+    fn record(country: &str, city: Option<&str>) -> LocationRecord {
+        LocationRecord {
+            city: city.map(str::to_string),
+            nodes: Some(1),
+            ..LocationRecord::sample(Provider::Infatica, country)
+        }
+    }
+
+    #[test]
+    fn lookups_match_a_case_insensitive_scan() {
+        let records = vec![
+            record("US", Some("Austin")),
+            record("DE", Some("Berlin")),
+            record("US", None),
+            record("US", Some("austin")),
+            record("CA", Some("Austin")),
+        ];
+        let index = SnapshotIndex::new(&records, &coverage(&records, 0.9));
+
+        let scan = |country: Option<&str>, city: Option<&str>| -> Vec<usize> {
+            let matches = |f: Option<&str>, v: Option<&str>| f.is_none_or(|f| v.is_some_and(|v| v.eq_ignore_ascii_case(f)));
+            (0..records.len())
+                .filter(|&i| matches(country, Some(&records[i].country)) && matches(city, records[i].city.as_deref()))
+                .collect()
+        };
+        for (country, city) in [(Some("us"), None), (Some("US"), Some("AUSTIN")), (None, Some("austin")), (Some("FR"), None), (Some("de"), Some("Austin"))] {
+            assert_eq!(index.records(country, city).unwrap(), scan(country, city), "{country:?} {city:?}");
+        }
+        assert!(index.records(None, None).is_none());
+        assert_eq!(index.coverage(Some("fr")), Some(&[][..]));
+    }
+}
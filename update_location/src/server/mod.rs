@@ -0,0 +1,56 @@
+//! # HTTP API
+//!
+//! Serves the unified location data of the latest [`Snapshot`] as JSON:
+//!
+//! | route                 | query parameters             | response                                   |
+//! |-----------------------|------------------------------|--------------------------------------------|
+//! | `GET /v1/locations`   | `provider`, `country`, `city`| unified records                            |
+//! | `GET /v1/coverage`    | `country`                    | matched cities with per-provider figures   |
+//! | `GET /v1/coverage/diff` | `country`, `provider`      | cities served by a single provider         |
+//! | `GET /healthz`        | –                            | snapshot age and size                      |
+//!
+//! Filters are optional and combine with AND; `country` and `city` are matched
+//! case-insensitively through per-country and per-city indexes built with each
+//! snapshot, so they do not scan the whole dataset. The snapshot is swapped atomically on refresh, so a request
+//! never sees a half-updated dataset.
+//!
+//! With the `grpc` feature, [`grpc`] serves the same data over gRPC.
+//! [`persist`] saves snapshots in a compact binary form to restart on.
+
+#[cfg(feature = "grpc")]
+pub mod grpc;
+mod errors;
+mod index;
+pub mod persist;
+mod routes;
+mod snapshot;
+
+pub use errors::PersistError;
+pub use snapshot::{Snapshot, SnapshotStore};
+
+use std::net::SocketAddr;
+use std::future::Future;
+use axum::Router;
+use axum::routing::get;
+
+/// Builds the API router over `store`.
+pub fn router(store: SnapshotStore) -> Router {
+    Router::new()
+        .route("/v1/locations", get(routes::locations))
+        .route("/v1/coverage", get(routes::coverage))
+        .route("/v1/coverage/diff", get(routes::coverage_diff))
+        .route("/healthz", get(routes::health))
+        .with_state(store)
+}
+
+/// Serves the API on `addr` until `shutdown` completes.
+pub async fn serve(
+    addr: SocketAddr,
+    store: SnapshotStore,
+    shutdown: impl Future<Output = ()> + Send + 'static,
+) -> std::io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, router(store))
+        .with_graceful_shutdown(shutdown)
+        .await
+}
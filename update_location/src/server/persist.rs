@@ -0,0 +1,166 @@
+//! Binary form of a [`Snapshot`], so `serve` can restart on the data it last
+//! served without refetching or re-parsing the JSON cache.
+//!
+//! The file holds a short header followed by the records as zstd-compressed
+//! postcard. Records go through [`StoredRecord`] rather than their JSON shape,
+//! which skips absent fields and so cannot be read back by a non-self-describing
+//! format. Changing [`StoredRecord`] requires bumping [`VERSION`]; files of other
+//! versions are rejected and the data refetched.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use crate::iproyal::ip_availability::Availability;
+use crate::unified::{LocationRecord, Provenance, Provider};
+use super::{PersistError, Snapshot};
+
+/// Identifies snapshot files, followed by the [`VERSION`] byte.
+const MAGIC: &[u8; 6] = b"ULSNAP";
+
+/// Version of the encoded layout.
+const VERSION: u8 = 3;
+
+/// zstd level; favours fast reloads over the smallest file.
+const ZSTD_LEVEL: i32 = 3;
+
+#[derive(Serialize, Deserialize)]
+struct Frame {
+    /// Unix seconds of the fetch behind the snapshot.
+    fetched_at: u64,
+    records: Vec<StoredRecord>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredRecord {
+    provider: Provider,
+    country: String,
+    subdivision: Option<String>,
+    subdivision_code: Option<String>,
+    city: Option<String>,
+    city_ascii: Option<String>,
+    isp: Option<String>,
+    nodes: Option<u64>,
+    availability: Option<Availability>,
+    latitude: Option<f64>,
+    longitude: Option<f64>,
+    targeting: Option<String>,
+    provenance: Option<Provenance>,
+}
+
+impl From<&LocationRecord> for StoredRecord {
+    fn from(record: &LocationRecord) -> Self {
+        let record = record.clone();
+        Self {
+            provider: record.provider,
+            country: record.country,
+            subdivision: record.subdivision,
+            subdivision_code: record.subdivision_code,
+            city: record.city,
+            city_ascii: record.city_ascii,
+            isp: record.isp,
+            nodes: record.nodes,
+            availability: record.availability,
+            latitude: record.latitude,
+            longitude: record.longitude,
+            targeting: record.targeting,
+            provenance: record.provenance,
+        }
+    }
+}
+
+impl From<StoredRecord> for LocationRecord {
+    fn from(record: StoredRecord) -> Self {
+        Self {
+            provider: record.provider,
+            country: record.country,
+            subdivision: record.subdivision,
+            subdivision_code: record.subdivision_code,
+            city: record.city,
+            city_ascii: record.city_ascii,
+            isp: record.isp,
+            nodes: record.nodes,
+            availability: record.availability,
+            latitude: record.latitude,
+            longitude: record.longitude,
+            targeting: record.targeting,
+            provenance: record.provenance,
+        }
+    }
+}
+
+/// Writes `snapshot` to `path`, replacing any previous file atomically.
+pub fn save(path: &Path, snapshot: &Snapshot) -> Result<(), PersistError> {
+    let frame = Frame {
+        fetched_at: snapshot.fetched_at_secs(),
+        records: snapshot.records.iter().map(StoredRecord::from).collect(),
+    };
+    let encoded = postcard::to_stdvec(&frame).map_err(PersistError::Encode)?;
+
+    let write = |target: &PathBuf| -> std::io::Result<()> {
+        if let Some(dir) = target.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = fs::File::create(target)?;
+        file.write_all(MAGIC)?;
+        file.write_all(&[VERSION])?;
+        zstd::stream::copy_encode(encoded.as_slice(), &mut file, ZSTD_LEVEL)?;
+        file.sync_all()
+    };
+    let partial = path.with_extension("partial");
+    write(&partial)
+        .and_then(|()| fs::rename(&partial, path))
+        .map_err(|source| PersistError::Write { path: path.to_path_buf(), source })
+}
+
+/// Reads the snapshot saved at `path`, deriving its coverage with `threshold`.
+pub fn load(path: &Path, threshold: f64) -> Result<Snapshot, PersistError> {
+    let bytes = fs::read(path).map_err(|source| PersistError::Read { path: path.to_path_buf(), source })?;
+    let compressed = bytes
+        .strip_prefix(MAGIC.as_slice())
+        .and_then(|rest| rest.strip_prefix(&[VERSION]))
+        .ok_or_else(|| PersistError::Incompatible { path: path.to_path_buf() })?;
+    let encoded = zstd::decode_all(compressed).map_err(|source| PersistError::Read { path: path.to_path_buf(), source })?;
+    let frame: Frame = postcard::from_bytes(&encoded).map_err(|source| PersistError::Decode { path: path.to_path_buf(), source })?;
+
+    let records = frame.records.into_iter().map(LocationRecord::from).collect();
+    Ok(Snapshot::restore(records, threshold, UNIX_EPOCH + Duration::from_secs(frame.fetched_at)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(city: Option<&str>, provenance: Option<Provenance>) -> LocationRecord {
+        LocationRecord {
+            subdivision: Some("Berlin".to_string()),
+            city: city.map(str::to_string),
+            availability: Some(Availability::Approx(10_000)),
+            latitude: Some(52.52),
+            targeting: Some("_country-de".to_string()),
+            provenance,
+            ..LocationRecord::sample(Provider::IPRoyal, "DE")
+        }
+    }
+
+    #[test]
+    fn snapshots_survive_a_round_trip() {
+        let path = std::env::temp_dir().join(format!("update_location_persist_{}", std::process::id()));
+        let provenance = Provenance { fetched_at: Some(1_700_000_000), source_endpoint: "https://example.com/v1".to_string(), run_id: "run".to_string() };
+        let records = vec![record(Some("Berlin"), Some(provenance)), record(None, None)];
+        let snapshot = Snapshot::restore(records.clone(), 0.9, UNIX_EPOCH + Duration::from_secs(1_700_000_100));
+
+        save(&path, &snapshot).unwrap();
+        let loaded = load(&path, 0.9);
+        fs::write(&path, b"ULSNAP\x00").unwrap();
+        let outdated = load(&path, 0.9);
+        let _ = fs::remove_file(&path);
+
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.records, records);
+        assert_eq!(loaded.fetched_at_secs(), 1_700_000_100);
+        assert_eq!(loaded.coverage.len(), snapshot.coverage.len());
+        assert!(matches!(outdated, Err(PersistError::Incompatible { .. })));
+    }
+}
@@ -0,0 +1,190 @@
+use axum::extract::{Query, State};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use crate::matching::CoverageEntry;
+use crate::server::SnapshotStore;
+use crate::unified::{LocationRecord, Provider};
+
+#[derive(Deserialize)]
+pub struct LocationQuery {
+    provider: Option<Provider>,
+    country: Option<String>,
+    city: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct CoverageQuery {
+    country: Option<String>,
+    /// Only for `/v1/coverage/diff`: cities served exclusively by this provider.
+    provider: Option<Provider>,
+}
+
+#[derive(Serialize)]
+pub struct Health {
+    records: usize,
+    cities: usize,
+    /// Unix seconds of the fetch behind the served data.
+    fetched_at: u64,
+}
+
+pub async fn locations(
+    State(store): State<SnapshotStore>,
+    Query(query): Query<LocationQuery>,
+) -> Json<Vec<LocationRecord>> {
+    let snapshot = store.current();
+    let records = snapshot
+        .find_records(query.country.as_deref(), query.city.as_deref())
+        .filter(|r| query.provider.is_none_or(|p| p == r.provider))
+        .cloned()
+        .collect();
+
+    Json(records)
+}
+
+pub async fn coverage(
+    State(store): State<SnapshotStore>,
+    Query(query): Query<CoverageQuery>,
+) -> Json<Vec<CoverageEntry>> {
+    let snapshot = store.current();
+    let entries = snapshot
+        .find_coverage(query.country.as_deref())
+        .cloned()
+        .collect();
+
+    Json(entries)
+}
+
+pub async fn coverage_diff(
+    State(store): State<SnapshotStore>,
+    Query(query): Query<CoverageQuery>,
+) -> Json<Vec<CoverageEntry>> {
+    let snapshot = store.current();
+    let entries = snapshot
+        .find_coverage(query.country.as_deref())
+        .filter(|e| e.is_exclusive())
+        .filter(|e| query.provider.is_none_or(|p| e.providers.contains_key(&p)))
+        .cloned()
+        .collect();
+
+    Json(entries)
+}
+
+pub async fn health(State(store): State<SnapshotStore>) -> Json<Health> {
+    let snapshot = store.current();
+    Json(Health {
+        records: snapshot.records.len(),
+        cities: snapshot.coverage.len(),
+        fetched_at: snapshot.fetched_at_secs(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use axum::Router;
+    use http_body_util::BodyExt;
+    use serde_json::Value;
+    use tower::ServiceExt;
+    use crate::server::{router, Snapshot};
+    use super::*;
+
+    fn record(provider: Provider, country: &str, city: &str, nodes: u64) -> LocationRecord {
+        LocationRecord {
+            city: Some(city.to_string()),
+            nodes: Some(nodes),
+            ..LocationRecord::sample(provider, country)
+        }
+    }
+
+    fn app() -> Router {
+        let records = vec![
+            record(Provider::IPRoyal, "DE", "Berlin", 10),
+            record(Provider::Infatica, "DE", "Berlin", 20),
+            record(Provider::Infatica, "DE", "Hamburg", 5),
+            record(Provider::IPRoyal, "US", "Boston", 7),
+        ];
+        router(SnapshotStore::new(Snapshot::new(records, 0.9)))
+    }
+
+    async fn get(app: Router, uri: &str) -> (StatusCode, Value) {
+        let response = app.oneshot(Request::get(uri).body(Body::empty()).unwrap()).await.unwrap();
+        let status = response.status();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&bytes).unwrap_or(Value::Null))
+    }
+
+    /// `(provider, country, city)` of each returned record.
+    fn locations(body: &Value) -> Vec<(&str, &str, &str)> {
+        body.as_array()
+            .unwrap()
+            .iter()
+            .map(|r| (r["provider"].as_str().unwrap(), r["country"].as_str().unwrap(), r["city"].as_str().unwrap()))
+            .collect()
+    }
+
+    fn cities(body: &Value) -> Vec<&str> {
+        body.as_array().unwrap().iter().map(|e| e["city"].as_str().unwrap()).collect()
+    }
+
+    #[tokio::test]
+    async fn locations_are_filtered_by_the_query() {
+        let (status, body) = get(app(), "/v1/locations").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.as_array().unwrap().len(), 4);
+
+        let (_, body) = get(app(), "/v1/locations?country=de&city=berlin").await;
+        assert_eq!(locations(&body), [("iproyal", "DE", "Berlin"), ("infatica", "DE", "Berlin")]);
+
+        let (_, body) = get(app(), "/v1/locations?country=DE&provider=infatica").await;
+        assert_eq!(locations(&body), [("infatica", "DE", "Berlin"), ("infatica", "DE", "Hamburg")]);
+    }
+
+    #[tokio::test]
+    async fn unknown_locations_are_an_empty_list() {
+        let (status, body) = get(app(), "/v1/locations?country=FR").await;
+        assert_eq!((status, body), (StatusCode::OK, Value::Array(Vec::new())));
+
+        let (status, body) = get(app(), "/v1/coverage?country=FR").await;
+        assert_eq!((status, body), (StatusCode::OK, Value::Array(Vec::new())));
+    }
+
+    #[tokio::test]
+    async fn unknown_routes_are_not_found() {
+        let (status, _) = get(app(), "/v1/location").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn invalid_filters_are_bad_requests() {
+        let (status, _) = get(app(), "/v1/locations?provider=netnut").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn coverage_merges_providers_per_city() {
+        let (status, body) = get(app(), "/v1/coverage?country=de").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(cities(&body), ["Berlin", "Hamburg"]);
+        assert_eq!(body[0]["providers"]["iproyal"]["nodes"], 10);
+        assert_eq!(body[0]["providers"]["infatica"]["nodes"], 20);
+    }
+
+    #[tokio::test]
+    async fn coverage_diff_lists_cities_of_a_single_provider() {
+        let (status, body) = get(app(), "/v1/coverage/diff").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(cities(&body), ["Hamburg", "Boston"]);
+
+        let (_, body) = get(app(), "/v1/coverage/diff?provider=iproyal").await;
+        assert_eq!(cities(&body), ["Boston"]);
+    }
+
+    #[tokio::test]
+    async fn health_reports_the_snapshot_size() {
+        let (status, body) = get(app(), "/healthz").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!((body["records"].as_u64(), body["cities"].as_u64()), (Some(4), Some(3)));
+        assert!(body["fetched_at"].as_u64().unwrap() > 0);
+    }
+}
@@ -0,0 +1,128 @@
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::watch;
+use crate::matching::{coverage, CoverageEntry};
+use crate::unified::LocationRecord;
+use super::index::SnapshotIndex;
+
+/// Immutable view of one fetch: records and the coverage derived from them.
+///
+/// Country and city lookups go through an index built with the snapshot, so
+/// filtered requests cost the size of their result rather than of the dataset.
+pub struct Snapshot {
+    pub records: Vec<LocationRecord>,
+    pub coverage: Vec<CoverageEntry>,
+    pub fetched_at: SystemTime,
+    index: SnapshotIndex,
+}
+
+impl Snapshot {
+    pub fn new(records: Vec<LocationRecord>, threshold: f64) -> Self {
+        Self::restore(records, threshold, SystemTime::now())
+    }
+
+    /// Snapshot of `records` fetched at `fetched_at`, e.g. when reloaded from disk.
+    pub fn restore(records: Vec<LocationRecord>, threshold: f64, fetched_at: SystemTime) -> Self {
+        let coverage = coverage(&records, threshold);
+        Self {
+            index: SnapshotIndex::new(&records, &coverage),
+            coverage,
+            records,
+            fetched_at,
+        }
+    }
+
+    /// Records in `country` and `city`, ignoring case; unset filters match all.
+    pub fn find_records(&self, country: Option<&str>, city: Option<&str>) -> Box<dyn Iterator<Item = &LocationRecord> + Send + '_> {
+        match self.index.records(country, city) {
+            Some(positions) => Box::new(positions.iter().map(|&i| &self.records[i])),
+            None => Box::new(self.records.iter()),
+        }
+    }
+
+    /// Coverage entries of `country`, ignoring case; unset matches all.
+    pub fn find_coverage(&self, country: Option<&str>) -> Box<dyn Iterator<Item = &CoverageEntry> + Send + '_> {
+        match self.index.coverage(country) {
+            Some(positions) => Box::new(positions.iter().map(|&i| &self.coverage[i])),
+            None => Box::new(self.coverage.iter()),
+        }
+    }
+
+    /// Unix seconds of [`fetched_at`](Self::fetched_at).
+    pub fn fetched_at_secs(&self) -> u64 {
+        self.fetched_at
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default()
+    }
+}
+
+/// Shared handle to the current snapshot.
+#[derive(Clone)]
+pub struct SnapshotStore(Arc<watch::Sender<Arc<Snapshot>>>);
+
+impl SnapshotStore {
+    pub fn new(snapshot: Snapshot) -> Self {
+        Self(Arc::new(watch::Sender::new(Arc::new(snapshot))))
+    }
+
+    /// Current snapshot; stays valid while a refresh replaces it.
+    pub fn current(&self) -> Arc<Snapshot> {
+        self.0.borrow().clone()
+    }
+
+    pub fn replace(&self, snapshot: Snapshot) {
+        self.0.send_replace(Arc::new(snapshot));
+    }
+
+    /// Receiver notified on every [`replace`](Self::replace).
+    #[cfg(feature = "grpc")]
+    pub fn subscribe(&self) -> watch::Receiver<Arc<Snapshot>> {
+        self.0.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+    use crate::unified::Provider;
+    use crate::server::router;
+    use super::*;
+
+    fn snapshot(countries: &[&str]) -> Snapshot {
+        let records = countries.iter().map(|c| LocationRecord::sample(Provider::IPRoyal, c)).collect();
+        Snapshot::restore(records, 0.9, UNIX_EPOCH + std::time::Duration::from_secs(60))
+    }
+
+    async fn countries(store: &SnapshotStore) -> Vec<String> {
+        let request = Request::get("/v1/locations").body(Body::empty()).unwrap();
+        let response = router(store.clone()).oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let records: Vec<LocationRecord> = serde_json::from_slice(&bytes).unwrap();
+        records.into_iter().map(|r| r.country).collect()
+    }
+
+    #[test]
+    fn lookups_ignore_case_and_unset_filters_match_all() {
+        let snapshot = snapshot(&["DE", "US", "DE"]);
+        assert_eq!(snapshot.find_records(Some("de"), None).count(), 2);
+        assert_eq!(snapshot.find_records(Some("FR"), None).count(), 0);
+        assert_eq!(snapshot.find_records(None, None).count(), 3);
+        assert_eq!(snapshot.fetched_at_secs(), 60);
+    }
+
+    #[tokio::test]
+    async fn replaced_snapshots_serve_the_next_request() {
+        let store = SnapshotStore::new(snapshot(&["DE"]));
+        let held = store.current();
+        assert_eq!(countries(&store).await, ["DE"]);
+
+        store.replace(snapshot(&["US", "FR"]));
+        assert_eq!(countries(&store).await, ["US", "FR"]);
+        assert_eq!(held.records.len(), 1);
+    }
+}
@@ -0,0 +1,55 @@
+//! # Graceful shutdown
+//!
+//! The first SIGINT / SIGTERM (Ctrl-C on Windows) cancels the token returned by
+//! [`cancel_on_signal`]. Provider fetches racing against it are dropped, which
+//! aborts their in-flight HTTP requests; commands then report whatever data was
+//! already retrieved and exit with [`EXIT_INTERRUPTED`]. A second signal exits
+//! immediately.
+//!
+//! Cache writes are synchronous and never interrupted halfway.
+
+use tokio_util::sync::CancellationToken;
+
+/// Exit code of a run cut short by a signal (128 + SIGINT, as shells report it).
+pub const EXIT_INTERRUPTED: u8 = 130;
+
+/// Returns a token cancelled on the first termination signal.
+///
+/// Must be called inside the tokio runtime. Until it is called, signals keep their
+/// default behaviour of killing the process.
+pub fn cancel_on_signal() -> CancellationToken {
+    let token = CancellationToken::new();
+    let cancel = token.clone();
+
+    tokio::spawn(async move {
+        if terminated().await.is_err() {
+            return;
+        }
+        crate::ui::suspend(|| eprintln!("interrupted, finishing with the data fetched so far (signal again to abort)"));
+        cancel.cancel();
+
+        if terminated().await.is_ok() {
+            std::process::exit(i32::from(EXIT_INTERRUPTED));
+        }
+    });
+
+    token
+}
+
+/// Waits for SIGINT or SIGTERM.
+#[cfg(unix)]
+async fn terminated() -> std::io::Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut sigterm = signal(SignalKind::terminate())?;
+    tokio::select! {
+        res = tokio::signal::ctrl_c() => res,
+        _ = sigterm.recv() => Ok(()),
+    }
+}
+
+/// Waits for Ctrl-C.
+#[cfg(not(unix))]
+async fn terminated() -> std::io::Result<()> {
+    tokio::signal::ctrl_c().await
+}
@@ -0,0 +1,34 @@
+use thiserror::Error;
+
+/// Failure while delivering change events to a sink.
+///
+/// Sink-specific variants only exist when a sink feature is enabled.
+#[derive(Debug, Error)]
+pub enum SinkError {
+    #[cfg(not(all(feature = "kafka", feature = "nats")))]
+    #[error("the `{sink}` sink needs the `{feature}` feature, which this build does not include")]
+    Unsupported { sink: &'static str, feature: &'static str },
+
+    #[error("the sink task panicked")]
+    Panicked,
+
+    #[cfg(any(feature = "kafka", feature = "nats"))]
+    #[error("failed to encode change event: {0}")]
+    Encode(#[from] serde_json::Error),
+
+    #[cfg(any(feature = "kafka", feature = "nats"))]
+    #[error("timed out after {}", humantime::format_duration(*.0))]
+    Timeout(std::time::Duration),
+
+    #[cfg(feature = "kafka")]
+    #[error("kafka error: {0}")]
+    Kafka(#[from] rskafka::client::error::Error),
+
+    #[cfg(feature = "kafka")]
+    #[error("kafka topic `{0}` does not exist or has no partitions")]
+    UnknownTopic(String),
+
+    #[cfg(feature = "nats")]
+    #[error("nats error: {0}")]
+    Nats(#[from] async_nats::Error),
+}
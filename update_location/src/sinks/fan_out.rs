@@ -0,0 +1,258 @@
+//! Concurrent delivery of batches to several sinks.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinSet;
+use tokio::time::Instant;
+use crate::sinks::{Batch, ChangeEvent, RunSummary, Sink, SinkError, SinkFailure};
+
+/// Writes every batch to all of its sinks concurrently, each in its own task.
+///
+/// A sink whose `write` fails or panics is not written to again nor finalized;
+/// its error is returned by [`finish`](FanOut::finish), so one broken sink never
+/// holds back the others.
+pub struct FanOut {
+    sinks: Vec<Arc<dyn Sink>>,
+    failures: Vec<SinkFailure>,
+}
+
+impl FanOut {
+    pub fn new(sinks: Vec<Arc<dyn Sink>>) -> Self {
+        Self { sinks, failures: Vec::new() }
+    }
+
+    /// Writes `batch` to every healthy sink, waiting for all of them.
+    pub async fn write(&mut self, batch: Batch) {
+        let batch = Arc::new(batch);
+        self.run(move |sink| {
+            let batch = batch.clone();
+            async move { sink.write(&batch).await }
+        })
+        .await;
+    }
+
+    /// Writes the events received on `events` in batches of up to `size`, the
+    /// first batch carrying `summaries`.
+    ///
+    /// A partial batch is written once `interval` passed since its first event.
+    /// Nothing is received while a batch is being written, so the sender waits as
+    /// soon as the channel is full.
+    pub async fn write_stream(
+        &mut self,
+        summaries: Vec<RunSummary>,
+        mut events: mpsc::Receiver<ChangeEvent>,
+        size: usize,
+        interval: Duration,
+    ) {
+        let mut batch = Batch { summaries, events: Vec::with_capacity(size) };
+        let mut deadline = Instant::now();
+        loop {
+            tokio::select! {
+                event = events.recv() => match event {
+                    Some(event) => {
+                        if batch.events.is_empty() {
+                            deadline = Instant::now() + interval;
+                        }
+                        batch.events.push(event);
+                        if batch.events.len() < size {
+                            continue;
+                        }
+                    }
+                    None => break,
+                },
+                () = tokio::time::sleep_until(deadline), if !batch.events.is_empty() => {}
+            }
+            let full = std::mem::replace(&mut batch, Batch { summaries: Vec::new(), events: Vec::with_capacity(size) });
+            self.write(full).await;
+        }
+
+        // The rest, or the summaries alone when there were no events
+        if !batch.events.is_empty() || !batch.summaries.is_empty() {
+            self.write(batch).await;
+        }
+    }
+
+    /// Finalizes every healthy sink, returning the failures of the run by sink name.
+    pub async fn finish(mut self) -> Vec<SinkFailure> {
+        self.run(|sink| async move { sink.finalize().await }).await;
+        self.failures
+    }
+
+    /// Runs `step` for each healthy sink concurrently, keeping those it succeeds for.
+    async fn run<F, Fut>(&mut self, step: F)
+    where
+        F: Fn(Arc<dyn Sink>) -> Fut,
+        Fut: Future<Output = Result<(), SinkError>> + Send + 'static,
+    {
+        let mut tasks = JoinSet::new();
+        for (i, sink) in self.sinks.iter().enumerate() {
+            let step = step(sink.clone());
+            tasks.spawn(async move { (i, step.await) });
+        }
+
+        // A panicked task leaves its sink without a result
+        let mut results: Vec<Option<Result<(), SinkError>>> = self.sinks.iter().map(|_| None).collect();
+        while let Some(joined) = tasks.join_next().await {
+            if let Ok((i, result)) = joined {
+                results[i] = Some(result);
+            }
+        }
+
+        // Sinks are kept in their configured order, so failures are reported in it
+        let sinks = std::mem::take(&mut self.sinks);
+        for (sink, result) in sinks.into_iter().zip(results) {
+            match result {
+                Some(Ok(())) => self.sinks.push(sink),
+                Some(Err(err)) => self.failures.push((sink.name(), err)),
+                None => self.failures.push((sink.name(), SinkError::Panicked)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use super::*;
+    use crate::changes::{Change, ChangeKind};
+    use crate::sinks::SinkFuture;
+    use crate::unified::{LocationRecord, Provider, SCHEMA_VERSION};
+
+    /// Counts its writes and finalizations, failing or panicking on the first write if asked to.
+    struct TestSink {
+        name: &'static str,
+        fail: bool,
+        panic: bool,
+        writes: AtomicUsize,
+        finalized: AtomicUsize,
+        /// Summaries and events of each batch written.
+        batches: Mutex<Vec<(usize, usize)>>,
+    }
+
+    impl TestSink {
+        fn new(name: &'static str, fail: bool, panic: bool) -> Arc<Self> {
+            Arc::new(Self {
+                name,
+                fail,
+                panic,
+                writes: AtomicUsize::new(0),
+                finalized: AtomicUsize::new(0),
+                batches: Mutex::new(Vec::new()),
+            })
+        }
+    }
+
+    fn event(country: &str) -> ChangeEvent {
+        let record = LocationRecord { nodes: Some(1), ..LocationRecord::sample(Provider::IPRoyal, country) };
+        let change = Change { kind: ChangeKind::Added, record, nodes_before: None, nodes_after: Some(1) };
+        ChangeEvent { change, observed_at: 0, run_id: String::new(), schema_version: SCHEMA_VERSION }
+    }
+
+    fn summary() -> RunSummary {
+        RunSummary {
+            provider: Provider::IPRoyal,
+            records: 5,
+            added: 5,
+            removed: 0,
+            count_changed: 0,
+            baseline: false,
+            anomalies: Vec::new(),
+            observed_at: 0,
+            run_id: String::new(),
+            schema_version: SCHEMA_VERSION,
+        }
+    }
+
+    impl Sink for TestSink {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+
+        fn write<'a>(&'a self, batch: &'a Batch) -> SinkFuture<'a> {
+            Box::pin(async move {
+                self.writes.fetch_add(1, Ordering::SeqCst);
+                self.batches.lock().unwrap().push((batch.summaries.len(), batch.events.len()));
+                assert!(!self.panic, "broken sink");
+                // The one variant every build has
+                if self.fail { Err(SinkError::Panicked) } else { Ok(()) }
+            })
+        }
+
+        fn finalize(&self) -> SinkFuture<'_> {
+            Box::pin(async move {
+                self.finalized.fetch_add(1, Ordering::SeqCst);
+                Ok(())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn failing_sinks_do_not_stop_the_others() {
+        let healthy = TestSink::new("healthy", false, false);
+        let failing = TestSink::new("failing", true, false);
+        let panicking = TestSink::new("panicking", false, true);
+
+        let mut fan_out = FanOut::new(vec![panicking.clone(), healthy.clone(), failing.clone()]);
+        fan_out.write(Batch::default()).await;
+        fan_out.write(Batch::default()).await;
+        let failures = fan_out.finish().await;
+
+        let names: Vec<_> = failures.iter().map(|(name, _)| *name).collect();
+        assert_eq!(names, ["panicking", "failing"]);
+        assert_eq!(healthy.writes.load(Ordering::SeqCst), 2);
+        assert_eq!(healthy.finalized.load(Ordering::SeqCst), 1);
+        assert_eq!(failing.writes.load(Ordering::SeqCst), 1);
+        assert_eq!(failing.finalized.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn events_are_written_in_batches() {
+        let sink = TestSink::new("sink", false, false);
+        let (sender, receiver) = mpsc::channel(2);
+        let produce = async move {
+            for country in ["US", "DE", "FR", "GB", "NL"] {
+                sender.send(event(country)).await.unwrap();
+            }
+        };
+
+        let mut fan_out = FanOut::new(vec![sink.clone()]);
+        let stream = fan_out.write_stream(vec![summary()], receiver, 2, Duration::from_secs(60));
+        tokio::join!(produce, stream);
+        assert!(fan_out.finish().await.is_empty());
+
+        assert_eq!(*sink.batches.lock().unwrap(), [(1, 2), (0, 2), (0, 1)]);
+    }
+
+    /// Events sent `pause` apart on a paused clock, batched with a 50ms interval.
+    async fn batches_of_events_sent(pause: Duration) -> Vec<(usize, usize)> {
+        let sink = TestSink::new("sink", false, false);
+        let (sender, receiver) = mpsc::channel(10);
+        sender.send(event("US")).await.unwrap();
+        let produce = async move {
+            tokio::time::advance(pause).await;
+            // Lets the stream see the expired interval before the next event arrives
+            tokio::task::yield_now().await;
+            sender.send(event("DE")).await.unwrap();
+        };
+
+        // The stream is polled first, so the interval starts before the clock moves
+        let mut fan_out = FanOut::new(vec![sink.clone()]);
+        let stream = fan_out.write_stream(Vec::new(), receiver, 10, Duration::from_millis(50));
+        tokio::join!(stream, produce);
+
+        sink.batches.lock().unwrap().clone()
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn partial_batches_are_flushed_after_the_interval() {
+        assert_eq!(batches_of_events_sent(Duration::from_millis(50)).await, [(0, 1), (0, 1)]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn partial_batches_wait_for_the_interval() {
+        assert_eq!(batches_of_events_sent(Duration::from_millis(49)).await, [(0, 2)]);
+    }
+}
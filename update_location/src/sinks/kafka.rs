@@ -0,0 +1,207 @@
+//! Kafka producer for change events.
+//!
+//! Connects to the configured brokers once per run, on the first batch with
+//! messages, picks each message's partition from its key with the Java
+//! client's default partitioner (murmur2), and produces per-partition batches.
+
+use std::collections::BTreeMap;
+use chrono::Utc;
+use rskafka::client::{Client, ClientBuilder};
+use rskafka::client::partition::{Compression, UnknownTopicHandling};
+use rskafka::record::Record;
+use tokio::sync::OnceCell;
+use crate::models::{KafkaConfig, KafkaKey};
+use crate::sinks::{print_dry_run, Batch, ChangeEvent, Message, Sink, SinkError, SinkFuture};
+
+/// Records per produce request, keeping requests below the broker's default size limit.
+const BATCH_SIZE: usize = 500;
+
+/// Produces one message per event to `kafka.topic`; in a dry run, only prints them.
+pub struct KafkaSink {
+    cfg: KafkaConfig,
+    dry_run: bool,
+    connection: OnceCell<Connection>,
+}
+
+/// A client and the partitions of `kafka.topic`.
+struct Connection {
+    client: Client,
+    partitions: Vec<i32>,
+}
+
+impl KafkaSink {
+    pub fn new(cfg: KafkaConfig, dry_run: bool) -> Self {
+        Self { cfg, dry_run, connection: OnceCell::new() }
+    }
+
+    async fn publish(&self, events: &[ChangeEvent]) -> Result<(), SinkError> {
+        let messages = messages(&self.cfg, events)?;
+        if self.dry_run {
+            print_dry_run("kafka", &messages);
+            return Ok(());
+        }
+        if messages.is_empty() {
+            return Ok(());
+        }
+        let timeout = self.cfg.get_timeout();
+        let produce = async {
+            let connection = self.connection.get_or_try_init(|| connect(&self.cfg)).await?;
+            produce(connection, self.cfg.get_topic(), messages).await
+        };
+        tokio::time::timeout(timeout, produce)
+            .await
+            .map_err(|_| SinkError::Timeout(timeout))?
+    }
+}
+
+impl Sink for KafkaSink {
+    fn name(&self) -> &'static str {
+        "kafka"
+    }
+
+    fn write<'a>(&'a self, batch: &'a Batch) -> SinkFuture<'a> {
+        Box::pin(self.publish(&batch.events))
+    }
+}
+
+/// One message per event, keyed per `kafka.key`.
+fn messages(cfg: &KafkaConfig, events: &[ChangeEvent]) -> Result<Vec<Message>, SinkError> {
+    events
+        .iter()
+        .map(|event| {
+            Ok(Message {
+                destination: cfg.get_topic().to_string(),
+                key: key(cfg.get_key(), event),
+                payload: serde_json::to_vec(event)?,
+            })
+        })
+        .collect()
+}
+
+async fn connect(cfg: &KafkaConfig) -> Result<Connection, SinkError> {
+    let mut builder = ClientBuilder::new(cfg.get_brokers().to_vec());
+    if let Some(client_id) = cfg.get_client_id() {
+        builder = builder.client_id(client_id);
+    }
+    let client = builder.build().await?;
+
+    let topic = cfg.get_topic();
+    let partitions = client
+        .list_topics()
+        .await?
+        .into_iter()
+        .find(|t| t.name == topic)
+        .map(|t| t.partitions.into_iter().collect::<Vec<_>>())
+        .filter(|p| !p.is_empty())
+        .ok_or_else(|| SinkError::UnknownTopic(topic.to_string()))?;
+
+    Ok(Connection { client, partitions })
+}
+
+async fn produce(connection: &Connection, topic: &str, messages: Vec<Message>) -> Result<(), SinkError> {
+    let Connection { client, partitions } = connection;
+    let mut batches: BTreeMap<i32, Vec<Record>> = BTreeMap::new();
+    for (i, message) in messages.into_iter().enumerate() {
+        let partition = match &message.key {
+            Some(key) => partitions[partition_index(key.as_bytes(), partitions.len())],
+            None => partitions[i % partitions.len()],
+        };
+        batches.entry(partition).or_default().push(Record {
+            key: message.key.map(String::into_bytes),
+            value: Some(message.payload),
+            headers: BTreeMap::new(),
+            timestamp: Utc::now(),
+        });
+    }
+
+    for (partition, records) in batches {
+        let client = client
+            .partition_client(topic, partition, UnknownTopicHandling::Error)
+            .await?;
+        let mut records = records.into_iter().peekable();
+        while records.peek().is_some() {
+            let batch = records.by_ref().take(BATCH_SIZE).collect();
+            client.produce(batch, Compression::NoCompression).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Message key of `event` under `scheme`.
+fn key(scheme: KafkaKey, event: &ChangeEvent) -> Option<String> {
+    let record = &event.change.record;
+    match scheme {
+        KafkaKey::Location => Some(format!(
+            "{}/{}/{}/{}/{}",
+            record.provider,
+            record.country,
+            record.subdivision.as_deref().unwrap_or_default(),
+            record.city.as_deref().unwrap_or_default(),
+            record.isp.as_deref().unwrap_or_default(),
+        )),
+        KafkaKey::Provider => Some(record.provider.to_string()),
+        KafkaKey::Country => Some(format!("{}/{}", record.provider, record.country)),
+        KafkaKey::None => None,
+    }
+}
+
+/// Partition index of `key`, as chosen by the Java client's default partitioner.
+fn partition_index(key: &[u8], partitions: usize) -> usize {
+    (murmur2(key) & 0x7fff_ffff) as usize % partitions
+}
+
+/// Kafka's variant of MurmurHash2 (`org.apache.kafka.common.utils.Utils.murmur2`).
+fn murmur2(data: &[u8]) -> u32 {
+    const SEED: u32 = 0x9747_b28c;
+    const M: u32 = 0x5bd1_e995;
+    const R: u32 = 24;
+
+    let mut h = SEED ^ data.len() as u32;
+    let chunks = data.chunks_exact(4);
+    let tail = chunks.remainder();
+    for chunk in chunks {
+        let mut k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        k = k.wrapping_mul(M);
+        k ^= k >> R;
+        k = k.wrapping_mul(M);
+        h = h.wrapping_mul(M);
+        h ^= k;
+    }
+
+    if tail.len() >= 3 {
+        h ^= u32::from(tail[2]) << 16;
+    }
+    if tail.len() >= 2 {
+        h ^= u32::from(tail[1]) << 8;
+    }
+    if !tail.is_empty() {
+        h ^= u32::from(tail[0]);
+        h = h.wrapping_mul(M);
+    }
+
+    h ^= h >> 13;
+    h = h.wrapping_mul(M);
+    h ^= h >> 15;
+    h
+}
+
+#[cfg(test)]
+mod tests {
+    use super::murmur2;
+
+    #[test]
+    fn murmur2_matches_the_java_client() {
+        let cases: [(&str, i32); 6] = [
+            ("21", -973932308),
+            ("foobar", -790332482),
+            ("a-little-bit-long-string", -985981536),
+            ("a-little-bit-longer-string", -1486304829),
+            ("lkjh234lh9fiuh90y23oiuhsafujhadof229phr9h19h89h8", -58897971),
+            ("abc", 479470107),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(murmur2(input.as_bytes()) as i32, expected, "{input}");
+        }
+    }
+}
@@ -0,0 +1,250 @@
+//! # Change sinks
+//!
+//! Delivers the [`Change`]s found by the default fetch command to external systems,
+//! so downstream consumers react to coverage changes without polling:
+//!
+//! - **Kafka** (`[kafka]`, feature `kafka`) — one JSON [`ChangeEvent`] message per
+//!   change, keyed per `kafka.key`.
+//! - **NATS** (`[nats]`, feature `nats`) — a [`RunSummary`] per fetched provider on
+//!   `<prefix>.<provider>.summary` and its change events on `<prefix>.<provider>.changes`,
+//!   optionally persisted through JetStream.
+//!
+//! Summaries and change events carry the id of the run that produced them (see
+//! [`crate::run_id`]), to correlate them with its report and export manifest, and
+//! the [schema version](crate::unified::schema) of the records they hold.
+//!
+//! Every configured sink publishes unless `output.sinks` (or `--sinks`) lists the
+//! ones to use, so a sink can be paused without removing its section.
+//!
+//! Changes are computed against the last run recorded by [`ChangeLog`](crate::changes::ChangeLog),
+//! which only advances once every sink accepted the events; a failed delivery is
+//! therefore repeated, merged with newer changes, by the next run.
+//!
+//! In a dry run (`--dry-run`), each sink builds its messages as usual but only
+//! prints their destinations, counts and a few samples, without connecting.
+//!
+//! Every sink implements [`Sink`] and is driven by a [`FanOut`], which writes each
+//! [`Batch`] to all sinks concurrently. A sink that fails is dropped from the run
+//! without affecting the others, and is reported with its error.
+//!
+//! Change events reach the fan-out through a bounded channel and are written in
+//! batches of `output.batch_size`, a partial batch once `output.flush_interval`
+//! passed. While the sinks are busy the channel fills up and new events wait, so
+//! memory stays bounded however slow a sink is.
+
+mod errors;
+mod fan_out;
+#[cfg(feature = "kafka")]
+mod kafka;
+#[cfg(feature = "nats")]
+mod nats;
+
+pub use errors::SinkError;
+pub use fan_out::FanOut;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::Serialize;
+use tokio::sync::mpsc;
+use crate::changes::{Change, ChangeKind};
+use crate::models::{AppConfig, KafkaConfig, NatsConfig, SinkKind};
+use crate::quality::Anomaly;
+use crate::unified::{LocationRecord, Provider, SCHEMA_VERSION};
+
+/// Message payload of one change.
+#[derive(Serialize)]
+pub struct ChangeEvent {
+    #[serde(flatten)]
+    pub change: Change,
+    /// Unix seconds of the run that observed the change.
+    pub observed_at: u64,
+    /// Id of the run that observed the change, see [`crate::run_id`].
+    pub run_id: String,
+    /// Version of the record layout, see [`crate::unified::schema`].
+    pub schema_version: u32,
+}
+
+/// Outcome of one run for one provider.
+#[derive(Serialize)]
+pub struct RunSummary {
+    pub provider: Provider,
+    pub records: usize,
+    pub added: usize,
+    pub removed: usize,
+    pub count_changed: usize,
+    /// The first run only records a baseline, without changes.
+    pub baseline: bool,
+    /// Quality rates of the provider that changed significantly since the last run.
+    pub anomalies: Vec<Anomaly>,
+    pub observed_at: u64,
+    pub run_id: String,
+    pub schema_version: u32,
+}
+
+/// What a sink publishes, as one unit of delivery.
+///
+/// The run summaries travel with the first batch of the run.
+#[derive(Default)]
+pub struct Batch {
+    pub summaries: Vec<RunSummary>,
+    pub events: Vec<ChangeEvent>,
+}
+
+/// A sink that failed, by name, and its error.
+pub type SinkFailure = (&'static str, SinkError);
+
+/// Future returned by [`Sink`] methods, boxed so sinks can be used as trait objects.
+pub type SinkFuture<'a> = Pin<Box<dyn Future<Output = Result<(), SinkError>> + Send + 'a>>;
+
+/// A destination of change events and run summaries.
+///
+/// [`write`](Sink::write) may be called several times per run; once all batches
+/// are written, [`finalize`](Sink::finalize) completes delivery, e.g. by flushing
+/// buffered messages.
+pub trait Sink: Send + Sync {
+    /// Name of the sink, as its config section.
+    fn name(&self) -> &'static str;
+
+    /// Delivers `batch`, or in a dry run prints what would be delivered.
+    fn write<'a>(&'a self, batch: &'a Batch) -> SinkFuture<'a>;
+
+    /// Completes the run's delivery; nothing to do by default.
+    fn finalize(&self) -> SinkFuture<'_> {
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// Messages printed per sink in a dry run.
+#[cfg(any(feature = "kafka", feature = "nats"))]
+const DRY_RUN_SAMPLES: usize = 3;
+
+/// One message a sink sends.
+#[cfg(any(feature = "kafka", feature = "nats"))]
+struct Message {
+    /// Kafka topic or NATS subject.
+    destination: String,
+    key: Option<String>,
+    payload: Vec<u8>,
+}
+
+/// Prints what `sink` would send: message counts per destination and the first few messages.
+#[cfg(any(feature = "kafka", feature = "nats"))]
+fn print_dry_run(sink: &str, messages: &[Message]) {
+    let mut counts = std::collections::BTreeMap::new();
+    for message in messages {
+        *counts.entry(message.destination.as_str()).or_insert(0) += 1;
+    }
+    println!("{sink}: dry run, would send {} message(s)", messages.len());
+    for (destination, count) in counts {
+        println!("  {destination}: {count}");
+    }
+    for message in messages.iter().take(DRY_RUN_SAMPLES) {
+        let key = message.key.as_deref().map(|k| format!(" [key {k}]")).unwrap_or_default();
+        println!("  {}{key}: {}", message.destination, String::from_utf8_lossy(&message.payload));
+    }
+}
+
+/// Whether any sink is configured and enabled.
+pub fn configured(cfg: &AppConfig) -> bool {
+    kafka_config(cfg).is_some() || nats_config(cfg).is_some()
+}
+
+fn kafka_config(cfg: &AppConfig) -> Option<&KafkaConfig> {
+    cfg.kafka.as_ref().filter(|_| cfg.output.sink_enabled(SinkKind::Kafka))
+}
+
+fn nats_config(cfg: &AppConfig) -> Option<&NatsConfig> {
+    cfg.nats.as_ref().filter(|_| cfg.output.sink_enabled(SinkKind::Nats))
+}
+
+/// The enabled sinks of `cfg`, and the failures of those this build cannot run.
+fn open(cfg: &AppConfig, dry_run: bool) -> (Vec<Arc<dyn Sink>>, Vec<SinkFailure>) {
+    // Depending on the features, only one of the two is ever pushed to
+    #[cfg_attr(not(any(feature = "kafka", feature = "nats")), allow(unused_mut))]
+    let mut sinks: Vec<Arc<dyn Sink>> = Vec::new();
+    #[cfg_attr(all(feature = "kafka", feature = "nats"), allow(unused_mut))]
+    let mut failures = Vec::new();
+
+    if let Some(kafka_cfg) = kafka_config(cfg) {
+        #[cfg(feature = "kafka")]
+        sinks.push(Arc::new(kafka::KafkaSink::new(kafka_cfg.clone(), dry_run)));
+        #[cfg(not(feature = "kafka"))]
+        {
+            let _ = (kafka_cfg, dry_run);
+            failures.push(("kafka", SinkError::Unsupported { sink: "kafka", feature: "kafka" }));
+        }
+    }
+
+    if let Some(nats_cfg) = nats_config(cfg) {
+        #[cfg(feature = "nats")]
+        sinks.push(Arc::new(nats::NatsSink::new(nats_cfg.clone(), dry_run)));
+        #[cfg(not(feature = "nats"))]
+        {
+            let _ = (nats_cfg, dry_run);
+            failures.push(("nats", SinkError::Unsupported { sink: "nats", feature: "nats" }));
+        }
+    }
+
+    (sinks, failures)
+}
+
+/// Publishes run `run_id` of the `fetched` providers to every enabled sink, returning
+/// the failures by sink name. `changes` is `None` on the first run; `anomalies` are
+/// attached to the summaries of their providers. With `dry_run`, the messages are
+/// printed instead of sent.
+pub async fn publish(
+    cfg: &AppConfig,
+    run_id: &str,
+    records: &[LocationRecord],
+    fetched: &[Provider],
+    changes: Option<&[Change]>,
+    anomalies: &[Anomaly],
+    dry_run: bool,
+) -> Vec<SinkFailure> {
+    let observed_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let summaries = fetched
+        .iter()
+        .map(|&provider| {
+            let count = |kind| {
+                changes.unwrap_or_default().iter().filter(|c| c.record.provider == provider && c.kind == kind).count()
+            };
+            RunSummary {
+                provider,
+                records: records.iter().filter(|r| r.provider == provider).count(),
+                added: count(ChangeKind::Added),
+                removed: count(ChangeKind::Removed),
+                count_changed: count(ChangeKind::CountChanged),
+                baseline: changes.is_none(),
+                anomalies: anomalies.iter().filter(|a| a.provider == provider).cloned().collect(),
+                observed_at,
+                run_id: run_id.to_string(),
+                schema_version: SCHEMA_VERSION,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // Events are created as the sinks take them, so a slow sink holds back the
+    // producer instead of letting events pile up
+    let batch_size = cfg.output.get_batch_size();
+    let (sender, receiver) = mpsc::channel(batch_size);
+    let produce = async move {
+        for change in changes.unwrap_or_default() {
+            let event = ChangeEvent { change: change.clone(), observed_at, run_id: run_id.to_string(), schema_version: SCHEMA_VERSION };
+            if sender.send(event).await.is_err() {
+                break;
+            }
+        }
+    };
+
+    let (sinks, mut failures) = open(cfg, dry_run);
+    let mut fan_out = FanOut::new(sinks);
+    let stream = fan_out.write_stream(summaries, receiver, batch_size, cfg.output.get_flush_interval());
+    tokio::join!(produce, stream);
+    failures.extend(fan_out.finish().await);
+    failures
+}
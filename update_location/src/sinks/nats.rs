@@ -0,0 +1,163 @@
+//! NATS publisher for run summaries and change events.
+//!
+//! Core NATS publishing is fire-and-forget and only flushed before returning;
+//! with `nats.jetstream`, every message waits for the stream's acknowledgement,
+//! so a missing stream for the subjects fails the run instead of losing events.
+//! The connection is opened on the first batch and kept for the run.
+
+use async_nats::jetstream;
+use bytes::Bytes;
+use tokio::sync::OnceCell;
+use crate::models::NatsConfig;
+use crate::sinks::{print_dry_run, Batch, ChangeEvent, Message, RunSummary, Sink, SinkError, SinkFuture};
+
+/// Publishes each summary and the events of its provider; in a dry run, only prints them.
+pub struct NatsSink {
+    cfg: NatsConfig,
+    dry_run: bool,
+    client: OnceCell<async_nats::Client>,
+}
+
+impl NatsSink {
+    pub fn new(cfg: NatsConfig, dry_run: bool) -> Self {
+        Self { cfg, dry_run, client: OnceCell::new() }
+    }
+
+    async fn publish(&self, summaries: &[RunSummary], events: &[ChangeEvent]) -> Result<(), SinkError> {
+        let messages = messages(&self.cfg, summaries, events)?;
+        if self.dry_run {
+            print_dry_run("nats", &messages);
+            return Ok(());
+        }
+        let timeout = self.cfg.get_timeout();
+        let send = async {
+            let client = self.client.get_or_try_init(|| connect(&self.cfg)).await?;
+            send(&self.cfg, client.clone(), messages).await
+        };
+        tokio::time::timeout(timeout, send)
+            .await
+            .map_err(|_| SinkError::Timeout(timeout))?
+    }
+}
+
+impl Sink for NatsSink {
+    fn name(&self) -> &'static str {
+        "nats"
+    }
+
+    fn write<'a>(&'a self, batch: &'a Batch) -> SinkFuture<'a> {
+        Box::pin(self.publish(&batch.summaries, &batch.events))
+    }
+}
+
+fn messages(cfg: &NatsConfig, summaries: &[RunSummary], events: &[ChangeEvent]) -> Result<Vec<Message>, SinkError> {
+    let mut messages = Vec::new();
+    for summary in summaries {
+        messages.push(Message {
+            destination: format!("{}.{}.summary", cfg.get_subject_prefix(), summary.provider),
+            key: None,
+            payload: serde_json::to_vec(summary)?,
+        });
+    }
+    for event in events {
+        messages.push(Message {
+            destination: format!("{}.{}.changes", cfg.get_subject_prefix(), event.change.record.provider),
+            key: None,
+            payload: serde_json::to_vec(event)?,
+        });
+    }
+    Ok(messages)
+}
+
+async fn connect(cfg: &NatsConfig) -> Result<async_nats::Client, SinkError> {
+    async_nats::connect(cfg.get_url().as_str()).await.map_err(|err| SinkError::Nats(err.into()))
+}
+
+async fn send(cfg: &NatsConfig, client: async_nats::Client, messages: Vec<Message>) -> Result<(), SinkError> {
+    if cfg.get_jetstream() {
+        let context = jetstream::new(client);
+        let mut acks = Vec::with_capacity(messages.len());
+        for message in messages {
+            let ack = context.publish(message.destination, Bytes::from(message.payload)).await;
+            acks.push(ack.map_err(async_nats::Error::from)?);
+        }
+        for ack in acks {
+            ack.await.map_err(async_nats::Error::from)?;
+        }
+    } else {
+        for message in messages {
+            let sent = client.publish(message.destination, Bytes::from(message.payload)).await;
+            sent.map_err(async_nats::Error::from)?;
+        }
+        client.flush().await.map_err(async_nats::Error::from)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::changes::{Change, ChangeKind};
+    use crate::unified::{LocationRecord, Provider, SCHEMA_VERSION};
+    use super::*;
+
+    fn config(subject_prefix: Option<&str>) -> NatsConfig {
+        let cfg = serde_json::json!({ "url": "nats://localhost:4222", "subject_prefix": subject_prefix });
+        serde_json::from_value(cfg).unwrap()
+    }
+
+    fn summary(provider: Provider) -> RunSummary {
+        RunSummary {
+            provider,
+            records: 1,
+            added: 1,
+            removed: 0,
+            count_changed: 0,
+            baseline: false,
+            anomalies: Vec::new(),
+            observed_at: 0,
+            run_id: "run-1".to_string(),
+            schema_version: SCHEMA_VERSION,
+        }
+    }
+
+    fn event(provider: Provider) -> ChangeEvent {
+        let record = LocationRecord::sample(provider, "DE");
+        let change = Change { kind: ChangeKind::Added, record, nodes_before: None, nodes_after: None };
+        ChangeEvent { change, observed_at: 0, run_id: "run-1".to_string(), schema_version: SCHEMA_VERSION }
+    }
+
+    fn subjects(cfg: &NatsConfig) -> Vec<String> {
+        let events = [event(Provider::Infatica), event(Provider::IPRoyal)];
+        let messages = messages(cfg, &[summary(Provider::IPRoyal)], &events).unwrap();
+        messages.into_iter().map(|m| m.destination).collect()
+    }
+
+    #[test]
+    fn subjects_follow_the_prefix_and_provider() {
+        assert_eq!(
+            subjects(&config(Some("geo.v2"))),
+            ["geo.v2.iproyal.summary", "geo.v2.infatica.changes", "geo.v2.iproyal.changes"]
+        );
+    }
+
+    #[test]
+    fn subjects_default_to_the_locations_prefix() {
+        assert_eq!(
+            subjects(&config(None)),
+            ["locations.iproyal.summary", "locations.infatica.changes", "locations.iproyal.changes"]
+        );
+    }
+
+    #[test]
+    fn payloads_are_the_json_of_each_summary_and_event() {
+        let messages = messages(&config(None), &[summary(Provider::IPRoyal)], &[event(Provider::Infatica)]).unwrap();
+        let payloads: Vec<serde_json::Value> =
+            messages.iter().map(|m| serde_json::from_slice(&m.payload).unwrap()).collect();
+
+        assert!(messages.iter().all(|m| m.key.is_none()));
+        assert_eq!((payloads[0]["provider"].as_str(), payloads[0]["added"].as_u64()), (Some("iproyal"), Some(1)));
+        assert_eq!(payloads[1]["run_id"], "run-1");
+        assert_eq!(payloads[1], serde_json::to_value(event(Provider::Infatica)).unwrap());
+    }
+}
@@ -0,0 +1,116 @@
+//! # Coverage statistics
+//!
+//! Per-provider aggregates over a set of unified records, for quick sanity
+//! checks of what a run covered: countries, distinct cities (per country),
+//! summed node estimates and the ISPs carrying the most nodes.
+//!
+//! Node figures use [`LocationRecord::node_estimate`], so iproyal contributes
+//! the lower bound of its reported availability.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use serde::Serialize;
+use crate::unified::{LocationRecord, Provider};
+
+/// Aggregates of one provider.
+#[derive(Debug, Serialize)]
+pub struct ProviderStats {
+    pub provider: Provider,
+    pub records: usize,
+    pub countries: usize,
+    /// Distinct (country, city) pairs; records without a city are not counted.
+    pub cities: usize,
+    pub nodes: u64,
+    /// Distinct cities per country code, including countries without named cities.
+    pub cities_per_country: BTreeMap<String, usize>,
+    /// ISPs with the most nodes, largest first.
+    pub top_isps: Vec<IspNodes>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IspNodes {
+    pub isp: String,
+    pub nodes: u64,
+}
+
+/// Statistics of every provider present in `records`, in [`Provider::ALL`] order,
+/// keeping the `top` ISPs of each.
+pub fn compute(records: &[LocationRecord], top: usize) -> Vec<ProviderStats> {
+    Provider::ALL
+        .into_iter()
+        .filter_map(|provider| {
+            let records: Vec<&LocationRecord> = records.iter().filter(|r| r.provider == provider).collect();
+            (!records.is_empty()).then(|| provider_stats(provider, &records, top))
+        })
+        .collect()
+}
+
+fn provider_stats(provider: Provider, records: &[&LocationRecord], top: usize) -> ProviderStats {
+    let mut cities: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+    let mut isps: HashMap<&str, u64> = HashMap::new();
+    let mut nodes = 0;
+
+    for record in records {
+        let country = cities.entry(&record.country).or_default();
+        if let Some(city) = &record.city {
+            country.insert(city);
+        }
+        let estimate = record.node_estimate().unwrap_or(0);
+        nodes += estimate;
+        if let Some(isp) = &record.isp {
+            *isps.entry(isp).or_default() += estimate;
+        }
+    }
+
+    let mut top_isps: Vec<IspNodes> = isps
+        .into_iter()
+        .map(|(isp, nodes)| IspNodes { isp: isp.to_string(), nodes })
+        .collect();
+    top_isps.sort_by(|a, b| b.nodes.cmp(&a.nodes).then_with(|| a.isp.cmp(&b.isp)));
+    top_isps.truncate(top);
+
+    ProviderStats {
+        provider,
+        records: records.len(),
+        countries: cities.len(),
+        cities: cities.values().map(BTreeSet::len).sum(),
+        nodes,
+        cities_per_country: cities.into_iter().map(|(c, set)| (c.to_string(), set.len())).collect(),
+        top_isps,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(provider: Provider, country: &str, city: Option<&str>, isp: &str, nodes: u64) -> LocationRecord {
+        LocationRecord {
+            city: city.map(str::to_string),
+            isp: Some(isp.to_string()),
+            nodes: Some(nodes),
+            ..LocationRecord::sample(provider, country)
+        }
+    }
+
+    #[test]
+    fn aggregates_per_provider() {
+        let records = [
+            record(Provider::Infatica, "DE", Some("Berlin"), "Telekom", 100),
+            record(Provider::Infatica, "DE", Some("Berlin"), "Vodafone", 30),
+            record(Provider::Infatica, "DE", Some("Hamburg"), "Telekom", 20),
+            record(Provider::Infatica, "US", None, "Comcast", 50),
+            record(Provider::IPRoyal, "FR", Some("Paris"), "Orange", 5),
+        ];
+
+        let stats = compute(&records, 2);
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].provider, Provider::IPRoyal);
+
+        let infatica = &stats[1];
+        assert_eq!((infatica.records, infatica.countries, infatica.cities, infatica.nodes), (4, 2, 2, 200));
+        assert_eq!(infatica.cities_per_country["DE"], 2);
+        assert_eq!(infatica.cities_per_country["US"], 0);
+        let top: Vec<(&str, u64)> = infatica.top_isps.iter().map(|i| (i.isp.as_str(), i.nodes)).collect();
+        assert_eq!(top, [("Telekom", 120), ("Comcast", 50)]);
+    }
+}
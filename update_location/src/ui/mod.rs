@@ -0,0 +1,70 @@
+//! # Terminal output
+//!
+//! Verbosity is chosen once from `--quiet` / `--verbose` and read by the
+//! [`status!`](crate::status) and [`verbose!`](crate::verbose) macros, so modules
+//! deep in the fetch path can report progress without threading a handle through.
+//!
+//! | level     | progress spinners | status lines | dataset details | errors, final report |
+//! |-----------|-------------------|--------------|-----------------|----------------------|
+//! | `quiet`   | –                 | –            | –               | ✓                    |
+//! | default   | ✓                 | ✓            | –               | ✓                    |
+//! | `verbose` | ✓                 | ✓            | ✓               | ✓                    |
+//!
+//! Spinners are drawn on stderr and only when it is a terminal; lines printed
+//! through the macros never tear an active spinner.
+
+mod progress;
+
+use std::sync::OnceLock;
+
+pub use progress::{spinner, suspend};
+
+/// How much the tool prints besides errors and the final report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    Quiet,
+    Normal,
+    Verbose,
+}
+
+impl Verbosity {
+    pub fn from_flags(quiet: bool, verbose: bool) -> Self {
+        match (quiet, verbose) {
+            (true, _) => Self::Quiet,
+            (false, true) => Self::Verbose,
+            (false, false) => Self::Normal,
+        }
+    }
+}
+
+static VERBOSITY: OnceLock<Verbosity> = OnceLock::new();
+
+/// Sets the verbosity for the rest of the run; later calls are ignored.
+pub fn init(verbosity: Verbosity) {
+    let _ = VERBOSITY.set(verbosity);
+}
+
+/// Current verbosity, [`Verbosity::Normal`] until [`init`] is called.
+pub fn verbosity() -> Verbosity {
+    VERBOSITY.get().copied().unwrap_or(Verbosity::Normal)
+}
+
+/// Prints a line to stdout unless `--quiet` is set.
+#[macro_export]
+macro_rules! status {
+    ($($arg:tt)*) => {
+        if $crate::ui::verbosity() >= $crate::ui::Verbosity::Normal {
+            $crate::ui::suspend(|| println!($($arg)*));
+        }
+    };
+}
+
+/// Prints a line to stdout only with `--verbose`.
+#[macro_export]
+macro_rules! verbose {
+    ($($arg:tt)*) => {
+        if $crate::ui::verbosity() >= $crate::ui::Verbosity::Verbose {
+            $crate::ui::suspend(|| println!($($arg)*));
+        }
+    };
+}
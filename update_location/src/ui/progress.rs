@@ -0,0 +1,40 @@
+use std::sync::OnceLock;
+use std::time::Duration;
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use crate::ui::{verbosity, Verbosity};
+
+/// Interval between spinner frames.
+const TICK: Duration = Duration::from_millis(100);
+
+/// Spinners of all providers, drawn together on stderr.
+fn multi() -> &'static MultiProgress {
+    static MULTI: OnceLock<MultiProgress> = OnceLock::new();
+    MULTI.get_or_init(|| {
+        let target = match verbosity() {
+            Verbosity::Quiet => ProgressDrawTarget::hidden(),
+            _ => ProgressDrawTarget::stderr(),
+        };
+        MultiProgress::with_draw_target(target)
+    })
+}
+
+/// Adds a spinner labelled with `name`; its message shows record counts.
+///
+/// Finish it with [`ProgressBar::finish_with_message`] (kept on screen) or
+/// [`ProgressBar::finish_and_clear`].
+pub fn spinner(name: &str) -> ProgressBar {
+    let style = ProgressStyle::with_template("{spinner} {prefix:<10} {msg} [{elapsed}]")
+        .expect("valid spinner template");
+
+    let bar = multi().add(ProgressBar::new_spinner());
+    bar.set_style(style);
+    bar.set_prefix(name.to_string());
+    bar.set_message("fetching");
+    bar.enable_steady_tick(TICK);
+    bar
+}
+
+/// Runs `f` with the spinners hidden, so printed lines do not mix with them.
+pub fn suspend<R>(f: impl FnOnce() -> R) -> R {
+    multi().suspend(f)
+}
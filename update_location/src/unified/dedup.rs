@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use crate::iproyal::ip_availability::Availability;
+use crate::models::{DedupConfig, DedupMerge};
+use crate::unified::LocationRecord;
+
+/// Identity of a location across providers: country, city and ISP, ignoring case.
+type LocationKey = (String, Option<String>, Option<String>);
+
+fn key(record: &LocationRecord) -> LocationKey {
+    let lower = |value: &Option<String>| value.as_deref().map(str::to_ascii_lowercase);
+    (record.country.to_ascii_uppercase(), lower(&record.city), lower(&record.isp))
+}
+
+/// Keeps one record per location reported by more than one provider, per `policy`.
+///
+/// The kept record is the first of the most preferred provider, with the figures
+/// of the others folded in per [`DedupConfig::get_merge`]; it stays where it was,
+/// and the others are dropped. Locations reported by a single provider, even more
+/// than once, are left alone, so the result depends only on the input order.
+/// Returns the number of records dropped.
+pub fn dedup(records: &mut Vec<LocationRecord>, policy: &DedupConfig) -> usize {
+    let mut groups: HashMap<LocationKey, Vec<usize>> = HashMap::new();
+    for (position, record) in records.iter().enumerate() {
+        groups.entry(key(record)).or_default().push(position);
+    }
+
+    let mut dropped = vec![false; records.len()];
+    for mut group in groups.into_values() {
+        if group.iter().all(|&i| records[i].provider == records[group[0]].provider) {
+            continue;
+        }
+        group.sort_by_key(|&i| (policy.rank(records[i].provider), i));
+        let (kept, others) = (group[0], &group[1..]);
+        let merged = policy.get_merge().map(|merge| merge_figures(merge, group.iter().map(|&i| &records[i])));
+        if let Some((nodes, availability)) = merged {
+            records[kept].nodes = nodes;
+            records[kept].availability = availability;
+        }
+        for &i in others {
+            dropped[i] = true;
+        }
+    }
+
+    let mut position = 0;
+    records.retain(|_| {
+        position += 1;
+        !dropped[position - 1]
+    });
+    dropped.iter().filter(|d| **d).count()
+}
+
+/// Node count and availability of a location reported as `group`, kept record first.
+fn merge_figures<'a>(merge: DedupMerge, group: impl Iterator<Item = &'a LocationRecord> + Clone) -> (Option<u64>, Option<Availability>) {
+    let kept = group.clone().next().expect("groups are not empty");
+    match merge {
+        DedupMerge::SumNodes => {
+            let estimates: Vec<u64> = group.filter_map(LocationRecord::node_estimate).collect();
+            let nodes = (!estimates.is_empty()).then(|| estimates.iter().sum());
+            (nodes, kept.availability)
+        }
+        DedupMerge::MaxAvailability => {
+            let nodes = group.clone().filter_map(|r| r.nodes).max();
+            // The first of the largest wins ties, keeping the preferred provider's figure
+            let availability = group
+                .filter_map(|r| r.availability.filter(|a| a.lower_bound().is_some()))
+                .fold(None, |best: Option<Availability>, a| match best {
+                    Some(b) if b.lower_bound() >= a.lower_bound() => Some(b),
+                    _ => Some(a),
+                })
+                .or(kept.availability);
+            (nodes, availability)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::unified::Provider;
+    use super::*;
+
+    fn record(provider: Provider, city: &str, isp: &str, nodes: Option<u64>, availability: Option<Availability>) -> LocationRecord {
+        LocationRecord {
+            city: Some(city.to_string()),
+            isp: Some(isp.to_string()),
+            nodes,
+            availability,
+            ..LocationRecord::sample(provider, "DE")
+        }
+    }
+
+    fn records() -> Vec<LocationRecord> {
+        vec![
+            record(Provider::Infatica, "Berlin", "Telekom", Some(40), None),
+            record(Provider::IPRoyal, "berlin", "TELEKOM", None, Some(Availability::Approx(100))),
+            record(Provider::Infatica, "Hamburg", "Telekom", Some(7), None),
+            record(Provider::Infatica, "Hamburg", "Telekom", Some(3), None),
+            record(Provider::IPRoyal, "Munich", "Vodafone", None, Some(Availability::Range { min: 10, max: 50 })),
+        ]
+    }
+
+    #[test]
+    fn keeps_the_preferred_provider_in_place() {
+        let mut deduped = records();
+        let dropped = dedup(&mut deduped, &DedupConfig::new(vec![Provider::IPRoyal], None));
+
+        let mut expected = records();
+        expected.remove(0);
+        assert_eq!((dropped, deduped), (1, expected));
+
+        let mut deduped = records();
+        dedup(&mut deduped, &DedupConfig::new(vec![Provider::Infatica, Provider::IPRoyal], None));
+        assert_eq!(deduped[0], records()[0]);
+        assert_eq!(deduped.len(), 4);
+    }
+
+    #[test]
+    fn merges_the_figures_of_all_providers() {
+        let mut summed = records();
+        dedup(&mut summed, &DedupConfig::new(vec![Provider::Infatica], Some(DedupMerge::SumNodes)));
+        assert_eq!((summed[0].provider, summed[0].nodes, summed[0].availability), (Provider::Infatica, Some(140), None));
+
+        let mut maxed = records();
+        dedup(&mut maxed, &DedupConfig::new(vec![Provider::Infatica], Some(DedupMerge::MaxAvailability)));
+        assert_eq!((maxed[0].nodes, maxed[0].availability), (Some(40), Some(Availability::Approx(100))));
+        assert_eq!(maxed[1..], records()[2..]);
+    }
+}
@@ -0,0 +1,37 @@
+//! Golden tests of the conversion from vendor payloads to normalized unified records.
+//!
+//! Each fixture in `fixtures/golden/` goes through parsing, flattening, unification
+//! and [`normalize`](crate::normalize::normalize); the records and the validation
+//! report are compared with the snapshots in `snapshots/`. A change in behavior
+//! shows up as a snapshot diff to review, then accept with `cargo insta review`
+//! (or `INSTA_UPDATE=always cargo test`).
+
+use insta::{assert_json_snapshot, assert_snapshot};
+use crate::infatica::{Flattened, InfaticaGeoNodeRecord, TargetingResolver};
+use crate::iproyal::{self, models::Root};
+use crate::normalize::{normalize, ValidationReport};
+use crate::unified::{from_infatica, from_iproyal, LocationRecord};
+
+const IPROYAL_COUNTRIES: &str = include_str!("../../fixtures/golden/iproyal_countries.json");
+const INFATICA_GEO_NODES: &str = include_str!("../../fixtures/golden/infatica_geo_nodes.json");
+
+fn normalized(mut records: Vec<LocationRecord>) -> (Vec<LocationRecord>, ValidationReport) {
+    let report = normalize(&mut records);
+    (records, report)
+}
+
+#[test]
+fn iproyal() {
+    let root: Root = serde_json::from_str(IPROYAL_COUNTRIES).unwrap();
+    let (records, report) = normalized(from_iproyal(&iproyal::flatten(&root)));
+    assert_json_snapshot!("iproyal_records", records);
+    assert_snapshot!("iproyal_report", report.to_string());
+}
+
+#[test]
+fn infatica() {
+    let nodes: Flattened<InfaticaGeoNodeRecord> = serde_json::from_str(INFATICA_GEO_NODES).unwrap();
+    let (records, report) = normalized(from_infatica(&nodes.into_inner(), &TargetingResolver::default()));
+    assert_json_snapshot!("infatica_records", records);
+    assert_snapshot!("infatica_report", report.to_string());
+}
@@ -0,0 +1,78 @@
+//! # Unified location model
+//!
+//! Converts vendor-specific datasets into a single [`LocationRecord`] shape so
+//! normalization, comparison and export can treat all providers the same way.
+//!
+//! Conversions copy values verbatim; cleaning up codes and placeholder values is
+//! the job of [`crate::normalize`]. The fetch that produced the records is attached
+//! afterwards as their [`Provenance`]. The combined behavior is pinned by golden
+//! snapshots of representative payloads, see `golden.rs`.
+//!
+//! Once normalized, locations several providers report can be collapsed into one
+//! record per the `[dedup]` policy, see [`dedup`].
+//!
+//! The serialized layout of records is versioned, see [`schema`].
+
+#[cfg(test)]
+mod golden;
+mod dedup;
+mod provenance;
+mod provider;
+mod record;
+pub mod schema;
+
+pub use dedup::dedup;
+pub use provenance::Provenance;
+pub use provider::Provider;
+pub use record::LocationRecord;
+pub use schema::SCHEMA_VERSION;
+
+use crate::infatica::{InfaticaGeoNodeRecord, TargetingResolver};
+use crate::iproyal::models::IPRoyalLocation;
+use crate::iproyal::Targeting;
+
+/// Converts flattened iproyal leaves into unified records.
+pub fn from_iproyal(locations: &[IPRoyalLocation]) -> Vec<LocationRecord> {
+    locations
+        .iter()
+        .map(|l| LocationRecord {
+            provider: Provider::IPRoyal,
+            country: l.country.clone(),
+            subdivision: l.state.clone(),
+            subdivision_code: None,
+            city: l.city.clone(),
+            city_ascii: None,
+            isp: l.isp.clone(),
+            nodes: None,
+            availability: Some(l.availability),
+            latitude: None,
+            longitude: None,
+            // Leaves are well-formed codes and a rotating IP needs no session
+            targeting: Targeting::builder(l).build().ok().map(|t| t.to_string()),
+            provenance: None,
+        })
+        .collect()
+}
+
+/// Converts Infatica geo-node records into unified records, with the targeting
+/// parameters `targeting` resolves from the dictionaries of the same fetch.
+pub fn from_infatica(records: &[InfaticaGeoNodeRecord], targeting: &TargetingResolver) -> Vec<LocationRecord> {
+    records
+        .iter()
+        .map(|r| LocationRecord {
+            provider: Provider::Infatica,
+            country: r.country.clone(),
+            subdivision: Some(r.subdivision.clone()),
+            subdivision_code: None,
+            city: Some(r.city.clone()),
+            city_ascii: None,
+            isp: Some(r.isp.clone()),
+            nodes: Some(u64::from(r.nodes)),
+            availability: None,
+            latitude: None,
+            longitude: None,
+            targeting: Some(targeting.resolve(r).to_string()),
+            provenance: None,
+        })
+        .collect()
+}
@@ -0,0 +1,41 @@
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
+
+/// Identifies the vendor a record came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    IPRoyal,
+    Infatica,
+}
+
+impl Provider {
+    /// All supported providers, in a stable order.
+    pub const ALL: [Provider; 2] = [Provider::IPRoyal, Provider::Infatica];
+
+    /// Lowercase name used in config keys, CLI flags and cache paths.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Provider::IPRoyal => "iproyal",
+            Provider::Infatica => "infatica",
+        }
+    }
+}
+
+impl fmt::Display for Provider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Provider {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Provider::ALL
+            .into_iter()
+            .find(|p| p.as_str().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| format!("unknown provider `{s}` (expected one of: iproyal, infatica)"))
+    }
+}
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+use crate::iproyal::ip_availability::Availability;
+use crate::unified::{Provenance, Provider};
+
+/// Provider-independent location record.
+///
+/// One record corresponds to one (country, subdivision, city, ISP) leaf reported
+/// by a vendor. Levels a vendor does not report are `None`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LocationRecord {
+    pub provider: Provider,
+
+    /// Country code; ISO 3166-1 alpha-2 once normalized.
+    pub country: String,
+
+    /// Subdivision (state / region) name as reported by the vendor.
+    pub subdivision: Option<String>,
+
+    /// ISO 3166-2 code of `subdivision`, filled in by normalization when resolvable.
+    pub subdivision_code: Option<String>,
+
+    pub city: Option<String>,
+
+    /// ASCII transliteration of a `city` in another script, filled in by
+    /// normalization with `matching.transliterate`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub city_ascii: Option<String>,
+
+    pub isp: Option<String>,
+
+    /// Exact node count (Infatica).
+    pub nodes: Option<u64>,
+
+    /// Parsed availability (iproyal).
+    pub availability: Option<Availability>,
+
+    /// Coordinates of `city`, filled in by [`crate::geo`] enrichment when configured.
+    #[serde(default)]
+    pub latitude: Option<f64>,
+    #[serde(default)]
+    pub longitude: Option<f64>,
+
+    /// Parameters selecting the location on a proxy connection, in the vendor's
+    /// targeting syntax (see [`crate::iproyal::Targeting`] and
+    /// [`crate::infatica::InfaticaTargeting`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub targeting: Option<String>,
+
+    /// Where and when the record was fetched; absent in snapshots written before it was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+impl LocationRecord {
+    /// The city in ASCII where transliterated, otherwise as reported.
+    pub fn ascii_city(&self) -> Option<&str> {
+        self.city_ascii.as_deref().or(self.city.as_deref())
+    }
+
+    /// Best numeric estimate of capacity: the node count, or the availability lower bound.
+    pub fn node_estimate(&self) -> Option<u64> {
+        self.nodes
+            .or_else(|| self.availability.as_ref().and_then(Availability::lower_bound))
+    }
+
+    /// A record of `provider` in `country` with every other field unset, for tests
+    /// to fill in with struct update syntax.
+    #[cfg(test)]
+    pub fn sample(provider: Provider, country: &str) -> Self {
+        Self {
+            provider,
+            country: country.to_string(),
+            subdivision: None,
+            subdivision_code: None,
+            city: None,
+            city_ascii: None,
+            isp: None,
+            nodes: None,
+            availability: None,
+            latitude: None,
+            longitude: None,
+            targeting: None,
+            provenance: None,
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! Versions of the serialized [`LocationRecord`] layout.
+//!
+//! Everything persisting records — export manifests, the change log, delta
+//! artifacts and sink messages — is stamped with [`SCHEMA_VERSION`]. Data written
+//! before versions were stamped counts as version 1.
+//!
+//! Records of an older version are upgraded by [`migrate`] when read back, one
+//! version at a time; records of a newer version are refused, as this build cannot
+//! know what they mean. Comparing runs or snapshots therefore always happens
+//! within one version.
+//!
+//! | Version | Layout |
+//! |---------|--------|
+//! | 1 | records without coordinates |
+//! | 2 | `latitude` / `longitude` always present, optional `city_ascii`, `targeting` and `provenance` |
+
+use serde_json::{Map, Value};
+use thiserror::Error;
+use crate::unified::LocationRecord;
+
+/// Version of the layout this build writes.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Version of data written before versions were stamped.
+pub const UNVERSIONED: u32 = 1;
+
+/// Upgrades of one record, the first from version 1 to 2.
+const MIGRATIONS: [fn(&mut Map<String, Value>); (SCHEMA_VERSION - UNVERSIONED) as usize] = [v1_to_v2];
+
+/// Records that cannot be read in this build's layout.
+#[derive(Debug, Error)]
+pub enum SchemaError {
+    #[error("records use schema version {found}, newer than version {SCHEMA_VERSION} of this build: upgrade update_location to read them")]
+    Newer { found: u32 },
+
+    #[error("unknown schema version {found}")]
+    Unknown { found: u32 },
+
+    #[error("invalid records of schema version {version}: {source}")]
+    Decode { version: u32, source: serde_json::Error },
+}
+
+/// Whether records of `version` can be read, possibly after migrating them.
+pub fn check(version: u32) -> Result<(), SchemaError> {
+    match version {
+        v if v > SCHEMA_VERSION => Err(SchemaError::Newer { found: v }),
+        v if v < UNVERSIONED => Err(SchemaError::Unknown { found: v }),
+        _ => Ok(()),
+    }
+}
+
+/// Upgrades `records`, serialized in `version`, to the current layout.
+pub fn migrate(version: u32, records: Vec<Value>) -> Result<Vec<LocationRecord>, SchemaError> {
+    check(version)?;
+    records
+        .into_iter()
+        .map(|mut record| {
+            if let Value::Object(fields) = &mut record {
+                for migration in &MIGRATIONS[(version - UNVERSIONED) as usize..] {
+                    migration(fields);
+                }
+            }
+            serde_json::from_value(record).map_err(|source| SchemaError::Decode { version, source })
+        })
+        .collect()
+}
+
+/// Coordinates became part of every record, `null` where unresolved.
+fn v1_to_v2(record: &mut Map<String, Value>) {
+    for field in ["latitude", "longitude"] {
+        record.entry(field).or_insert(Value::Null);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+
+    #[test]
+    fn upgrades_older_and_refuses_newer_versions() {
+        let v1 = json!({
+            "provider": "infatica",
+            "country": "DE",
+            "subdivision": null,
+            "subdivision_code": null,
+            "city": "Berlin",
+            "isp": null,
+            "nodes": 5,
+            "availability": null,
+        });
+        let records = migrate(UNVERSIONED, vec![v1]).unwrap();
+        assert_eq!((records[0].city.as_deref(), records[0].latitude), (Some("Berlin"), None));
+        let current = serde_json::to_value(&records[0]).unwrap();
+        assert!(current.get("latitude").is_some());
+
+        assert!(matches!(migrate(SCHEMA_VERSION + 1, vec![current]), Err(SchemaError::Newer { found: 3 })));
+        assert!(matches!(check(0), Err(SchemaError::Unknown { found: 0 })));
+    }
+}
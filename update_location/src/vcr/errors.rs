@@ -0,0 +1,21 @@
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Failure to load, serve or save a cassette.
+#[derive(Debug, Error)]
+pub enum VcrError {
+    #[error("failed to read cassette {}: {source}", .path.display())]
+    Read { path: PathBuf, source: std::io::Error },
+
+    #[error("invalid cassette {}: {source}", .path.display())]
+    Parse { path: PathBuf, source: serde_json::Error },
+
+    #[error("failed to write cassette {}: {source}", .path.display())]
+    Write { path: PathBuf, source: std::io::Error },
+
+    #[error("failed to start the cassette server: {0}")]
+    Bind(#[source] std::io::Error),
+
+    #[error("{count} request(s) had no recorded interaction, first: {first}")]
+    Unmatched { count: usize, first: String },
+}
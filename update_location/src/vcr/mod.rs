@@ -0,0 +1,354 @@
+//! # HTTP cassettes
+//!
+//! Records provider HTTP interactions into cassette files and replays them, so
+//! the provider clients can be tested against real response shapes without
+//! credentials (feature `vcr`).
+//!
+//! A [`VcrServer`] is a local HTTP server the client under test is pointed at,
+//! by setting the provider `endpoint` to [`VcrServer::url`] joined with the
+//! endpoint's path. [`VcrServer::record`] forwards every request to the real
+//! vendor and appends the exchange to the cassette; [`VcrServer::replay`]
+//! answers from the cassette without any network access. Requests match on
+//! method, path, query and form fields.
+//!
+//! Credentials never reach the cassette: request headers are not stored, and
+//! the form fields in [`SECRET_FIELDS`] are dropped before recording and
+//! ignored when matching. Record with a static iproyal `token`; an OAuth token
+//! endpoint is not proxied.
+//!
+//! The cassettes under `fixtures/cassettes/` are re-recorded against the vendors
+//! by the ignored `record_cassettes` test, which reads the `VCR_*` variables it
+//! documents.
+
+mod errors;
+
+pub use errors::VcrError;
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use axum::body::{Body, Bytes};
+use axum::extract::State;
+use axum::http::{header, HeaderMap, Method, StatusCode, Uri};
+use axum::response::{IntoResponse, Response};
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use url::Url;
+
+/// Form fields holding credentials, never written to a cassette.
+pub const SECRET_FIELDS: &[&str] = &["email", "password", "token", "client_secret"];
+
+/// Recorded interactions, in the order they happened.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+/// The parts of a request interactions are matched on.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query: Option<String>,
+    /// Fields of a form body, without [`SECRET_FIELDS`].
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub form: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    pub body: String,
+}
+
+impl RecordedRequest {
+    fn new(method: &Method, uri: &Uri, headers: &HeaderMap, body: &[u8]) -> Self {
+        let is_form = headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.starts_with("application/x-www-form-urlencoded"));
+        let form = if is_form {
+            url::form_urlencoded::parse(body)
+                .filter(|(name, _)| !SECRET_FIELDS.contains(&name.as_ref()))
+                .map(|(name, value)| (name.into_owned(), value.into_owned()))
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
+
+        Self {
+            method: method.to_string(),
+            path: uri.path().to_string(),
+            query: uri.query().map(str::to_string),
+            form,
+        }
+    }
+}
+
+impl std::fmt::Display for RecordedRequest {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.method, self.path)?;
+        if let Some(query) = &self.query {
+            write!(f, "?{query}")?;
+        }
+        if !self.form.is_empty() {
+            write!(f, " {:?}", self.form)?;
+        }
+        Ok(())
+    }
+}
+
+enum Mode {
+    /// Whether each interaction has been served.
+    Replay(Vec<bool>),
+    /// Origin of the real vendor.
+    Record(Url, reqwest::Client),
+}
+
+struct Tape {
+    cassette: Cassette,
+    mode: Mode,
+    unmatched: Vec<RecordedRequest>,
+}
+
+type Shared = Arc<Mutex<Tape>>;
+
+/// Local server recording or replaying one cassette, until [`VcrServer::finish`].
+pub struct VcrServer {
+    url: Url,
+    path: PathBuf,
+    tape: Shared,
+    shutdown: CancellationToken,
+    task: JoinHandle<std::io::Result<()>>,
+}
+
+impl VcrServer {
+    /// Serves the interactions recorded in the cassette at `path`.
+    pub async fn replay(path: impl Into<PathBuf>) -> Result<Self, VcrError> {
+        let path = path.into();
+        let bytes = std::fs::read(&path).map_err(|source| VcrError::Read { path: path.clone(), source })?;
+        let cassette: Cassette =
+            serde_json::from_slice(&bytes).map_err(|source| VcrError::Parse { path: path.clone(), source })?;
+        let used = vec![false; cassette.interactions.len()];
+        Self::start(path, cassette, Mode::Replay(used)).await
+    }
+
+    /// Forwards requests to `upstream`, e.g. `https://api.vendor.example`, recording
+    /// them into a new cassette written to `path` by [`VcrServer::finish`].
+    pub async fn record(path: impl Into<PathBuf>, upstream: Url) -> Result<Self, VcrError> {
+        Self::start(path.into(), Cassette::default(), Mode::Record(upstream, reqwest::Client::new())).await
+    }
+
+    async fn start(path: PathBuf, cassette: Cassette, mode: Mode) -> Result<Self, VcrError> {
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0)).await.map_err(VcrError::Bind)?;
+        let addr = listener.local_addr().map_err(VcrError::Bind)?;
+        let url = Url::parse(&format!("http://{addr}/")).expect("a socket address forms a valid URL");
+
+        let tape = Arc::new(Mutex::new(Tape { cassette, mode, unmatched: Vec::new() }));
+        let router = Router::new().fallback(handle).with_state(tape.clone());
+        let shutdown = CancellationToken::new();
+        let task = tokio::spawn(
+            axum::serve(listener, router)
+                .with_graceful_shutdown(shutdown.clone().cancelled_owned())
+                .into_future(),
+        );
+
+        Ok(Self { url, path, tape, shutdown, task })
+    }
+
+    /// Base URL of the server, ending with `/`.
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Stops the server and, when recording, writes the cassette.
+    ///
+    /// Fails when a replayed request had no recorded interaction.
+    pub async fn finish(self) -> Result<Cassette, VcrError> {
+        self.shutdown.cancel();
+        // The server only fails while accepting, which cannot lose recorded interactions
+        let _ = self.task.await;
+
+        let tape = Arc::into_inner(self.tape)
+            .expect("the server is stopped")
+            .into_inner()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if let Some(first) = tape.unmatched.first() {
+            return Err(VcrError::Unmatched { count: tape.unmatched.len(), first: first.to_string() });
+        }
+        if let Mode::Record(..) = tape.mode {
+            let write = |source| VcrError::Write { path: self.path.clone(), source };
+            if let Some(dir) = self.path.parent() {
+                std::fs::create_dir_all(dir).map_err(write)?;
+            }
+            let json = serde_json::to_vec_pretty(&tape.cassette).expect("cassettes serialize");
+            std::fs::write(&self.path, json).map_err(write)?;
+        }
+        Ok(tape.cassette)
+    }
+}
+
+async fn handle(State(tape): State<Shared>, method: Method, uri: Uri, headers: HeaderMap, body: Bytes) -> Response {
+    let request = RecordedRequest::new(&method, &uri, &headers, &body);
+
+    let upstream = {
+        let mut tape = tape.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let Tape { cassette, mode, unmatched } = &mut *tape;
+        match mode {
+            Mode::Record(origin, client) => (origin.clone(), client.clone()),
+            Mode::Replay(used) => {
+                let matching = |i: &usize| cassette.interactions[*i].request == request;
+                let all = 0..cassette.interactions.len();
+                // Unused interactions first, in order; then the last one again for repeated requests
+                let found = all.clone().filter(matching).find(|i| !used[*i]).or_else(|| all.rev().find(matching));
+                return match found {
+                    Some(i) => {
+                        used[i] = true;
+                        respond(&cassette.interactions[i].response)
+                    }
+                    None => {
+                        let message = format!("no recorded interaction for {request}");
+                        unmatched.push(request);
+                        (StatusCode::NOT_IMPLEMENTED, message).into_response()
+                    }
+                };
+            }
+        }
+    };
+
+    let response = match forward(upstream, method, &uri, headers, body).await {
+        Ok(response) => response,
+        Err(err) => return (StatusCode::BAD_GATEWAY, format!("recording failed: {err}")).into_response(),
+    };
+    let reply = respond(&response);
+    tape.lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .cassette
+        .interactions
+        .push(Interaction { request, response });
+    reply
+}
+
+/// Sends the request to the real vendor at `origin`.
+async fn forward(
+    (origin, client): (Url, reqwest::Client),
+    method: Method,
+    uri: &Uri,
+    mut headers: HeaderMap,
+    body: Bytes,
+) -> Result<RecordedResponse, reqwest::Error> {
+    headers.remove(header::HOST);
+    headers.remove(header::CONTENT_LENGTH);
+    let path = uri.path_and_query().map_or("/", |p| p.as_str());
+    let url = origin.join(path).expect("a request path joins onto the origin");
+
+    let response = client.request(method, url).headers(headers).body(body).send().await?;
+    let status = response.status().as_u16();
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+    let body = response.text().await?;
+    Ok(RecordedResponse { status, content_type, body })
+}
+
+fn respond(recorded: &RecordedResponse) -> Response {
+    let mut response = Response::builder().status(recorded.status);
+    if let Some(content_type) = &recorded.content_type {
+        response = response.header(header::CONTENT_TYPE, content_type);
+    }
+    response
+        .body(Body::from(recorded.body.clone()))
+        .unwrap_or_else(|_| StatusCode::INTERNAL_SERVER_ERROR.into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use crate::models::{InfaticaConfig, IPRoyalConfig};
+    use crate::{infatica, iproyal};
+    use super::*;
+
+    fn cassette(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/cassettes").join(name)
+    }
+
+    #[tokio::test]
+    async fn replays_iproyal() {
+        let vcr = VcrServer::replay(cassette("iproyal.json")).await.unwrap();
+        let cfg = IPRoyalConfig::new(vcr.url().join("v1/").unwrap(), "test-token");
+
+        let results = iproyal::get_all(&cfg).await.unwrap();
+        assert_eq!(results.countries().countries.len(), 3);
+        assert_eq!(results.isps().len(), 1);
+        assert_eq!(results.availability().len(), 1);
+        vcr.finish().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn replays_infatica_without_recorded_credentials() {
+        let vcr = VcrServer::replay(cassette("infatica.json")).await.unwrap();
+        let cfg = InfaticaConfig::new(vcr.url().join("infatica/").unwrap(), "someone@example.com", "other-password");
+
+        let results = infatica::get_all(&cfg).await.unwrap();
+        assert!(!results.geo_nodes().is_empty());
+        assert!(!results.region_codes().is_empty());
+        let cassette = vcr.finish().await.unwrap();
+        assert!(cassette.interactions.iter().all(|i| !i.request.form.contains_key("password")));
+    }
+
+    #[tokio::test]
+    async fn reports_unrecorded_requests() {
+        let vcr = VcrServer::replay(cassette("infatica.json")).await.unwrap();
+        let response = reqwest::get(vcr.url().join("unknown").unwrap()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_IMPLEMENTED);
+
+        let err = vcr.finish().await.unwrap_err();
+        assert!(matches!(err, VcrError::Unmatched { count: 1, .. }), "{err}");
+    }
+
+    /// Re-records `fixtures/cassettes/` against the vendors:
+    ///
+    /// ```text
+    /// VCR_IPROYAL_ENDPOINT=... VCR_IPROYAL_TOKEN=... \
+    /// VCR_INFATICA_ENDPOINT=... VCR_INFATICA_EMAIL=... VCR_INFATICA_PASSWORD=... \
+    /// cargo test -p update_location --features vcr -- --ignored record_cassettes
+    /// ```
+    ///
+    /// Providers without an endpoint variable are skipped.
+    #[tokio::test]
+    #[ignore = "calls the vendors with real credentials"]
+    async fn record_cassettes() {
+        let var = |name: &str| std::env::var(name).unwrap_or_else(|_| panic!("{name} is not set"));
+        let proxied = |vcr: &VcrServer, endpoint: &Url| vcr.url().join(endpoint.path().trim_start_matches('/')).unwrap();
+
+        if let Ok(endpoint) = std::env::var("VCR_IPROYAL_ENDPOINT") {
+            let endpoint = Url::parse(&endpoint).unwrap();
+            let vcr = VcrServer::record(cassette("iproyal.json"), endpoint.join("/").unwrap()).await.unwrap();
+            let cfg = IPRoyalConfig::new(proxied(&vcr, &endpoint), var("VCR_IPROYAL_TOKEN"));
+            iproyal::get_all(&cfg).await.unwrap();
+            vcr.finish().await.unwrap();
+        }
+
+        if let Ok(endpoint) = std::env::var("VCR_INFATICA_ENDPOINT") {
+            let endpoint = Url::parse(&endpoint).unwrap();
+            let vcr = VcrServer::record(cassette("infatica.json"), endpoint.join("/").unwrap()).await.unwrap();
+            let cfg = InfaticaConfig::new(proxied(&vcr, &endpoint), var("VCR_INFATICA_EMAIL"), var("VCR_INFATICA_PASSWORD"));
+            infatica::get_all(&cfg).await.unwrap();
+            vcr.finish().await.unwrap();
+        }
+    }
+}